@@ -0,0 +1,623 @@
+//! `minivec-derive` provides `#[derive(MiniSoA)]`, which generates a struct-of-arrays vector for a struct on top of
+//! [`minivec::soa::RawMiniSoA`](https://docs.rs/minivec/latest/minivec/soa/struct.RawMiniSoA.html).
+//!
+//! It is usually used through the `derive` feature of `minivec`, which re-exports it as `minivec::MiniSoA`.
+//!
+#![warn(clippy::pedantic, missing_docs)]
+
+extern crate proc_macro;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Ident, Type, Visibility};
+
+// Fields with these names would collide with the methods of the generated vector.
+//
+const RESERVED: &[&str] = &[
+  "capacity",
+  "clear",
+  "extend",
+  "from_columns",
+  "get",
+  "get_mut",
+  "is_empty",
+  "iter",
+  "iter_mut",
+  "len",
+  "new",
+  "pop",
+  "push",
+  "reserve",
+  "reserve_exact",
+  "row_at",
+  "sort_by",
+  "sort_by_key",
+  "swap",
+  "swap_remove",
+  "truncate",
+  "with_capacity",
+];
+
+struct Column {
+  vis: Visibility,
+  name: Ident,
+  name_mut: Ident,
+  ty: Type,
+  idx: usize,
+}
+
+/// `MiniSoA` generates a struct-of-arrays vector for a struct with named fields. For a struct `Particle` it emits
+/// `ParticleMiniVec`, which keeps every field in its own column of a single allocation, `ParticleRef` and
+/// `ParticleRefMut`, which borrow every field of one row, and the `ParticleIter` and `ParticleIterMut` iterators.
+///
+/// Adding `#[mini_soa(serde)]` also generates `Serialize` and `Deserialize` impls for the vector, which stores it as
+/// a struct of per-field sequences. These require the `serde` feature of `minivec`.
+///
+/// Generic structs are not supported.
+///
+#[proc_macro_derive(MiniSoA, attributes(mini_soa))]
+pub fn derive_mini_soa(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let input = syn::parse_macro_input!(input as DeriveInput);
+  expand(&input)
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<bool> {
+  let mut serde = false;
+  for attr in input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("mini_soa"))
+  {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("serde") {
+        serde = true;
+        Ok(())
+      } else {
+        Err(meta.error("unsupported mini_soa option, expected `serde`"))
+      }
+    })?;
+  }
+
+  Ok(serde)
+}
+
+fn parse_columns(input: &DeriveInput) -> syn::Result<Vec<Column>> {
+  let Data::Struct(data) = &input.data else {
+    return Err(syn::Error::new(
+      input.span(),
+      "MiniSoA can only be derived for structs",
+    ));
+  };
+
+  let Fields::Named(fields) = &data.fields else {
+    return Err(syn::Error::new(
+      input.span(),
+      "MiniSoA requires a struct with named fields",
+    ));
+  };
+
+  if fields.named.is_empty() {
+    return Err(syn::Error::new(
+      input.span(),
+      "MiniSoA requires at least one field",
+    ));
+  }
+
+  if !input.generics.params.is_empty() || input.generics.where_clause.is_some() {
+    return Err(syn::Error::new(
+      input.generics.span(),
+      "MiniSoA does not support generic structs",
+    ));
+  }
+
+  fields
+    .named
+    .iter()
+    .enumerate()
+    .map(|(idx, field)| {
+      let Some(name) = field.ident.clone() else {
+        unreachable!("named fields always have an identifier");
+      };
+
+      if RESERVED.contains(&name.to_string().as_str()) {
+        return Err(syn::Error::new(
+          name.span(),
+          format!("field `{name}` collides with a method of the generated vector"),
+        ));
+      }
+
+      Ok(Column {
+        vis: field.vis.clone(),
+        name_mut: format_ident!("{}_mut", name),
+        name,
+        ty: field.ty.clone(),
+        idx,
+      })
+    })
+    .collect()
+}
+
+#[allow(clippy::too_many_lines)]
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+  let serde = parse_options(input)?;
+  let columns = parse_columns(input)?;
+
+  let vis = &input.vis;
+  let name = &input.ident;
+  let vec = format_ident!("{}MiniVec", name);
+  let row = format_ident!("{}Ref", name);
+  let row_mut = format_ident!("{}RefMut", name);
+  let iter = format_ident!("{}Iter", name);
+  let iter_mut = format_ident!("{}IterMut", name);
+
+  let field_vis: Vec<_> = columns.iter().map(|column| &column.vis).collect();
+  let fields: Vec<_> = columns.iter().map(|column| &column.name).collect();
+  let fields_mut: Vec<_> = columns.iter().map(|column| &column.name_mut).collect();
+  let types: Vec<_> = columns.iter().map(|column| &column.ty).collect();
+  let indices: Vec<_> = columns.iter().map(|column| column.idx).collect();
+  let first = fields[0];
+
+  let row_doc = format!("`{row}` borrows every field of one row of a [`{vec}`]({vec}).");
+  let row_mut_doc =
+    format!("`{row_mut}` mutably borrows every field of one row of a [`{vec}`]({vec}).");
+  let vec_doc = format!(
+    "`{vec}` is a struct-of-arrays vector of [`{name}`]({name}), keeping every field in its own column of a single \
+     allocation."
+  );
+  let iter_doc = format!("`{iter}` is an iterator over the rows of a [`{vec}`]({vec}).");
+  let iter_mut_doc = format!(
+    "`{iter_mut}` is an iterator over mutable references to the rows of a [`{vec}`]({vec})."
+  );
+  let column_docs: Vec<_> = fields
+    .iter()
+    .map(|field| format!("`{field}` returns the `{field}` column as a slice."))
+    .collect();
+  let row_field_docs: Vec<_> = fields
+    .iter()
+    .map(|field| format!("`{field}` borrows the `{field}` field of the row."))
+    .collect();
+  let column_mut_docs: Vec<_> = fields
+    .iter()
+    .map(|field| format!("`{field}_mut` returns the `{field}` column as a mutable slice."))
+    .collect();
+
+  let serde_impls = if serde {
+    expand_serde(&vec, &fields, &types)
+  } else {
+    TokenStream::new()
+  };
+
+  Ok(quote! {
+    unsafe impl ::minivec::soa::SoAColumns for #name {
+      const LAYOUTS: &'static [::core::alloc::Layout] = &[#(::core::alloc::Layout::new::<#types>()),*];
+    }
+
+    #[doc = #row_doc]
+    #[derive(Clone, Copy)]
+    #[allow(dead_code)]
+    #vis struct #row<'a> {
+      #(#[doc = #row_field_docs] #field_vis #fields: &'a #types,)*
+    }
+
+    #[doc = #row_mut_doc]
+    #[allow(dead_code)]
+    #vis struct #row_mut<'a> {
+      #(#[doc = #row_field_docs] #field_vis #fields: &'a mut #types,)*
+    }
+
+    #[doc = #vec_doc]
+    #vis struct #vec {
+      raw: ::minivec::soa::RawMiniSoA<#name>,
+    }
+
+    #[doc = #iter_doc]
+    #vis struct #iter<'a> {
+      #(#fields: ::core::slice::Iter<'a, #types>,)*
+    }
+
+    #[doc = #iter_mut_doc]
+    #vis struct #iter_mut<'a> {
+      #(#fields: ::core::slice::IterMut<'a, #types>,)*
+    }
+
+    #[allow(dead_code)]
+    impl #vec {
+      fn row_at(raw: &::minivec::soa::RawMiniSoA<#name>, idx: usize) -> #row<'_> {
+        unsafe {
+          #row {
+            #(#fields: &*raw.column(#indices).cast::<#types>().add(idx),)*
+          }
+        }
+      }
+
+      /// `capacity` returns the number of rows the vector can hold without reallocating.
+      #[must_use]
+      pub fn capacity(&self) -> usize {
+        self.raw.capacity()
+      }
+
+      /// `clear` drops every row, keeping the allocation.
+      pub fn clear(&mut self) {
+        self.truncate(0);
+      }
+
+      #(
+        #[doc = #column_docs]
+        #[must_use]
+        #field_vis fn #fields(&self) -> &[#types] {
+          unsafe { ::core::slice::from_raw_parts(self.raw.column(#indices).cast::<#types>(), self.raw.len()) }
+        }
+
+        #[doc = #column_mut_docs]
+        #field_vis fn #fields_mut(&mut self) -> &mut [#types] {
+          unsafe { ::core::slice::from_raw_parts_mut(self.raw.column(#indices).cast::<#types>(), self.raw.len()) }
+        }
+      )*
+
+      // locals carry a `__minivec_` prefix so they can't collide with the field-named parameters
+      /// `from_columns` builds a vector from one `MiniVec` per field, or returns `None` if their lengths differ.
+      #[must_use]
+      pub fn from_columns(#(mut #fields: ::minivec::MiniVec<#types>),*) -> ::core::option::Option<Self> {
+        let __minivec_len = #first.len();
+        if #(#fields.len() != __minivec_len)||* {
+          return ::core::option::Option::None;
+        }
+
+        let mut __minivec_vec = Self::with_capacity(__minivec_len);
+        unsafe {
+          #(
+            ::core::ptr::copy_nonoverlapping(#fields.as_ptr(), __minivec_vec.raw.column(#indices).cast::<#types>(), __minivec_len);
+            if __minivec_len > 0 {
+              #fields.set_len(0);
+            }
+          )*
+          __minivec_vec.raw.set_len(__minivec_len);
+        }
+
+        ::core::option::Option::Some(__minivec_vec)
+      }
+
+      /// `get` returns the row at `idx`, or `None` if it is out of bounds.
+      #[must_use]
+      pub fn get(&self, idx: usize) -> ::core::option::Option<#row<'_>> {
+        if idx < self.len() {
+          ::core::option::Option::Some(Self::row_at(&self.raw, idx))
+        } else {
+          ::core::option::Option::None
+        }
+      }
+
+      /// `get_mut` returns the row at `idx` mutably, or `None` if it is out of bounds.
+      pub fn get_mut(&mut self, idx: usize) -> ::core::option::Option<#row_mut<'_>> {
+        if idx >= self.len() {
+          return ::core::option::Option::None;
+        }
+
+        unsafe {
+          ::core::option::Option::Some(#row_mut {
+            #(#fields: &mut *self.raw.column(#indices).cast::<#types>().add(idx),)*
+          })
+        }
+      }
+
+      /// `is_empty` returns whether or not the vector contains any rows.
+      #[must_use]
+      pub fn is_empty(&self) -> bool {
+        self.raw.is_empty()
+      }
+
+      /// `iter` returns an iterator over the rows of the vector.
+      #[must_use]
+      pub fn iter(&self) -> #iter<'_> {
+        #iter {
+          #(#fields: self.#fields().iter(),)*
+        }
+      }
+
+      /// `iter_mut` returns an iterator over mutable references to the rows of the vector.
+      pub fn iter_mut(&mut self) -> #iter_mut<'_> {
+        let len = self.raw.len();
+        unsafe {
+          #iter_mut {
+            #(#fields: ::core::slice::from_raw_parts_mut(self.raw.column(#indices).cast::<#types>(), len).iter_mut(),)*
+          }
+        }
+      }
+
+      /// `len` returns the number of rows in the vector.
+      #[must_use]
+      pub fn len(&self) -> usize {
+        self.raw.len()
+      }
+
+      /// `new` constructs an empty vector.
+      ///
+      /// Note: does not allocate any memory.
+      #[must_use]
+      pub fn new() -> Self {
+        #vec {
+          raw: ::minivec::soa::RawMiniSoA::new(),
+        }
+      }
+
+      /// `pop` removes the last row and returns it, or `None` if the vector is empty.
+      pub fn pop(&mut self) -> ::core::option::Option<#name> {
+        let len = self.len();
+        if len == 0 {
+          return ::core::option::Option::None;
+        }
+
+        unsafe {
+          self.raw.set_len(len - 1);
+          ::core::option::Option::Some(#name {
+            #(#fields: self.raw.column(#indices).cast::<#types>().add(len - 1).read(),)*
+          })
+        }
+      }
+
+      /// `push` appends `value` to the vector, writing each of its fields to its column.
+      pub fn push(&mut self, value: #name) {
+        self.raw.reserve(1);
+
+        let len = self.len();
+        unsafe {
+          #(self.raw.column(#indices).cast::<#types>().add(len).write(value.#fields);)*
+          self.raw.set_len(len + 1);
+        }
+      }
+
+      /// `reserve` ensures there is room for at least `additional` more rows.
+      pub fn reserve(&mut self, additional: usize) {
+        self.raw.reserve(additional);
+      }
+
+      /// `reserve_exact` ensures there is room for at least `additional` more rows without over-allocating.
+      pub fn reserve_exact(&mut self, additional: usize) {
+        self.raw.reserve_exact(additional);
+      }
+
+      /// `sort_by` stably sorts the rows with the comparator `compare`, reordering every column.
+      pub fn sort_by<F>(&mut self, mut compare: F)
+      where
+        F: ::core::ops::FnMut(#row<'_>, #row<'_>) -> ::core::cmp::Ordering,
+      {
+        self.raw.sort_by(|raw, a, b| compare(Self::row_at(raw, a), Self::row_at(raw, b)));
+      }
+
+      /// `sort_by_key` stably sorts the rows by the key `f` extracts from them, reordering every column.
+      pub fn sort_by_key<K, F>(&mut self, mut f: F)
+      where
+        K: ::core::cmp::Ord,
+        F: ::core::ops::FnMut(#row<'_>) -> K,
+      {
+        self.raw.sort_by(|raw, a, b| f(Self::row_at(raw, a)).cmp(&f(Self::row_at(raw, b))));
+      }
+
+      /// `swap` swaps the rows `a` and `b`.
+      ///
+      /// # Panics
+      ///
+      /// Panics if either index is out of bounds.
+      pub fn swap(&mut self, a: usize, b: usize) {
+        self.raw.swap(a, b);
+      }
+
+      /// `swap_remove` removes the row at `idx` and returns it, moving the last row into its place.
+      ///
+      /// # Panics
+      ///
+      /// Panics if `idx` is out of bounds.
+      pub fn swap_remove(&mut self, idx: usize) -> #name {
+        let len = self.len();
+        ::core::assert!(idx < len, "swap_remove index (is {}) should be < len (is {})", idx, len);
+
+        self.raw.swap(idx, len - 1);
+        match self.pop() {
+          ::core::option::Option::Some(value) => value,
+          ::core::option::Option::None => ::core::unreachable!("the vector is not empty"),
+        }
+      }
+
+      /// `truncate` drops every row past the first `len`.
+      pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len();
+        if len >= old_len {
+          return;
+        }
+
+        // rows are leaked rather than double-dropped should a destructor panic
+        unsafe {
+          self.raw.set_len(len);
+          #(
+            ::core::ptr::drop_in_place(::core::ptr::slice_from_raw_parts_mut(
+              self.raw.column(#indices).cast::<#types>().add(len),
+              old_len - len,
+            ));
+          )*
+        }
+      }
+
+      /// `with_capacity` constructs an empty vector with room for `capacity` rows.
+      #[must_use]
+      pub fn with_capacity(capacity: usize) -> Self {
+        #vec {
+          raw: ::minivec::soa::RawMiniSoA::with_capacity(capacity),
+        }
+      }
+    }
+
+    impl ::core::ops::Drop for #vec {
+      fn drop(&mut self) {
+        self.clear();
+      }
+    }
+
+    impl ::core::default::Default for #vec {
+      fn default() -> Self {
+        Self::new()
+      }
+    }
+
+    impl ::core::iter::Extend<#name> for #vec {
+      fn extend<I: ::core::iter::IntoIterator<Item = #name>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+          self.push(value);
+        }
+      }
+    }
+
+    impl ::core::iter::FromIterator<#name> for #vec {
+      fn from_iter<I: ::core::iter::IntoIterator<Item = #name>>(iter: I) -> Self {
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
+      }
+    }
+
+    impl<'a> ::core::iter::IntoIterator for &'a #vec {
+      type Item = #row<'a>;
+      type IntoIter = #iter<'a>;
+
+      fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+      }
+    }
+
+    impl<'a> ::core::iter::IntoIterator for &'a mut #vec {
+      type Item = #row_mut<'a>;
+      type IntoIter = #iter_mut<'a>;
+
+      fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+      }
+    }
+
+    impl<'a> ::core::iter::Iterator for #iter<'a> {
+      type Item = #row<'a>;
+
+      fn next(&mut self) -> ::core::option::Option<Self::Item> {
+        ::core::option::Option::Some(#row {
+          #(#fields: self.#fields.next()?,)*
+        })
+      }
+
+      fn size_hint(&self) -> (usize, ::core::option::Option<usize>) {
+        self.#first.size_hint()
+      }
+    }
+
+    impl ::core::iter::ExactSizeIterator for #iter<'_> {}
+
+    impl<'a> ::core::iter::Iterator for #iter_mut<'a> {
+      type Item = #row_mut<'a>;
+
+      fn next(&mut self) -> ::core::option::Option<Self::Item> {
+        ::core::option::Option::Some(#row_mut {
+          #(#fields: self.#fields.next()?,)*
+        })
+      }
+
+      fn size_hint(&self) -> (usize, ::core::option::Option<usize>) {
+        self.#first.size_hint()
+      }
+    }
+
+    impl ::core::iter::ExactSizeIterator for #iter_mut<'_> {}
+
+    #serde_impls
+  })
+}
+
+fn expand_serde(vec: &Ident, fields: &[&Ident], types: &[&Type]) -> TokenStream {
+  let vec_name = vec.to_string();
+  let field_names: Vec<_> = fields.iter().map(ToString::to_string).collect();
+  let count = fields.len();
+  let positions: Vec<_> = (0..count).collect();
+  let expecting = format!("struct {vec}");
+
+  quote! {
+    impl ::minivec::soa::__serde::Serialize for #vec {
+      fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+      where
+        S: ::minivec::soa::__serde::Serializer,
+      {
+        use ::minivec::soa::__serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct(#vec_name, #count)?;
+        #(state.serialize_field(#field_names, self.#fields())?;)*
+        state.end()
+      }
+    }
+
+    impl<'de> ::minivec::soa::__serde::Deserialize<'de> for #vec {
+      fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+      where
+        D: ::minivec::soa::__serde::Deserializer<'de>,
+      {
+        use ::minivec::soa::__serde::de::{Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+        const FIELDS: &[&str] = &[#(#field_names),*];
+
+        struct ColumnsVisitor;
+
+        impl<'de> Visitor<'de> for ColumnsVisitor {
+          type Value = #vec;
+
+          fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+            f.write_str(#expecting)
+          }
+
+          // as in `from_columns`, the field names are bound as locals so nothing else may use a plain name
+          fn visit_seq<A>(self, mut __minivec_seq: A) -> ::core::result::Result<Self::Value, A::Error>
+          where
+            A: SeqAccess<'de>,
+          {
+            #(
+              let #fields: ::minivec::MiniVec<#types> = __minivec_seq
+                .next_element()?
+                .ok_or_else(|| Error::invalid_length(#positions, &self))?;
+            )*
+
+            #vec::from_columns(#(#fields),*)
+              .ok_or_else(|| Error::custom("columns differ in length"))
+          }
+
+          fn visit_map<A>(self, mut __minivec_map: A) -> ::core::result::Result<Self::Value, A::Error>
+          where
+            A: MapAccess<'de>,
+          {
+            #(let mut #fields: ::core::option::Option<::minivec::MiniVec<#types>> = ::core::option::Option::None;)*
+
+            while let ::core::option::Option::Some(__minivec_key) = __minivec_map.next_key_seed(::minivec::soa::FieldSeed(FIELDS))? {
+              match __minivec_key {
+                #(
+                  ::core::option::Option::Some(#positions) => {
+                    if #fields.is_some() {
+                      return ::core::result::Result::Err(Error::duplicate_field(#field_names));
+                    }
+                    #fields = ::core::option::Option::Some(__minivec_map.next_value()?);
+                  }
+                )*
+                _ => {
+                  __minivec_map.next_value::<IgnoredAny>()?;
+                }
+              }
+            }
+
+            #(let #fields = #fields.ok_or_else(|| Error::missing_field(#field_names))?;)*
+
+            #vec::from_columns(#(#fields),*)
+              .ok_or_else(|| Error::custom("columns differ in length"))
+          }
+        }
+
+        deserializer.deserialize_struct(#vec_name, FIELDS, ColumnsVisitor)
+      }
+    }
+  }
+}
@@ -0,0 +1,412 @@
+//! `atomic` contains [`AtomicMiniVec`](AtomicMiniVec), a [`MiniVec`](crate::MiniVec) which can be read and
+//! replaced as a whole from many threads at once.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::sync::{fence, AtomicBool, AtomicPtr, Ordering};
+use crate::MiniVec;
+
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// `AtomicMiniVec` holds a [`MiniVec`](crate::MiniVec) which readers can access without locking while writers
+/// atomically publish whole replacement vectors, in the style of read-copy-update.
+///
+/// Since a `MiniVec` is a single non-null pointer, publishing a new vector is a single atomic swap.
+/// [`load`](AtomicMiniVec::load) returns a [`Guard`](Guard) that keeps the current vector alive by announcing it in a
+/// hazard pointer. Writers never wait on readers: [`swap`](AtomicMiniVec::swap),
+/// [`store`](AtomicMiniVec::store) and [`compare_exchange`](AtomicMiniVec::compare_exchange) publish the new vector
+/// and leave the previous one to whichever guard is the last to observe it, which drops it on release. A vector no
+/// guard observes is dropped right away.
+///
+/// Neither readers nor writers ever block, so a thread may replace the vector while it holds guards from the same
+/// `AtomicMiniVec`.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{mini_vec, AtomicMiniVec};
+///
+/// let config = AtomicMiniVec::new(mini_vec!["a", "b"]);
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         let table = config.load();
+///         assert!(*table == ["a", "b"] || *table == ["c"]);
+///     });
+///
+///     s.spawn(|| {
+///         let old = config.swap(mini_vec!["c"]);
+///         assert_eq!(*old, ["a", "b"]);
+///     });
+/// });
+///
+/// assert_eq!(*config.load(), ["c"]);
+/// ```
+///
+pub struct AtomicMiniVec<T> {
+  buf: AtomicPtr<u8>,
+  records: AtomicPtr<Record>,
+  phantom: core::marker::PhantomData<MiniVec<T>>,
+}
+
+// Set in a hazard pointer once the vector it protects has been replaced and the guard holding the record is
+// responsible for dropping it. Vectors are always aligned to at least their header so the low bit is free.
+//
+const OWNED: usize = 1;
+
+// A hazard record announces the vector one guard is reading. Records are pushed onto their `AtomicMiniVec`'s list
+// the first time no free one can be claimed and are only freed along with it. `next` is written before the record
+// is published and never changes afterwards.
+//
+struct Record {
+  hazard: AtomicPtr<u8>,
+  active: AtomicBool,
+  next: *mut Record,
+}
+
+unsafe impl core::marker::Send for Record {}
+unsafe impl core::marker::Sync for Record {}
+
+/// `Guard` is a read-only view of the vector an [`AtomicMiniVec`](AtomicMiniVec) held when it was
+/// [`load`](AtomicMiniVec::load)ed, or of the vector a writer replaced. The vector is kept alive until the guard is
+/// dropped, even if it has since been replaced.
+///
+/// A guard can only be sent to another thread when its `AtomicMiniVec` could be shared with that thread, since the
+/// vector it views is shared with every other reader:
+///
+/// ```compile_fail
+/// use minivec::{mini_vec, AtomicMiniVec};
+/// use std::cell::Cell;
+///
+/// let vec = AtomicMiniVec::new(mini_vec![Cell::new(1)]);
+/// let guard = vec.load();
+/// let other = vec.load();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(move || guard[0].set(2));
+///     other[0].set(3);
+/// });
+/// ```
+///
+pub struct Guard<'a, T> {
+  vec: ManuallyDrop<MiniVec<T>>,
+  record: &'a Record,
+  atomic: &'a AtomicMiniVec<T>,
+}
+
+impl<T> Drop for Guard<'_, T> {
+  fn drop(&mut self) {
+    let hazard = self
+      .record
+      .hazard
+      .swap(core::ptr::null_mut(), Ordering::AcqRel);
+    self.record.active.store(false, Ordering::Release);
+
+    if hazard.addr() & OWNED != 0 {
+      self.atomic.retire(hazard.map_addr(|addr| addr & !OWNED));
+    }
+  }
+}
+
+impl<T> core::ops::Deref for Guard<'_, T> {
+  type Target = MiniVec<T>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.vec
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for Guard<'_, T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    core::fmt::Debug::fmt(&*self.vec, f)
+  }
+}
+
+fn into_raw<T>(vec: MiniVec<T>) -> *mut u8 {
+  let buf = ManuallyDrop::new(vec).buf.as_ptr();
+  debug_assert!(buf.addr() & OWNED == 0);
+  buf
+}
+
+unsafe fn from_raw<T>(buf: *mut u8) -> MiniVec<T> {
+  MiniVec {
+    buf: NonNull::new_unchecked(buf),
+    phantom: core::marker::PhantomData,
+  }
+}
+
+impl<T> AtomicMiniVec<T> {
+  // Claims a record no guard is using, or publishes a new one.
+  //
+  fn acquire(&self) -> &Record {
+    let mut next = self.records.load(Ordering::Acquire);
+    while let Some(record) = unsafe { next.as_ref() } {
+      if !record.active.load(Ordering::Relaxed)
+        && record
+          .active
+          .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+          .is_ok()
+      {
+        return record;
+      }
+
+      next = record.next;
+    }
+
+    let record = Box::into_raw(Box::new(Record {
+      hazard: AtomicPtr::new(core::ptr::null_mut()),
+      active: AtomicBool::new(true),
+      next: core::ptr::null_mut(),
+    }));
+
+    let mut head = self.records.load(Ordering::Relaxed);
+    loop {
+      unsafe { (*record).next = head };
+      match self
+        .records
+        .compare_exchange(head, record, Ordering::Release, Ordering::Relaxed)
+      {
+        Ok(_) => return unsafe { &*record },
+        Err(actual) => head = actual,
+      }
+    }
+  }
+
+  fn guard<'a>(&'a self, record: &'a Record, buf: *mut u8) -> Guard<'a, T> {
+    Guard {
+      vec: ManuallyDrop::new(unsafe { from_raw(buf) }),
+      record,
+      atomic: self,
+    }
+  }
+
+  // Called by the sole owner of `buf` after it's been replaced. Hands `buf` over to a guard still announcing it, or
+  // drops it if there is none.
+  //
+  // The fence pairs with the one in `load`. Either the reader's fence comes first, and its hazard is visible to the
+  // scan below, or ours does, and the reader sees that `buf` has been replaced and never reads it. A hazard that's
+  // changed since only belonged to a reader which has moved on, and its release synchronizes with our load.
+  //
+  // Vectors without an allocation share their address with every other empty vector of the same alignment and have
+  // nothing to drop, so they're never handed over.
+  //
+  fn retire(&self, buf: *mut u8) {
+    let vec = unsafe { from_raw::<T>(buf) };
+    if vec.is_default() {
+      return;
+    }
+
+    fence(Ordering::SeqCst);
+
+    let mut next = self.records.load(Ordering::Acquire);
+    while let Some(record) = unsafe { next.as_ref() } {
+      if record.hazard.load(Ordering::Acquire) == buf
+        && record
+          .hazard
+          .compare_exchange(
+            buf,
+            buf.map_addr(|addr| addr | OWNED),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+          )
+          .is_ok()
+      {
+        core::mem::forget(vec);
+        return;
+      }
+
+      next = record.next;
+    }
+
+    drop(vec);
+  }
+
+  /// `compare_exchange` stores `new` if the vector is still the one `current` views, returning `current` which now
+  /// keeps the previous vector alive until it's dropped. Otherwise `new` is handed back unchanged and `current` is
+  /// released.
+  ///
+  /// Vectors are compared by address, which `current` keeps from being reused. Empty vectors without an allocation
+  /// compare equal to any other such vector of the same alignment.
+  ///
+  /// # Errors
+  ///
+  /// Returns `Err(new)` if another vector has been stored since `current` was loaded.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `current` was loaded from a different `AtomicMiniVec`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::{mini_vec, AtomicMiniVec};
+  ///
+  /// let vec = AtomicMiniVec::new(mini_vec![1, 2]);
+  ///
+  /// let current = vec.load();
+  /// let mut next = current.clone();
+  /// next.push(3);
+  ///
+  /// let old = vec.compare_exchange(current, next).unwrap();
+  /// assert_eq!(*old, [1, 2]);
+  /// assert_eq!(*vec.load(), [1, 2, 3]);
+  /// ```
+  ///
+  pub fn compare_exchange<'a>(
+    &'a self,
+    current: Guard<'a, T>,
+    new: MiniVec<T>,
+  ) -> Result<Guard<'a, T>, MiniVec<T>> {
+    assert!(
+      core::ptr::eq(self, current.atomic),
+      "guard was loaded from a different AtomicMiniVec"
+    );
+
+    let expected = current.vec.buf.as_ptr();
+    let new = into_raw(new);
+
+    match self
+      .buf
+      .compare_exchange(expected, new, Ordering::AcqRel, Ordering::Relaxed)
+    {
+      Ok(old) => {
+        self.retire(old);
+        Ok(current)
+      }
+      Err(_) => Err(unsafe { from_raw(new) }),
+    }
+  }
+
+  /// `into_inner` consumes the `AtomicMiniVec` and returns the vector it holds.
+  ///
+  #[must_use]
+  pub fn into_inner(self) -> MiniVec<T> {
+    let buf = self
+      .buf
+      .swap(into_raw(MiniVec::<T>::new()), Ordering::Acquire);
+    unsafe { from_raw(buf) }
+  }
+
+  /// `load` returns a guard which dereferences to the vector currently held. Never blocks, and only retries when a
+  /// writer replaces the vector while it's being loaded.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::AtomicMiniVec::new(minivec::mini_vec![1, 2, 3]);
+  ///
+  /// let guard = vec.load();
+  /// assert_eq!(guard.len(), 3);
+  /// assert_eq!(guard.iter().sum::<i32>(), 6);
+  /// ```
+  ///
+  #[must_use]
+  pub fn load(&self) -> Guard<'_, T> {
+    let record = self.acquire();
+
+    let mut buf = self.buf.load(Ordering::Acquire);
+    loop {
+      // a writer may have handed over a vector we announced but never got to read
+      //
+      let hazard = record.hazard.swap(buf, Ordering::AcqRel);
+      if hazard.addr() & OWNED != 0 {
+        self.retire(hazard.map_addr(|addr| addr & !OWNED));
+      }
+
+      fence(Ordering::SeqCst);
+
+      let current = self.buf.load(Ordering::Acquire);
+      if current == buf {
+        return self.guard(record, buf);
+      }
+
+      buf = current;
+    }
+  }
+
+  /// `new` constructs an `AtomicMiniVec` holding `vec`.
+  ///
+  #[must_use]
+  pub fn new(vec: MiniVec<T>) -> Self {
+    AtomicMiniVec {
+      buf: AtomicPtr::new(into_raw(vec)),
+      records: AtomicPtr::new(core::ptr::null_mut()),
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  /// `store` replaces the vector with `vec`. The previous vector is dropped once no guard can observe it anymore.
+  ///
+  pub fn store(&self, vec: MiniVec<T>) {
+    let old = self.buf.swap(into_raw(vec), Ordering::AcqRel);
+    self.retire(old);
+  }
+
+  /// `swap` replaces the vector with `vec` and returns a guard viewing the previous vector, which is dropped once
+  /// neither it nor any other guard can observe it anymore.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::{mini_vec, AtomicMiniVec};
+  ///
+  /// let vec = AtomicMiniVec::new(mini_vec![1]);
+  ///
+  /// let reader = vec.load();
+  /// let old = vec.swap(mini_vec![2]);
+  ///
+  /// assert_eq!(*reader, [1]);
+  /// assert_eq!(*old, [1]);
+  /// assert_eq!(*vec.load(), [2]);
+  /// ```
+  ///
+  pub fn swap(&self, vec: MiniVec<T>) -> Guard<'_, T> {
+    let record = self.acquire();
+
+    // nothing else writes to an active record's hazard unless it already matches, so the owner can just announce
+    // the vector it's responsible for
+    //
+    let old = self.buf.swap(into_raw(vec), Ordering::AcqRel);
+    record
+      .hazard
+      .store(old.map_addr(|addr| addr | OWNED), Ordering::Release);
+
+    self.guard(record, old)
+  }
+}
+
+impl<T> Drop for AtomicMiniVec<T> {
+  fn drop(&mut self) {
+    drop(unsafe { from_raw::<T>(self.buf.load(Ordering::Acquire)) });
+
+    let mut next = self.records.load(Ordering::Acquire);
+    while !next.is_null() {
+      let record = unsafe { Box::from_raw(next) };
+      debug_assert!(record.hazard.load(Ordering::Relaxed).is_null());
+      next = record.next;
+    }
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for AtomicMiniVec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    core::fmt::Debug::fmt(&*self.load(), f)
+  }
+}
+
+impl<T> Default for AtomicMiniVec<T> {
+  fn default() -> Self {
+    AtomicMiniVec::new(MiniVec::new())
+  }
+}
+
+impl<T> From<MiniVec<T>> for AtomicMiniVec<T> {
+  fn from(vec: MiniVec<T>) -> Self {
+    AtomicMiniVec::new(vec)
+  }
+}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for AtomicMiniVec<T> {}
+unsafe impl<T: core::marker::Send + core::marker::Sync> core::marker::Sync for AtomicMiniVec<T> {}
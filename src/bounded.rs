@@ -0,0 +1,669 @@
+//! `bounded` contains [`BoundedMiniVec`](BoundedMiniVec), a [`MiniVec`](crate::MiniVec) whose capacity can never
+//! exceed a compile-time limit, along with the [`CapacityError`](CapacityError) returned by its fallible operations.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::{next_capacity, resolve_range};
+use crate::{Drain, MiniVec, Splice};
+
+use core::convert::TryFrom;
+
+/// `CapacityError` is returned by the fallible operations of [`BoundedMiniVec`](BoundedMiniVec) when completing them
+/// would exceed the vector's limit. It hands back whatever the operation would have consumed.
+///
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+  element: T,
+}
+
+impl<T> CapacityError<T> {
+  fn new(element: T) -> Self {
+    CapacityError { element }
+  }
+
+  /// `element` returns the value that could not be added to the vector.
+  ///
+  pub fn element(self) -> T {
+    self.element
+  }
+}
+
+impl<T> core::fmt::Debug for CapacityError<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("CapacityError: BoundedMiniVec capacity limit exceeded")
+  }
+}
+
+impl<T> core::fmt::Display for CapacityError<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("BoundedMiniVec capacity limit exceeded")
+  }
+}
+
+/// `BoundedMiniVec` is a [`MiniVec`](crate::MiniVec) that will never allocate room for more than `N` elements. Like
+/// `MiniVec`, it is only the size of a single pointer.
+///
+/// Growth follows the same amortized strategy as `MiniVec` but is clamped to `N`. Every operation that adds elements
+/// comes in two flavors: a fallible `try_` variant which returns a [`CapacityError`](CapacityError) and leaves the
+/// vector untouched, and a panicking variant mirroring the `MiniVec` API. The one exception is
+/// [`try_extend`](BoundedMiniVec::try_extend), which can't hand back the elements it already took from its iterator and
+/// so keeps every element that fit.
+///
+/// `BoundedMiniVec` dereferences to a slice, so all of the read-only and in-place slice methods are available.
+///
+/// # Example
+///
+/// ```
+/// let mut frame = minivec::BoundedMiniVec::<u8, 4>::new();
+///
+/// frame.extend_from_slice(&[1, 2, 3]);
+/// assert!(frame.try_push(4).is_ok());
+///
+/// let err = frame.try_push(5).unwrap_err();
+/// assert_eq!(err.element(), 5);
+///
+/// assert_eq!(frame, [1, 2, 3, 4]);
+/// assert_eq!(frame.capacity(), 4);
+/// ```
+///
+pub struct BoundedMiniVec<T, const N: usize> {
+  inner: MiniVec<T>,
+}
+
+impl<T, const N: usize> BoundedMiniVec<T, N> {
+  fn try_reserve_for(&mut self, additional: usize) -> bool {
+    let required = match self.inner.len().checked_add(additional) {
+      Some(required) if required <= N => required,
+      _ => return false,
+    };
+
+    let capacity = self.inner.capacity();
+    if required > capacity {
+      let target = core::cmp::min(core::cmp::max(required, next_capacity::<T>(capacity)), N);
+      self.inner.reserve_exact(target - self.inner.len());
+    }
+
+    true
+  }
+
+  /// `new` constructs an empty vector.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    BoundedMiniVec {
+      inner: MiniVec::new(),
+    }
+  }
+
+  /// `with_capacity` constructs an empty vector with space for `capacity` elements. The capacity is clamped to `N`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::BoundedMiniVec::<i32, 8>::with_capacity(1024);
+  /// assert_eq!(vec.capacity(), 8);
+  /// ```
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    BoundedMiniVec {
+      inner: MiniVec::with_capacity(core::cmp::min(capacity, N)),
+    }
+  }
+
+  /// `as_mini_vec` returns a reference to the underlying `MiniVec`.
+  ///
+  /// There is no mutable counterpart as it would allow the vector to grow past its limit.
+  ///
+  #[must_use]
+  pub fn as_mini_vec(&self) -> &MiniVec<T> {
+    &self.inner
+  }
+
+  /// `capacity` returns the number of elements the vector can hold without reallocating. It is never greater than
+  /// `N`.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.inner.capacity()
+  }
+
+  /// `clear` removes every element from the vector.
+  ///
+  pub fn clear(&mut self) {
+    self.inner.clear();
+  }
+
+  /// `drain` removes the elements in the supplied range from the vector, returning them as an iterator.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+  where
+    R: core::ops::RangeBounds<usize>,
+  {
+    self.inner.drain(range)
+  }
+
+  /// `extend_from_slice` clones every element of `elems` onto the end of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the vector would grow past `N` elements.
+  ///
+  pub fn extend_from_slice(&mut self, elems: &[T])
+  where
+    T: Clone,
+  {
+    self
+      .try_extend_from_slice(elems)
+      .expect("BoundedMiniVec capacity limit exceeded");
+  }
+
+  /// `extend_from_within` clones the elements in `range` onto the end of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds, or if the vector would grow past `N` elements.
+  ///
+  pub fn extend_from_within<R>(&mut self, range: R)
+  where
+    T: Clone,
+    R: core::ops::RangeBounds<usize>,
+  {
+    self
+      .try_extend_from_within(range)
+      .expect("BoundedMiniVec capacity limit exceeded");
+  }
+
+  /// `insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len` or if the vector is full.
+  ///
+  pub fn insert(&mut self, index: usize, element: T) {
+    assert!(
+      self.try_reserve_for(1),
+      "BoundedMiniVec capacity limit exceeded"
+    );
+    self.inner.insert(index, element);
+  }
+
+  /// `into_inner` unwraps the underlying `MiniVec`.
+  ///
+  #[must_use]
+  pub fn into_inner(self) -> MiniVec<T> {
+    self.inner
+  }
+
+  /// `is_empty` returns whether or not the vector contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.inner.is_empty()
+  }
+
+  /// `is_full` returns whether or not the vector contains `N` elements.
+  ///
+  #[must_use]
+  pub fn is_full(&self) -> bool {
+    self.inner.len() == N
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.inner.len()
+  }
+
+  /// `limit` returns `N`, the maximum number of elements the vector can ever hold.
+  ///
+  #[must_use]
+  pub const fn limit(&self) -> usize {
+    N
+  }
+
+  /// `pop` removes the last element of the vector and returns it, or `None` if the vector is empty.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    self.inner.pop()
+  }
+
+  /// `push` appends `value` to the end of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the vector is full.
+  ///
+  pub fn push(&mut self, value: T) {
+    assert!(
+      self.try_reserve_for(1),
+      "BoundedMiniVec capacity limit exceeded"
+    );
+    self.inner.push(value);
+  }
+
+  /// `remaining` returns the number of elements that can still be added before the vector is full.
+  ///
+  #[must_use]
+  pub fn remaining(&self) -> usize {
+    N - self.inner.len()
+  }
+
+  /// `remove` removes the element at `index` and returns it, shifting every element after it to the left.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn remove(&mut self, index: usize) -> T {
+    self.inner.remove(index)
+  }
+
+  /// `resize` clones `value` onto the end of the vector until its length becomes `new_len`, or truncates it if it's
+  /// longer.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `new_len > N`.
+  ///
+  pub fn resize(&mut self, new_len: usize, value: T)
+  where
+    T: Clone,
+  {
+    self
+      .try_resize(new_len, value)
+      .expect("BoundedMiniVec capacity limit exceeded");
+  }
+
+  /// `retain` removes every element for which `f` returns `false`.
+  ///
+  pub fn retain<F>(&mut self, f: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    self.inner.retain(f);
+  }
+
+  /// `shrink_to_fit` reduces the capacity of the vector as much as possible.
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    self.inner.shrink_to_fit();
+  }
+
+  /// `splice` replaces the elements in `range` with those yielded by `replace_with`, returning the removed elements
+  /// as an iterator.
+  ///
+  /// Unlike [`MiniVec::splice`](crate::MiniVec::splice), the replacement elements are collected up front so the
+  /// final length can be checked against `N` before the vector is modified.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds, or if the vector would grow past `N` elements.
+  ///
+  pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, crate::IntoIter<T>>
+  where
+    I: IntoIterator<Item = T>,
+    R: core::ops::RangeBounds<usize>,
+  {
+    self
+      .try_splice(range, replace_with)
+      .expect("BoundedMiniVec capacity limit exceeded")
+  }
+
+  /// `swap_remove` removes the element at `index` and returns it, replacing it with the last element of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn swap_remove(&mut self, index: usize) -> T {
+    self.inner.swap_remove(index)
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    self.inner.truncate(len);
+  }
+
+  /// `try_extend` appends the elements of `iter` to the vector, stopping at the first element that does not fit.
+  ///
+  /// # Errors
+  ///
+  /// Returns the first element that could not be added. Every element before it remains in the vector.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::BoundedMiniVec::<i32, 3>::new();
+  ///
+  /// let err = vec.try_extend(1..10).unwrap_err();
+  ///
+  /// assert_eq!(err.element(), 4);
+  /// assert_eq!(vec, [1, 2, 3]);
+  /// ```
+  ///
+  pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+  where
+    I: IntoIterator<Item = T>,
+  {
+    let iter = iter.into_iter();
+
+    let (lower, _) = iter.size_hint();
+    self.try_reserve_for(core::cmp::min(lower, self.remaining()));
+
+    for value in iter {
+      self.try_push(value)?;
+    }
+
+    Ok(())
+  }
+
+  /// `try_extend_from_slice` clones every element of `elems` onto the end of the vector.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error and leaves the vector untouched if it would grow past `N` elements.
+  ///
+  pub fn try_extend_from_slice<'a>(&mut self, elems: &'a [T]) -> Result<(), CapacityError<&'a [T]>>
+  where
+    T: Clone,
+  {
+    if !self.try_reserve_for(elems.len()) {
+      return Err(CapacityError::new(elems));
+    }
+
+    self.inner.extend_from_slice(elems);
+    Ok(())
+  }
+
+  /// `try_extend_from_within` clones the elements in `range` onto the end of the vector.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error and leaves the vector untouched if it would grow past `N` elements.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  pub fn try_extend_from_within<R>(&mut self, range: R) -> Result<(), CapacityError<()>>
+  where
+    T: Clone,
+    R: core::ops::RangeBounds<usize>,
+  {
+    let (start_idx, end_idx) = resolve_range(&range, self.inner.len());
+
+    if !self.try_reserve_for(end_idx - start_idx) {
+      return Err(CapacityError::new(()));
+    }
+
+    self.inner.extend_from_within(start_idx..end_idx);
+    Ok(())
+  }
+
+  /// `try_insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Errors
+  ///
+  /// Returns `element` if the vector is full.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len`.
+  ///
+  pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), CapacityError<T>> {
+    if !self.try_reserve_for(1) {
+      return Err(CapacityError::new(element));
+    }
+
+    self.inner.insert(index, element);
+    Ok(())
+  }
+
+  /// `try_push` appends `value` to the end of the vector.
+  ///
+  /// # Errors
+  ///
+  /// Returns `value` if the vector is full.
+  ///
+  pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+    if !self.try_reserve_for(1) {
+      return Err(CapacityError::new(value));
+    }
+
+    self.inner.push(value);
+    Ok(())
+  }
+
+  /// `try_reserve` reserves space for at least `additional` more elements, clamping the new capacity to `N`.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error and leaves the vector untouched if `len + additional > N`.
+  ///
+  pub fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError<()>> {
+    if self.try_reserve_for(additional) {
+      Ok(())
+    } else {
+      Err(CapacityError::new(()))
+    }
+  }
+
+  /// `try_resize` clones `value` onto the end of the vector until its length becomes `new_len`, or truncates it if
+  /// it's longer.
+  ///
+  /// # Errors
+  ///
+  /// Returns `value` and leaves the vector untouched if `new_len > N`.
+  ///
+  pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), CapacityError<T>>
+  where
+    T: Clone,
+  {
+    let len = self.inner.len();
+    if new_len > len && !self.try_reserve_for(new_len - len) {
+      return Err(CapacityError::new(value));
+    }
+
+    self.inner.resize(new_len, value);
+    Ok(())
+  }
+
+  /// `try_splice` replaces the elements in `range` with those yielded by `replace_with`, returning the removed
+  /// elements as an iterator.
+  ///
+  /// # Errors
+  ///
+  /// Returns the replacement elements taken from `replace_with` and leaves the vector untouched if it would grow past
+  /// `N` elements. No more than one element beyond what fits is taken, so `replace_with` may be unbounded.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::BoundedMiniVec::<i32, 4>::new();
+  /// vec.extend_from_slice(&[1, 2, 3]);
+  ///
+  /// assert!(vec.try_splice(1..2, vec![7, 8, 9]).is_err());
+  ///
+  /// let removed: Vec<_> = vec.try_splice(1..2, vec![7, 8]).unwrap().collect();
+  /// assert_eq!(removed, [2]);
+  /// assert_eq!(vec, [1, 7, 8, 3]);
+  /// ```
+  ///
+  pub fn try_splice<R, I>(
+    &mut self,
+    range: R,
+    replace_with: I,
+  ) -> Result<Splice<'_, crate::IntoIter<T>>, CapacityError<MiniVec<T>>>
+  where
+    I: IntoIterator<Item = T>,
+    R: core::ops::RangeBounds<usize>,
+  {
+    let (start_idx, end_idx) = resolve_range(&range, self.inner.len());
+    let removed = end_idx - start_idx;
+    let room = N - (self.inner.len() - removed);
+
+    // buffering at most one more replacement than fits keeps an unbounded iterator from allocating past the limit
+    //
+    let mut replace_with = replace_with.into_iter();
+    let mut buffered = MiniVec::new();
+    buffered.extend(replace_with.by_ref().take(room));
+
+    if let Some(extra) = replace_with.next() {
+      buffered.push(extra);
+      return Err(CapacityError::new(buffered));
+    }
+
+    if buffered.len() > removed {
+      let reserved = self.try_reserve_for(buffered.len() - removed);
+      debug_assert!(reserved);
+    }
+
+    Ok(self.inner.splice(start_idx..end_idx, buffered))
+  }
+}
+
+impl<T, const N: usize> core::ops::Deref for BoundedMiniVec<T, N> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<T, const N: usize> core::ops::DerefMut for BoundedMiniVec<T, N> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
+}
+
+impl<T, const N: usize> AsRef<[T]> for BoundedMiniVec<T, N> {
+  fn as_ref(&self) -> &[T] {
+    &self.inner
+  }
+}
+
+impl<T, const N: usize> AsMut<[T]> for BoundedMiniVec<T, N> {
+  fn as_mut(&mut self) -> &mut [T] {
+    &mut self.inner
+  }
+}
+
+impl<T: Clone, const N: usize> Clone for BoundedMiniVec<T, N> {
+  fn clone(&self) -> Self {
+    BoundedMiniVec {
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for BoundedMiniVec<T, N> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.inner.fmt(f)
+  }
+}
+
+impl<T, const N: usize> Default for BoundedMiniVec<T, N> {
+  fn default() -> Self {
+    BoundedMiniVec::new()
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<BoundedMiniVec<U, M>>
+  for BoundedMiniVec<T, N>
+{
+  fn eq(&self, other: &BoundedMiniVec<U, M>) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]>
+  for BoundedMiniVec<T, N>
+{
+  fn eq(&self, other: &[U; M]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for BoundedMiniVec<T, N> {
+  fn eq(&self, other: &[U]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: Eq, const N: usize> Eq for BoundedMiniVec<T, N> {}
+
+impl<T: core::hash::Hash, const N: usize> core::hash::Hash for BoundedMiniVec<T, N> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.inner.hash(state);
+  }
+}
+
+impl<T, const N: usize> core::iter::Extend<T> for BoundedMiniVec<T, N> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    self
+      .try_extend(iter)
+      .expect("BoundedMiniVec capacity limit exceeded");
+  }
+}
+
+impl<T, const N: usize> From<BoundedMiniVec<T, N>> for MiniVec<T> {
+  fn from(vec: BoundedMiniVec<T, N>) -> Self {
+    vec.inner
+  }
+}
+
+impl<T, const N: usize> TryFrom<MiniVec<T>> for BoundedMiniVec<T, N> {
+  type Error = MiniVec<T>;
+
+  fn try_from(mut vec: MiniVec<T>) -> Result<Self, Self::Error> {
+    if vec.len() > N {
+      return Err(vec);
+    }
+
+    if vec.capacity() > N {
+      vec.shrink_to(N);
+    }
+
+    Ok(BoundedMiniVec { inner: vec })
+  }
+}
+
+impl<T, const N: usize> core::iter::IntoIterator for BoundedMiniVec<T, N> {
+  type Item = T;
+  type IntoIter = crate::IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.into_iter()
+  }
+}
+
+impl<'a, T, const N: usize> core::iter::IntoIterator for &'a BoundedMiniVec<T, N> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.iter()
+  }
+}
+
+impl<'a, T, const N: usize> core::iter::IntoIterator for &'a mut BoundedMiniVec<T, N> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.iter_mut()
+  }
+}
@@ -0,0 +1,925 @@
+//! `bytes` contains [`MiniBytesMut`](MiniBytesMut), a growable byte buffer built on a [`MiniVec`](crate::MiniVec),
+//! and [`MiniBytes`](MiniBytes), the immutable reference-counted buffer it [`freeze`](MiniBytesMut::freeze)s into.
+//!
+//! With the `bytes` feature enabled, `MiniBytes` implements `bytes::Buf`, `MiniBytesMut` implements `bytes::BufMut`
+//! and `MiniBytes` converts to and from `bytes::Bytes`.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::resolve_range;
+use crate::sync::{fence, AtomicUsize, Ordering};
+use crate::MiniVec;
+
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+use core::ops::RangeBounds;
+use core::ptr::NonNull;
+
+// A frozen vector along with the number of `MiniBytes` which view it. It's boxed so that every handle can reach the
+// count through a single pointer no matter which part of the vector it views.
+//
+struct Shared {
+  vec: MiniVec<u8>,
+  refs: AtomicUsize,
+}
+
+/// `MiniBytes` is an immutable, cheaply cloneable view into a shared buffer of bytes, created by
+/// [`MiniBytesMut::freeze`](MiniBytesMut::freeze).
+///
+/// Cloning, [`slice`](MiniBytes::slice)ing and splitting never copy any bytes, they only adjust the view and the
+/// reference count of the underlying allocation, which is freed when its last view is dropped. A `MiniBytes` is three
+/// words wide: a pointer to the viewed bytes, their length and a pointer to the shared reference count, which is
+/// null for views of `'static` data.
+///
+/// # Example
+///
+/// ```
+/// use minivec::MiniBytesMut;
+///
+/// let mut buf = MiniBytesMut::new();
+/// buf.extend_from_slice(b"GET /index.html");
+///
+/// let mut request = buf.freeze();
+/// let method = request.split_to(3);
+/// let path = request.slice(1..);
+///
+/// assert_eq!(method, b"GET");
+/// assert_eq!(path, b"/index.html");
+/// assert_eq!(request.as_ptr().wrapping_add(1), path.as_ptr());
+/// ```
+///
+pub struct MiniBytes {
+  ptr: NonNull<u8>,
+  len: usize,
+  shared: Option<NonNull<Shared>>,
+}
+
+/// `MiniBytesMut` is a growable buffer of bytes which derefs to a mutable byte slice and can be
+/// [`freeze`](MiniBytesMut::freeze)d into a [`MiniBytes`](MiniBytes) without copying.
+///
+/// A buffer starts out as a uniquely owned [`MiniVec`](crate::MiniVec). [`split_to`](MiniBytesMut::split_to) and
+/// [`split_off`](MiniBytesMut::split_off) take constant time: the first split moves the allocation into the same
+/// reference-counted storage that `freeze` uses and each half then views its own, disjoint part of it. A half has no
+/// spare capacity, so growing it moves its bytes into an allocation of its own, reusing the shared one if no other
+/// view of it remains. A `MiniBytesMut` is three words wide, the same as a `MiniBytes`.
+///
+/// # Example
+///
+/// ```
+/// use minivec::MiniBytesMut;
+///
+/// let mut buf = MiniBytesMut::from(&b"HEADbody"[..]);
+/// let ptr = buf.as_ptr();
+///
+/// let head = buf.split_to(4);
+/// assert_eq!(head, b"HEAD");
+/// assert_eq!(buf, b"body");
+/// assert_eq!(buf.as_ptr(), ptr.wrapping_add(4));
+///
+/// let frozen = head.freeze();
+/// assert_eq!(frozen.as_ptr(), ptr);
+/// ```
+///
+pub struct MiniBytesMut {
+  repr: Repr,
+}
+
+// A buffer either owns its vector outright or, once split, is the only handle allowed to write to the bytes its
+// `MiniBytes` views. The views of split halves never overlap.
+//
+enum Repr {
+  Unique(MiniVec<u8>),
+  Shared(MiniBytes),
+}
+
+unsafe impl Send for MiniBytes {}
+unsafe impl Sync for MiniBytes {}
+
+impl MiniBytes {
+  fn view(&self, start: usize, end: usize) -> MiniBytes {
+    let mut bytes = self.clone();
+    bytes.ptr = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(start)) };
+    bytes.len = end - start;
+    bytes
+  }
+
+  /// `as_slice` returns the viewed bytes.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+  }
+
+  /// `clear` empties the view, leaving the underlying buffer untouched.
+  ///
+  pub fn clear(&mut self) {
+    self.len = 0;
+  }
+
+  /// `copy_from_slice` constructs a new `MiniBytes` holding a copy of `data`.
+  ///
+  #[must_use]
+  pub fn copy_from_slice(data: &[u8]) -> MiniBytes {
+    MiniBytesMut::from(data).freeze()
+  }
+
+  /// `from_static` constructs a `MiniBytes` viewing `data`.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn from_static(data: &'static [u8]) -> MiniBytes {
+    MiniBytes {
+      ptr: NonNull::from(data).cast::<u8>(),
+      len: data.len(),
+      shared: None,
+    }
+  }
+
+  /// `is_empty` returns whether or not the view is empty.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// `len` returns the number of viewed bytes.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// `new` constructs an empty `MiniBytes`.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> MiniBytes {
+    MiniBytes::from_static(&[])
+  }
+
+  /// `slice` returns a new view of the bytes in `range`, sharing the same allocation.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `range` is out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let bytes = minivec::MiniBytes::from_static(b"hello world");
+  ///
+  /// assert_eq!(bytes.slice(6..), b"world");
+  /// assert_eq!(bytes.slice(..=4), b"hello");
+  /// ```
+  ///
+  #[must_use]
+  pub fn slice<R>(&self, range: R) -> MiniBytes
+  where
+    R: RangeBounds<usize>,
+  {
+    let (start, end) = resolve_range(&range, self.len);
+    self.view(start, end)
+  }
+
+  /// `split_off` splits the view in two at `at`, keeping `[0, at)` and returning `[at, len)`. No bytes are copied.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len()`.
+  ///
+  #[must_use]
+  pub fn split_off(&mut self, at: usize) -> MiniBytes {
+    assert!(
+      at <= self.len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      self.len
+    );
+
+    let tail = self.view(at, self.len);
+    self.len = at;
+    tail
+  }
+
+  /// `split_to` splits the view in two at `at`, keeping `[at, len)` and returning `[0, at)`. No bytes are copied.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len()`.
+  ///
+  #[must_use]
+  pub fn split_to(&mut self, at: usize) -> MiniBytes {
+    assert!(
+      at <= self.len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      self.len
+    );
+
+    let head = self.view(0, at);
+    self.ptr = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(at)) };
+    self.len -= at;
+    head
+  }
+
+  /// `truncate` shortens the view to its first `len` bytes, doing nothing if it is already shorter.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    self.len = core::cmp::min(self.len, len);
+  }
+
+  /// `try_into_mut` converts the view back into a [`MiniBytesMut`](MiniBytesMut) holding just the viewed bytes,
+  /// reusing the allocation. This only succeeds if no other `MiniBytes` shares the allocation, otherwise `self` is
+  /// handed back.
+  ///
+  /// # Errors
+  ///
+  /// Returns `self` if the allocation is shared or the view is of `'static` data.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let bytes = minivec::MiniBytes::copy_from_slice(b"abc");
+  /// let other = bytes.clone();
+  ///
+  /// let bytes = bytes.try_into_mut().unwrap_err();
+  /// drop(other);
+  ///
+  /// let mut buf = bytes.try_into_mut().unwrap();
+  /// buf.extend_from_slice(b"def");
+  /// assert_eq!(buf, b"abcdef");
+  /// ```
+  ///
+  pub fn try_into_mut(self) -> Result<MiniBytesMut, MiniBytes> {
+    self.try_into_vec().map(MiniBytesMut::from)
+  }
+
+  fn try_into_vec(self) -> Result<MiniVec<u8>, MiniBytes> {
+    let Some(shared) = self.shared else {
+      return Err(self);
+    };
+
+    if unsafe { shared.as_ref() }.refs.load(Ordering::Acquire) != 1 {
+      return Err(self);
+    }
+
+    let this = ManuallyDrop::new(self);
+    let Shared { mut vec, .. } = *unsafe { Box::from_raw(shared.as_ptr()) };
+
+    let start = this.ptr.as_ptr() as usize - vec.as_ptr() as usize;
+    vec.truncate(start + this.len);
+    vec.drain(..start);
+
+    Ok(vec)
+  }
+}
+
+impl Drop for MiniBytes {
+  fn drop(&mut self) {
+    let Some(shared) = self.shared else {
+      return;
+    };
+
+    if unsafe { shared.as_ref() }
+      .refs
+      .fetch_sub(1, Ordering::Release)
+      != 1
+    {
+      return;
+    }
+
+    fence(Ordering::Acquire);
+    drop(unsafe { Box::from_raw(shared.as_ptr()) });
+  }
+}
+
+impl Clone for MiniBytes {
+  fn clone(&self) -> Self {
+    if let Some(shared) = self.shared {
+      unsafe { shared.as_ref() }
+        .refs
+        .fetch_add(1, Ordering::Relaxed);
+    }
+
+    MiniBytes {
+      ptr: self.ptr,
+      len: self.len,
+      shared: self.shared,
+    }
+  }
+}
+
+impl core::ops::Deref for MiniBytes {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl AsRef<[u8]> for MiniBytes {
+  fn as_ref(&self) -> &[u8] {
+    self.as_slice()
+  }
+}
+
+impl core::borrow::Borrow<[u8]> for MiniBytes {
+  fn borrow(&self) -> &[u8] {
+    self.as_slice()
+  }
+}
+
+impl core::fmt::Debug for MiniBytes {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "b\"{}\"", self.as_slice().escape_ascii())
+  }
+}
+
+impl Default for MiniBytes {
+  fn default() -> Self {
+    MiniBytes::new()
+  }
+}
+
+impl PartialEq for MiniBytes {
+  fn eq(&self, other: &Self) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl Eq for MiniBytes {}
+
+impl PartialEq<[u8]> for MiniBytes {
+  fn eq(&self, other: &[u8]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for MiniBytes {
+  fn eq(&self, other: &[u8; N]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for MiniBytes {
+  fn eq(&self, other: &&[u8; N]) -> bool {
+    self.as_slice() == *other
+  }
+}
+
+impl PartialOrd for MiniBytes {
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for MiniBytes {
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    self.as_slice().cmp(other.as_slice())
+  }
+}
+
+impl core::hash::Hash for MiniBytes {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl From<&'static [u8]> for MiniBytes {
+  fn from(data: &'static [u8]) -> Self {
+    MiniBytes::from_static(data)
+  }
+}
+
+impl From<&'static str> for MiniBytes {
+  fn from(data: &'static str) -> Self {
+    MiniBytes::from_static(data.as_bytes())
+  }
+}
+
+impl From<MiniVec<u8>> for MiniBytes {
+  fn from(vec: MiniVec<u8>) -> Self {
+    MiniBytesMut::from(vec).freeze()
+  }
+}
+
+impl From<MiniBytesMut> for MiniBytes {
+  fn from(buf: MiniBytesMut) -> Self {
+    buf.freeze()
+  }
+}
+
+impl<'a> IntoIterator for &'a MiniBytes {
+  type Item = &'a u8;
+  type IntoIter = core::slice::Iter<'a, u8>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.as_slice().iter()
+  }
+}
+
+impl MiniBytesMut {
+  fn as_slice(&self) -> &[u8] {
+    match &self.repr {
+      Repr::Unique(vec) => vec.as_slice(),
+      Repr::Shared(view) => view.as_slice(),
+    }
+  }
+
+  fn as_mut_slice(&mut self) -> &mut [u8] {
+    match &mut self.repr {
+      Repr::Unique(vec) => vec.as_mut_slice(),
+      // no other handle views these bytes mutably, and none views them at all until this buffer is frozen
+      //
+      Repr::Shared(view) => unsafe { core::slice::from_raw_parts_mut(view.ptr.as_ptr(), view.len) },
+    }
+  }
+
+  // Returns the vector backing the buffer with room for `additional` more bytes, first moving the bytes of a split
+  // half into an allocation of its own.
+  //
+  fn make_unique(&mut self, additional: usize) -> &mut MiniVec<u8> {
+    if let Repr::Shared(view) = &mut self.repr {
+      let vec = match core::mem::take(view).try_into_vec() {
+        Ok(vec) => vec,
+        Err(view) => {
+          let mut vec = MiniVec::with_capacity(view.len + additional);
+          vec.extend_from_slice(&view);
+          vec
+        }
+      };
+      self.repr = Repr::Unique(vec);
+    }
+
+    let Repr::Unique(vec) = &mut self.repr else {
+      unreachable!("the buffer was just made unique");
+    };
+    vec.reserve(additional);
+    vec
+  }
+
+  // Returns the view of the buffer, first moving a uniquely owned vector into shared storage.
+  //
+  fn make_shared(&mut self) -> &mut MiniBytes {
+    if let Repr::Unique(vec) = &mut self.repr {
+      let buf = MiniBytesMut::from(core::mem::take(vec));
+      self.repr = Repr::Shared(buf.freeze());
+    }
+
+    let Repr::Shared(view) = &mut self.repr else {
+      unreachable!("the buffer was just shared");
+    };
+    view
+  }
+
+  /// `capacity` returns the number of bytes the buffer can hold without reallocating. A buffer which shares its
+  /// allocation after a split can't grow in place, so its capacity is its length.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    match &self.repr {
+      Repr::Unique(vec) => vec.capacity(),
+      Repr::Shared(view) => view.len,
+    }
+  }
+
+  /// `clear` removes every byte from the buffer, keeping its capacity. A buffer which shares its allocation after a
+  /// split gives up its part of it instead.
+  ///
+  pub fn clear(&mut self) {
+    match &mut self.repr {
+      Repr::Unique(vec) => vec.clear(),
+      Repr::Shared(_) => self.repr = Repr::Unique(MiniVec::new()),
+    }
+  }
+
+  /// `extend_from_slice` appends `data` to the end of the buffer.
+  ///
+  pub fn extend_from_slice(&mut self, data: &[u8]) {
+    self.make_unique(data.len()).extend_from_slice(data);
+  }
+
+  /// `freeze` converts the buffer into an immutable [`MiniBytes`](MiniBytes) without copying its contents.
+  ///
+  /// Note: allocates the shared reference count unless the buffer is empty, in which case its capacity is released
+  /// and the returned `MiniBytes` doesn't allocate, or the buffer already shares its allocation after a split.
+  ///
+  #[must_use]
+  pub fn freeze(self) -> MiniBytes {
+    let vec = match self.repr {
+      Repr::Unique(vec) => vec,
+      Repr::Shared(view) => return view,
+    };
+
+    if vec.is_empty() {
+      return MiniBytes::new();
+    }
+
+    let shared = Box::new(Shared {
+      vec,
+      refs: AtomicUsize::new(1),
+    });
+
+    MiniBytes {
+      ptr: unsafe { NonNull::new_unchecked(shared.vec.as_ptr().cast_mut()) },
+      len: shared.vec.len(),
+      shared: Some(NonNull::from(Box::leak(shared))),
+    }
+  }
+
+  /// `is_empty` returns whether or not the buffer is empty.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of bytes in the buffer.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    match &self.repr {
+      Repr::Unique(vec) => vec.len(),
+      Repr::Shared(view) => view.len,
+    }
+  }
+
+  /// `new` constructs an empty buffer.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> MiniBytesMut {
+    MiniBytesMut::from(MiniVec::new())
+  }
+
+  /// `reserve` ensures there is room for at least `additional` more bytes.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    if additional > 0 {
+      self.make_unique(additional);
+    }
+  }
+
+  /// `resize` grows or shrinks the buffer to `new_len` bytes, filling any new bytes with `value`.
+  ///
+  pub fn resize(&mut self, new_len: usize, value: u8) {
+    let len = self.len();
+    if new_len <= len {
+      self.truncate(new_len);
+    } else {
+      self.make_unique(new_len - len).resize(new_len, value);
+    }
+  }
+
+  /// `split_off` splits the buffer in two at `at`, keeping `[0, at)` and returning `[at, len)`. No bytes are copied,
+  /// both halves share the buffer's allocation.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len()`.
+  ///
+  #[must_use]
+  pub fn split_off(&mut self, at: usize) -> MiniBytesMut {
+    let len = self.len();
+    assert!(
+      at <= len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      len
+    );
+
+    if at == len {
+      return MiniBytesMut::new();
+    }
+
+    if at == 0 {
+      return core::mem::take(self);
+    }
+
+    MiniBytesMut {
+      repr: Repr::Shared(self.make_shared().split_off(at)),
+    }
+  }
+
+  /// `split_to` splits the buffer in two at `at`, keeping `[at, len)` and returning `[0, at)`. No bytes are copied,
+  /// both halves share the buffer's allocation.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len()`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut buf = minivec::MiniBytesMut::from(&b"\x03abcde"[..]);
+  ///
+  /// let len = usize::from(buf[0]);
+  /// let frame = buf.split_to(1 + len);
+  ///
+  /// assert_eq!(frame, b"\x03abc");
+  /// assert_eq!(buf, b"de");
+  /// ```
+  ///
+  #[must_use]
+  pub fn split_to(&mut self, at: usize) -> MiniBytesMut {
+    let len = self.len();
+    assert!(
+      at <= len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      len
+    );
+
+    if at == 0 {
+      return MiniBytesMut::new();
+    }
+
+    if at == len {
+      return core::mem::take(self);
+    }
+
+    MiniBytesMut {
+      repr: Repr::Shared(self.make_shared().split_to(at)),
+    }
+  }
+
+  /// `truncate` shortens the buffer to its first `len` bytes, doing nothing if it is already shorter.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    match &mut self.repr {
+      Repr::Unique(vec) => vec.truncate(len),
+      Repr::Shared(view) => view.truncate(len),
+    }
+  }
+
+  /// `with_capacity` constructs an empty buffer with room for at least `capacity` bytes.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> MiniBytesMut {
+    MiniBytesMut::from(MiniVec::with_capacity(capacity))
+  }
+}
+
+impl core::ops::Deref for MiniBytesMut {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl core::ops::DerefMut for MiniBytesMut {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.as_mut_slice()
+  }
+}
+
+impl AsRef<[u8]> for MiniBytesMut {
+  fn as_ref(&self) -> &[u8] {
+    self.as_slice()
+  }
+}
+
+impl AsMut<[u8]> for MiniBytesMut {
+  fn as_mut(&mut self) -> &mut [u8] {
+    self.as_mut_slice()
+  }
+}
+
+impl core::borrow::Borrow<[u8]> for MiniBytesMut {
+  fn borrow(&self) -> &[u8] {
+    self.as_slice()
+  }
+}
+
+impl core::borrow::BorrowMut<[u8]> for MiniBytesMut {
+  fn borrow_mut(&mut self) -> &mut [u8] {
+    self.as_mut_slice()
+  }
+}
+
+impl Clone for MiniBytesMut {
+  fn clone(&self) -> Self {
+    MiniBytesMut::from(self.as_slice())
+  }
+}
+
+impl core::fmt::Debug for MiniBytesMut {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "b\"{}\"", self.as_slice().escape_ascii())
+  }
+}
+
+impl core::fmt::Write for MiniBytesMut {
+  fn write_str(&mut self, s: &str) -> core::fmt::Result {
+    self.extend_from_slice(s.as_bytes());
+    Ok(())
+  }
+}
+
+impl Default for MiniBytesMut {
+  fn default() -> Self {
+    MiniBytesMut::new()
+  }
+}
+
+impl PartialEq for MiniBytesMut {
+  fn eq(&self, other: &Self) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl Eq for MiniBytesMut {}
+
+impl PartialEq<[u8]> for MiniBytesMut {
+  fn eq(&self, other: &[u8]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for MiniBytesMut {
+  fn eq(&self, other: &[u8; N]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for MiniBytesMut {
+  fn eq(&self, other: &&[u8; N]) -> bool {
+    self.as_slice() == *other
+  }
+}
+
+impl PartialOrd for MiniBytesMut {
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for MiniBytesMut {
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    self.as_slice().cmp(other.as_slice())
+  }
+}
+
+impl core::hash::Hash for MiniBytesMut {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl core::iter::Extend<u8> for MiniBytesMut {
+  fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+    let iter = iter.into_iter();
+    self.make_unique(iter.size_hint().0).extend(iter);
+  }
+}
+
+impl<'a> core::iter::Extend<&'a u8> for MiniBytesMut {
+  fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+    self.extend(iter.into_iter().copied());
+  }
+}
+
+impl core::iter::FromIterator<u8> for MiniBytesMut {
+  fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+    MiniBytesMut::from(iter.into_iter().collect::<MiniVec<u8>>())
+  }
+}
+
+impl From<&[u8]> for MiniBytesMut {
+  fn from(data: &[u8]) -> Self {
+    MiniBytesMut::from(MiniVec::from(data))
+  }
+}
+
+impl From<&str> for MiniBytesMut {
+  fn from(data: &str) -> Self {
+    MiniBytesMut::from(data.as_bytes())
+  }
+}
+
+impl From<MiniVec<u8>> for MiniBytesMut {
+  fn from(vec: MiniVec<u8>) -> Self {
+    MiniBytesMut {
+      repr: Repr::Unique(vec),
+    }
+  }
+}
+
+impl From<MiniBytesMut> for MiniVec<u8> {
+  fn from(mut buf: MiniBytesMut) -> Self {
+    core::mem::take(buf.make_unique(0))
+  }
+}
+
+impl<'a> IntoIterator for &'a MiniBytesMut {
+  type Item = &'a u8;
+  type IntoIter = core::slice::Iter<'a, u8>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.as_slice().iter()
+  }
+}
+
+impl<'a> IntoIterator for &'a mut MiniBytesMut {
+  type Item = &'a mut u8;
+  type IntoIter = core::slice::IterMut<'a, u8>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.as_mut_slice().iter_mut()
+  }
+}
+
+#[cfg(feature = "bytes")]
+impl ::bytes::Buf for MiniBytes {
+  fn remaining(&self) -> usize {
+    self.len
+  }
+
+  fn chunk(&self) -> &[u8] {
+    self.as_slice()
+  }
+
+  fn advance(&mut self, cnt: usize) {
+    assert!(
+      cnt <= self.len,
+      "cannot advance past `remaining` (is {}) by {}",
+      self.len,
+      cnt
+    );
+
+    self.ptr = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(cnt)) };
+    self.len -= cnt;
+  }
+
+  fn copy_to_bytes(&mut self, len: usize) -> ::bytes::Bytes {
+    assert!(
+      len <= self.len,
+      "cannot copy past `remaining` (is {}) by {}",
+      self.len,
+      len
+    );
+
+    ::bytes::Bytes::from(self.split_to(len))
+  }
+}
+
+#[cfg(feature = "bytes")]
+unsafe impl ::bytes::BufMut for MiniBytesMut {
+  fn remaining_mut(&self) -> usize {
+    isize::MAX.unsigned_abs() - self.len()
+  }
+
+  unsafe fn advance_mut(&mut self, cnt: usize) {
+    if cnt == 0 {
+      return;
+    }
+
+    let len = self.len();
+    assert!(
+      cnt <= self.capacity() - len,
+      "cannot advance past the spare capacity (is {}) by {}",
+      self.capacity() - len,
+      cnt
+    );
+
+    self.make_unique(0).set_len(len + cnt);
+  }
+
+  fn chunk_mut(&mut self) -> &mut ::bytes::buf::UninitSlice {
+    if self.capacity() == self.len() {
+      self.reserve(64);
+    }
+
+    ::bytes::buf::UninitSlice::uninit(self.make_unique(0).spare_capacity_mut())
+  }
+
+  fn put_slice(&mut self, src: &[u8]) {
+    self.extend_from_slice(src);
+  }
+}
+
+#[cfg(feature = "bytes")]
+impl From<MiniBytes> for ::bytes::Bytes {
+  fn from(bytes: MiniBytes) -> Self {
+    if bytes.shared.is_none() {
+      // views without a shared allocation only ever point at `'static` data
+      //
+      let data: &'static [u8] =
+        unsafe { core::slice::from_raw_parts(bytes.ptr.as_ptr(), bytes.len) };
+      return ::bytes::Bytes::from_static(data);
+    }
+
+    ::bytes::Bytes::from_owner(bytes)
+  }
+}
+
+#[cfg(feature = "bytes")]
+impl From<::bytes::Bytes> for MiniBytes {
+  fn from(bytes: ::bytes::Bytes) -> Self {
+    MiniBytes::copy_from_slice(&bytes)
+  }
+}
@@ -0,0 +1,236 @@
+//! `cast` reinterprets a [`MiniVec`](crate::MiniVec) of plain old data as a vector of another such type, reusing its
+//! allocation instead of copying its elements. It's available with the `bytemuck` feature.
+//!
+//! Unlike `Vec`, which only knows that its allocation is aligned for its own element type, a `MiniVec` records the
+//! alignment of its allocation in its header. A vector created with [`with_alignment`](crate::MiniVec::with_alignment)
+//! can therefore be cast to any type which that alignment satisfies, e.g. a `MiniVec<u8>` aligned to 16 can become a
+//! `MiniVec<u128>`.
+//!
+//! With the feature enabled, `MiniVec<T>` for `T: Pod` also gains [`as_bytes`](crate::MiniVec::as_bytes),
+//! [`as_bytes_mut`](crate::MiniVec::as_bytes_mut) and [`from_bytes`](crate::MiniVec::from_bytes).
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::max_align;
+use crate::{Header, MiniVec};
+
+use bytemuck::checked::{self, CheckedBitPattern, CheckedCastError};
+use bytemuck::{NoUninit, Pod, PodCastError};
+
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+
+/// `cast_vec` reinterprets the elements and spare capacity of `vec` as `B`s, reusing its allocation.
+///
+/// The cast succeeds when the bytes of both the elements and the capacity divide evenly into `B`s and the alignment
+/// recorded in the vector's header is at least `align_of::<B>()`. An empty vector which never allocated always casts.
+///
+/// # Errors
+///
+/// Returns the reason along with the unchanged `vec` when:
+/// * `B` is zero-sized ([`SizeMismatch`](PodCastError::SizeMismatch))
+/// * the length or capacity of `vec` would leave a partial `B` behind
+///   ([`OutputSliceWouldHaveSlop`](PodCastError::OutputSliceWouldHaveSlop))
+/// * the allocation isn't aligned for `B`
+///   ([`TargetAlignmentGreaterAndInputNotAligned`](PodCastError::TargetAlignmentGreaterAndInputNotAligned))
+///
+/// # Example
+///
+/// ```
+/// use minivec::{cast::cast_vec, mini_vec, MiniVec};
+///
+/// let words = mini_vec![0x0102_0304_u32; 2];
+/// let ptr = words.as_ptr();
+///
+/// let bytes: MiniVec<u8> = cast_vec(words).unwrap();
+/// assert_eq!(bytes.len(), 8);
+/// assert_eq!(bytes.as_ptr().cast::<u32>(), ptr);
+///
+/// // the allocation of a `MiniVec<u8>` is only aligned to its header by default
+/// let (_, bytes) = cast_vec::<u8, u128>(bytes).unwrap_err();
+///
+/// let mut aligned = MiniVec::<u8>::with_alignment(32, 16).unwrap();
+/// aligned.extend_from_slice(&bytes);
+/// aligned.extend_from_slice(&bytes);
+///
+/// let wide: MiniVec<u128> = cast_vec(aligned).unwrap();
+/// assert_eq!(wide.len(), 1);
+/// assert_eq!(wide.capacity(), 2);
+/// ```
+///
+pub fn cast_vec<A: Pod, B: Pod>(vec: MiniVec<A>) -> Result<MiniVec<B>, (PodCastError, MiniVec<A>)> {
+  match cast_capacity::<A, B>(&vec) {
+    Ok(capacity) => Ok(unsafe { reinterpret(vec, capacity) }),
+    Err(e) => Err((e, vec)),
+  }
+}
+
+/// `try_cast_vec` is the checked counterpart of [`cast_vec`](cast_vec) for target types which don't accept every bit
+/// pattern, such as `bool` or `char`. Every element is validated before the allocation is reused.
+///
+/// # Errors
+///
+/// Returns [`InvalidBitPattern`](CheckedCastError::InvalidBitPattern) along with the unchanged `vec` when any of the
+/// reinterpreted elements isn't a valid `B`, or the error [`cast_vec`](cast_vec) would have returned when the
+/// allocation can't hold `B`s.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{cast::try_cast_vec, mini_vec};
+///
+/// let flags = try_cast_vec::<u8, bool>(mini_vec![0, 1, 1]).unwrap();
+/// assert_eq!(flags, [false, true, true]);
+///
+/// let (_, bytes) = try_cast_vec::<u8, bool>(mini_vec![0, 2]).unwrap_err();
+/// assert_eq!(bytes, [0, 2]);
+/// ```
+///
+pub fn try_cast_vec<A: NoUninit, B: CheckedBitPattern>(
+  vec: MiniVec<A>,
+) -> Result<MiniVec<B>, (CheckedCastError, MiniVec<A>)> {
+  let capacity = match cast_capacity::<A, B>(&vec) {
+    Ok(capacity) => capacity,
+    Err(e) => return Err((CheckedCastError::PodCastError(e), vec)),
+  };
+
+  if let Err(e) = checked::try_cast_slice::<A, B>(&vec) {
+    return Err((e, vec));
+  }
+
+  Ok(unsafe { reinterpret(vec, capacity) })
+}
+
+// Checks that the allocation of `vec` can hold `B`s in place of its `A`s and returns its capacity in `B`s.
+//
+fn cast_capacity<A, B>(vec: &MiniVec<A>) -> Result<usize, PodCastError> {
+  let size_a = core::mem::size_of::<A>();
+  let size_b = core::mem::size_of::<B>();
+
+  if size_b == 0 {
+    return Err(PodCastError::SizeMismatch);
+  }
+
+  if vec.is_default() {
+    return Ok(0);
+  }
+
+  let Header {
+    len,
+    cap,
+    alignment,
+  } = *vec.header();
+
+  if alignment < core::mem::align_of::<B>() {
+    return Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned);
+  }
+
+  if !(len * size_a).is_multiple_of(size_b) || !(cap * size_a).is_multiple_of(size_b) {
+    return Err(PodCastError::OutputSliceWouldHaveSlop);
+  }
+
+  Ok(cap * size_a / size_b)
+}
+
+// Safety: `capacity` must have been returned by `cast_capacity::<A, B>(&vec)` and every element of `vec` must be a
+// valid sequence of `B`s.
+//
+unsafe fn reinterpret<A, B>(vec: MiniVec<A>, capacity: usize) -> MiniVec<B> {
+  // an empty vector only carries its alignment, which is kept unless `B` needs more
+  //
+  if vec.is_default() {
+    let alignment = core::cmp::max(vec.alignment(), max_align::<B>());
+    return match MiniVec::with_alignment(0, alignment) {
+      Ok(vec) => vec,
+      Err(_) => unreachable!(),
+    };
+  }
+
+  let vec = ManuallyDrop::new(vec);
+
+  let mut cast = MiniVec::<B> {
+    buf: vec.buf,
+    phantom: PhantomData,
+  };
+
+  let header = cast.header_mut();
+  header.len = header.len * core::mem::size_of::<A>() / core::mem::size_of::<B>();
+  header.cap = capacity;
+
+  cast
+}
+
+impl<T: Pod> MiniVec<T> {
+  /// `as_bytes` views the elements of the vector as a slice of bytes.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::mini_vec![1_u16, 2];
+  /// assert_eq!(vec.as_bytes().len(), 4);
+  /// assert_eq!(vec.as_bytes(), bytemuck::cast_slice::<u16, u8>(&vec));
+  /// ```
+  ///
+  #[must_use]
+  pub fn as_bytes(&self) -> &[u8] {
+    bytemuck::cast_slice(self.as_slice())
+  }
+
+  /// `as_bytes_mut` views the elements of the vector as a mutable slice of bytes.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::mini_vec![0_u32; 2];
+  /// vec.as_bytes_mut()[4..].fill(0xff);
+  /// assert_eq!(vec, [0, u32::MAX]);
+  /// ```
+  ///
+  pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+    bytemuck::cast_slice_mut(self.as_mut_slice())
+  }
+
+  /// `from_bytes` copies `bytes` into a new vector, which unlike the bytes themselves is always aligned for `T`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`OutputSliceWouldHaveSlop`](PodCastError::OutputSliceWouldHaveSlop) when the length of `bytes` isn't a
+  /// multiple of the size of `T`, or [`SizeMismatch`](PodCastError::SizeMismatch) when `T` is zero-sized.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::MiniVec;
+  ///
+  /// let bytes = [0_u8, 1, 0, 0, 0, 2, 0, 0, 0];
+  ///
+  /// let vec = MiniVec::<u32>::from_bytes(&bytes[1..]).unwrap();
+  /// assert_eq!(vec, [u32::from_ne_bytes([1, 0, 0, 0]), u32::from_ne_bytes([2, 0, 0, 0])]);
+  ///
+  /// assert!(MiniVec::<u32>::from_bytes(&bytes).is_err());
+  /// ```
+  ///
+  pub fn from_bytes(bytes: &[u8]) -> Result<MiniVec<T>, PodCastError> {
+    let size = core::mem::size_of::<T>();
+
+    if size == 0 {
+      return Err(PodCastError::SizeMismatch);
+    }
+
+    if !bytes.len().is_multiple_of(size) {
+      return Err(PodCastError::OutputSliceWouldHaveSlop);
+    }
+
+    let len = bytes.len() / size;
+    let mut vec = MiniVec::<T>::with_capacity(len);
+
+    if len > 0 {
+      unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), vec.as_mut_ptr().cast::<u8>(), bytes.len());
+        vec.set_len(len);
+      }
+    }
+
+    Ok(vec)
+  }
+}
@@ -0,0 +1,403 @@
+//! `concurrent` contains [`ConcurrentMiniVec`](ConcurrentMiniVec), an append-only vector which can be pushed to
+//! from many threads at once.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::locate_segment;
+use crate::sync::{AtomicBool, AtomicPtr, AtomicUsize, Ordering, UnsafeCell};
+use crate::MiniVec;
+
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+// Segment `k` holds `FIRST_SEGMENT_LEN << k` elements so the segments together can address every `usize` index.
+// The model checker uses a few tiny segments so that a handful of pushes is enough to race on allocating a new one
+// without every table having to register over a hundred model-checked atomics.
+//
+const FIRST_SEGMENT_SHIFT: usize = if cfg!(loom) { 1 } else { 3 };
+const FIRST_SEGMENT_LEN: usize = 1 << FIRST_SEGMENT_SHIFT;
+const NUM_SEGMENTS: usize = if cfg!(loom) {
+  4
+} else {
+  usize::BITS as usize - FIRST_SEGMENT_SHIFT
+};
+
+// Outside of the model checker a cell has the same layout as a `T`, so a segment of cells can be handed over as a
+// `MiniVec<T>`.
+//
+type Cell<T> = UnsafeCell<MaybeUninit<T>>;
+
+// The single allocation a `ConcurrentMiniVec` points to, made by its first push. The length acts as the header and
+// is followed by the addresses of every segment's elements and of the flags marking which of them are written.
+//
+struct Table<T> {
+  len: AtomicUsize,
+  values: [AtomicPtr<Cell<T>>; NUM_SEGMENTS],
+  ready: [AtomicPtr<AtomicBool>; NUM_SEGMENTS],
+}
+
+impl<T> Table<T> {
+  fn new() -> Self {
+    Table {
+      len: AtomicUsize::new(0),
+      values: core::array::from_fn(|_| AtomicPtr::new(core::ptr::null_mut())),
+      ready: core::array::from_fn(|_| AtomicPtr::new(core::ptr::null_mut())),
+    }
+  }
+}
+
+impl<T> Drop for Table<T> {
+  fn drop(&mut self) {
+    for segment in 0..NUM_SEGMENTS {
+      let values = self.values[segment].load(Ordering::Acquire);
+      let ready = self.ready[segment].load(Ordering::Acquire);
+      let len = segment_len(segment);
+
+      if !values.is_null() && !ready.is_null() {
+        for offset in 0..len {
+          if unsafe { &*ready.add(offset) }.load(Ordering::Acquire) {
+            unsafe { &*values.add(offset) }
+              .with_mut(|p| unsafe { core::ptr::drop_in_place((*p).as_mut_ptr()) });
+          }
+        }
+      }
+
+      free_segment(values, len);
+      free_segment(ready, len);
+    }
+  }
+}
+
+fn segment_len(segment: usize) -> usize {
+  FIRST_SEGMENT_LEN << segment
+}
+
+// Returns the segment stored in `slot`, first allocating `len` elements made by `make` and installing them if no
+// other thread has yet.
+//
+fn segment_or_allocate<U>(slot: &AtomicPtr<U>, len: usize, make: impl Fn() -> U) -> *mut U {
+  let p = slot.load(Ordering::Acquire);
+  if !p.is_null() {
+    return p;
+  }
+
+  let mut segment = MiniVec::with_capacity(len);
+  for _ in 0..len {
+    segment.push(make());
+  }
+
+  let (new, _, _) = segment.into_raw_parts();
+
+  match slot.compare_exchange(
+    core::ptr::null_mut(),
+    new,
+    Ordering::AcqRel,
+    Ordering::Acquire,
+  ) {
+    Ok(_) => new,
+    Err(current) => {
+      // another thread installed the segment first
+      //
+      free_segment(new, len);
+      current
+    }
+  }
+}
+
+fn free_segment<U>(p: *mut U, len: usize) {
+  if !p.is_null() {
+    drop(unsafe { MiniVec::from_raw_parts(p, len, len) });
+  }
+}
+
+/// `ConcurrentMiniVec` is an append-only vector that supports [`push`](ConcurrentMiniVec::push) through a shared
+/// reference, making it suitable for collecting results from many threads without a `Mutex`.
+///
+/// Like `MiniVec`, it is only the size of a single pointer. The first push allocates a table headed by the length of
+/// the vector, which is followed by the addresses of a series of segments, each a `MiniVec` twice as large as the
+/// last. Each push claims a unique index with an atomic increment of the length and segments are allocated on demand.
+/// They never move, so references handed out by [`get`](ConcurrentMiniVec::get) and
+/// [`iter`](ConcurrentMiniVec::iter) stay valid while other threads keep pushing. Readers only ever observe fully
+/// written elements.
+///
+/// Once every writer is done, [`into_inner`](ConcurrentMiniVec::into_inner) moves the elements into a plain `MiniVec`
+/// in index order.
+///
+/// # Example
+///
+/// ```
+/// let results = minivec::ConcurrentMiniVec::new();
+///
+/// std::thread::scope(|s| {
+///     for t in 0..4 {
+///         let results = &results;
+///         s.spawn(move || {
+///             for i in 0..100 {
+///                 results.push(t * 100 + i);
+///             }
+///         });
+///     }
+/// });
+///
+/// let mut results = results.into_inner();
+/// results.sort_unstable();
+///
+/// assert!(results.iter().copied().eq(0..400));
+/// ```
+///
+pub struct ConcurrentMiniVec<T> {
+  table: AtomicPtr<Table<T>>,
+  marker: PhantomData<T>,
+}
+
+impl<T> ConcurrentMiniVec<T> {
+  fn table(&self) -> Option<&Table<T>> {
+    unsafe { self.table.load(Ordering::Acquire).as_ref() }
+  }
+
+  fn table_or_allocate(&self) -> &Table<T> {
+    if let Some(table) = self.table() {
+      return table;
+    }
+
+    let new = Box::into_raw(Box::new(Table::new()));
+
+    match self.table.compare_exchange(
+      core::ptr::null_mut(),
+      new,
+      Ordering::AcqRel,
+      Ordering::Acquire,
+    ) {
+      Ok(_) => unsafe { &*new },
+      Err(current) => {
+        drop(unsafe { Box::from_raw(new) });
+        unsafe { &*current }
+      }
+    }
+  }
+
+  /// `get` returns a reference to the element at `idx`, or `None` if that element hasn't been pushed or is still
+  /// being written by another thread.
+  ///
+  #[must_use]
+  pub fn get(&self, idx: usize) -> Option<&T> {
+    let table = self.table()?;
+
+    // only indices handed out by `push` are known to map onto a segment
+    //
+    if idx >= table.len.load(Ordering::Relaxed) {
+      return None;
+    }
+
+    let (segment, offset) = locate_segment(idx, FIRST_SEGMENT_SHIFT);
+
+    let ready = table.ready[segment].load(Ordering::Acquire);
+    if ready.is_null() || !unsafe { &*ready.add(offset) }.load(Ordering::Acquire) {
+      return None;
+    }
+
+    // the element was written, and so its segment installed, before its flag was set
+    //
+    let values = table.values[segment].load(Ordering::Acquire);
+    Some(unsafe { &*values.add(offset) }.with(|p| unsafe { &*(*p).as_ptr() }))
+  }
+
+  /// `into_inner` consumes the vector and returns its elements in index order as a `MiniVec`.
+  ///
+  /// Elements are moved rather than cloned. When they all fit in the first segment, its allocation becomes the
+  /// returned vector as is; otherwise each fully written segment is moved over with a single copy of its bytes.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::ConcurrentMiniVec::new();
+  /// vec.push("a");
+  /// vec.push("b");
+  ///
+  /// assert_eq!(vec.into_inner(), ["a", "b"]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn into_inner(self) -> MiniVec<T> {
+    let p = self.table.swap(core::ptr::null_mut(), Ordering::Acquire);
+    if p.is_null() {
+      return MiniVec::new();
+    }
+
+    let table = unsafe { Box::from_raw(p) };
+    let len = table.len.load(Ordering::Acquire);
+
+    let same_layout = core::mem::size_of::<Cell<T>>() == core::mem::size_of::<T>()
+      && core::mem::align_of::<Cell<T>>() == core::mem::align_of::<T>();
+
+    let is_ready = |segment: usize, offset: usize| {
+      let ready = table.ready[segment].load(Ordering::Acquire);
+      !ready.is_null() && unsafe { &*ready.add(offset) }.load(Ordering::Acquire)
+    };
+
+    // every segment whose elements are moved out is detached from the table first so that dropping the table only
+    // frees the flags
+    //
+    if same_layout
+      && len > 0
+      && len <= FIRST_SEGMENT_LEN
+      && (0..len).all(|offset| is_ready(0, offset))
+    {
+      let values = table.values[0].swap(core::ptr::null_mut(), Ordering::Acquire);
+
+      // the segment's header counts every cell in it, only the first `len` of which hold elements
+      //
+      let mut vec = unsafe {
+        MiniVec::from_raw_parts(values.cast::<T>(), FIRST_SEGMENT_LEN, FIRST_SEGMENT_LEN)
+      };
+      unsafe { vec.set_len(len) };
+      return vec;
+    }
+
+    let mut vec = MiniVec::<T>::with_capacity(len);
+
+    for segment in 0..NUM_SEGMENTS {
+      let start = segment_len(segment) - FIRST_SEGMENT_LEN;
+      if start >= len {
+        break;
+      }
+
+      let values = table.values[segment].swap(core::ptr::null_mut(), Ordering::Acquire);
+      if values.is_null() {
+        continue;
+      }
+
+      let count = core::cmp::min(segment_len(segment), len - start);
+
+      if same_layout && (0..count).all(|offset| is_ready(segment, offset)) {
+        unsafe {
+          let dst = vec.as_mut_ptr().add(vec.len());
+          core::ptr::copy_nonoverlapping(values.cast::<T>(), dst, count);
+          vec.set_len(vec.len() + count);
+        }
+      } else {
+        // a push which panicked part way through leaves a hole behind, and under the model checker cells and
+        // elements differ in layout
+        //
+        for offset in 0..count {
+          if is_ready(segment, offset) {
+            vec.push(unsafe { &*values.add(offset) }.with(|p| unsafe { (*p).as_ptr().read() }));
+          }
+        }
+      }
+
+      free_segment(values, segment_len(segment));
+    }
+
+    vec
+  }
+
+  /// `is_empty` returns whether or not any element has been pushed yet.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `iter` returns an iterator over the completed prefix of the vector, i.e. every element up to the first one
+  /// which is still being written.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::ConcurrentMiniVec::new();
+  /// vec.push(1);
+  /// vec.push(2);
+  ///
+  /// assert_eq!(vec.iter().sum::<i32>(), 3);
+  /// ```
+  ///
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    (0..self.len()).map_while(move |idx| self.get(idx))
+  }
+
+  /// `len` returns the number of pushes which have started. Some of those elements may still be in the middle of
+  /// being written by other threads.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self
+      .table()
+      .map_or(0, |table| table.len.load(Ordering::Acquire))
+  }
+
+  /// `new` constructs an empty vector.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    ConcurrentMiniVec {
+      table: AtomicPtr::new(core::ptr::null_mut()),
+      marker: PhantomData,
+    }
+  }
+
+  /// `push` appends `value` to the vector and returns the index it was stored at. It may be called from any number
+  /// of threads at once.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the number of elements overflows a `usize`.
+  ///
+  pub fn push(&self, value: T) -> usize {
+    let table = self.table_or_allocate();
+
+    let idx = table.len.fetch_add(1, Ordering::Relaxed);
+    let (segment, offset) = locate_segment(idx, FIRST_SEGMENT_SHIFT);
+    let len = segment_len(segment);
+
+    let values = segment_or_allocate(&table.values[segment], len, || {
+      UnsafeCell::new(MaybeUninit::uninit())
+    });
+    let ready = segment_or_allocate(&table.ready[segment], len, || AtomicBool::new(false));
+
+    unsafe { &*values.add(offset) }.with_mut(|p| unsafe { (*p).as_mut_ptr().write(value) });
+    unsafe { &*ready.add(offset) }.store(true, Ordering::Release);
+
+    idx
+  }
+}
+
+impl<T> Drop for ConcurrentMiniVec<T> {
+  fn drop(&mut self) {
+    let p = self.table.load(Ordering::Acquire);
+    if !p.is_null() {
+      drop(unsafe { Box::from_raw(p) });
+    }
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ConcurrentMiniVec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T> Default for ConcurrentMiniVec<T> {
+  fn default() -> Self {
+    ConcurrentMiniVec::new()
+  }
+}
+
+impl<T> core::iter::FromIterator<T> for ConcurrentMiniVec<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let vec = ConcurrentMiniVec::new();
+    for value in iter {
+      vec.push(value);
+    }
+    vec
+  }
+}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for ConcurrentMiniVec<T> {}
+unsafe impl<T: core::marker::Send + core::marker::Sync> core::marker::Sync
+  for ConcurrentMiniVec<T>
+{
+}
@@ -0,0 +1,277 @@
+//! `cow` contains [`MiniCow`](MiniCow), a single-pointer clone-on-write slice which either borrows its elements or
+//! owns them in a [`MiniVec`](crate::MiniVec).
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::MiniVec;
+
+use alloc::boxed::Box;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+// The pointer of an owned `MiniVec` is always aligned to at least its header so its low bit is free to mark a
+// borrowed cow, whose pointer instead leads to a boxed handle holding the borrowed slice.
+//
+const BORROWED: usize = 1;
+
+/// `MiniCow` is a clone-on-write slice which is only the size of a single pointer, compared to the four words of a
+/// `Cow<'a, [T]>`.
+///
+/// An owned `MiniCow` is a [`MiniVec`](crate::MiniVec). A borrowed one keeps its slice in a small boxed handle, so
+/// borrowing a non-empty slice allocates the two words of the handle but never copies any elements. The low bit of
+/// the pointer tells the two states apart.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{mini_vec, MiniCow};
+///
+/// let data = [1, 2, 3];
+///
+/// let mut cow = MiniCow::borrowed(&data[..]);
+/// assert!(cow.is_borrowed());
+/// assert_eq!(cow.iter().sum::<i32>(), 6);
+///
+/// cow.to_mut().push(4);
+/// assert!(cow.is_owned());
+/// assert_eq!(cow.into_owned(), mini_vec![1, 2, 3, 4]);
+/// assert_eq!(data, [1, 2, 3]);
+/// ```
+///
+pub struct MiniCow<'a, T> {
+  vec: ManuallyDrop<MiniVec<T>>,
+  marker: core::marker::PhantomData<&'a [T]>,
+}
+
+impl<'a, T> MiniCow<'a, T> {
+  fn handle(&self) -> Option<*mut &'a [T]> {
+    let ptr = self.vec.buf.as_ptr();
+    if ptr.addr() & BORROWED == 0 {
+      return None;
+    }
+
+    #[allow(clippy::cast_ptr_alignment)]
+    Some(ptr.map_addr(|addr| addr & !BORROWED).cast::<&'a [T]>())
+  }
+
+  /// `as_slice` returns the elements of the cow, whether borrowed or owned.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    match self.handle() {
+      Some(handle) => unsafe { *handle },
+      None => self.vec.as_slice(),
+    }
+  }
+
+  /// `borrowed` constructs a cow borrowing `slice`.
+  ///
+  /// Note: allocates a two-word handle unless `slice` is empty, in which case the cow is an empty owned
+  /// [`MiniVec`](crate::MiniVec) which doesn't allocate.
+  ///
+  #[must_use]
+  pub fn borrowed(slice: &'a [T]) -> Self {
+    if slice.is_empty() {
+      return MiniCow::owned(MiniVec::new());
+    }
+
+    let handle = Box::into_raw(Box::new(slice)).map_addr(|addr| addr | BORROWED);
+    MiniCow {
+      vec: ManuallyDrop::new(MiniVec {
+        buf: unsafe { NonNull::new_unchecked(handle.cast::<u8>()) },
+        phantom: core::marker::PhantomData,
+      }),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `is_borrowed` returns whether or not the cow borrows its elements.
+  ///
+  #[must_use]
+  pub fn is_borrowed(&self) -> bool {
+    self.handle().is_some()
+  }
+
+  /// `is_owned` returns whether or not the cow owns its elements.
+  ///
+  #[must_use]
+  pub fn is_owned(&self) -> bool {
+    self.handle().is_none()
+  }
+
+  /// `owned` constructs a cow owning the elements of `vec`.
+  ///
+  #[must_use]
+  pub fn owned(vec: MiniVec<T>) -> Self {
+    MiniCow {
+      vec: ManuallyDrop::new(vec),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<T: Clone> MiniCow<'_, T> {
+  /// `into_owned` returns the owned vector, cloning the elements first if they are borrowed.
+  ///
+  #[must_use]
+  pub fn into_owned(mut self) -> MiniVec<T> {
+    self.to_mut();
+
+    let mut this = ManuallyDrop::new(self);
+    unsafe { ManuallyDrop::take(&mut this.vec) }
+  }
+
+  /// `to_mut` returns the owned vector mutably, cloning the elements into a new vector first if they are borrowed.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut cow = minivec::MiniCow::borrowed(&[1, 2][..]);
+  /// cow.to_mut()[0] = 10;
+  ///
+  /// assert_eq!(cow, [10, 2]);
+  /// assert!(cow.is_owned());
+  /// ```
+  ///
+  pub fn to_mut(&mut self) -> &mut MiniVec<T> {
+    // the handle is only freed once the clone is stored, so a panicking `T::clone` leaves the cow borrowed
+    //
+    if let Some(handle) = self.handle() {
+      let owned = MiniVec::from(unsafe { *handle });
+      self.vec = ManuallyDrop::new(owned);
+      drop(unsafe { Box::from_raw(handle) });
+    }
+
+    &mut self.vec
+  }
+}
+
+impl<T> Drop for MiniCow<'_, T> {
+  fn drop(&mut self) {
+    match self.handle() {
+      Some(handle) => drop(unsafe { Box::from_raw(handle) }),
+      None => unsafe { ManuallyDrop::drop(&mut self.vec) },
+    }
+  }
+}
+
+impl<T> core::ops::Deref for MiniCow<'_, T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl<T> AsRef<[T]> for MiniCow<'_, T> {
+  fn as_ref(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<T> core::borrow::Borrow<[T]> for MiniCow<'_, T> {
+  fn borrow(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<T: Clone> Clone for MiniCow<'_, T> {
+  fn clone(&self) -> Self {
+    match self.handle() {
+      Some(handle) => MiniCow::borrowed(unsafe { *handle }),
+      None => MiniCow::owned((*self.vec).clone()),
+    }
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniCow<'_, T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.as_slice().fmt(f)
+  }
+}
+
+impl<T> Default for MiniCow<'_, T> {
+  fn default() -> Self {
+    MiniCow::owned(MiniVec::new())
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<MiniCow<'_, U>> for MiniCow<'_, T> {
+  fn eq(&self, other: &MiniCow<'_, U>) -> bool {
+    self.as_slice() == other.as_slice()
+  }
+}
+
+impl<T: Eq> Eq for MiniCow<'_, T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for MiniCow<'_, T> {
+  fn eq(&self, other: &[U]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for MiniCow<'_, T> {
+  fn eq(&self, other: &[U; N]) -> bool {
+    self.as_slice() == other
+  }
+}
+
+impl<T: PartialOrd> PartialOrd for MiniCow<'_, T> {
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    self.as_slice().partial_cmp(other.as_slice())
+  }
+}
+
+impl<T: Ord> Ord for MiniCow<'_, T> {
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    self.as_slice().cmp(other.as_slice())
+  }
+}
+
+impl<T: core::hash::Hash> core::hash::Hash for MiniCow<'_, T> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl<'a, T> From<&'a [T]> for MiniCow<'a, T> {
+  fn from(slice: &'a [T]) -> Self {
+    MiniCow::borrowed(slice)
+  }
+}
+
+impl<'a, T> From<&'a MiniVec<T>> for MiniCow<'a, T> {
+  fn from(vec: &'a MiniVec<T>) -> Self {
+    MiniCow::borrowed(vec.as_slice())
+  }
+}
+
+impl<T> From<MiniVec<T>> for MiniCow<'_, T> {
+  fn from(vec: MiniVec<T>) -> Self {
+    MiniCow::owned(vec)
+  }
+}
+
+impl<'a, T: Clone> From<alloc::borrow::Cow<'a, [T]>> for MiniCow<'a, T> {
+  fn from(cow: alloc::borrow::Cow<'a, [T]>) -> Self {
+    match cow {
+      alloc::borrow::Cow::Borrowed(slice) => MiniCow::borrowed(slice),
+      alloc::borrow::Cow::Owned(vec) => MiniCow::owned(MiniVec::from(vec.as_slice())),
+    }
+  }
+}
+
+impl<T: Clone> From<MiniCow<'_, T>> for MiniVec<T> {
+  fn from(cow: MiniCow<'_, T>) -> Self {
+    cow.into_owned()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a MiniCow<'_, T> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.as_slice().iter()
+  }
+}
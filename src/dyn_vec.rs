@@ -0,0 +1,513 @@
+//! `dyn_vec` contains [`MiniDynVec`](MiniDynVec), a vector of unsized values such as trait objects stored back to
+//! back in a single allocation.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::{max_align, next_aligned};
+use crate::MiniVecWithHeader;
+
+// `last` is the offset of the last element's record and is only meaningful while `len > 0`.
+//
+#[derive(Clone, Copy, Default)]
+struct DynHeader {
+  len: usize,
+  last: usize,
+}
+
+// Every element is preceded by a record which knows how to turn a pointer to the element's bytes back into a
+// pointer to `D`, i.e. which holds on to the element's vtable. Offsets are relative to the start of the buffer, whose
+// alignment is raised to that of the most aligned element pushed so far.
+//
+struct Record<D: ?Sized> {
+  unsize: fn(*mut u8) -> *mut D,
+  prev: usize,
+  value: usize,
+  end: usize,
+}
+
+impl<D: ?Sized> Clone for Record<D> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<D: ?Sized> Copy for Record<D> {}
+
+const fn record_align<D: ?Sized>() -> usize {
+  core::mem::align_of::<Record<D>>()
+}
+
+const fn record_size<D: ?Sized>() -> usize {
+  core::mem::size_of::<Record<D>>()
+}
+
+/// `MiniDynVec` is a vector of unsized values, typically trait objects like `MiniDynVec<dyn Handler>`. Instead of
+/// boxing every element, each one is stored inline in a single allocation along with a small record holding its
+/// vtable and offset, so a whole list of heterogeneous values costs one allocation and one pointer.
+///
+/// Values are pushed through the [`mini_dyn_push!`](crate::mini_dyn_push) macro, which performs the unsizing
+/// coercion from the concrete type to `D` that stable Rust can't express as a generic bound.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{mini_dyn_push, MiniDynVec};
+///
+/// trait Shape {
+///     fn area(&self) -> f64;
+/// }
+///
+/// struct Square(f64);
+/// struct Circle(f64);
+///
+/// impl Shape for Square {
+///     fn area(&self) -> f64 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// impl Shape for Circle {
+///     fn area(&self) -> f64 {
+///         3.0 * self.0 * self.0
+///     }
+/// }
+///
+/// let mut shapes = MiniDynVec::<dyn Shape>::new();
+/// mini_dyn_push!(shapes, Square(2.0));
+/// mini_dyn_push!(shapes, Circle(1.0));
+///
+/// let total: f64 = shapes.iter().map(|shape| shape.area()).sum();
+/// assert_eq!(total, 7.0);
+/// ```
+///
+pub struct MiniDynVec<D: ?Sized> {
+  buf: MiniVecWithHeader<DynHeader, u8>,
+  marker: core::marker::PhantomData<D>,
+}
+
+/// `Iter` is an iterator over the elements of a [`MiniDynVec`](MiniDynVec), created by
+/// [`iter`](MiniDynVec::iter).
+///
+pub struct Iter<'a, D: ?Sized> {
+  vec: &'a MiniDynVec<D>,
+  offset: usize,
+  remaining: usize,
+}
+
+/// `IterMut` is an iterator over mutable references to the elements of a [`MiniDynVec`](MiniDynVec), created by
+/// [`iter_mut`](MiniDynVec::iter_mut).
+///
+pub struct IterMut<'a, D: ?Sized> {
+  base: *mut u8,
+  offset: usize,
+  remaining: usize,
+  marker: core::marker::PhantomData<&'a mut D>,
+}
+
+impl<D: ?Sized> MiniDynVec<D> {
+  fn header(&self) -> DynHeader {
+    self.buf.header().copied().unwrap_or_default()
+  }
+
+  fn base(&self) -> *mut u8 {
+    self.buf.as_ptr().cast_mut()
+  }
+
+  unsafe fn record(base: *mut u8, offset: usize) -> Record<D> {
+    #[allow(clippy::cast_ptr_alignment)]
+    core::ptr::read(base.add(offset).cast::<Record<D>>())
+  }
+
+  unsafe fn element(base: *mut u8, record: Record<D>) -> *mut D {
+    (record.unsize)(base.add(record.value))
+  }
+
+  // Moves the bytes into a new buffer if `alignment` is larger than the current one. Offsets stay valid as every one
+  // of them was aligned relative to a buffer at least as aligned as what they needed.
+  //
+  fn ensure_alignment(&mut self, alignment: usize) {
+    if alignment <= self.buf.alignment() {
+      return;
+    }
+
+    let len = self.buf.len();
+    let Ok(mut buf) =
+      MiniVecWithHeader::with_header_and_alignment(self.header(), self.buf.capacity(), alignment)
+    else {
+      unreachable!("alignments of types are always valid for a byte buffer");
+    };
+
+    if len > 0 {
+      unsafe {
+        core::ptr::copy_nonoverlapping(self.buf.as_ptr(), buf.as_mut_ptr(), len);
+        buf.set_len(len);
+      }
+    }
+
+    self.buf = buf;
+  }
+
+  /// `byte_capacity` returns the number of bytes of elements and their records the vector can hold without
+  /// reallocating.
+  ///
+  #[must_use]
+  pub fn byte_capacity(&self) -> usize {
+    self.buf.capacity()
+  }
+
+  /// `clear` drops every element, keeping the allocation.
+  ///
+  pub fn clear(&mut self) {
+    let len = self.header().len;
+    if len == 0 {
+      return;
+    }
+
+    // elements are leaked rather than double-dropped should a destructor panic
+    //
+    *self.buf.header_mut() = DynHeader::default();
+    unsafe { self.buf.set_len(0) };
+
+    let base = self.base();
+    let mut offset = 0;
+    for _ in 0..len {
+      unsafe {
+        let record = Self::record(base, offset);
+        offset = next_aligned(record.end, record_align::<D>());
+        core::ptr::drop_in_place(Self::element(base, record));
+      }
+    }
+  }
+
+  /// `is_empty` returns whether or not the vector contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `iter` returns an iterator over the elements of the vector.
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_, D> {
+    Iter {
+      vec: self,
+      offset: 0,
+      remaining: self.len(),
+    }
+  }
+
+  /// `iter_mut` returns an iterator over mutable references to the elements of the vector.
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, D> {
+    IterMut {
+      base: self.base(),
+      offset: 0,
+      remaining: self.len(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `last` returns the last element of the vector, or `None` if it is empty.
+  ///
+  #[must_use]
+  pub fn last(&self) -> Option<&D> {
+    let header = self.header();
+    if header.len == 0 {
+      return None;
+    }
+
+    unsafe {
+      let base = self.base();
+      Some(&*Self::element(base, Self::record(base, header.last)))
+    }
+  }
+
+  /// `last_mut` returns the last element of the vector mutably, or `None` if it is empty.
+  ///
+  pub fn last_mut(&mut self) -> Option<&mut D> {
+    let header = self.header();
+    if header.len == 0 {
+      return None;
+    }
+
+    unsafe {
+      let base = self.base();
+      Some(&mut *Self::element(base, Self::record(base, header.last)))
+    }
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.header().len
+  }
+
+  /// `new` constructs an empty vector.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniDynVec {
+      buf: MiniVecWithHeader::new(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `pop` drops the last element of the vector and returns whether there was one to drop. Unsized values can't be
+  /// returned by value.
+  ///
+  pub fn pop(&mut self) -> bool {
+    let header = self.header();
+    if header.len == 0 {
+      return false;
+    }
+
+    unsafe {
+      let base = self.base();
+      let record = Self::record(base, header.last);
+
+      *self.buf.header_mut() = DynHeader {
+        len: header.len - 1,
+        last: record.prev,
+      };
+      self.buf.set_len(header.last);
+
+      core::ptr::drop_in_place(Self::element(base, record));
+    }
+
+    true
+  }
+
+  /// `push_unsized` appends `value` to the vector, using `unsize` to turn a pointer to it into a pointer to `D`.
+  ///
+  /// Prefer the [`mini_dyn_push!`](crate::mini_dyn_push) macro, which supplies `unsize` safely.
+  ///
+  /// # Safety
+  ///
+  /// `unsize` must return its argument unchanged save for attaching the pointer metadata of `D`, i.e. it must be an
+  /// unsizing coercion such as `|p| p`.
+  ///
+  pub unsafe fn push_unsized<U>(&mut self, value: U, unsize: fn(*mut U) -> *mut D) {
+    self.ensure_alignment(core::cmp::max(max_align::<U>(), record_align::<D>()));
+
+    let header = self.header();
+    let start = next_aligned(self.buf.len(), record_align::<D>());
+    let value_offset = next_aligned(start + record_size::<D>(), core::mem::align_of::<U>());
+    let end = value_offset + core::mem::size_of::<U>();
+
+    self.buf.reserve(end - self.buf.len());
+
+    // `*mut U` and `*mut u8` are both thin pointers so the two function pointer types are ABI compatible
+    //
+    let unsize = core::mem::transmute::<fn(*mut U) -> *mut D, fn(*mut u8) -> *mut D>(unsize);
+
+    let base = self.buf.as_mut_ptr();
+    base.add(value_offset).cast::<U>().write(value);
+
+    #[allow(clippy::cast_ptr_alignment)]
+    base.add(start).cast::<Record<D>>().write(Record {
+      unsize,
+      prev: header.last,
+      value: value_offset,
+      end,
+    });
+
+    self.buf.set_len(end);
+    *self.buf.header_mut() = DynHeader {
+      len: header.len + 1,
+      last: start,
+    };
+  }
+
+  /// `retain` drops every element for which `f` returns `false`, packing the remaining elements together.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use core::fmt::Display;
+  ///
+  /// let mut values = minivec::MiniDynVec::<dyn Display>::new();
+  /// minivec::mini_dyn_push!(values, 1);
+  /// minivec::mini_dyn_push!(values, "two");
+  /// minivec::mini_dyn_push!(values, 3.5);
+  ///
+  /// values.retain(|value| value.to_string().len() > 1);
+  ///
+  /// let strings: Vec<String> = values.iter().map(ToString::to_string).collect();
+  /// assert_eq!(strings, ["two", "3.5"]);
+  /// ```
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&D) -> bool,
+  {
+    let len = self.header().len;
+    if len == 0 {
+      return;
+    }
+
+    // elements are leaked rather than double-dropped should `f` or a destructor panic
+    //
+    *self.buf.header_mut() = DynHeader::default();
+    unsafe { self.buf.set_len(0) };
+
+    let base = self.base();
+    let mut read = 0;
+    let mut write = 0;
+    let mut kept = DynHeader::default();
+
+    for _ in 0..len {
+      unsafe {
+        let record = Self::record(base, read);
+        read = next_aligned(record.end, record_align::<D>());
+
+        let element = Self::element(base, record);
+        if !f(&*element) {
+          core::ptr::drop_in_place(element);
+          continue;
+        }
+
+        // elements only ever move towards the front so they are copied before their record is rewritten
+        //
+        let size = core::mem::size_of_val(&*element);
+        let start = next_aligned(write, record_align::<D>());
+        let value = next_aligned(
+          start + record_size::<D>(),
+          core::mem::align_of_val(&*element),
+        );
+        core::ptr::copy(base.add(record.value), base.add(value), size);
+
+        #[allow(clippy::cast_ptr_alignment)]
+        base.add(start).cast::<Record<D>>().write(Record {
+          unsize: record.unsize,
+          prev: kept.last,
+          value,
+          end: value + size,
+        });
+
+        write = value + size;
+        kept = DynHeader {
+          len: kept.len + 1,
+          last: start,
+        };
+      }
+    }
+
+    unsafe { self.buf.set_len(write) };
+    *self.buf.header_mut() = kept;
+  }
+
+  /// `with_byte_capacity` constructs an empty vector with room for `capacity` bytes of elements and their records.
+  ///
+  #[must_use]
+  pub fn with_byte_capacity(capacity: usize) -> Self {
+    let mut vec = MiniDynVec::new();
+    vec.buf.reserve_exact(capacity);
+    vec
+  }
+}
+
+impl<D: ?Sized> Drop for MiniDynVec<D> {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl<D: ?Sized + core::fmt::Debug> core::fmt::Debug for MiniDynVec<D> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<D: ?Sized> Default for MiniDynVec<D> {
+  fn default() -> Self {
+    MiniDynVec::new()
+  }
+}
+
+impl<'a, D: ?Sized> core::iter::IntoIterator for &'a MiniDynVec<D> {
+  type Item = &'a D;
+  type IntoIter = Iter<'a, D>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, D: ?Sized> core::iter::IntoIterator for &'a mut MiniDynVec<D> {
+  type Item = &'a mut D;
+  type IntoIter = IterMut<'a, D>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<'a, D: ?Sized> Iterator for Iter<'a, D> {
+  type Item = &'a D;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+
+    self.remaining -= 1;
+    unsafe {
+      let base = self.vec.base();
+      let record = MiniDynVec::<D>::record(base, self.offset);
+      self.offset = next_aligned(record.end, record_align::<D>());
+      Some(&*MiniDynVec::<D>::element(base, record))
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<D: ?Sized> ExactSizeIterator for Iter<'_, D> {}
+
+impl<'a, D: ?Sized> Iterator for IterMut<'a, D> {
+  type Item = &'a mut D;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+
+    self.remaining -= 1;
+    unsafe {
+      let record = MiniDynVec::<D>::record(self.base, self.offset);
+      self.offset = next_aligned(record.end, record_align::<D>());
+      Some(&mut *MiniDynVec::<D>::element(self.base, record))
+    }
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<D: ?Sized> ExactSizeIterator for IterMut<'_, D> {}
+
+/// `mini_dyn_push!` pushes a value onto a [`MiniDynVec`](crate::MiniDynVec), coercing it to the vector's unsized
+/// element type.
+///
+/// * `mini_dyn_push!(vec, value)`
+///
+/// The value's type must unsize to the element type, e.g. implement the trait of a `MiniDynVec<dyn Trait>`.
+///
+#[macro_export]
+macro_rules! mini_dyn_push {
+  ($vec:expr, $value:expr) => {{
+    let vec: &mut $crate::MiniDynVec<_> = &mut $vec;
+    let value = $value;
+
+    // the closure is an unsizing coercion, the only conversion which type checks here
+    //
+    unsafe { vec.push_unsized(value, |p| p) }
+  }};
+}
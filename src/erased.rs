@@ -0,0 +1,574 @@
+//! `erased` contains [`ErasedMiniVec`](ErasedMiniVec), a vector whose element type is only known at runtime.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::max_align_with;
+use crate::{LayoutErr, MiniVec, MiniVecWithHeader};
+
+use core::alloc::Layout;
+use core::any::TypeId;
+
+/// `DropFn` drops the element `ptr` points to in place.
+///
+pub type DropFn = unsafe fn(ptr: *mut u8);
+
+/// `CloneFn` writes a clone of the element `src` points to into the uninitialized `dst`.
+///
+pub type CloneFn = unsafe fn(src: *const u8, dst: *mut u8);
+
+// Everything known about the element type lives in the user header of the allocation so the vector stays the size of
+// a single pointer. `len` counts elements as the byte length can't for zero-sized ones.
+//
+#[derive(Clone, Copy)]
+struct Erased {
+  len: usize,
+  layout: Layout,
+  drop: Option<DropFn>,
+  clone: Option<CloneFn>,
+  type_id: Option<TypeId>,
+}
+
+impl Erased {
+  fn of<T: 'static>(clone: Option<CloneFn>) -> Self {
+    Erased {
+      len: 0,
+      layout: Layout::new::<T>(),
+      drop: if core::mem::needs_drop::<T>() {
+        Some(drop_erased::<T>)
+      } else {
+        None
+      },
+      clone,
+      type_id: Some(TypeId::of::<T>()),
+    }
+  }
+
+  fn min_alignment(&self) -> usize {
+    core::cmp::max(self.layout.align(), max_align_with::<Erased, u8>())
+  }
+}
+
+unsafe fn drop_erased<T>(ptr: *mut u8) {
+  #[allow(clippy::cast_ptr_alignment)]
+  core::ptr::drop_in_place(ptr.cast::<T>());
+}
+
+unsafe fn clone_erased<T: Clone>(src: *const u8, dst: *mut u8) {
+  #[allow(clippy::cast_ptr_alignment)]
+  dst.cast::<T>().write((*src.cast::<T>()).clone());
+}
+
+/// `ErasedMiniVec` is a vector whose element type is described at runtime by a [`Layout`](core::alloc::Layout)
+/// along with optional drop and clone functions, which makes it suitable for columns of component storage where the
+/// set of types isn't known at compile time.
+///
+/// The description lives in the vector's allocation, so `ErasedMiniVec` is the size of a single pointer but always
+/// allocates. Vectors built from a concrete type also record its [`TypeId`](core::any::TypeId) so they can be
+/// safely viewed as slices with [`downcast_ref`](ErasedMiniVec::downcast_ref) or turned back into a
+/// [`MiniVec`](crate::MiniVec).
+///
+/// As the element type is unknown, `ErasedMiniVec` is neither `Send` nor `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{mini_vec, ErasedMiniVec, MiniVec};
+///
+/// let mut column = ErasedMiniVec::from(mini_vec![1.0_f32, 2.0]);
+///
+/// let value = 3.0_f32;
+/// unsafe { column.push_raw((&value as *const f32).cast()) };
+///
+/// assert_eq!(column.len(), 3);
+/// assert_eq!(column.downcast_ref::<f32>(), Some(&[1.0, 2.0, 3.0][..]));
+/// assert_eq!(column.downcast_ref::<u32>(), None);
+///
+/// let vec: MiniVec<f32> = column.into_typed().unwrap();
+/// assert_eq!(vec, [1.0, 2.0, 3.0]);
+/// ```
+///
+pub struct ErasedMiniVec {
+  buf: MiniVecWithHeader<Erased, u8>,
+  marker: core::marker::PhantomData<*mut u8>,
+}
+
+impl ErasedMiniVec {
+  fn erased(&self) -> Erased {
+    let Some(erased) = self.buf.header() else {
+      unreachable!("an erased vector always allocates its header");
+    };
+
+    *erased
+  }
+
+  fn from_erased(erased: Erased, alignment: usize) -> Result<Self, LayoutErr> {
+    if alignment < erased.min_alignment() {
+      return Err(LayoutErr::AlignmentTooSmall);
+    }
+
+    if !alignment.is_power_of_two() {
+      return Err(LayoutErr::AlignmentNotDivisibleByTwo);
+    }
+
+    Ok(ErasedMiniVec {
+      buf: MiniVecWithHeader::with_header_and_alignment(erased, 0, alignment)?,
+      marker: core::marker::PhantomData,
+    })
+  }
+
+  fn with_default_alignment(erased: Erased) -> Self {
+    let Ok(vec) = Self::from_erased(erased, erased.min_alignment()) else {
+      unreachable!("the minimum alignment is always a valid one");
+    };
+
+    vec
+  }
+
+  // Hands ownership of every element to the caller, who has already moved them out.
+  //
+  unsafe fn forget_elements(&mut self) {
+    if self.erased().len > 0 {
+      self.buf.header_mut().len = 0;
+      self.buf.set_len(0);
+    }
+  }
+
+  unsafe fn set_len(&mut self, len: usize) {
+    let size = self.erased().layout.size();
+    self.buf.header_mut().len = len;
+    self.buf.set_len(len * size);
+  }
+
+  /// `alignment` returns the alignment of the vector's element storage.
+  ///
+  #[must_use]
+  pub fn alignment(&self) -> usize {
+    self.buf.alignment()
+  }
+
+  /// `as_bytes` returns the raw bytes of the vector's elements.
+  ///
+  /// # Safety
+  ///
+  /// The element type must not contain any padding or otherwise uninitialized bytes.
+  ///
+  #[must_use]
+  pub unsafe fn as_bytes(&self) -> &[u8] {
+    self.buf.as_slice()
+  }
+
+  /// `as_mut_ptr` returns a mutable pointer to the vector's first element.
+  ///
+  pub fn as_mut_ptr(&mut self) -> *mut u8 {
+    self.buf.as_mut_ptr()
+  }
+
+  /// `as_ptr` returns a pointer to the vector's first element.
+  ///
+  #[must_use]
+  pub fn as_ptr(&self) -> *const u8 {
+    self.buf.as_ptr()
+  }
+
+  /// `capacity` returns the number of elements the vector can hold without reallocating.
+  ///
+  /// Vectors of zero-sized elements have a capacity of `usize::MAX`.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    match self.erased().layout.size() {
+      0 => usize::MAX,
+      size => self.buf.capacity() / size,
+    }
+  }
+
+  /// `clear` drops every element of the vector, keeping its allocation.
+  ///
+  pub fn clear(&mut self) {
+    let erased = self.erased();
+
+    // elements are leaked rather than double-dropped should a destructor panic
+    //
+    unsafe { self.forget_elements() };
+
+    if let Some(drop) = erased.drop {
+      let base = self.buf.as_mut_ptr();
+      for idx in 0..erased.len {
+        unsafe { drop(base.add(idx * erased.layout.size())) };
+      }
+    }
+  }
+
+  /// `downcast_mut` returns the elements of the vector as a mutable slice of `T`, or `None` if the vector wasn't
+  /// built for `T`.
+  ///
+  pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut [T]> {
+    if !self.is::<T>() {
+      return None;
+    }
+
+    let len = self.len();
+
+    #[allow(clippy::cast_ptr_alignment)]
+    Some(unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr().cast::<T>(), len) })
+  }
+
+  /// `downcast_ref` returns the elements of the vector as a slice of `T`, or `None` if the vector wasn't built for
+  /// `T`.
+  ///
+  #[must_use]
+  pub fn downcast_ref<T: 'static>(&self) -> Option<&[T]> {
+    if !self.is::<T>() {
+      return None;
+    }
+
+    #[allow(clippy::cast_ptr_alignment)]
+    Some(unsafe { core::slice::from_raw_parts(self.as_ptr().cast::<T>(), self.len()) })
+  }
+
+  /// `element_type_id` returns the [`TypeId`](core::any::TypeId) of the element type, or `None` if the vector was
+  /// built from a bare layout.
+  ///
+  #[must_use]
+  pub fn element_type_id(&self) -> Option<TypeId> {
+    self.erased().type_id
+  }
+
+  /// `from_layout` constructs an empty vector of elements described by `layout`, dropped with `drop` and cloned with
+  /// `clone`.
+  ///
+  /// # Safety
+  ///
+  /// `drop` and `clone`, when supplied, must be sound to call on any properly aligned element described by `layout`
+  /// which is later pushed onto the vector.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use core::alloc::Layout;
+  ///
+  /// let mut column = unsafe { minivec::ErasedMiniVec::from_layout(Layout::new::<[u8; 3]>(), None, None) };
+  /// unsafe { column.push_raw([1_u8, 2, 3].as_ptr()) };
+  ///
+  /// assert_eq!(column.len(), 1);
+  /// assert_eq!(unsafe { column.as_bytes() }, [1, 2, 3]);
+  /// assert!(column.element_type_id().is_none());
+  /// ```
+  ///
+  #[must_use]
+  pub unsafe fn from_layout(layout: Layout, drop: Option<DropFn>, clone: Option<CloneFn>) -> Self {
+    Self::with_default_alignment(Erased {
+      len: 0,
+      layout,
+      drop,
+      clone,
+      type_id: None,
+    })
+  }
+
+  /// `get_raw` returns a pointer to the element at `index`, or `None` if it is out of bounds.
+  ///
+  #[must_use]
+  pub fn get_raw(&self, index: usize) -> Option<*const u8> {
+    let erased = self.erased();
+    if index >= erased.len {
+      return None;
+    }
+
+    Some(unsafe { self.as_ptr().add(index * erased.layout.size()) })
+  }
+
+  /// `get_raw_mut` returns a mutable pointer to the element at `index`, or `None` if it is out of bounds.
+  ///
+  pub fn get_raw_mut(&mut self, index: usize) -> Option<*mut u8> {
+    let erased = self.erased();
+    if index >= erased.len {
+      return None;
+    }
+
+    Some(unsafe { self.as_mut_ptr().add(index * erased.layout.size()) })
+  }
+
+  /// `into_typed` converts the vector back into a [`MiniVec`](crate::MiniVec), keeping its alignment, or returns it
+  /// unchanged if it wasn't built for `T`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `self` if the vector's element type isn't `T` or if `T` is zero-sized, as `MiniVec` doesn't support
+  /// those.
+  ///
+  pub fn into_typed<T: 'static>(mut self) -> Result<MiniVec<T>, Self> {
+    if !self.is::<T>() || core::mem::size_of::<T>() == 0 {
+      return Err(self);
+    }
+
+    let len = self.len();
+    let Ok(mut vec) = MiniVec::<T>::with_alignment(len, self.alignment()) else {
+      unreachable!("the alignment of an erased vector is always valid for its element type");
+    };
+
+    if len > 0 {
+      unsafe {
+        core::ptr::copy_nonoverlapping(
+          self.as_ptr(),
+          vec.as_mut_ptr().cast::<u8>(),
+          self.buf.len(),
+        );
+        vec.set_len(len);
+        self.forget_elements();
+      }
+    }
+
+    Ok(vec)
+  }
+
+  /// `is` returns whether or not the vector's elements are of type `T`.
+  ///
+  #[must_use]
+  pub fn is<T: 'static>(&self) -> bool {
+    self.element_type_id() == Some(TypeId::of::<T>())
+  }
+
+  /// `is_empty` returns whether or not the vector contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `layout` returns the layout of a single element.
+  ///
+  #[must_use]
+  pub fn layout(&self) -> Layout {
+    self.erased().layout
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.erased().len
+  }
+
+  /// `new` constructs an empty vector of `T`, which can be dropped but not cloned.
+  ///
+  /// Note: allocates room for the description of `T` but not for any elements.
+  ///
+  #[must_use]
+  pub fn new<T: 'static>() -> Self {
+    Self::with_default_alignment(Erased::of::<T>(None))
+  }
+
+  /// `new_cloneable` constructs an empty vector of `T` which also supports
+  /// [`try_clone`](ErasedMiniVec::try_clone).
+  ///
+  /// Note: allocates room for the description of `T` but not for any elements.
+  ///
+  #[must_use]
+  pub fn new_cloneable<T: Clone + 'static>() -> Self {
+    Self::with_default_alignment(Erased::of::<T>(Some(clone_erased::<T>)))
+  }
+
+  /// `push_raw` appends the element `value` points to, taking ownership of it.
+  ///
+  /// # Safety
+  ///
+  /// `value` must point to a valid element of the vector's element type, which is moved into the vector and must not
+  /// be used or dropped afterwards. It need not be aligned.
+  ///
+  pub unsafe fn push_raw(&mut self, value: *const u8) {
+    let len = self.len();
+    self.reserve(1);
+
+    let size = self.erased().layout.size();
+    core::ptr::copy_nonoverlapping(value, self.as_mut_ptr().add(len * size), size);
+    self.set_len(len + 1);
+  }
+
+  /// `reserve` ensures there is room for at least `additional` more elements.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the required number of bytes overflows `usize`.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    let Some(bytes) = additional.checked_mul(self.erased().layout.size()) else {
+      panic!("capacity overflow");
+    };
+
+    self.buf.reserve(bytes);
+  }
+
+  /// `swap_remove_and_drop` removes the element at `index` and drops it, moving the last element into its place.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn swap_remove_and_drop(&mut self, index: usize) {
+    let erased = self.erased();
+    assert!(
+      index < erased.len,
+      "swap_remove index (is {}) should be < len (is {})",
+      index,
+      erased.len
+    );
+
+    let size = erased.layout.size();
+    let last = erased.len - 1;
+
+    // the removed element is swapped to the back and forgotten before it is dropped so a panicking destructor
+    // can't cause a double drop
+    //
+    unsafe {
+      let base = self.as_mut_ptr();
+      if index != last {
+        core::ptr::swap_nonoverlapping(base.add(index * size), base.add(last * size), size);
+      }
+
+      self.set_len(last);
+
+      if let Some(drop) = erased.drop {
+        drop(base.add(last * size));
+      }
+    }
+  }
+
+  /// `swap_remove_raw` moves the element at `index` into `dst` and moves the last element into its place.
+  ///
+  /// # Safety
+  ///
+  /// `dst` must be valid for writes of [`layout().size()`](ErasedMiniVec::layout) bytes. It need not be aligned.
+  /// The caller takes ownership of the element.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub unsafe fn swap_remove_raw(&mut self, index: usize, dst: *mut u8) {
+    let erased = self.erased();
+    assert!(
+      index < erased.len,
+      "swap_remove index (is {}) should be < len (is {})",
+      index,
+      erased.len
+    );
+
+    let size = erased.layout.size();
+    let last = erased.len - 1;
+    let base = self.as_mut_ptr();
+
+    core::ptr::copy_nonoverlapping(base.add(index * size), dst, size);
+    if index != last {
+      core::ptr::copy_nonoverlapping(base.add(last * size), base.add(index * size), size);
+    }
+
+    self.set_len(last);
+  }
+
+  /// `try_clone` returns a clone of the vector, or `None` if it has no clone function.
+  ///
+  #[must_use]
+  pub fn try_clone(&self) -> Option<Self> {
+    let erased = self.erased();
+    let clone = erased.clone?;
+
+    let Ok(mut vec) = Self::from_erased(Erased { len: 0, ..erased }, self.alignment()) else {
+      unreachable!("the vector's alignment was already validated");
+    };
+    vec.reserve(erased.len);
+
+    let size = erased.layout.size();
+    for idx in 0..erased.len {
+      unsafe {
+        clone(
+          self.as_ptr().add(idx * size),
+          vec.as_mut_ptr().add(idx * size),
+        );
+        vec.set_len(idx + 1);
+      }
+    }
+
+    Some(vec)
+  }
+
+  /// `with_alignment` moves the vector's elements into storage aligned to `alignment` bytes, which is kept across
+  /// reallocations. This makes SIMD-friendly columns possible.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LayoutErr::AlignmentTooSmall`](LayoutErr::AlignmentTooSmall) if `alignment` is smaller than the
+  /// element's alignment or that of the vector's header and
+  /// [`LayoutErr::AlignmentNotDivisibleByTwo`](LayoutErr::AlignmentNotDivisibleByTwo) if it isn't a power of two.
+  /// The vector is handed back unchanged alongside the error.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut column = minivec::ErasedMiniVec::new::<f32>().with_alignment(64).unwrap();
+  /// column.reserve(16);
+  ///
+  /// assert_eq!(column.alignment(), 64);
+  /// assert_eq!(column.as_ptr() as usize % 64, 0);
+  /// ```
+  ///
+  pub fn with_alignment(mut self, alignment: usize) -> Result<Self, (LayoutErr, Self)> {
+    let erased = self.erased();
+    let mut vec = match Self::from_erased(Erased { len: 0, ..erased }, alignment) {
+      Ok(vec) => vec,
+      Err(err) => return Err((err, self)),
+    };
+    vec.reserve(erased.len);
+
+    unsafe {
+      core::ptr::copy_nonoverlapping(self.as_ptr(), vec.as_mut_ptr(), self.buf.len());
+      vec.set_len(erased.len);
+      self.forget_elements();
+    }
+
+    Ok(vec)
+  }
+}
+
+impl Drop for ErasedMiniVec {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+impl core::fmt::Debug for ErasedMiniVec {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("ErasedMiniVec")
+      .field("layout", &self.layout())
+      .field("len", &self.len())
+      .finish()
+  }
+}
+
+impl<T: 'static> From<MiniVec<T>> for ErasedMiniVec {
+  fn from(mut vec: MiniVec<T>) -> Self {
+    let erased = Erased::of::<T>(None);
+    let Ok(mut erased_vec) = Self::from_erased(
+      erased,
+      core::cmp::max(vec.alignment().next_power_of_two(), erased.min_alignment()),
+    ) else {
+      unreachable!("the alignment is a power of two no smaller than the minimum");
+    };
+
+    let len = vec.len();
+    if len > 0 {
+      erased_vec.reserve(len);
+      unsafe {
+        core::ptr::copy_nonoverlapping(
+          vec.as_ptr().cast::<u8>(),
+          erased_vec.as_mut_ptr(),
+          len * core::mem::size_of::<T>(),
+        );
+        erased_vec.set_len(len);
+        vec.set_len(0);
+      }
+    }
+
+    erased_vec
+  }
+}
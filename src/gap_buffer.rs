@@ -0,0 +1,750 @@
+//! `gap_buffer` contains [`MiniGapBuffer`](MiniGapBuffer), a sequence whose spare capacity sits at a movable cursor,
+//! along with [`MiniGapString`](MiniGapString), its UTF-8 counterpart for editing text.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::resolve_range;
+use crate::MiniVec;
+
+use core::ops::RangeBounds;
+
+/// `MiniGapBuffer` is a sequence which keeps its unused capacity as a gap at a cursor. Inserting and deleting at the
+/// cursor is constant time and moving the cursor only moves the elements it passes over, which makes it a good fit
+/// for text editors and other workloads whose edits are clustered around one spot.
+///
+/// The buffer is a single pointer wide and its allocation is that of an ordinary [`MiniVec`](crate::MiniVec), so it
+/// converts to and from one without copying every element. The start of the gap is the vector's length while its
+/// end is kept in the last few slots of the capacity, enough to hold a `usize`, which are never used for elements.
+///
+/// # Example
+///
+/// ```
+/// let mut buf: minivec::MiniGapBuffer<_> = [1, 2, 5].iter().copied().collect();
+///
+/// buf.move_cursor(2);
+/// buf.insert_at_cursor(3);
+/// buf.insert_at_cursor(4);
+///
+/// assert_eq!(buf.as_slices(), (&[1, 2, 3, 4][..], &[5][..]));
+/// assert_eq!(buf.delete_after(), Some(5));
+/// assert_eq!(buf.make_contiguous(), [1, 2, 3, 4]);
+/// ```
+///
+pub struct MiniGapBuffer<T> {
+  buf: MiniVec<T>,
+}
+
+/// `Iter` is an iterator over the elements of a [`MiniGapBuffer`](MiniGapBuffer), created by
+/// [`iter`](MiniGapBuffer::iter).
+///
+pub type Iter<'a, T> = core::iter::Chain<core::slice::Iter<'a, T>, core::slice::Iter<'a, T>>;
+
+/// `IterMut` is an iterator over mutable references to the elements of a [`MiniGapBuffer`](MiniGapBuffer), created
+/// by [`iter_mut`](MiniGapBuffer::iter_mut).
+///
+pub type IterMut<'a, T> =
+  core::iter::Chain<core::slice::IterMut<'a, T>, core::slice::IterMut<'a, T>>;
+
+// The elements before the cursor are the vector's own elements so its length is where the gap starts. The elements
+// after the cursor sit at the end of the usable capacity, starting at `end`, which is stored unaligned in the
+// reserved slots past them. An allocated buffer always has room for those slots.
+//
+impl<T> MiniGapBuffer<T> {
+  fn reserved_slots() -> usize {
+    core::mem::size_of::<usize>().div_ceil(core::cmp::max(core::mem::size_of::<T>(), 1))
+  }
+
+  fn usable_capacity(&self) -> usize {
+    self.buf.capacity().saturating_sub(Self::reserved_slots())
+  }
+
+  fn gap_end(&self) -> usize {
+    if self.buf.capacity() == 0 {
+      return 0;
+    }
+
+    unsafe {
+      self
+        .buf
+        .as_ptr()
+        .add(self.usable_capacity())
+        .cast::<usize>()
+        .read_unaligned()
+    }
+  }
+
+  fn set_gap_end(&mut self, end: usize) {
+    debug_assert!(self.buf.capacity() >= Self::reserved_slots());
+
+    let slots = self.usable_capacity();
+    unsafe {
+      self
+        .buf
+        .as_mut_ptr()
+        .add(slots)
+        .cast::<usize>()
+        .write_unaligned(end);
+    }
+  }
+
+  fn back_len(&self) -> usize {
+    self.usable_capacity() - self.gap_end()
+  }
+
+  fn gap_len(&self) -> usize {
+    self.gap_end() - self.buf.len()
+  }
+
+  /// `as_mut_slices` returns the elements before and after the cursor mutably.
+  ///
+  pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+    let front = self.buf.len();
+    let (end, back) = (self.gap_end(), self.back_len());
+
+    unsafe {
+      let p = self.buf.as_mut_ptr();
+      (
+        core::slice::from_raw_parts_mut(p, front),
+        core::slice::from_raw_parts_mut(p.add(end), back),
+      )
+    }
+  }
+
+  /// `as_slices` returns the elements before and after the cursor.
+  ///
+  #[must_use]
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let back = unsafe {
+      core::slice::from_raw_parts(self.buf.as_ptr().add(self.gap_end()), self.back_len())
+    };
+    (self.buf.as_slice(), back)
+  }
+
+  /// `capacity` returns the number of elements the buffer can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.usable_capacity()
+  }
+
+  /// `clear` drops every element, keeping the allocation.
+  ///
+  pub fn clear(&mut self) {
+    let (end, back) = (self.gap_end(), self.back_len());
+    if back > 0 {
+      self.set_gap_end(end + back);
+      unsafe {
+        core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+          self.buf.as_mut_ptr().add(end),
+          back,
+        ));
+      }
+    }
+
+    self.buf.clear();
+  }
+
+  /// `cursor` returns the position of the cursor, i.e. the number of elements before it.
+  ///
+  #[must_use]
+  pub fn cursor(&self) -> usize {
+    self.buf.len()
+  }
+
+  /// `delete_after` removes the element directly after the cursor and returns it, or `None` if the cursor is at
+  /// the end.
+  ///
+  pub fn delete_after(&mut self) -> Option<T> {
+    if self.back_len() == 0 {
+      return None;
+    }
+
+    let end = self.gap_end();
+    self.set_gap_end(end + 1);
+    Some(unsafe { core::ptr::read(self.buf.as_ptr().add(end)) })
+  }
+
+  /// `delete_before` removes the element directly before the cursor and returns it, or `None` if the cursor is at
+  /// the start.
+  ///
+  pub fn delete_before(&mut self) -> Option<T> {
+    self.buf.pop()
+  }
+
+  /// `get` returns a reference to the element at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  #[must_use]
+  pub fn get(&self, idx: usize) -> Option<&T> {
+    let (front, back) = self.as_slices();
+    match idx.checked_sub(front.len()) {
+      None => front.get(idx),
+      Some(idx) => back.get(idx),
+    }
+  }
+
+  /// `get_mut` returns a mutable reference to the element at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+    let (front, back) = self.as_mut_slices();
+    match idx.checked_sub(front.len()) {
+      None => front.get_mut(idx),
+      Some(idx) => back.get_mut(idx),
+    }
+  }
+
+  /// `insert_at_cursor` inserts `value` directly before the cursor, which then moves past it.
+  ///
+  pub fn insert_at_cursor(&mut self, value: T) {
+    self.reserve(1);
+    self.buf.push(value);
+  }
+
+  /// `into_vec` closes the gap and returns the elements in a `MiniVec` which takes over the buffer's allocation.
+  /// Only the elements after the cursor are moved.
+  ///
+  #[must_use]
+  pub fn into_vec(mut self) -> MiniVec<T> {
+    let (front, back) = (self.buf.len(), self.back_len());
+    if back > 0 {
+      unsafe {
+        let p = self.buf.as_mut_ptr();
+        core::ptr::copy(p.add(self.gap_end()), p.add(front), back);
+        self.buf.set_len(front + back);
+      }
+    }
+
+    // the buffer is left unallocated, so its destructor has nothing left to drop
+    //
+    core::mem::take(&mut self.buf)
+  }
+
+  /// `is_empty` returns whether or not the buffer contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `iter` returns an iterator over the elements of the buffer.
+  ///
+  pub fn iter(&self) -> Iter<'_, T> {
+    let (front, back) = self.as_slices();
+    front.iter().chain(back.iter())
+  }
+
+  /// `iter_mut` returns an iterator over mutable references to the elements of the buffer.
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    let (front, back) = self.as_mut_slices();
+    front.iter_mut().chain(back.iter_mut())
+  }
+
+  /// `len` returns the number of elements in the buffer.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.buf.len() + self.back_len()
+  }
+
+  /// `make_contiguous` moves the gap, and with it the cursor, to the end of the buffer and returns every element as
+  /// one slice.
+  ///
+  pub fn make_contiguous(&mut self) -> &mut [T] {
+    let len = self.len();
+    self.move_cursor(len);
+    self.buf.as_mut_slice()
+  }
+
+  /// `move_cursor` moves the cursor so that `pos` elements come before it. Only the elements between the old and new
+  /// cursor positions are moved.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `pos` is greater than the length of the buffer.
+  ///
+  pub fn move_cursor(&mut self, pos: usize) {
+    let len = self.len();
+    assert!(
+      pos <= len,
+      "cursor position (is {}) should be <= len (is {})",
+      pos,
+      len
+    );
+
+    let (start, end) = (self.buf.len(), self.gap_end());
+    if pos == start {
+      return;
+    }
+
+    unsafe {
+      let p = self.buf.as_mut_ptr();
+      if pos < start {
+        let count = start - pos;
+        core::ptr::copy(p.add(pos), p.add(end - count), count);
+        self.set_gap_end(end - count);
+      } else {
+        let count = pos - start;
+        core::ptr::copy(p.add(end), p.add(start), count);
+        self.set_gap_end(end + count);
+      }
+
+      self.buf.set_len(pos);
+    }
+  }
+
+  /// `new` constructs an empty buffer.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniGapBuffer {
+      buf: MiniVec::new(),
+    }
+  }
+
+  /// `reserve` ensures that the gap has room for at least `additional` more elements, growing the capacity
+  /// geometrically.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    if self.gap_len() >= additional {
+      return;
+    }
+
+    let (old_end, back) = (self.gap_end(), self.back_len());
+    let required = back
+      .checked_add(additional)
+      .and_then(|n| n.checked_add(Self::reserved_slots()))
+      .expect("capacity overflow");
+    self.buf.reserve(required);
+
+    // the elements after the gap have to stay at the very end of the usable capacity
+    //
+    let end = self.usable_capacity() - back;
+    unsafe {
+      let p = self.buf.as_mut_ptr();
+      core::ptr::copy(p.add(old_end), p.add(end), back);
+    }
+    self.set_gap_end(end);
+  }
+
+  /// `splice` replaces the elements in `range` with the values of `replace_with` and returns the removed elements.
+  /// The cursor is left directly after the inserted values.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `range` is out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut buf: minivec::MiniGapBuffer<_> = "hello world".chars().collect();
+  ///
+  /// let removed = buf.splice(0..5, "goodbye".chars());
+  /// assert_eq!(removed.iter().collect::<String>(), "hello");
+  /// assert_eq!(buf.iter().collect::<String>(), "goodbye world");
+  /// assert_eq!(buf.cursor(), 7);
+  /// ```
+  ///
+  pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> MiniVec<T>
+  where
+    R: RangeBounds<usize>,
+    I: IntoIterator<Item = T>,
+  {
+    let (start, end) = resolve_range(&range, self.len());
+
+    self.move_cursor(start);
+    let removed: MiniVec<T> = (start..end).filter_map(|_| self.delete_after()).collect();
+
+    self.extend(replace_with);
+    removed
+  }
+
+  /// `with_capacity` constructs an empty buffer with room for `capacity` elements.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    let mut buf = MiniGapBuffer::new();
+    buf.reserve(capacity);
+    buf
+  }
+}
+
+impl<T> Drop for MiniGapBuffer<T> {
+  fn drop(&mut self) {
+    // the inner vector only drops the elements before the cursor
+    //
+    let back = self.back_len();
+    if back > 0 {
+      unsafe {
+        core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+          self.buf.as_mut_ptr().add(self.gap_end()),
+          back,
+        ));
+      }
+    }
+  }
+}
+
+impl<T: Clone> Clone for MiniGapBuffer<T> {
+  fn clone(&self) -> Self {
+    let mut buf = MiniGapBuffer::with_capacity(self.len());
+    buf.extend(self.iter().cloned());
+    buf.move_cursor(self.cursor());
+    buf
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniGapBuffer<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T> Default for MiniGapBuffer<T> {
+  fn default() -> Self {
+    MiniGapBuffer::new()
+  }
+}
+
+impl<T: PartialEq> PartialEq for MiniGapBuffer<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for MiniGapBuffer<T> {}
+
+impl<T> core::ops::Index<usize> for MiniGapBuffer<T> {
+  type Output = T;
+
+  fn index(&self, idx: usize) -> &T {
+    let len = self.len();
+    match self.get(idx) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+}
+
+impl<T> core::ops::IndexMut<usize> for MiniGapBuffer<T> {
+  fn index_mut(&mut self, idx: usize) -> &mut T {
+    let len = self.len();
+    match self.get_mut(idx) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+}
+
+// Every value is inserted at the cursor.
+//
+impl<T> core::iter::Extend<T> for MiniGapBuffer<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    let iter = iter.into_iter();
+    self.reserve(iter.size_hint().0);
+    for value in iter {
+      self.insert_at_cursor(value);
+    }
+  }
+}
+
+impl<T> core::iter::FromIterator<T> for MiniGapBuffer<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut buf = MiniGapBuffer::new();
+    buf.extend(iter);
+    buf
+  }
+}
+
+// The buffer takes over the vector's allocation and its spare capacity becomes the gap, with the cursor left at the
+// end. Only a vector without room for the reserved slots has to grow first.
+//
+impl<T> From<MiniVec<T>> for MiniGapBuffer<T> {
+  fn from(mut vec: MiniVec<T>) -> Self {
+    if vec.capacity() == 0 {
+      return MiniGapBuffer { buf: vec };
+    }
+
+    vec.reserve(Self::reserved_slots());
+
+    let mut buf = MiniGapBuffer { buf: vec };
+    buf.set_gap_end(buf.usable_capacity());
+    buf
+  }
+}
+
+impl<T> From<MiniGapBuffer<T>> for MiniVec<T> {
+  fn from(buf: MiniGapBuffer<T>) -> Self {
+    buf.into_vec()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a MiniGapBuffer<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a mut MiniGapBuffer<T> {
+  type Item = &'a mut T;
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+/// `MiniGapString` is a UTF-8 string built on a [`MiniGapBuffer<u8>`](MiniGapBuffer) whose cursor is always on a
+/// `char` boundary, so text can be typed and deleted at the cursor as cheaply as in the underlying buffer.
+///
+/// # Example
+///
+/// ```
+/// let mut text = minivec::MiniGapString::from("héllo");
+///
+/// text.move_cursor(3);
+/// text.insert_str("---");
+/// assert_eq!(text.as_strs(), ("hé---", "llo"));
+///
+/// assert_eq!(text.delete_before(), Some('-'));
+/// assert_eq!(text.delete_after(), Some('l'));
+/// assert_eq!(text.to_string(), "hé--lo");
+/// ```
+///
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct MiniGapString {
+  buf: MiniGapBuffer<u8>,
+}
+
+impl MiniGapString {
+  fn is_char_boundary(&self, pos: usize) -> bool {
+    match self.buf.get(pos) {
+      // continuation bytes are the only ones of the form 0b10xx_xxxx
+      //
+      Some(&byte) => byte & 0xc0 != 0x80,
+      None => pos == self.len(),
+    }
+  }
+
+  /// `as_strs` returns the text before and after the cursor.
+  ///
+  #[must_use]
+  pub fn as_strs(&self) -> (&str, &str) {
+    let (front, back) = self.buf.as_slices();
+    unsafe {
+      (
+        core::str::from_utf8_unchecked(front),
+        core::str::from_utf8_unchecked(back),
+      )
+    }
+  }
+
+  /// `capacity` returns the number of bytes the string can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.buf.capacity()
+  }
+
+  /// `chars` returns an iterator over the `char`s of the string.
+  ///
+  pub fn chars(&self) -> core::iter::Chain<core::str::Chars<'_>, core::str::Chars<'_>> {
+    let (front, back) = self.as_strs();
+    front.chars().chain(back.chars())
+  }
+
+  /// `clear` removes all of the text, keeping the allocation.
+  ///
+  pub fn clear(&mut self) {
+    self.buf.clear();
+  }
+
+  /// `cursor` returns the byte position of the cursor.
+  ///
+  #[must_use]
+  pub fn cursor(&self) -> usize {
+    self.buf.cursor()
+  }
+
+  /// `delete_after` removes the `char` directly after the cursor and returns it, or `None` if the cursor is at the
+  /// end.
+  ///
+  pub fn delete_after(&mut self) -> Option<char> {
+    let c = self.as_strs().1.chars().next()?;
+    for _ in 0..c.len_utf8() {
+      self.buf.delete_after();
+    }
+    Some(c)
+  }
+
+  /// `delete_before` removes the `char` directly before the cursor and returns it, or `None` if the cursor is at
+  /// the start.
+  ///
+  pub fn delete_before(&mut self) -> Option<char> {
+    let c = self.as_strs().0.chars().next_back()?;
+    unsafe { self.buf.buf.set_len(self.cursor() - c.len_utf8()) };
+    Some(c)
+  }
+
+  /// `insert` inserts `c` directly before the cursor, which then moves past it.
+  ///
+  pub fn insert(&mut self, c: char) {
+    self.insert_str(c.encode_utf8(&mut [0; 4]));
+  }
+
+  /// `insert_str` inserts `s` directly before the cursor, which then moves past it.
+  ///
+  pub fn insert_str(&mut self, s: &str) {
+    self.buf.reserve(s.len());
+    self.buf.buf.extend_from_slice(s.as_bytes());
+  }
+
+  /// `is_empty` returns whether or not the string is empty.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.buf.is_empty()
+  }
+
+  /// `len` returns the length of the string in bytes.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.buf.len()
+  }
+
+  /// `make_contiguous` moves the gap, and with it the cursor, to the end of the string and returns the whole string.
+  ///
+  pub fn make_contiguous(&mut self) -> &mut str {
+    unsafe { core::str::from_utf8_unchecked_mut(self.buf.make_contiguous()) }
+  }
+
+  /// `move_cursor` moves the cursor to the byte position `pos`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `pos` is greater than the length of the string or doesn't lie on a `char` boundary.
+  ///
+  pub fn move_cursor(&mut self, pos: usize) {
+    assert!(
+      self.is_char_boundary(pos),
+      "cursor position {} is not a char boundary",
+      pos
+    );
+    self.buf.move_cursor(pos);
+  }
+
+  /// `new` constructs an empty string.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniGapString {
+      buf: MiniGapBuffer::new(),
+    }
+  }
+
+  /// `splice` replaces the bytes in `range` with `replace_with`, leaving the cursor directly after it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `range` is out of bounds or either of its ends doesn't lie on a `char` boundary.
+  ///
+  pub fn splice<R: RangeBounds<usize>>(&mut self, range: R, replace_with: &str) {
+    let (start, end) = resolve_range(&range, self.len());
+    assert!(
+      self.is_char_boundary(start) && self.is_char_boundary(end),
+      "splice range {}..{} does not lie on char boundaries",
+      start,
+      end
+    );
+
+    self.buf.move_cursor(start);
+    if end > start {
+      let gap_end = self.buf.gap_end();
+      self.buf.set_gap_end(gap_end + (end - start));
+    }
+    self.insert_str(replace_with);
+  }
+
+  /// `with_capacity` constructs an empty string with room for `capacity` bytes.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniGapString {
+      buf: MiniGapBuffer::with_capacity(capacity),
+    }
+  }
+}
+
+impl core::fmt::Debug for MiniGapString {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+
+    f.write_char('"')?;
+    for c in self.chars().flat_map(char::escape_debug) {
+      f.write_char(c)?;
+    }
+    f.write_char('"')
+  }
+}
+
+impl core::fmt::Display for MiniGapString {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let (front, back) = self.as_strs();
+    f.write_str(front)?;
+    f.write_str(back)
+  }
+}
+
+impl PartialEq<str> for MiniGapString {
+  fn eq(&self, other: &str) -> bool {
+    let (front, back) = self.as_strs();
+    other.len() == self.len() && other.starts_with(front) && other.ends_with(back)
+  }
+}
+
+impl PartialEq<&str> for MiniGapString {
+  fn eq(&self, other: &&str) -> bool {
+    *self == **other
+  }
+}
+
+impl From<&str> for MiniGapString {
+  fn from(s: &str) -> Self {
+    let mut string = MiniGapString::with_capacity(s.len());
+    string.insert_str(s);
+    string
+  }
+}
+
+impl core::iter::Extend<char> for MiniGapString {
+  fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+    for c in iter {
+      self.insert(c);
+    }
+  }
+}
+
+impl<'a> core::iter::Extend<&'a str> for MiniGapString {
+  fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+    for s in iter {
+      self.insert_str(s);
+    }
+  }
+}
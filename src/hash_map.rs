@@ -0,0 +1,1682 @@
+//! `hash_map` contains [`MiniHashMap`](MiniHashMap), an open-addressing hash table that is only the size of a
+//! single pointer, along with its iterator and entry types.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::next_aligned;
+
+extern crate alloc;
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+/// `DefaultHashBuilder` is the hash builder used by [`MiniHashMap`](MiniHashMap) and
+/// [`MiniHashSet`](crate::MiniHashSet) when one isn't specified by the user.
+///
+pub type DefaultHashBuilder = core::hash::BuildHasherDefault<FxHasher>;
+
+/// `FxHasher` is a port of the fast, non-cryptographic hash function used by `rustc`.
+///
+/// Note: `FxHasher` makes no attempt to resist `HashDoS` attacks. Use a keyed hasher when the
+/// keys come from an untrusted source.
+///
+#[derive(Clone, Copy, Default)]
+pub struct FxHasher {
+  hash: usize,
+}
+
+#[cfg(target_pointer_width = "64")]
+const FX_SEED: usize = 0xf135_7aea_2e62_a9c5;
+#[cfg(target_pointer_width = "64")]
+const FX_ROTATE: u32 = 26;
+
+#[cfg(not(target_pointer_width = "64"))]
+const FX_SEED: usize = 0x93d7_65dd;
+#[cfg(not(target_pointer_width = "64"))]
+const FX_ROTATE: u32 = 15;
+
+impl FxHasher {
+  fn add_to_hash(&mut self, i: usize) {
+    self.hash = self.hash.wrapping_add(i).wrapping_mul(FX_SEED);
+  }
+}
+
+impl Hasher for FxHasher {
+  fn write(&mut self, bytes: &[u8]) {
+    const WORD: usize = core::mem::size_of::<usize>();
+
+    for chunk in bytes.chunks(WORD) {
+      let mut word = [0_u8; WORD];
+      word[..chunk.len()].copy_from_slice(chunk);
+      self.add_to_hash(usize::from_ne_bytes(word));
+    }
+
+    self.add_to_hash(bytes.len());
+  }
+
+  fn write_u8(&mut self, i: u8) {
+    self.add_to_hash(i as usize);
+  }
+
+  fn write_u16(&mut self, i: u16) {
+    self.add_to_hash(i as usize);
+  }
+
+  fn write_u32(&mut self, i: u32) {
+    self.add_to_hash(i as usize);
+  }
+
+  #[allow(clippy::cast_possible_truncation)]
+  fn write_u64(&mut self, i: u64) {
+    self.add_to_hash(i as usize);
+    if core::mem::size_of::<usize>() < 8 {
+      self.add_to_hash((i >> 32) as usize);
+    }
+  }
+
+  fn write_usize(&mut self, i: usize) {
+    self.add_to_hash(i);
+  }
+
+  fn finish(&self) -> u64 {
+    self.hash.rotate_left(FX_ROTATE) as u64
+  }
+}
+
+/// `StatelessBuildHasher` marks the hash builders that [`MiniHashMap`](MiniHashMap) and
+/// [`MiniHashSet`](crate::MiniHashSet) accept.
+///
+/// Neither collection has room to store a hash builder, so one is created via `Default::default` every time a key
+/// needs to be hashed. That is only sound for builders that hash the same key the same way on every call, so the
+/// trait is sealed and implemented solely for [`BuildHasherDefault`](core::hash::BuildHasherDefault). Stateful
+/// builders like `std::collections::hash_map::RandomState` are rejected at compile time:
+///
+/// ```compile_fail
+/// use minivec::MiniHashMap;
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut map: MiniHashMap<u32, u32, RandomState> = MiniHashMap::new();
+/// map.insert(1, 2);
+/// ```
+///
+/// The `Hasher` given to `BuildHasherDefault` must itself be deterministic in its `Default` impl.
+///
+pub trait StatelessBuildHasher: BuildHasher + Default + sealed::Sealed {}
+
+impl<H> StatelessBuildHasher for core::hash::BuildHasherDefault<H> where H: Hasher + Default {}
+
+mod sealed {
+  pub trait Sealed {}
+
+  impl<H> Sealed for core::hash::BuildHasherDefault<H> {}
+}
+
+const EMPTY: u8 = 0xff;
+const DELETED: u8 = 0x80;
+
+fn is_full(ctrl: u8) -> bool {
+  ctrl & 0x80 == 0
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn h1(hash: u64) -> usize {
+  hash as usize
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn h2(hash: u64) -> u8 {
+  (hash >> 57) as u8
+}
+
+pub(crate) fn make_hash<Q, S>(key: &Q) -> u64
+where
+  Q: Hash + ?Sized,
+  S: StatelessBuildHasher,
+{
+  S::default().hash_one(key)
+}
+
+fn max_load(buckets: usize) -> usize {
+  if buckets < 8 {
+    buckets.saturating_sub(1)
+  } else {
+    buckets / 8 * 7
+  }
+}
+
+fn capacity_to_buckets(capacity: usize) -> usize {
+  if capacity == 0 {
+    return 0;
+  }
+
+  if capacity < 4 {
+    return 4;
+  }
+
+  if capacity < 8 {
+    return 8;
+  }
+
+  let adjusted = capacity.checked_mul(8).expect("capacity overflow") / 7;
+  adjusted
+    .checked_next_power_of_two()
+    .expect("capacity overflow")
+}
+
+#[derive(Clone, Copy)]
+struct Header {
+  len: usize,
+  buckets: usize,
+  growth_left: usize,
+}
+
+fn buckets_offset<K, V>(buckets: usize) -> usize {
+  next_aligned(
+    core::mem::size_of::<Header>() + buckets,
+    core::mem::align_of::<(K, V)>(),
+  )
+}
+
+fn make_table_layout<K, V>(buckets: usize) -> alloc::alloc::Layout {
+  let num_bytes = buckets
+    .checked_mul(core::mem::size_of::<(K, V)>())
+    .and_then(|n| n.checked_add(buckets_offset::<K, V>(buckets)))
+    .expect("capacity overflow");
+
+  let alignment = core::cmp::max(
+    core::mem::align_of::<Header>(),
+    core::mem::align_of::<(K, V)>(),
+  );
+
+  alloc::alloc::Layout::from_size_align(num_bytes, alignment).expect("capacity overflow")
+}
+
+/// `MiniHashMap` is a space-optimized hash map that is only the size of a single pointer.
+///
+/// The length, number of buckets, control bytes and key-value pairs all live in one allocation, much like the header
+/// that sits in front of the elements of a [`MiniVec`](crate::MiniVec). Collisions are resolved via linear probing.
+/// An empty map does not allocate.
+///
+/// Because the map only stores a single pointer, the hash builder `S` is never stored. Instead, a fresh instance is
+/// created via `S::default()` every time a key needs to be hashed, which is why `S` is limited to the
+/// [`StatelessBuildHasher`](StatelessBuildHasher) builders.
+///
+pub struct MiniHashMap<K, V, S = DefaultHashBuilder> {
+  buf: core::ptr::NonNull<u8>,
+  phantom: core::marker::PhantomData<(K, V)>,
+  hasher: core::marker::PhantomData<fn() -> S>,
+}
+
+impl<K, V, S> MiniHashMap<K, V, S> {
+  fn is_default(&self) -> bool {
+    crate::is_sentinel(self.buf.as_ptr())
+  }
+
+  fn header(&self) -> &Header {
+    debug_assert!(!self.is_default());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &*self.buf.as_ptr().cast::<Header>()
+    }
+  }
+
+  fn header_mut(&mut self) -> &mut Header {
+    debug_assert!(!self.is_default());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &mut *self.buf.as_ptr().cast::<Header>()
+    }
+  }
+
+  fn num_buckets(&self) -> usize {
+    if self.is_default() {
+      0
+    } else {
+      self.header().buckets
+    }
+  }
+
+  fn growth_left(&self) -> usize {
+    if self.is_default() {
+      0
+    } else {
+      self.header().growth_left
+    }
+  }
+
+  fn ctrl(&self) -> *mut u8 {
+    debug_assert!(!self.is_default());
+    unsafe { self.buf.as_ptr().add(core::mem::size_of::<Header>()) }
+  }
+
+  fn buckets(&self) -> *mut (K, V) {
+    debug_assert!(!self.is_default());
+
+    let offset = buckets_offset::<K, V>(self.num_buckets());
+    unsafe { self.buf.as_ptr().add(offset).cast::<(K, V)>() }
+  }
+
+  fn ctrl_at(&self, idx: usize) -> u8 {
+    debug_assert!(idx < self.num_buckets());
+    unsafe { *self.ctrl().add(idx) }
+  }
+
+  fn set_ctrl(&mut self, idx: usize, ctrl: u8) {
+    debug_assert!(idx < self.num_buckets());
+    unsafe { *self.ctrl().add(idx) = ctrl };
+  }
+
+  fn bucket(&self, idx: usize) -> *mut (K, V) {
+    debug_assert!(idx < self.num_buckets());
+    unsafe { self.buckets().add(idx) }
+  }
+
+  fn allocate(buckets: usize) -> Self {
+    if buckets == 0 {
+      return MiniHashMap::new_default();
+    }
+
+    debug_assert!(buckets.is_power_of_two());
+
+    let layout = make_table_layout::<K, V>(buckets);
+    let buf = unsafe { alloc::alloc::alloc(layout) };
+    if buf.is_null() {
+      alloc::alloc::handle_alloc_error(layout);
+    }
+
+    let header = Header {
+      len: 0,
+      buckets,
+      growth_left: max_load(buckets),
+    };
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      core::ptr::write(buf.cast::<Header>(), header);
+      core::ptr::write_bytes(buf.add(core::mem::size_of::<Header>()), EMPTY, buckets);
+    }
+
+    MiniHashMap {
+      buf: unsafe { core::ptr::NonNull::new_unchecked(buf) },
+      phantom: core::marker::PhantomData,
+      hasher: core::marker::PhantomData,
+    }
+  }
+
+  fn new_default() -> Self {
+    MiniHashMap {
+      buf: crate::sentinel(core::mem::align_of::<Header>()),
+      phantom: core::marker::PhantomData,
+      hasher: core::marker::PhantomData,
+    }
+  }
+
+  fn find_insert_slot(&self, hash: u64) -> usize {
+    let mask = self.num_buckets() - 1;
+    let mut pos = h1(hash) & mask;
+    loop {
+      if !is_full(self.ctrl_at(pos)) {
+        return pos;
+      }
+      pos = (pos + 1) & mask;
+    }
+  }
+
+  fn insert_in_slot(&mut self, hash: u64, idx: usize, kv: (K, V)) {
+    if self.ctrl_at(idx) == EMPTY {
+      self.header_mut().growth_left -= 1;
+    }
+
+    self.set_ctrl(idx, h2(hash));
+    unsafe { core::ptr::write(self.bucket(idx), kv) };
+    self.header_mut().len += 1;
+  }
+
+  // marks the bucket at `idx` as unoccupied without dropping its contents
+  //
+  fn erase(&mut self, idx: usize) {
+    let mask = self.num_buckets() - 1;
+
+    // a slot can only become `EMPTY` again if it doesn't sit in the middle of a probe sequence
+    //
+    if self.ctrl_at((idx + 1) & mask) == EMPTY {
+      self.set_ctrl(idx, EMPTY);
+      self.header_mut().growth_left += 1;
+    } else {
+      self.set_ctrl(idx, DELETED);
+    }
+
+    self.header_mut().len -= 1;
+  }
+
+  fn next_full(&self, mut pos: usize) -> Option<usize> {
+    let buckets = self.num_buckets();
+    while pos < buckets {
+      if is_full(self.ctrl_at(pos)) {
+        return Some(pos);
+      }
+      pos += 1;
+    }
+    None
+  }
+
+  // moves the next pair out of the table, tombstoning its bucket so that the map is always safe to drop
+  //
+  fn take_next(&mut self, pos: &mut usize) -> Option<(K, V)> {
+    let idx = self.next_full(*pos)?;
+    *pos = idx + 1;
+
+    self.set_ctrl(idx, DELETED);
+    self.header_mut().len -= 1;
+    Some(unsafe { core::ptr::read(self.bucket(idx)) })
+  }
+
+  /// `capacity` returns the number of elements the map can hold before it needs to reallocate.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map = minivec::MiniHashMap::<i32, i32>::with_capacity(100);
+  /// assert!(map.capacity() >= 100);
+  /// ```
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    if self.is_default() {
+      0
+    } else {
+      self.header().len + self.header().growth_left
+    }
+  }
+
+  /// `clear` removes every key-value pair from the map. The backing allocation is kept.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, "a"), (2, "b")].into();
+  /// let cap = map.capacity();
+  ///
+  /// map.clear();
+  ///
+  /// assert!(map.is_empty());
+  /// assert_eq!(map.capacity(), cap);
+  /// ```
+  ///
+  pub fn clear(&mut self) {
+    if self.is_default() {
+      return;
+    }
+
+    self.drain();
+  }
+
+  /// `drain` clears the map, returning every key-value pair as an iterator. The backing allocation is kept.
+  ///
+  /// If the returned iterator is dropped before being fully consumed, the remaining pairs are dropped.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, "a"), (2, "b")].into();
+  ///
+  /// let mut pairs: Vec<_> = map.drain().collect();
+  /// pairs.sort_unstable();
+  ///
+  /// assert_eq!(pairs, [(1, "a"), (2, "b")]);
+  /// assert!(map.is_empty());
+  /// ```
+  ///
+  pub fn drain(&mut self) -> Drain<'_, K, V, S> {
+    Drain { map: self, pos: 0 }
+  }
+
+  /// `is_empty` returns whether or not the map contains any elements.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  /// assert!(map.is_empty());
+  ///
+  /// map.insert(1, "a");
+  /// assert!(!map.is_empty());
+  /// ```
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `iter` returns an iterator over every key-value pair in the map, in arbitrary order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, 10), (2, 20)].into();
+  ///
+  /// let sum: i32 = map.iter().map(|(k, v)| k + v).sum();
+  /// assert_eq!(sum, 33);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    if self.is_default() {
+      return Iter {
+        ctrl: core::ptr::null(),
+        buckets: core::ptr::null(),
+        pos: 0,
+        end: 0,
+        remaining: 0,
+        marker: core::marker::PhantomData,
+      };
+    }
+
+    Iter {
+      ctrl: self.ctrl(),
+      buckets: self.buckets(),
+      pos: 0,
+      end: self.num_buckets(),
+      remaining: self.len(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `iter_mut` returns an iterator over every key-value pair in the map, in arbitrary order, with mutable references
+  /// to the values.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, 10), (2, 20)].into();
+  ///
+  /// for (_, v) in map.iter_mut() {
+  ///     *v *= 2;
+  /// }
+  ///
+  /// assert_eq!(map[&1], 20);
+  /// assert_eq!(map[&2], 40);
+  /// ```
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+    let Iter {
+      ctrl,
+      buckets,
+      pos,
+      end,
+      remaining,
+      ..
+    } = self.iter();
+
+    IterMut {
+      ctrl,
+      buckets: buckets.cast_mut(),
+      pos,
+      end,
+      remaining,
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `keys` returns an iterator over every key in the map, in arbitrary order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, "a"), (2, "b")].into();
+  ///
+  /// let mut keys: Vec<_> = map.keys().copied().collect();
+  /// keys.sort_unstable();
+  ///
+  /// assert_eq!(keys, [1, 2]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn keys(&self) -> Keys<'_, K, V> {
+    Keys { inner: self.iter() }
+  }
+
+  /// `len` returns the number of key-value pairs in the map.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  /// map.insert(1, "a");
+  /// map.insert(1, "b");
+  ///
+  /// assert_eq!(map.len(), 1);
+  /// ```
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    if self.is_default() {
+      0
+    } else {
+      self.header().len
+    }
+  }
+
+  /// `retain` removes every key-value pair for which `f(&key, &mut value)` returns `false`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+  /// map.retain(|&k, _| k % 2 == 0);
+  ///
+  /// assert_eq!(map.len(), 4);
+  /// assert_eq!(map.get(&3), None);
+  /// ```
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&K, &mut V) -> bool,
+  {
+    let mut pos = 0;
+    while let Some(idx) = self.next_full(pos) {
+      pos = idx + 1;
+
+      let bucket = self.bucket(idx);
+      let keep = unsafe { f(&(*bucket).0, &mut (*bucket).1) };
+      if !keep {
+        self.erase(idx);
+        unsafe { core::ptr::drop_in_place(bucket) };
+      }
+    }
+  }
+
+  /// `values` returns an iterator over every value in the map, in arbitrary order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, 10), (2, 20)].into();
+  /// assert_eq!(map.values().sum::<i32>(), 30);
+  /// ```
+  ///
+  #[must_use]
+  pub fn values(&self) -> Values<'_, K, V> {
+    Values { inner: self.iter() }
+  }
+
+  /// `values_mut` returns an iterator over mutable references to every value in the map, in arbitrary order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, 10), (2, 20)].into();
+  /// map.values_mut().for_each(|v| *v += 1);
+  ///
+  /// assert_eq!(map.values().sum::<i32>(), 32);
+  /// ```
+  ///
+  pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+    ValuesMut {
+      inner: self.iter_mut(),
+    }
+  }
+}
+
+impl<K, V> MiniHashMap<K, V, DefaultHashBuilder> {
+  /// `MiniHashMap::new` constructs an empty map.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map = minivec::MiniHashMap::<i32, i32>::new();
+  ///
+  /// assert_eq!(map.len(), 0);
+  /// assert_eq!(map.capacity(), 0);
+  /// ```
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniHashMap::new_default()
+  }
+
+  /// `with_capacity` constructs an empty map that can hold at least `capacity` elements without reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the required allocation size overflows.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map = minivec::MiniHashMap::<i32, i32>::with_capacity(10);
+  ///
+  /// assert_eq!(map.len(), 0);
+  /// assert!(map.capacity() >= 10);
+  /// ```
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniHashMap::allocate(capacity_to_buckets(capacity))
+  }
+}
+
+impl<K, V, S> MiniHashMap<K, V, S>
+where
+  K: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+  where
+    K: Borrow<Q>,
+    Q: Eq + ?Sized,
+  {
+    let buckets = self.num_buckets();
+    if buckets == 0 {
+      return None;
+    }
+
+    let mask = buckets - 1;
+    let tag = h2(hash);
+    let mut pos = h1(hash) & mask;
+
+    for _ in 0..buckets {
+      let ctrl = self.ctrl_at(pos);
+      if ctrl == EMPTY {
+        return None;
+      }
+
+      if ctrl == tag && unsafe { (*self.bucket(pos)).0.borrow() } == key {
+        return Some(pos);
+      }
+
+      pos = (pos + 1) & mask;
+    }
+
+    None
+  }
+
+  fn insert_new(&mut self, hash: u64, kv: (K, V)) -> usize {
+    if self.growth_left() == 0 {
+      self.reserve_rehash(1);
+    }
+
+    let idx = self.find_insert_slot(hash);
+    self.insert_in_slot(hash, idx, kv);
+    idx
+  }
+
+  fn reserve_rehash(&mut self, additional: usize) {
+    let required = self
+      .len()
+      .checked_add(additional)
+      .expect("capacity overflow");
+
+    let full_capacity = max_load(self.num_buckets());
+
+    // if the table is mostly tombstones, rebuild it at its current size instead of growing it
+    //
+    let buckets = if required <= full_capacity / 2 {
+      self.num_buckets()
+    } else {
+      capacity_to_buckets(core::cmp::max(required, full_capacity + 1))
+    };
+
+    self.resize(buckets);
+  }
+
+  fn resize(&mut self, buckets: usize) {
+    debug_assert!(max_load(buckets) >= self.len());
+
+    let mut table = MiniHashMap::<K, V, S>::allocate(buckets);
+
+    let mut pos = 0;
+    while let Some(idx) = self.next_full(pos) {
+      pos = idx + 1;
+
+      // hashing is allowed to panic so we only move the pair once its new home is known, leaving both tables
+      // in a valid state
+      //
+      let hash = make_hash::<K, S>(unsafe { &(*self.bucket(idx)).0 });
+      let kv = unsafe { core::ptr::read(self.bucket(idx)) };
+      self.set_ctrl(idx, DELETED);
+      self.header_mut().len -= 1;
+
+      let slot = table.find_insert_slot(hash);
+      table.insert_in_slot(hash, slot, kv);
+    }
+
+    core::mem::swap(self, &mut table);
+  }
+
+  /// `contains_key` returns whether or not the map contains a value for the specified key.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, "a")].into();
+  ///
+  /// assert!(map.contains_key(&1));
+  /// assert!(!map.contains_key(&2));
+  /// ```
+  ///
+  pub fn contains_key<Q>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.get(key).is_some()
+  }
+
+  /// `entry` returns the [`Entry`](Entry) for the supplied key, allowing for in-place manipulation of the map.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut letters = minivec::MiniHashMap::new();
+  ///
+  /// for ch in "a short treatise on fungi".chars() {
+  ///     *letters.entry(ch).or_insert(0) += 1;
+  /// }
+  ///
+  /// assert_eq!(letters[&'s'], 2);
+  /// assert_eq!(letters[&'t'], 3);
+  /// assert_eq!(letters.get(&'y'), None);
+  /// ```
+  ///
+  pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+    let hash = make_hash::<K, S>(&key);
+    match self.find(hash, &key) {
+      Some(idx) => Entry::Occupied(OccupiedEntry { map: self, idx }),
+      None => Entry::Vacant(VacantEntry {
+        map: self,
+        hash,
+        key,
+      }),
+    }
+  }
+
+  /// `get` returns a reference to the value corresponding to the supplied key.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, "a")].into();
+  ///
+  /// assert_eq!(map.get(&1), Some(&"a"));
+  /// assert_eq!(map.get(&2), None);
+  /// ```
+  ///
+  pub fn get<Q>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.get_key_value(key).map(|(_, v)| v)
+  }
+
+  /// `get_key_value` returns references to the key-value pair corresponding to the supplied key.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map: minivec::MiniHashMap<_, _> = [(1, "a")].into();
+  ///
+  /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
+  /// assert_eq!(map.get_key_value(&2), None);
+  /// ```
+  ///
+  pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    if self.is_empty() {
+      return None;
+    }
+
+    let idx = self.find(make_hash::<Q, S>(key), key)?;
+    let bucket = self.bucket(idx);
+    unsafe { Some((&(*bucket).0, &(*bucket).1)) }
+  }
+
+  /// `get_mut` returns a mutable reference to the value corresponding to the supplied key.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, 10)].into();
+  ///
+  /// if let Some(v) = map.get_mut(&1) {
+  ///     *v += 1;
+  /// }
+  ///
+  /// assert_eq!(map[&1], 11);
+  /// ```
+  ///
+  pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    if self.is_empty() {
+      return None;
+    }
+
+    let idx = self.find(make_hash::<Q, S>(key), key)?;
+    unsafe { Some(&mut (*self.bucket(idx)).1) }
+  }
+
+  /// `insert` places the key-value pair in the map.
+  ///
+  /// If the map did not already contain the key, `None` is returned. Otherwise, the value is updated and the old
+  /// value is returned. The key itself is not updated.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  ///
+  /// assert_eq!(map.insert(37, "a"), None);
+  /// assert_eq!(map.insert(37, "b"), Some("a"));
+  /// assert_eq!(map[&37], "b");
+  /// ```
+  ///
+  pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+    let hash = make_hash::<K, S>(&key);
+    if let Some(idx) = self.find(hash, &key) {
+      return Some(unsafe { core::mem::replace(&mut (*self.bucket(idx)).1, value) });
+    }
+
+    self.insert_new(hash, (key, value));
+    None
+  }
+
+  /// `remove` removes the supplied key from the map, returning its value if it was present.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, "a")].into();
+  ///
+  /// assert_eq!(map.remove(&1), Some("a"));
+  /// assert_eq!(map.remove(&1), None);
+  /// ```
+  ///
+  pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.remove_entry(key).map(|(_, v)| v)
+  }
+
+  /// `remove_entry` removes the supplied key from the map, returning the stored key and its value if it was present.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [(1, "a")].into();
+  ///
+  /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
+  /// assert_eq!(map.remove_entry(&1), None);
+  /// ```
+  ///
+  pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+  where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    if self.is_empty() {
+      return None;
+    }
+
+    let idx = self.find(make_hash::<Q, S>(key), key)?;
+    self.erase(idx);
+    Some(unsafe { core::ptr::read(self.bucket(idx)) })
+  }
+
+  /// `reserve` ensures there is sufficient capacity for at least `additional` more elements to be inserted without
+  /// reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::<i32, i32>::new();
+  /// map.reserve(10);
+  ///
+  /// assert!(map.capacity() >= 10);
+  /// ```
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    if additional > self.growth_left() {
+      self.reserve_rehash(additional);
+    }
+  }
+
+  /// `shrink_to_fit` shrinks the backing allocation as much as possible while still holding every element. An empty
+  /// map releases its allocation entirely.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::<i32, i32>::with_capacity(100);
+  /// map.insert(1, 2);
+  /// map.shrink_to_fit();
+  ///
+  /// assert!(map.capacity() >= 1);
+  /// assert!(map.capacity() < 100);
+  /// ```
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    let buckets = capacity_to_buckets(self.len());
+    if buckets < self.num_buckets() {
+      self.resize(buckets);
+    }
+  }
+}
+
+impl<K, V, S> Drop for MiniHashMap<K, V, S> {
+  fn drop(&mut self) {
+    if self.is_default() {
+      return;
+    }
+
+    if core::mem::needs_drop::<(K, V)>() {
+      let mut pos = 0;
+      while let Some(idx) = self.next_full(pos) {
+        pos = idx + 1;
+        unsafe { core::ptr::drop_in_place(self.bucket(idx)) };
+      }
+    }
+
+    let layout = make_table_layout::<K, V>(self.num_buckets());
+    unsafe { alloc::alloc::dealloc(self.buf.as_ptr(), layout) };
+  }
+}
+
+impl<K, V, S> Clone for MiniHashMap<K, V, S>
+where
+  K: Clone + Eq + Hash,
+  V: Clone,
+  S: StatelessBuildHasher,
+{
+  fn clone(&self) -> Self {
+    let mut map = MiniHashMap::<K, V, S>::allocate(capacity_to_buckets(self.len()));
+    for (k, v) in self {
+      let hash = make_hash::<K, S>(k);
+      let slot = map.find_insert_slot(hash);
+      map.insert_in_slot(hash, slot, (k.clone(), v.clone()));
+    }
+    map
+  }
+}
+
+impl<K, V, S> core::fmt::Debug for MiniHashMap<K, V, S>
+where
+  K: core::fmt::Debug,
+  V: core::fmt::Debug,
+{
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+impl<K, V, S> Default for MiniHashMap<K, V, S> {
+  fn default() -> Self {
+    MiniHashMap::new_default()
+  }
+}
+
+impl<K, V, S> PartialEq for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash,
+  V: PartialEq,
+  S: StatelessBuildHasher,
+{
+  fn eq(&self, other: &Self) -> bool {
+    if self.len() != other.len() {
+      return false;
+    }
+
+    self
+      .iter()
+      .all(|(k, v)| other.get(k).is_some_and(|w| *v == *w))
+  }
+}
+
+impl<K, V, S> Eq for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash,
+  V: Eq,
+  S: StatelessBuildHasher,
+{
+}
+
+impl<K, V, S> core::iter::Extend<(K, V)> for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn extend<I>(&mut self, iter: I)
+  where
+    I: IntoIterator<Item = (K, V)>,
+  {
+    let iter = iter.into_iter();
+
+    // duplicate keys are common when extending a non-empty map so only reserve for half of them
+    //
+    let (lower, _) = iter.size_hint();
+    let additional = if self.is_empty() {
+      lower
+    } else {
+      lower.div_ceil(2)
+    };
+    self.reserve(additional);
+
+    for (k, v) in iter {
+      self.insert(k, v);
+    }
+  }
+}
+
+impl<'a, K, V, S> core::iter::Extend<(&'a K, &'a V)> for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash + Copy,
+  V: Copy,
+  S: StatelessBuildHasher,
+{
+  fn extend<I>(&mut self, iter: I)
+  where
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+  {
+    self.extend(iter.into_iter().map(|(&k, &v)| (k, v)));
+  }
+}
+
+impl<K, V, S> core::iter::FromIterator<(K, V)> for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = (K, V)>,
+  {
+    let mut map = MiniHashMap::default();
+    map.extend(iter);
+    map
+  }
+}
+
+impl<K, V, const N: usize> core::convert::From<[(K, V); N]>
+  for MiniHashMap<K, V, DefaultHashBuilder>
+where
+  K: Eq + Hash,
+{
+  fn from(arr: [(K, V); N]) -> Self {
+    core::iter::IntoIterator::into_iter(arr).collect()
+  }
+}
+
+impl<K, Q, V, S> core::ops::Index<&Q> for MiniHashMap<K, V, S>
+where
+  K: Eq + Hash + Borrow<Q>,
+  Q: Eq + Hash + ?Sized,
+  S: StatelessBuildHasher,
+{
+  type Output = V;
+
+  fn index(&self, key: &Q) -> &V {
+    self.get(key).expect("key not found in MiniHashMap")
+  }
+}
+
+impl<K, V, S> core::iter::IntoIterator for MiniHashMap<K, V, S> {
+  type Item = (K, V);
+  type IntoIter = IntoIter<K, V, S>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter { map: self, pos: 0 }
+  }
+}
+
+impl<'a, K, V, S> core::iter::IntoIterator for &'a MiniHashMap<K, V, S> {
+  type Item = (&'a K, &'a V);
+  type IntoIter = Iter<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, K, V, S> core::iter::IntoIterator for &'a mut MiniHashMap<K, V, S> {
+  type Item = (&'a K, &'a mut V);
+  type IntoIter = IterMut<'a, K, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+unsafe impl<K: Send, V: Send, S> Send for MiniHashMap<K, V, S> {}
+unsafe impl<K: Sync, V: Sync, S> Sync for MiniHashMap<K, V, S> {}
+
+/// `Entry` is a view into a single slot of a [`MiniHashMap`](MiniHashMap), which is either occupied or vacant.
+///
+/// It is created by [`MiniHashMap::entry`](MiniHashMap::entry).
+///
+pub enum Entry<'a, K, V, S> {
+  /// An occupied slot.
+  ///
+  Occupied(OccupiedEntry<'a, K, V, S>),
+  /// A vacant slot.
+  ///
+  Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// `OccupiedEntry` is a view into a slot of a [`MiniHashMap`](MiniHashMap) that contains a key-value pair.
+///
+pub struct OccupiedEntry<'a, K, V, S> {
+  map: &'a mut MiniHashMap<K, V, S>,
+  idx: usize,
+}
+
+/// `VacantEntry` is a view into a slot of a [`MiniHashMap`](MiniHashMap) that does not yet contain the key.
+///
+pub struct VacantEntry<'a, K, V, S> {
+  map: &'a mut MiniHashMap<K, V, S>,
+  hash: u64,
+  key: K,
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+  K: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  /// `and_modify` invokes `f` with the current value if the entry is occupied.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  ///
+  /// map.entry("poneyland").and_modify(|e| *e += 1).or_insert(42);
+  /// assert_eq!(map["poneyland"], 42);
+  ///
+  /// map.entry("poneyland").and_modify(|e| *e += 1).or_insert(42);
+  /// assert_eq!(map["poneyland"], 43);
+  /// ```
+  ///
+  #[must_use]
+  pub fn and_modify<F>(self, f: F) -> Self
+  where
+    F: FnOnce(&mut V),
+  {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      Entry::Vacant(entry) => Entry::Vacant(entry),
+    }
+  }
+
+  /// `key` returns a reference to the key of the entry.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::<&str, i32>::new();
+  /// assert_eq!(map.entry("poneyland").key(), &"poneyland");
+  /// ```
+  ///
+  #[must_use]
+  pub fn key(&self) -> &K {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key(),
+    }
+  }
+
+  /// `or_default` inserts `V::default()` if the entry is vacant and returns a mutable reference to the value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::<&str, Option<u32>>::new();
+  /// map.entry("poneyland").or_default();
+  ///
+  /// assert_eq!(map["poneyland"], None);
+  /// ```
+  ///
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// `or_insert` inserts `default` if the entry is vacant and returns a mutable reference to the value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  ///
+  /// map.entry("poneyland").or_insert(3);
+  /// *map.entry("poneyland").or_insert(10) *= 2;
+  ///
+  /// assert_eq!(map["poneyland"], 6);
+  /// ```
+  ///
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default),
+    }
+  }
+
+  /// `or_insert_with` inserts the result of `default()` if the entry is vacant and returns a mutable reference to
+  /// the value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  /// map.entry("poneyland").or_insert_with(|| String::from("hoho"));
+  ///
+  /// assert_eq!(map["poneyland"], "hoho");
+  /// ```
+  ///
+  pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+  where
+    F: FnOnce() -> V,
+  {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default()),
+    }
+  }
+
+  /// `or_insert_with_key` inserts the result of `default(&key)` if the entry is vacant and returns a mutable
+  /// reference to the value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniHashMap::new();
+  /// map.entry("poneyland").or_insert_with_key(|key| key.len());
+  ///
+  /// assert_eq!(map["poneyland"], 9);
+  /// ```
+  ///
+  pub fn or_insert_with_key<F>(self, default: F) -> &'a mut V
+  where
+    F: FnOnce(&K) -> V,
+  {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => {
+        let value = default(entry.key());
+        entry.insert(value)
+      }
+    }
+  }
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S> {
+  /// `get` returns a reference to the value in the entry.
+  ///
+  #[must_use]
+  pub fn get(&self) -> &V {
+    unsafe { &(*self.map.bucket(self.idx)).1 }
+  }
+
+  /// `get_mut` returns a mutable reference to the value in the entry.
+  ///
+  /// See [`into_mut`](OccupiedEntry::into_mut) for a reference that outlives the entry.
+  ///
+  pub fn get_mut(&mut self) -> &mut V {
+    unsafe { &mut (*self.map.bucket(self.idx)).1 }
+  }
+
+  /// `insert` replaces the value in the entry, returning the old value.
+  ///
+  pub fn insert(&mut self, value: V) -> V {
+    core::mem::replace(self.get_mut(), value)
+  }
+
+  /// `into_mut` converts the entry into a mutable reference to its value with the lifetime of the map.
+  ///
+  #[must_use]
+  pub fn into_mut(self) -> &'a mut V {
+    unsafe { &mut (*self.map.bucket(self.idx)).1 }
+  }
+
+  /// `key` returns a reference to the key in the entry.
+  ///
+  #[must_use]
+  pub fn key(&self) -> &K {
+    unsafe { &(*self.map.bucket(self.idx)).0 }
+  }
+
+  /// `remove` takes the value out of the map and returns it.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map: minivec::MiniHashMap<_, _> = [("poneyland", 12)].into();
+  ///
+  /// if let minivec::hash_map::Entry::Occupied(o) = map.entry("poneyland") {
+  ///     assert_eq!(o.remove(), 12);
+  /// }
+  ///
+  /// assert!(!map.contains_key("poneyland"));
+  /// ```
+  ///
+  #[must_use]
+  pub fn remove(self) -> V {
+    self.remove_entry().1
+  }
+
+  /// `remove_entry` takes the key-value pair out of the map and returns it.
+  ///
+  #[must_use]
+  pub fn remove_entry(self) -> (K, V) {
+    self.map.erase(self.idx);
+    unsafe { core::ptr::read(self.map.bucket(self.idx)) }
+  }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+  K: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  /// `insert` places `value` in the map with the entry's key and returns a mutable reference to it.
+  ///
+  pub fn insert(self, value: V) -> &'a mut V {
+    let VacantEntry { map, hash, key } = self;
+    let idx = map.insert_new(hash, (key, value));
+    unsafe { &mut (*map.bucket(idx)).1 }
+  }
+}
+
+impl<K, V, S> VacantEntry<'_, K, V, S> {
+  /// `into_key` takes ownership of the key that was used to create the entry.
+  ///
+  #[must_use]
+  pub fn into_key(self) -> K {
+    self.key
+  }
+
+  /// `key` returns a reference to the key that was used to create the entry.
+  ///
+  #[must_use]
+  pub fn key(&self) -> &K {
+    &self.key
+  }
+}
+
+impl<K: core::fmt::Debug, V: core::fmt::Debug, S> core::fmt::Debug for Entry<'_, K, V, S> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+      Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+    }
+  }
+}
+
+impl<K: core::fmt::Debug, V: core::fmt::Debug, S> core::fmt::Debug for OccupiedEntry<'_, K, V, S> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("OccupiedEntry")
+      .field("key", self.key())
+      .field("value", self.get())
+      .finish()
+  }
+}
+
+impl<K: core::fmt::Debug, V, S> core::fmt::Debug for VacantEntry<'_, K, V, S> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_tuple("VacantEntry").field(self.key()).finish()
+  }
+}
+
+/// `Iter` is an iterator over the key-value pairs of a [`MiniHashMap`](MiniHashMap).
+///
+pub struct Iter<'a, K, V> {
+  ctrl: *const u8,
+  buckets: *const (K, V),
+  pos: usize,
+  end: usize,
+  remaining: usize,
+  marker: core::marker::PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.pos < self.end {
+      let idx = self.pos;
+      self.pos += 1;
+
+      if is_full(unsafe { *self.ctrl.add(idx) }) {
+        self.remaining -= 1;
+        let bucket = unsafe { &*self.buckets.add(idx) };
+        return Some((&bucket.0, &bucket.1));
+      }
+    }
+
+    None
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> core::iter::FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+  fn clone(&self) -> Self {
+    Iter {
+      ctrl: self.ctrl,
+      buckets: self.buckets,
+      pos: self.pos,
+      end: self.end,
+      remaining: self.remaining,
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+unsafe impl<K: Sync, V: Sync> Send for Iter<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for Iter<'_, K, V> {}
+
+/// `IterMut` is an iterator over the key-value pairs of a [`MiniHashMap`](MiniHashMap) which yields mutable
+/// references to the values.
+///
+pub struct IterMut<'a, K, V> {
+  ctrl: *const u8,
+  buckets: *mut (K, V),
+  pos: usize,
+  end: usize,
+  remaining: usize,
+  marker: core::marker::PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+  type Item = (&'a K, &'a mut V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.pos < self.end {
+      let idx = self.pos;
+      self.pos += 1;
+
+      if is_full(unsafe { *self.ctrl.add(idx) }) {
+        self.remaining -= 1;
+        let bucket = unsafe { &mut *self.buckets.add(idx) };
+        return Some((&bucket.0, &mut bucket.1));
+      }
+    }
+
+    None
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> core::iter::FusedIterator for IterMut<'_, K, V> {}
+
+unsafe impl<K: Sync, V: Send> Send for IterMut<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync> Sync for IterMut<'_, K, V> {}
+
+/// `Keys` is an iterator over the keys of a [`MiniHashMap`](MiniHashMap).
+///
+#[derive(Clone)]
+pub struct Keys<'a, K, V> {
+  inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+  type Item = &'a K;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(k, _)| k)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> core::iter::FusedIterator for Keys<'_, K, V> {}
+
+/// `Values` is an iterator over the values of a [`MiniHashMap`](MiniHashMap).
+///
+#[derive(Clone)]
+pub struct Values<'a, K, V> {
+  inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+  type Item = &'a V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(_, v)| v)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+impl<K, V> core::iter::FusedIterator for Values<'_, K, V> {}
+
+/// `ValuesMut` is an iterator over mutable references to the values of a [`MiniHashMap`](MiniHashMap).
+///
+pub struct ValuesMut<'a, K, V> {
+  inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+  type Item = &'a mut V;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(_, v)| v)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+impl<K, V> core::iter::FusedIterator for ValuesMut<'_, K, V> {}
+
+/// `IntoIter` is an iterator that consumes a [`MiniHashMap`](MiniHashMap) and yields its key-value pairs.
+///
+pub struct IntoIter<K, V, S> {
+  map: MiniHashMap<K, V, S>,
+  pos: usize,
+}
+
+impl<K, V, S> Iterator for IntoIter<K, V, S> {
+  type Item = (K, V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.map.take_next(&mut self.pos)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.map.len();
+    (len, Some(len))
+  }
+}
+
+impl<K, V, S> ExactSizeIterator for IntoIter<K, V, S> {}
+impl<K, V, S> core::iter::FusedIterator for IntoIter<K, V, S> {}
+
+/// `Drain` is an iterator that removes every key-value pair from a [`MiniHashMap`](MiniHashMap) while keeping its
+/// allocation.
+///
+pub struct Drain<'a, K, V, S> {
+  map: &'a mut MiniHashMap<K, V, S>,
+  pos: usize,
+}
+
+impl<K, V, S> Iterator for Drain<'_, K, V, S> {
+  type Item = (K, V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.map.take_next(&mut self.pos)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.map.len();
+    (len, Some(len))
+  }
+}
+
+impl<K, V, S> ExactSizeIterator for Drain<'_, K, V, S> {}
+impl<K, V, S> core::iter::FusedIterator for Drain<'_, K, V, S> {}
+
+impl<K, V, S> Drop for Drain<'_, K, V, S> {
+  fn drop(&mut self) {
+    struct DropGuard<'a, 'b, K, V, S>(&'a mut Drain<'b, K, V, S>);
+
+    impl<K, V, S> Drop for DropGuard<'_, '_, K, V, S> {
+      fn drop(&mut self) {
+        // every pair has been moved out so the tombstones can be wiped
+        //
+        while self.0.next().is_some() {}
+
+        let map = &mut *self.0.map;
+        if map.is_default() {
+          return;
+        }
+
+        let buckets = map.num_buckets();
+        unsafe { core::ptr::write_bytes(map.ctrl(), EMPTY, buckets) };
+        map.header_mut().growth_left = max_load(buckets);
+      }
+    }
+
+    let guard = DropGuard(self);
+    for kv in guard.0.by_ref() {
+      core::mem::drop(kv);
+    }
+  }
+}
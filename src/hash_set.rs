@@ -0,0 +1,551 @@
+//! `hash_set` contains [`MiniHashSet`](MiniHashSet), a hash set built on top of
+//! [`MiniHashMap`](crate::MiniHashMap) that is only the size of a single pointer, along with its iterator types.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::hash_map::{self, DefaultHashBuilder, MiniHashMap, StatelessBuildHasher};
+
+use core::borrow::Borrow;
+use core::hash::Hash;
+
+/// `MiniHashSet` is a space-optimized hash set that is only the size of a single pointer.
+///
+/// It is implemented as a [`MiniHashMap<T, ()>`](crate::MiniHashMap) and so shares its requirements on the hash
+/// builder `S`.
+///
+pub struct MiniHashSet<T, S = DefaultHashBuilder> {
+  map: MiniHashMap<T, (), S>,
+}
+
+impl<T, S> MiniHashSet<T, S> {
+  /// `capacity` returns the number of elements the set can hold before it needs to reallocate.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set = minivec::MiniHashSet::<i32>::with_capacity(100);
+  /// assert!(set.capacity() >= 100);
+  /// ```
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.map.capacity()
+  }
+
+  /// `clear` removes every element from the set. The backing allocation is kept.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  /// set.clear();
+  ///
+  /// assert!(set.is_empty());
+  /// ```
+  ///
+  pub fn clear(&mut self) {
+    self.map.clear();
+  }
+
+  /// `drain` clears the set, returning every element as an iterator. The backing allocation is kept.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  ///
+  /// let mut xs: Vec<_> = set.drain().collect();
+  /// xs.sort_unstable();
+  ///
+  /// assert_eq!(xs, [1, 2, 3]);
+  /// assert!(set.is_empty());
+  /// ```
+  ///
+  pub fn drain(&mut self) -> Drain<'_, T, S> {
+    Drain {
+      inner: self.map.drain(),
+    }
+  }
+
+  /// `is_empty` returns whether or not the set contains any elements.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set = minivec::MiniHashSet::new();
+  /// assert!(set.is_empty());
+  ///
+  /// set.insert(1);
+  /// assert!(!set.is_empty());
+  /// ```
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.map.is_empty()
+  }
+
+  /// `iter` returns an iterator over every element of the set, in arbitrary order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  /// assert_eq!(set.iter().sum::<i32>(), 6);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter {
+      inner: self.map.keys(),
+    }
+  }
+
+  /// `len` returns the number of elements in the set.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set = minivec::MiniHashSet::new();
+  /// set.insert(1);
+  /// set.insert(1);
+  ///
+  /// assert_eq!(set.len(), 1);
+  /// ```
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.map.len()
+  }
+
+  /// `retain` removes every element for which `f(&elem)` returns `false`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set: minivec::MiniHashSet<i32> = (0..8).collect();
+  /// set.retain(|&x| x % 2 == 0);
+  ///
+  /// assert_eq!(set.len(), 4);
+  /// ```
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    self.map.retain(|k, ()| f(k));
+  }
+}
+
+impl<T> MiniHashSet<T, DefaultHashBuilder> {
+  /// `MiniHashSet::new` constructs an empty set.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set = minivec::MiniHashSet::<i32>::new();
+  ///
+  /// assert_eq!(set.len(), 0);
+  /// assert_eq!(set.capacity(), 0);
+  /// ```
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniHashSet {
+      map: MiniHashMap::new(),
+    }
+  }
+
+  /// `with_capacity` constructs an empty set that can hold at least `capacity` elements without reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the required allocation size overflows.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set = minivec::MiniHashSet::<i32>::with_capacity(10);
+  /// assert!(set.capacity() >= 10);
+  /// ```
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniHashSet {
+      map: MiniHashMap::with_capacity(capacity),
+    }
+  }
+}
+
+impl<T, S> MiniHashSet<T, S>
+where
+  T: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  /// `contains` returns whether or not the set contains the supplied value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  ///
+  /// assert!(set.contains(&1));
+  /// assert!(!set.contains(&4));
+  /// ```
+  ///
+  pub fn contains<Q>(&self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.contains_key(value)
+  }
+
+  /// `get` returns a reference to the element in the set that's equal to the supplied value.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let set: minivec::MiniHashSet<_> = [String::from("a")].into();
+  ///
+  /// assert_eq!(set.get("a").map(String::as_str), Some("a"));
+  /// assert_eq!(set.get("b"), None);
+  /// ```
+  ///
+  pub fn get<Q>(&self, value: &Q) -> Option<&T>
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.get_key_value(value).map(|(k, ())| k)
+  }
+
+  /// `insert` adds the value to the set, returning whether or not it was newly inserted.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set = minivec::MiniHashSet::new();
+  ///
+  /// assert!(set.insert(2));
+  /// assert!(!set.insert(2));
+  /// ```
+  ///
+  pub fn insert(&mut self, value: T) -> bool {
+    match self.map.entry(value) {
+      hash_map::Entry::Occupied(_) => false,
+      hash_map::Entry::Vacant(entry) => {
+        entry.insert(());
+        true
+      }
+    }
+  }
+
+  /// `is_disjoint` returns whether or not `self` and `other` have no elements in common.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let a: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  /// let b: minivec::MiniHashSet<_> = [4, 5].into();
+  ///
+  /// assert!(a.is_disjoint(&b));
+  /// ```
+  ///
+  #[must_use]
+  pub fn is_disjoint(&self, other: &Self) -> bool {
+    let (small, large) = if self.len() <= other.len() {
+      (self, other)
+    } else {
+      (other, self)
+    };
+
+    small.iter().all(|x| !large.contains(x))
+  }
+
+  /// `is_subset` returns whether or not every element of `self` is contained in `other`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let a: minivec::MiniHashSet<_> = [1, 2].into();
+  /// let b: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  ///
+  /// assert!(a.is_subset(&b));
+  /// assert!(!b.is_subset(&a));
+  /// ```
+  ///
+  #[must_use]
+  pub fn is_subset(&self, other: &Self) -> bool {
+    self.len() <= other.len() && self.iter().all(|x| other.contains(x))
+  }
+
+  /// `is_superset` returns whether or not every element of `other` is contained in `self`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let a: minivec::MiniHashSet<_> = [1, 2, 3].into();
+  /// let b: minivec::MiniHashSet<_> = [1, 2].into();
+  ///
+  /// assert!(a.is_superset(&b));
+  /// ```
+  ///
+  #[must_use]
+  pub fn is_superset(&self, other: &Self) -> bool {
+    other.is_subset(self)
+  }
+
+  /// `remove` removes the supplied value from the set, returning whether or not it was present.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set: minivec::MiniHashSet<_> = [1].into();
+  ///
+  /// assert!(set.remove(&1));
+  /// assert!(!set.remove(&1));
+  /// ```
+  ///
+  pub fn remove<Q>(&mut self, value: &Q) -> bool
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.remove(value).is_some()
+  }
+
+  /// `reserve` ensures there is sufficient capacity for at least `additional` more elements to be inserted without
+  /// reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set = minivec::MiniHashSet::<i32>::new();
+  /// set.reserve(10);
+  ///
+  /// assert!(set.capacity() >= 10);
+  /// ```
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    self.map.reserve(additional);
+  }
+
+  /// `shrink_to_fit` shrinks the backing allocation as much as possible while still holding every element.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set = minivec::MiniHashSet::<i32>::with_capacity(100);
+  /// set.insert(1);
+  /// set.shrink_to_fit();
+  ///
+  /// assert!(set.capacity() < 100);
+  /// ```
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    self.map.shrink_to_fit();
+  }
+
+  /// `take` removes the element equal to the supplied value from the set and returns it.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut set: minivec::MiniHashSet<_> = [1].into();
+  ///
+  /// assert_eq!(set.take(&1), Some(1));
+  /// assert_eq!(set.take(&1), None);
+  /// ```
+  ///
+  pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+  where
+    T: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+  {
+    self.map.remove_entry(value).map(|(k, ())| k)
+  }
+}
+
+impl<T, S> Clone for MiniHashSet<T, S>
+where
+  T: Clone + Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn clone(&self) -> Self {
+    MiniHashSet {
+      map: self.map.clone(),
+    }
+  }
+}
+
+impl<T: core::fmt::Debug, S> core::fmt::Debug for MiniHashSet<T, S> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_set().entries(self.iter()).finish()
+  }
+}
+
+impl<T, S> Default for MiniHashSet<T, S> {
+  fn default() -> Self {
+    MiniHashSet {
+      map: MiniHashMap::default(),
+    }
+  }
+}
+
+impl<T, S> PartialEq for MiniHashSet<T, S>
+where
+  T: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.is_subset(other)
+  }
+}
+
+impl<T, S> Eq for MiniHashSet<T, S>
+where
+  T: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+}
+
+impl<T, S> core::iter::Extend<T> for MiniHashSet<T, S>
+where
+  T: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn extend<I>(&mut self, iter: I)
+  where
+    I: IntoIterator<Item = T>,
+  {
+    self.map.extend(iter.into_iter().map(|x| (x, ())));
+  }
+}
+
+impl<'a, T, S> core::iter::Extend<&'a T> for MiniHashSet<T, S>
+where
+  T: 'a + Eq + Hash + Copy,
+  S: StatelessBuildHasher,
+{
+  fn extend<I>(&mut self, iter: I)
+  where
+    I: IntoIterator<Item = &'a T>,
+  {
+    self.extend(iter.into_iter().copied());
+  }
+}
+
+impl<T, S> core::iter::FromIterator<T> for MiniHashSet<T, S>
+where
+  T: Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = T>,
+  {
+    let mut set = MiniHashSet::default();
+    set.extend(iter);
+    set
+  }
+}
+
+impl<T, const N: usize> core::convert::From<[T; N]> for MiniHashSet<T, DefaultHashBuilder>
+where
+  T: Eq + Hash,
+{
+  fn from(arr: [T; N]) -> Self {
+    core::iter::IntoIterator::into_iter(arr).collect()
+  }
+}
+
+impl<T, S> core::iter::IntoIterator for MiniHashSet<T, S> {
+  type Item = T;
+  type IntoIter = IntoIter<T, S>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter {
+      inner: self.map.into_iter(),
+    }
+  }
+}
+
+impl<'a, T, S> core::iter::IntoIterator for &'a MiniHashSet<T, S> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+/// `Iter` is an iterator over the elements of a [`MiniHashSet`](MiniHashSet).
+///
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+  inner: hash_map::Keys<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> core::iter::FusedIterator for Iter<'_, T> {}
+
+/// `IntoIter` is an iterator that consumes a [`MiniHashSet`](MiniHashSet) and yields its elements.
+///
+pub struct IntoIter<T, S> {
+  inner: hash_map::IntoIter<T, (), S>,
+}
+
+impl<T, S> Iterator for IntoIter<T, S> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(k, ())| k)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<T, S> ExactSizeIterator for IntoIter<T, S> {}
+impl<T, S> core::iter::FusedIterator for IntoIter<T, S> {}
+
+/// `Drain` is an iterator that removes every element from a [`MiniHashSet`](MiniHashSet) while keeping its
+/// allocation.
+///
+pub struct Drain<'a, T, S> {
+  inner: hash_map::Drain<'a, T, (), S>,
+}
+
+impl<T, S> Iterator for Drain<'_, T, S> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next().map(|(k, ())| k)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<T, S> ExactSizeIterator for Drain<'_, T, S> {}
+impl<T, S> core::iter::FusedIterator for Drain<'_, T, S> {}
@@ -5,6 +5,7 @@ extern crate alloc;
 /// `Drain` is an iterator that removes the selected sub-range from the `MiniVec` and returns the removed elements to
 /// the caller lazily.
 ///
+#[allow(clippy::struct_field_names)]
 pub struct Drain<'a, T: 'a> {
   vec_: core::ptr::NonNull<MiniVec<T>>,
   drain_pos_: core::ptr::NonNull<T>,
@@ -57,8 +58,8 @@ impl<T> Iterator for Drain<'_, T> {
   }
 
   fn size_hint(&self) -> (usize, Option<usize>) {
-    let len = (self.drain_end_.as_ptr() as *const _ as usize
-      - self.drain_pos_.as_ptr() as *const _ as usize)
+    let len = (self.drain_end_.as_ptr().cast_const() as usize
+      - self.drain_pos_.as_ptr().cast_const() as usize)
       / core::mem::size_of::<T>();
 
     (len, Some(len))
@@ -86,7 +87,7 @@ impl<T> Drop for Drain<'_, T> {
       drain: &'b mut Drain<'a, T>,
     }
 
-    impl<'b, 'a, T> Drop for DropGuard<'b, 'a, T> {
+    impl<T> Drop for DropGuard<'_, '_, T> {
       fn drop(&mut self) {
         for x in &mut self.drain {
           core::mem::drop(x);
@@ -42,21 +42,21 @@ where
   fn next(&mut self) -> Option<Self::Item> {
     while self.pos < self.old_len {
       let data = self.vec.data();
-      let mut val = unsafe { &mut *data.add(self.pos) };
+      let val = unsafe { &mut *data.add(self.pos) };
 
       self.panicked = true;
 
-      let pred_result = (self.pred)(&mut val);
+      let pred_result = (self.pred)(val);
 
       self.panicked = false;
 
       if pred_result {
         self.pos += 1;
-        return Some(unsafe { core::ptr::read(val as *mut T) });
+        return Some(unsafe { core::ptr::read(core::ptr::from_mut::<T>(val)) });
       }
 
       if self.pos > self.new_len {
-        let src = val as *mut T;
+        let src = core::ptr::from_mut::<T>(val);
         let dst = unsafe { data.add(self.new_len) };
         unsafe {
           core::ptr::copy_nonoverlapping(src, dst, 1);
@@ -82,7 +82,7 @@ where
   drain: &'b mut DrainFilter<'a, T, F>,
 }
 
-impl<'a, 'b, T, F> Drop for DropGuard<'a, 'b, T, F>
+impl<T, F> Drop for DropGuard<'_, '_, T, F>
 where
   F: core::ops::FnMut(&mut T) -> bool,
 {
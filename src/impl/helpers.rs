@@ -31,18 +31,74 @@ pub fn max_align<T>() -> usize {
   core::cmp::max(align_t, header_align)
 }
 
+pub fn max_align_with<H, T>() -> usize {
+  core::cmp::max(max_align::<T>(), core::mem::align_of::<H>())
+}
+
+pub fn data_offset<H>(alignment: usize) -> usize {
+  let user_header_offset = next_aligned(core::mem::size_of::<Header>(), core::mem::align_of::<H>());
+  next_aligned(user_header_offset + core::mem::size_of::<H>(), alignment)
+}
+
 pub fn make_layout<T>(capacity: usize, alignment: usize) -> alloc::alloc::Layout {
-  let header_size = core::mem::size_of::<Header>();
+  make_layout_with::<(), T>(capacity, alignment)
+}
+
+pub fn make_layout_with<H, T>(capacity: usize, alignment: usize) -> alloc::alloc::Layout {
   let num_bytes = if capacity == 0 {
-    next_aligned(header_size, alignment)
+    data_offset::<H>(alignment)
   } else {
-    next_aligned(header_size, alignment)
-      + next_aligned(capacity * core::mem::size_of::<T>(), alignment)
+    data_offset::<H>(alignment) + next_aligned(capacity * core::mem::size_of::<T>(), alignment)
   };
 
   alloc::alloc::Layout::from_size_align(num_bytes, alignment).unwrap()
 }
 
+// Maps `idx` onto a series of segments where segment `k` holds `1 << (first_shift + k)` elements, returning the
+// segment and the offset within it.
+//
+pub fn locate_segment(idx: usize, first_shift: usize) -> (usize, usize) {
+  let pos = idx
+    .checked_add(1 << first_shift)
+    .expect("segment index overflow");
+
+  let bit = (usize::BITS - 1 - pos.leading_zeros()) as usize;
+  (bit - first_shift, pos - (1 << bit))
+}
+
+pub fn resolve_range<R>(range: &R, len: usize) -> (usize, usize)
+where
+  R: core::ops::RangeBounds<usize>,
+{
+  let start_idx = match range.start_bound() {
+    core::ops::Bound::Included(&n) => n,
+    core::ops::Bound::Excluded(&n) => n.checked_add(1).expect("Start idx exceeded numeric limits"),
+    core::ops::Bound::Unbounded => 0,
+  };
+
+  let end_idx = match range.end_bound() {
+    core::ops::Bound::Included(&n) => n.checked_add(1).expect("End idx exceeded numeric limits"),
+    core::ops::Bound::Excluded(&n) => n,
+    core::ops::Bound::Unbounded => len,
+  };
+
+  assert!(
+    start_idx <= end_idx,
+    "start idx {} is greater than end idx {}",
+    start_idx,
+    end_idx
+  );
+
+  assert!(
+    end_idx <= len,
+    "end idx {} is out of bounds for length {}",
+    end_idx,
+    len
+  );
+
+  (start_idx, end_idx)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -57,6 +113,16 @@ mod tests {
     assert_eq!(next_aligned(16, 512), 512);
   }
 
+  #[test]
+  fn locate_segment_test() {
+    assert_eq!(locate_segment(0, 3), (0, 0));
+    assert_eq!(locate_segment(7, 3), (0, 7));
+    assert_eq!(locate_segment(8, 3), (1, 0));
+    assert_eq!(locate_segment(23, 3), (1, 15));
+    assert_eq!(locate_segment(24, 3), (2, 0));
+    assert_eq!(locate_segment(1, 0), (1, 0));
+  }
+
   #[repr(align(512))]
   struct OverAligned {
     _data: [u8; 512],
@@ -132,4 +198,38 @@ mod tests {
         + next_aligned(core::mem::size_of::<i32>() * 512, 32)
     );
   }
+
+  #[test]
+  fn make_layout_with_test() {
+    let header_size = core::mem::size_of::<Header>();
+
+    // a unit user header matches the plain layout
+    //
+    assert_eq!(
+      make_layout_with::<(), i32>(512, max_align::<i32>()),
+      make_layout::<i32>(512, max_align::<i32>())
+    );
+
+    // small user header packed after the internal one
+    //
+    let alignment = max_align_with::<u8, i32>();
+    assert_eq!(alignment, core::mem::align_of::<Header>());
+    assert_eq!(
+      data_offset::<u8>(alignment),
+      next_aligned(header_size + 1, alignment)
+    );
+
+    let layout = make_layout_with::<u8, i32>(0, alignment);
+    assert_eq!(layout.size(), data_offset::<u8>(alignment));
+
+    // over-aligned user header
+    //
+    let alignment = max_align_with::<OverAligned, u8>();
+    assert_eq!(alignment, core::mem::align_of::<OverAligned>());
+    assert_eq!(data_offset::<OverAligned>(alignment), 1024);
+
+    let layout = make_layout_with::<OverAligned, u8>(3, alignment);
+    assert_eq!(layout.align(), 512);
+    assert_eq!(layout.size(), 1024 + 512);
+  }
 }
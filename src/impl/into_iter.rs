@@ -51,7 +51,7 @@ impl<T> IntoIter<T> {
     if self.v.is_default() {
       &mut []
     } else {
-      let data: *mut T = self.pos as *mut T;
+      let data: *mut T = self.pos.cast_mut();
       unsafe { core::slice::from_raw_parts_mut(data, self.v.len()) }
     }
   }
@@ -97,7 +97,7 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 
     if data >= end {
       return None;
-    };
+    }
 
     header.len -= 1;
 
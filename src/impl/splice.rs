@@ -5,6 +5,7 @@ extern crate alloc;
 /// `Splice` is an iterator that removes a sub-section of the backing `MiniVec` and then replaces it with the contents
 /// of another iterator. The removed sub-section and the iterator used to replace it can have independent lengths.
 ///
+#[allow(clippy::struct_field_names)]
 pub struct Splice<'a, I>
 where
   I: 'a + Iterator,
@@ -69,8 +70,8 @@ where
   }
 
   fn size_hint(&self) -> (usize, Option<usize>) {
-    let len = (self.drain_end_.as_ptr() as *const _ as usize
-      - self.drain_pos_.as_ptr() as *const _ as usize)
+    let len = (self.drain_end_.as_ptr().cast_const() as usize
+      - self.drain_pos_.as_ptr().cast_const() as usize)
       / core::mem::size_of::<I::Item>();
 
     (len, Some(len))
@@ -102,7 +103,7 @@ where
   splice: &'b mut Splice<'a, I>,
 }
 
-impl<'b, 'a, I> Drop for DropGuard<'b, 'a, I>
+impl<I> Drop for DropGuard<'_, '_, I>
 where
   I: Iterator,
 {
@@ -0,0 +1,487 @@
+//! `index_vec` contains [`MiniIndexVec`](MiniIndexVec), a [`MiniVec`](crate::MiniVec) that can only be indexed by
+//! its own index type, along with the [`Idx`](Idx) trait used to describe such index types.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::MiniVec;
+
+use core::convert::TryFrom;
+
+/// `Idx` describes a type that can be used as a dense index into a [`MiniIndexVec`](MiniIndexVec) or a
+/// [`MiniVecMap`](crate::MiniVecMap). It is typically implemented by small newtypes around an integer.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct NodeId(u32);
+///
+/// impl minivec::Idx for NodeId {
+///     fn new(idx: usize) -> Self {
+///         NodeId(u32::try_from(idx).unwrap())
+///     }
+///
+///     fn index(self) -> usize {
+///         self.0 as usize
+///     }
+/// }
+///
+/// let mut nodes = minivec::MiniIndexVec::<NodeId, &str>::new();
+/// let root = nodes.push("root");
+///
+/// assert_eq!(root, NodeId(0));
+/// assert_eq!(nodes[root], "root");
+/// ```
+///
+pub trait Idx: Copy {
+  /// `new` constructs the index type from a raw `usize` offset.
+  ///
+  fn new(idx: usize) -> Self;
+
+  /// `index` returns the raw `usize` offset this index refers to.
+  ///
+  fn index(self) -> usize;
+
+  /// `try_new` constructs the index type from a raw `usize` offset, returning `None` when the index type can't
+  /// represent it. Deserializing a [`MiniVecMap`](crate::MiniVecMap) uses it to reject such keys instead of panicking.
+  ///
+  /// The default implementation forwards to [`new`](Idx::new) so index types narrower than `usize` should override it.
+  ///
+  #[must_use]
+  fn try_new(idx: usize) -> Option<Self> {
+    Some(Self::new(idx))
+  }
+}
+
+impl Idx for usize {
+  fn new(idx: usize) -> Self {
+    idx
+  }
+
+  fn index(self) -> usize {
+    self
+  }
+}
+
+impl Idx for u32 {
+  fn new(idx: usize) -> Self {
+    u32::try_from(idx).expect("index exceeded u32::MAX")
+  }
+
+  fn index(self) -> usize {
+    self as usize
+  }
+
+  fn try_new(idx: usize) -> Option<Self> {
+    u32::try_from(idx).ok()
+  }
+}
+
+/// `MiniIndexVec` is a [`MiniVec`](crate::MiniVec) that can only be indexed by the index type `I`, saving users from
+/// writing `.0 as usize` at every access. Like `MiniVec`, it is only the size of a single pointer.
+///
+/// `MiniIndexVec` intentionally does not implement `Deref<Target = [T]>` so that it can't accidentally be indexed by
+/// a plain `usize`. Use [`as_slice`](MiniIndexVec::as_slice) or [`raw`](MiniIndexVec::raw) to opt out of the typed
+/// interface.
+///
+pub struct MiniIndexVec<I, T> {
+  raw: MiniVec<T>,
+  marker: core::marker::PhantomData<fn(&I)>,
+}
+
+impl<I: Idx, T> MiniIndexVec<I, T> {
+  /// `MiniIndexVec::new` constructs an empty vector.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::MiniIndexVec::<u32, i32>::new();
+  /// assert!(vec.is_empty());
+  /// ```
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniIndexVec::from_raw(MiniVec::new())
+  }
+
+  /// `with_capacity` constructs an empty vector with space for `capacity` elements.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::MiniIndexVec::<u32, i32>::with_capacity(16);
+  /// assert_eq!(vec.capacity(), 16);
+  /// ```
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniIndexVec::from_raw(MiniVec::with_capacity(capacity))
+  }
+
+  /// `from_raw` wraps an existing `MiniVec`, reinterpreting its positions as indices of type `I`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::MiniIndexVec::<u32, _>::from_raw(minivec::mini_vec![1, 2, 3]);
+  /// assert_eq!(vec[2_u32], 3);
+  /// ```
+  ///
+  #[must_use]
+  pub fn from_raw(raw: MiniVec<T>) -> Self {
+    MiniIndexVec {
+      raw,
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `as_slice` returns the elements as a plain slice, indexable by `usize`.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    &self.raw
+  }
+
+  /// `as_mut_slice` returns the elements as a plain mutable slice, indexable by `usize`.
+  ///
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    &mut self.raw
+  }
+
+  /// `capacity` returns the number of elements the vector can hold before it needs to reallocate.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.raw.capacity()
+  }
+
+  /// `clear` removes every element from the vector.
+  ///
+  pub fn clear(&mut self) {
+    self.raw.clear();
+  }
+
+  /// `get` returns a reference to the element at `index`, or `None` if it's out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec: minivec::MiniIndexVec<u32, _> = vec![10, 20].into_iter().collect();
+  ///
+  /// assert_eq!(vec.get(1), Some(&20));
+  /// assert_eq!(vec.get(2), None);
+  /// ```
+  ///
+  pub fn get(&self, index: I) -> Option<&T> {
+    self.raw.get(index.index())
+  }
+
+  /// `get_mut` returns a mutable reference to the element at `index`, or `None` if it's out of bounds.
+  ///
+  pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+    self.raw.get_mut(index.index())
+  }
+
+  /// `indices` returns an iterator over every valid index of the vector.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec: minivec::MiniIndexVec<u32, _> = vec!["a", "b", "c"].into_iter().collect();
+  /// assert_eq!(vec.indices().collect::<Vec<_>>(), [0, 1, 2]);
+  /// ```
+  ///
+  pub fn indices(&self) -> core::iter::Map<core::ops::Range<usize>, fn(usize) -> I> {
+    (0..self.len()).map(I::new)
+  }
+
+  /// `into_raw` unwraps the underlying `MiniVec`.
+  ///
+  #[must_use]
+  pub fn into_raw(self) -> MiniVec<T> {
+    self.raw
+  }
+
+  /// `is_empty` returns whether or not the vector contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.raw.is_empty()
+  }
+
+  /// `iter` returns an iterator over the elements of the vector.
+  ///
+  pub fn iter(&self) -> core::slice::Iter<'_, T> {
+    self.raw.iter()
+  }
+
+  /// `iter_mut` returns an iterator over mutable references to the elements of the vector.
+  ///
+  pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+    self.raw.iter_mut()
+  }
+
+  /// `iter_enumerated` returns an iterator over the elements of the vector paired with their typed index.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec: minivec::MiniIndexVec<u32, _> = vec!["a", "b"].into_iter().collect();
+  /// let pairs: Vec<_> = vec.iter_enumerated().collect();
+  ///
+  /// assert_eq!(pairs, [(0, &"a"), (1, &"b")]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter_enumerated(
+    &self,
+  ) -> impl DoubleEndedIterator<Item = (I, &T)> + ExactSizeIterator + '_ {
+    self.raw.iter().enumerate().map(|(idx, x)| (I::new(idx), x))
+  }
+
+  /// `iter_enumerated_mut` returns an iterator over mutable references to the elements of the vector paired with
+  /// their typed index.
+  ///
+  pub fn iter_enumerated_mut(
+    &mut self,
+  ) -> impl DoubleEndedIterator<Item = (I, &mut T)> + ExactSizeIterator + '_ {
+    self
+      .raw
+      .iter_mut()
+      .enumerate()
+      .map(|(idx, x)| (I::new(idx), x))
+  }
+
+  /// `last_index` returns the index of the last element, or `None` if the vector is empty.
+  ///
+  #[must_use]
+  pub fn last_index(&self) -> Option<I> {
+    self.len().checked_sub(1).map(I::new)
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.raw.len()
+  }
+
+  /// `next_index` returns the index that the next call to [`push`](MiniIndexVec::push) will return.
+  ///
+  #[must_use]
+  pub fn next_index(&self) -> I {
+    I::new(self.len())
+  }
+
+  /// `pop` removes the last element from the vector and returns it, or `None` if the vector is empty.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    self.raw.pop()
+  }
+
+  /// `push` appends `value` to the back of the vector and returns its index.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniIndexVec::<u32, _>::new();
+  ///
+  /// let a = vec.push("a");
+  /// let b = vec.push("b");
+  ///
+  /// assert_eq!((a, b), (0, 1));
+  /// assert_eq!(vec[b], "b");
+  /// ```
+  ///
+  pub fn push(&mut self, value: T) -> I {
+    let idx = self.next_index();
+    self.raw.push(value);
+    idx
+  }
+
+  /// `raw` returns a reference to the underlying `MiniVec`.
+  ///
+  #[must_use]
+  pub fn raw(&self) -> &MiniVec<T> {
+    &self.raw
+  }
+
+  /// `raw_mut` returns a mutable reference to the underlying `MiniVec`.
+  ///
+  pub fn raw_mut(&mut self) -> &mut MiniVec<T> {
+    &mut self.raw
+  }
+
+  /// `reserve` ensures there is sufficient capacity for `additional` extra elements.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    self.raw.reserve(additional);
+  }
+
+  /// `split_at` divides the vector into two slices at `mid`. The first contains the elements before `mid` and the
+  /// second contains the rest.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `mid` is greater than [`len()`](MiniIndexVec::len).
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec: minivec::MiniIndexVec<u32, _> = vec![1, 2, 3, 4].into_iter().collect();
+  /// let (head, tail) = vec.split_at(1);
+  ///
+  /// assert_eq!(head, [1]);
+  /// assert_eq!(tail, [2, 3, 4]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn split_at(&self, mid: I) -> (&[T], &[T]) {
+    self.raw.split_at(mid.index())
+  }
+
+  /// `split_at_mut` divides the vector into two mutable slices at `mid`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `mid` is greater than [`len()`](MiniIndexVec::len).
+  ///
+  pub fn split_at_mut(&mut self, mid: I) -> (&mut [T], &mut [T]) {
+    self.raw.split_at_mut(mid.index())
+  }
+
+  /// `swap` exchanges the elements at indices `a` and `b`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either index is out of bounds.
+  ///
+  pub fn swap(&mut self, a: I, b: I) {
+    self.raw.swap(a.index(), b.index());
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    self.raw.truncate(len);
+  }
+}
+
+impl<I: Idx, T> core::ops::Index<I> for MiniIndexVec<I, T> {
+  type Output = T;
+
+  fn index(&self, index: I) -> &T {
+    &self.raw[index.index()]
+  }
+}
+
+impl<I: Idx, T> core::ops::IndexMut<I> for MiniIndexVec<I, T> {
+  fn index_mut(&mut self, index: I) -> &mut T {
+    &mut self.raw[index.index()]
+  }
+}
+
+impl<I, T: Clone> Clone for MiniIndexVec<I, T> {
+  fn clone(&self) -> Self {
+    MiniIndexVec {
+      raw: self.raw.clone(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, T: core::fmt::Debug> core::fmt::Debug for MiniIndexVec<I, T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.raw.fmt(f)
+  }
+}
+
+impl<I, T> Default for MiniIndexVec<I, T> {
+  fn default() -> Self {
+    MiniIndexVec {
+      raw: MiniVec::new(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, T: PartialEq> PartialEq for MiniIndexVec<I, T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.raw == other.raw
+  }
+}
+
+impl<I, T: Eq> Eq for MiniIndexVec<I, T> {}
+
+impl<I, T: core::hash::Hash> core::hash::Hash for MiniIndexVec<I, T> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.raw.hash(state);
+  }
+}
+
+impl<I, T> core::iter::Extend<T> for MiniIndexVec<I, T> {
+  fn extend<It>(&mut self, iter: It)
+  where
+    It: IntoIterator<Item = T>,
+  {
+    self.raw.extend(iter);
+  }
+}
+
+impl<I, T> core::iter::FromIterator<T> for MiniIndexVec<I, T> {
+  fn from_iter<It>(iter: It) -> Self
+  where
+    It: IntoIterator<Item = T>,
+  {
+    MiniIndexVec {
+      raw: iter.into_iter().collect(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, T> core::convert::From<MiniVec<T>> for MiniIndexVec<I, T> {
+  fn from(raw: MiniVec<T>) -> Self {
+    MiniIndexVec {
+      raw,
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, T> core::convert::From<MiniIndexVec<I, T>> for MiniVec<T> {
+  fn from(vec: MiniIndexVec<I, T>) -> Self {
+    vec.raw
+  }
+}
+
+impl<I, T> core::iter::IntoIterator for MiniIndexVec<I, T> {
+  type Item = T;
+  type IntoIter = crate::IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.raw.into_iter()
+  }
+}
+
+impl<'a, I, T> core::iter::IntoIterator for &'a MiniIndexVec<I, T> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.raw.iter()
+  }
+}
+
+impl<'a, I, T> core::iter::IntoIterator for &'a mut MiniIndexVec<I, T> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.raw.iter_mut()
+  }
+}
@@ -0,0 +1,406 @@
+//! `interner` contains [`MiniInterner`](MiniInterner), a string interner handing out [`Symbol`](Symbol)s, along
+//! with its iterator.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::hash_map::{make_hash, DefaultHashBuilder, StatelessBuildHasher};
+use crate::MiniVec;
+
+use core::convert::TryFrom;
+
+// An index slot holds `symbol + 1`, leaving zero for empty slots.
+//
+const EMPTY: u32 = 0;
+
+/// `Symbol` is the handle [`MiniInterner::intern`](MiniInterner::intern) hands out for a string. Symbols are numbered
+/// from zero in the order their strings were first interned.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+  /// `as_u32` returns the number of the symbol.
+  ///
+  #[must_use]
+  pub fn as_u32(self) -> u32 {
+    self.0
+  }
+
+  /// `from_u32` constructs the symbol numbered `n`, e.g. when loading symbols which were saved alongside their
+  /// interner. [`resolve`](MiniInterner::resolve) panics if the interner has no such symbol.
+  ///
+  #[must_use]
+  pub fn from_u32(n: u32) -> Symbol {
+    Symbol(n)
+  }
+
+  /// `index` returns the number of the symbol as a `usize`, suitable for indexing side tables.
+  ///
+  #[must_use]
+  pub fn index(self) -> usize {
+    self.0 as usize
+  }
+}
+
+impl From<Symbol> for u32 {
+  fn from(symbol: Symbol) -> Self {
+    symbol.0
+  }
+}
+
+/// `MiniInterner` deduplicates strings, handing out a small copyable [`Symbol`](Symbol) for each distinct string.
+///
+/// All of the string bytes live back to back in a single `MiniVec<u8>` arena, with a `MiniVec<u32>` recording where
+/// each string ends and another `MiniVec<u32>` serving as an open-addressing index from string hashes to symbols. Each
+/// of the three is a single pointer wide, so the interner itself is only three words. Strings are never removed, and
+/// the arena is limited to `u32::MAX` bytes.
+///
+/// As with [`MiniHashMap`](crate::MiniHashMap), the hash builder `S` is never stored and is instead created via
+/// `S::default()` whenever a string needs to be hashed, so it is limited to the
+/// [`StatelessBuildHasher`](crate::hash_map::StatelessBuildHasher) builders.
+///
+/// # Example
+///
+/// ```
+/// let mut interner = minivec::MiniInterner::new();
+///
+/// let foo = interner.intern("foo");
+/// let bar = interner.intern("bar");
+///
+/// assert_eq!(interner.intern("foo"), foo);
+/// assert_ne!(foo, bar);
+/// assert_eq!(interner.resolve(bar), "bar");
+/// assert_eq!(interner.get("baz"), None);
+/// assert_eq!(interner.len(), 2);
+/// ```
+///
+pub struct MiniInterner<S = DefaultHashBuilder> {
+  bytes: MiniVec<u8>,
+  ends: MiniVec<u32>,
+  index: MiniVec<u32>,
+  hasher: core::marker::PhantomData<fn() -> S>,
+}
+
+/// `Iter` is an iterator over the symbols of a [`MiniInterner`](MiniInterner) along with their strings, in symbol
+/// order, created by [`iter`](MiniInterner::iter).
+///
+pub struct Iter<'a> {
+  bytes: &'a [u8],
+  ends: core::slice::Iter<'a, u32>,
+  start: usize,
+  next: u32,
+}
+
+impl<'a> Iterator for Iter<'a> {
+  type Item = (Symbol, &'a str);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let end = *self.ends.next()? as usize;
+    let s = unsafe { core::str::from_utf8_unchecked(&self.bytes[self.start..end]) };
+
+    let symbol = Symbol(self.next);
+    self.start = end;
+    self.next += 1;
+
+    Some((symbol, s))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.ends.size_hint()
+  }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl core::iter::FusedIterator for Iter<'_> {}
+
+impl<S> MiniInterner<S> {
+  fn str_at(&self, idx: usize) -> &str {
+    let start = match idx {
+      0 => 0,
+      _ => self.ends[idx - 1] as usize,
+    };
+    let end = self.ends[idx] as usize;
+
+    unsafe { core::str::from_utf8_unchecked(&self.bytes[start..end]) }
+  }
+
+  /// `byte_len` returns the total length in bytes of every interned string.
+  ///
+  #[must_use]
+  pub fn byte_len(&self) -> usize {
+    self.bytes.len()
+  }
+
+  /// `clear` removes every string from the interner, invalidating every symbol handed out so far but keeping the
+  /// allocated memory.
+  ///
+  pub fn clear(&mut self) {
+    self.bytes.clear();
+    self.ends.clear();
+    self.index.iter_mut().for_each(|slot| *slot = EMPTY);
+  }
+
+  /// `is_empty` returns whether or not the interner holds any strings.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.ends.is_empty()
+  }
+
+  /// `iter` returns an iterator over every symbol and its string, in symbol order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let interner: minivec::MiniInterner = ["a", "b", "a"].iter().copied().collect();
+  ///
+  /// let strs: Vec<_> = interner.iter().map(|(_, s)| s).collect();
+  /// assert_eq!(strs, ["a", "b"]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_> {
+    Iter {
+      bytes: &self.bytes,
+      ends: self.ends.iter(),
+      start: 0,
+      next: 0,
+    }
+  }
+
+  /// `len` returns the number of distinct strings in the interner.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.ends.len()
+  }
+
+  /// `resolve` returns the string `symbol` was handed out for.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `symbol` was not handed out by this interner.
+  ///
+  #[must_use]
+  pub fn resolve(&self, symbol: Symbol) -> &str {
+    let len = self.len();
+    assert!(
+      symbol.index() < len,
+      "symbol (is {}) should be < len (is {})",
+      symbol.0,
+      len
+    );
+
+    self.str_at(symbol.index())
+  }
+
+  /// `try_resolve` returns the string `symbol` was handed out for, or `None` if the interner has no such symbol.
+  ///
+  #[must_use]
+  pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+    if symbol.index() < self.len() {
+      Some(self.str_at(symbol.index()))
+    } else {
+      None
+    }
+  }
+}
+
+impl MiniInterner<DefaultHashBuilder> {
+  /// `new` constructs an empty interner.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniInterner::default()
+  }
+
+  /// `with_capacity` constructs an empty interner with room for at least `symbols` strings totalling `bytes` bytes.
+  ///
+  #[must_use]
+  pub fn with_capacity(symbols: usize, bytes: usize) -> Self {
+    let mut interner = MiniInterner::new();
+    interner.reserve(symbols, bytes);
+    interner
+  }
+}
+
+impl<S: StatelessBuildHasher> MiniInterner<S> {
+  // Returns the symbol of `s` if it's been interned, otherwise the empty index slot it would be placed in.
+  //
+  fn probe(&self, hash: u64, s: &str) -> Result<Symbol, usize> {
+    debug_assert!(self.index.len().is_power_of_two());
+
+    let mask = self.index.len() - 1;
+    #[allow(clippy::cast_possible_truncation)]
+    let mut pos = hash as usize & mask;
+
+    loop {
+      match self.index[pos] {
+        EMPTY => return Err(pos),
+        slot if self.str_at(slot as usize - 1) == s => return Ok(Symbol(slot - 1)),
+        _ => pos = (pos + 1) & mask,
+      }
+    }
+  }
+
+  // Returns the first empty index slot along the probe sequence of `hash`.
+  //
+  fn empty_slot(&self, hash: u64) -> usize {
+    let mask = self.index.len() - 1;
+    #[allow(clippy::cast_possible_truncation)]
+    let mut pos = hash as usize & mask;
+
+    while self.index[pos] != EMPTY {
+      pos = (pos + 1) & mask;
+    }
+
+    pos
+  }
+
+  // Rebuilds the index with room for `symbols` strings while keeping at least one slot in four empty.
+  //
+  fn rehash(&mut self, symbols: usize) {
+    let buckets = symbols
+      .checked_add(symbols / 3)
+      .and_then(|n| n.checked_add(1))
+      .and_then(usize::checked_next_power_of_two)
+      .expect("capacity overflow");
+    let buckets = core::cmp::max(buckets, 8);
+
+    let mut index = MiniVec::with_capacity(buckets);
+    index.resize(buckets, EMPTY);
+
+    let old = core::mem::replace(&mut self.index, index);
+    for slot in old.into_iter().filter(|&slot| slot != EMPTY) {
+      let hash = make_hash::<str, S>(self.str_at(slot as usize - 1));
+      let pos = self.empty_slot(hash);
+      self.index[pos] = slot;
+    }
+  }
+
+  /// `get` returns the symbol of `s` if it has been interned.
+  ///
+  #[must_use]
+  pub fn get(&self, s: &str) -> Option<Symbol> {
+    if self.index.is_empty() {
+      return None;
+    }
+
+    self.probe(make_hash::<str, S>(s), s).ok()
+  }
+
+  /// `intern` returns the symbol of `s`, copying it into the interner first if it hasn't been interned before.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the interned strings would exceed `u32::MAX` bytes in total, or if there would be more than
+  /// `u32::MAX` of them.
+  ///
+  pub fn intern(&mut self, s: &str) -> Symbol {
+    let hash = make_hash::<str, S>(s);
+    if !self.index.is_empty() {
+      if let Ok(symbol) = self.probe(hash, s) {
+        return symbol;
+      }
+    }
+
+    let symbol = u32::try_from(self.len())
+      .ok()
+      .filter(|&symbol| symbol < u32::MAX)
+      .expect("interner symbols exceeded u32::MAX");
+    let end = u32::try_from(self.bytes.len() + s.len()).expect("interner bytes exceeded u32::MAX");
+
+    if (self.len() + 1) * 4 > self.index.len() * 3 {
+      self.rehash(self.len() + 1);
+    }
+
+    let pos = self.empty_slot(hash);
+    self.bytes.extend_from_slice(s.as_bytes());
+    self.ends.push(end);
+    self.index[pos] = symbol + 1;
+
+    Symbol(symbol)
+  }
+
+  /// `reserve` ensures there is room for at least `symbols` more strings totalling `bytes` more bytes.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve(&mut self, symbols: usize, bytes: usize) {
+    self.bytes.reserve(bytes);
+    self.ends.reserve(symbols);
+
+    let required = self.len().checked_add(symbols).expect("capacity overflow");
+    if required * 4 > self.index.len() * 3 {
+      self.rehash(required);
+    }
+  }
+}
+
+impl<S> Clone for MiniInterner<S> {
+  fn clone(&self) -> Self {
+    MiniInterner {
+      bytes: self.bytes.clone(),
+      ends: self.ends.clone(),
+      index: self.index.clone(),
+      hasher: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<S> core::fmt::Debug for MiniInterner<S> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_map()
+      .entries(self.iter().map(|(symbol, s)| (symbol.0, s)))
+      .finish()
+  }
+}
+
+impl<S> Default for MiniInterner<S> {
+  fn default() -> Self {
+    MiniInterner {
+      bytes: MiniVec::new(),
+      ends: MiniVec::new(),
+      index: MiniVec::new(),
+      hasher: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<S> core::ops::Index<Symbol> for MiniInterner<S> {
+  type Output = str;
+
+  fn index(&self, symbol: Symbol) -> &str {
+    self.resolve(symbol)
+  }
+}
+
+impl<'a, S: StatelessBuildHasher> core::iter::Extend<&'a str> for MiniInterner<S> {
+  fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+    for s in iter {
+      self.intern(s);
+    }
+  }
+}
+
+impl<'a, S: StatelessBuildHasher> core::iter::FromIterator<&'a str> for MiniInterner<S> {
+  fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+    let mut interner = MiniInterner::default();
+    interner.extend(iter);
+    interner
+  }
+}
+
+impl<'a, S> IntoIterator for &'a MiniInterner<S> {
+  type Item = (Symbol, &'a str);
+  type IntoIter = Iter<'a>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
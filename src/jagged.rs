@@ -0,0 +1,882 @@
+//! `jagged` contains [`MiniJagged`](MiniJagged), a ragged array of variable-length rows stored in compressed sparse
+//! row form, along with [`MiniStrVec`](MiniStrVec), its specialization for packed strings.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::next_aligned;
+use crate::{is_sentinel, sentinel, MiniVec};
+
+use core::ptr::NonNull;
+
+extern crate alloc;
+
+// The allocation starts with a `JaggedHeader` followed by `row_cap` row offsets and then `value_cap` values. Row `i`
+// spans the values from the end of row `i - 1` up to `offsets[i]`.
+//
+#[derive(Clone, Copy, Default)]
+struct JaggedHeader {
+  rows: usize,
+  row_cap: usize,
+  values: usize,
+  value_cap: usize,
+}
+
+fn alignment<T>() -> usize {
+  core::cmp::max(
+    core::mem::align_of::<JaggedHeader>(),
+    core::mem::align_of::<T>(),
+  )
+}
+
+fn values_offset<T>(row_cap: usize) -> usize {
+  let offsets_len = row_cap
+    .checked_mul(core::mem::size_of::<usize>())
+    .expect("capacity overflow");
+
+  next_aligned(
+    core::mem::size_of::<JaggedHeader>() + offsets_len,
+    core::mem::align_of::<T>(),
+  )
+}
+
+fn make_layout<T>(row_cap: usize, value_cap: usize) -> alloc::alloc::Layout {
+  let num_bytes = value_cap
+    .checked_mul(core::mem::size_of::<T>())
+    .and_then(|n| n.checked_add(values_offset::<T>(row_cap)))
+    .expect("capacity overflow");
+
+  alloc::alloc::Layout::from_size_align(num_bytes, alignment::<T>()).unwrap()
+}
+
+/// `MiniJagged` is a ragged array, i.e. a sequence of rows of varying length, which stores every row back to back in
+/// a single allocation along with an array of row offsets. Compared to a `MiniVec<MiniVec<T>>` it needs no
+/// allocation or header per row, which suits adjacency lists and token lists made of many short rows. Like
+/// `MiniVec`, it is only the size of a single pointer.
+///
+/// Rows can only be added or removed at the end, though the values of any row can be modified in place.
+///
+/// # Example
+///
+/// ```
+/// let mut adjacency = minivec::MiniJagged::new();
+///
+/// adjacency.push_row([1, 2]);
+/// adjacency.push_row([]);
+/// adjacency.push_row([0]);
+///
+/// assert_eq!(adjacency.len(), 3);
+/// assert_eq!(adjacency[0], [1, 2]);
+/// assert!(adjacency[1].is_empty());
+///
+/// adjacency.extend_last_row([1]);
+/// assert_eq!(adjacency[2], [0, 1]);
+/// ```
+///
+pub struct MiniJagged<T> {
+  buf: NonNull<u8>,
+  phantom: core::marker::PhantomData<T>,
+}
+
+/// `Rows` is an iterator over the rows of a [`MiniJagged`](MiniJagged), created by
+/// [`iter_rows`](MiniJagged::iter_rows).
+///
+pub struct Rows<'a, T> {
+  jagged: &'a MiniJagged<T>,
+  front: usize,
+  back: usize,
+}
+
+// Drops the values of a row which was only partially pushed should its iterator panic, so that they don't end up
+// in the next row.
+//
+struct PendingRow<'a, T> {
+  jagged: &'a mut MiniJagged<T>,
+  start: usize,
+}
+
+impl<T> Drop for PendingRow<'_, T> {
+  fn drop(&mut self) {
+    self.jagged.truncate_values(self.start);
+  }
+}
+
+impl<T> MiniJagged<T> {
+  fn is_allocated(&self) -> bool {
+    !is_sentinel(self.buf.as_ptr())
+  }
+
+  fn header(&self) -> JaggedHeader {
+    if self.is_allocated() {
+      #[allow(clippy::cast_ptr_alignment)]
+      unsafe {
+        *self.buf.as_ptr().cast::<JaggedHeader>()
+      }
+    } else {
+      JaggedHeader::default()
+    }
+  }
+
+  fn header_mut(&mut self) -> &mut JaggedHeader {
+    debug_assert!(self.is_allocated());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &mut *self.buf.as_ptr().cast::<JaggedHeader>()
+    }
+  }
+
+  fn offsets(&self) -> *mut usize {
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      self
+        .buf
+        .as_ptr()
+        .add(core::mem::size_of::<JaggedHeader>())
+        .cast::<usize>()
+    }
+  }
+
+  fn data(&self) -> *mut T {
+    if self.is_allocated() {
+      #[allow(clippy::cast_ptr_alignment)]
+      unsafe {
+        self
+          .buf
+          .as_ptr()
+          .add(values_offset::<T>(self.header().row_cap))
+          .cast::<T>()
+      }
+    } else {
+      NonNull::dangling().as_ptr()
+    }
+  }
+
+  fn row_range(&self, idx: usize) -> (usize, usize) {
+    debug_assert!(idx < self.len());
+
+    let start = if idx == 0 {
+      0
+    } else {
+      unsafe { *self.offsets().add(idx - 1) }
+    };
+
+    (start, unsafe { *self.offsets().add(idx) })
+  }
+
+  fn committed_values(&self) -> usize {
+    match self.len() {
+      0 => 0,
+      rows => unsafe { *self.offsets().add(rows - 1) },
+    }
+  }
+
+  fn grow(&mut self, row_cap: usize, value_cap: usize) {
+    let new_layout = make_layout::<T>(row_cap, value_cap);
+
+    if !self.is_allocated() {
+      let buf = unsafe { alloc::alloc::alloc(new_layout) };
+      if buf.is_null() {
+        alloc::alloc::handle_alloc_error(new_layout);
+      }
+
+      self.buf = unsafe { NonNull::new_unchecked(buf) };
+      *self.header_mut() = JaggedHeader {
+        rows: 0,
+        row_cap,
+        values: 0,
+        value_cap,
+      };
+
+      return;
+    }
+
+    let old = self.header();
+    debug_assert!(row_cap >= old.row_cap && value_cap >= old.value_cap);
+
+    let old_layout = make_layout::<T>(old.row_cap, old.value_cap);
+    let buf = unsafe { alloc::alloc::realloc(self.buf.as_ptr(), old_layout, new_layout.size()) };
+    if buf.is_null() {
+      alloc::alloc::handle_alloc_error(new_layout);
+    }
+
+    self.buf = unsafe { NonNull::new_unchecked(buf) };
+
+    // more room for offsets pushes the values further back
+    //
+    let old_offset = values_offset::<T>(old.row_cap);
+    let new_offset = values_offset::<T>(row_cap);
+    if new_offset != old_offset {
+      unsafe {
+        core::ptr::copy(
+          buf.add(old_offset).cast::<T>(),
+          buf.add(new_offset).cast::<T>(),
+          old.values,
+        );
+      }
+    }
+
+    let header = self.header_mut();
+    header.row_cap = row_cap;
+    header.value_cap = value_cap;
+  }
+
+  // Pushes a value after the last row without adding it to any row yet.
+  //
+  fn push_value(&mut self, value: T) {
+    if self.header().values == self.value_capacity() {
+      self.reserve_values(1);
+    }
+
+    unsafe {
+      let values = self.header().values;
+      self.data().add(values).write(value);
+      self.header_mut().values = values + 1;
+    }
+  }
+
+  fn truncate_values(&mut self, len: usize) {
+    let values = self.header().values;
+    if len >= values {
+      return;
+    }
+
+    unsafe {
+      self.header_mut().values = len;
+      core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+        self.data().add(len),
+        values - len,
+      ));
+    }
+  }
+
+  /// `clear` removes every row, keeping the allocation.
+  ///
+  pub fn clear(&mut self) {
+    self.truncate(0);
+  }
+
+  /// `extend_last_row` appends the values of `iter` to the last row.
+  ///
+  /// # Panics
+  ///
+  /// Panics if there are no rows.
+  ///
+  pub fn extend_last_row<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    let last = self.len().checked_sub(1).expect("no row to extend");
+
+    for value in iter {
+      self.push_value(value);
+      unsafe { *self.offsets().add(last) += 1 };
+    }
+  }
+
+  /// `get_row` returns the row at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  #[must_use]
+  pub fn get_row(&self, idx: usize) -> Option<&[T]> {
+    if idx >= self.len() {
+      return None;
+    }
+
+    let (start, end) = self.row_range(idx);
+    Some(unsafe { core::slice::from_raw_parts(self.data().add(start), end - start) })
+  }
+
+  /// `get_row_mut` returns the row at `idx` mutably, or `None` if `idx` is out of bounds.
+  ///
+  pub fn get_row_mut(&mut self, idx: usize) -> Option<&mut [T]> {
+    if idx >= self.len() {
+      return None;
+    }
+
+    let (start, end) = self.row_range(idx);
+    Some(unsafe { core::slice::from_raw_parts_mut(self.data().add(start), end - start) })
+  }
+
+  /// `is_empty` returns whether or not there are any rows.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `iter_rows` returns an iterator over the rows.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let tokens: minivec::MiniJagged<_> = vec![vec!["let", "x"], vec!["x"]].into_iter().collect();
+  ///
+  /// let lens: Vec<usize> = tokens.iter_rows().map(<[_]>::len).collect();
+  /// assert_eq!(lens, [2, 1]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter_rows(&self) -> Rows<'_, T> {
+    Rows {
+      jagged: self,
+      front: 0,
+      back: self.len(),
+    }
+  }
+
+  /// `len` returns the number of rows.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.header().rows
+  }
+
+  /// `new` constructs an empty ragged array.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `T` is zero-sized, as with [`MiniVec`](MiniVec).
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    assert!(
+      core::mem::size_of::<T>() > 0,
+      "ZSTs currently not supported"
+    );
+
+    MiniJagged {
+      buf: sentinel(alignment::<T>()),
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  /// `pop_row` removes the last row and returns its values, or `None` if there are no rows.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut jagged = minivec::MiniJagged::new();
+  /// jagged.push_row([1, 2, 3]);
+  ///
+  /// assert_eq!(jagged.pop_row().unwrap(), [1, 2, 3]);
+  /// assert_eq!(jagged.pop_row(), None);
+  /// ```
+  ///
+  pub fn pop_row(&mut self) -> Option<MiniVec<T>> {
+    let last = self.len().checked_sub(1)?;
+    let (start, end) = self.row_range(last);
+
+    let mut row = MiniVec::with_capacity(end - start);
+    unsafe {
+      let header = self.header_mut();
+      header.rows = last;
+      header.values = start;
+
+      // an empty `MiniVec` has no header to record the length in
+      //
+      if end > start {
+        core::ptr::copy_nonoverlapping(self.data().add(start), row.as_mut_ptr(), end - start);
+        row.set_len(end - start);
+      }
+    }
+
+    Some(row)
+  }
+
+  /// `push_row` appends a new row holding the values of `iter`.
+  ///
+  pub fn push_row<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    let iter = iter.into_iter();
+
+    if self.len() == self.row_capacity() {
+      self.reserve_rows(1);
+    }
+    self.reserve_values(iter.size_hint().0);
+
+    let start = self.committed_values();
+    let mut pending = PendingRow {
+      jagged: self,
+      start,
+    };
+
+    for value in iter {
+      pending.jagged.push_value(value);
+    }
+
+    // committing the row moves the guard's watermark past its values so they're kept
+    //
+    let jagged = &mut *pending.jagged;
+    unsafe {
+      let rows = jagged.len();
+      let end = jagged.header().values;
+      *jagged.offsets().add(rows) = end;
+      jagged.header_mut().rows = rows + 1;
+      pending.start = end;
+    }
+  }
+
+  /// `reserve_rows` makes room for at least `additional` more rows without reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve_rows(&mut self, additional: usize) {
+    let header = self.header();
+    let needed = header
+      .rows
+      .checked_add(additional)
+      .expect("capacity overflow");
+    if needed <= header.row_cap {
+      return;
+    }
+
+    let row_cap = core::cmp::max(needed, 2 * header.row_cap).max(4);
+    self.grow(row_cap, header.value_cap);
+  }
+
+  /// `reserve_values` makes room for at least `additional` more values without reallocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve_values(&mut self, additional: usize) {
+    let header = self.header();
+    let needed = header
+      .values
+      .checked_add(additional)
+      .expect("capacity overflow");
+    if needed <= header.value_cap {
+      return;
+    }
+
+    let value_cap = core::cmp::max(needed, 2 * header.value_cap).max(8);
+    self.grow(header.row_cap, value_cap);
+  }
+
+  /// `row` returns the row at `idx`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `idx` is out of bounds.
+  ///
+  #[must_use]
+  pub fn row(&self, idx: usize) -> &[T] {
+    let len = self.len();
+    match self.get_row(idx) {
+      Some(row) => row,
+      None => panic!(
+        "row index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+
+  /// `row_capacity` returns the number of rows the array can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn row_capacity(&self) -> usize {
+    self.header().row_cap
+  }
+
+  /// `row_mut` returns the row at `idx` mutably.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `idx` is out of bounds.
+  ///
+  pub fn row_mut(&mut self, idx: usize) -> &mut [T] {
+    let len = self.len();
+    match self.get_row_mut(idx) {
+      Some(row) => row,
+      None => panic!(
+        "row index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+
+  /// `truncate` removes every row past the first `len`, dropping their values.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    if len >= self.len() {
+      return;
+    }
+
+    let end = match len {
+      0 => 0,
+      len => self.row_range(len - 1).1,
+    };
+
+    self.header_mut().rows = len;
+    self.truncate_values(end);
+  }
+
+  /// `value_capacity` returns the number of values the array can hold across all rows without reallocating.
+  ///
+  #[must_use]
+  pub fn value_capacity(&self) -> usize {
+    self.header().value_cap
+  }
+
+  /// `values` returns the values of every row as one contiguous slice.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut jagged = minivec::MiniJagged::new();
+  /// jagged.push_row([1, 2]);
+  /// jagged.push_row([3]);
+  ///
+  /// assert_eq!(jagged.values(), [1, 2, 3]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn values(&self) -> &[T] {
+    unsafe { core::slice::from_raw_parts(self.data(), self.committed_values()) }
+  }
+
+  /// `values_mut` returns the values of every row as one contiguous mutable slice.
+  ///
+  pub fn values_mut(&mut self) -> &mut [T] {
+    unsafe { core::slice::from_raw_parts_mut(self.data(), self.committed_values()) }
+  }
+
+  /// `with_capacity` constructs an empty ragged array with room for `rows` rows holding `values` values in total.
+  ///
+  #[must_use]
+  pub fn with_capacity(rows: usize, values: usize) -> Self {
+    let mut jagged = MiniJagged::new();
+    if rows > 0 || values > 0 {
+      jagged.grow(rows, values);
+    }
+    jagged
+  }
+}
+
+impl<T> Drop for MiniJagged<T> {
+  fn drop(&mut self) {
+    if !self.is_allocated() {
+      return;
+    }
+
+    let header = self.header();
+    unsafe {
+      core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+        self.data(),
+        header.values,
+      ));
+      alloc::alloc::dealloc(
+        self.buf.as_ptr(),
+        make_layout::<T>(header.row_cap, header.value_cap),
+      );
+    }
+  }
+}
+
+impl<T: Clone> Clone for MiniJagged<T> {
+  fn clone(&self) -> Self {
+    let mut jagged = MiniJagged::with_capacity(self.len(), self.values().len());
+    for row in self.iter_rows() {
+      jagged.push_row(row.iter().cloned());
+    }
+    jagged
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniJagged<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter_rows()).finish()
+  }
+}
+
+impl<T> Default for MiniJagged<T> {
+  fn default() -> Self {
+    MiniJagged::new()
+  }
+}
+
+impl<T: PartialEq> PartialEq for MiniJagged<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter_rows().eq(other.iter_rows())
+  }
+}
+
+impl<T: Eq> Eq for MiniJagged<T> {}
+
+impl<T> core::ops::Index<usize> for MiniJagged<T> {
+  type Output = [T];
+
+  fn index(&self, idx: usize) -> &[T] {
+    self.row(idx)
+  }
+}
+
+impl<T> core::ops::IndexMut<usize> for MiniJagged<T> {
+  fn index_mut(&mut self, idx: usize) -> &mut [T] {
+    self.row_mut(idx)
+  }
+}
+
+impl<T> From<MiniVec<MiniVec<T>>> for MiniJagged<T> {
+  fn from(rows: MiniVec<MiniVec<T>>) -> Self {
+    let values = rows.iter().map(MiniVec::len).sum();
+
+    let mut jagged = MiniJagged::with_capacity(rows.len(), values);
+    for row in rows {
+      jagged.push_row(row);
+    }
+    jagged
+  }
+}
+
+impl<T, R: IntoIterator<Item = T>> core::iter::Extend<R> for MiniJagged<T> {
+  fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+    for row in iter {
+      self.push_row(row);
+    }
+  }
+}
+
+impl<T, R: IntoIterator<Item = T>> core::iter::FromIterator<R> for MiniJagged<T> {
+  fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+    let mut jagged = MiniJagged::new();
+    jagged.extend(iter);
+    jagged
+  }
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+  type Item = &'a [T];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.front == self.back {
+      return None;
+    }
+
+    let row = self.jagged.get_row(self.front);
+    self.front += 1;
+    row
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let remaining = self.back - self.front;
+    (remaining, Some(remaining))
+  }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.front == self.back {
+      return None;
+    }
+
+    self.back -= 1;
+    self.jagged.get_row(self.back)
+  }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for MiniJagged<T> {}
+unsafe impl<T: core::marker::Sync> core::marker::Sync for MiniJagged<T> {}
+
+/// `MiniStrVec` is a list of strings packed back to back into a single allocation, built on a
+/// [`MiniJagged<u8>`](MiniJagged). It avoids the allocation and capacity slack of every individual `String` in a
+/// `Vec<String>` while still being only the size of a single pointer.
+///
+/// # Example
+///
+/// ```
+/// let mut names = minivec::MiniStrVec::new();
+/// names.push("alice");
+/// names.push("bob");
+///
+/// assert_eq!(&names[1], "bob");
+/// assert_eq!(names.iter().collect::<Vec<_>>(), ["alice", "bob"]);
+/// ```
+///
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct MiniStrVec {
+  raw: MiniJagged<u8>,
+}
+
+/// `Strs` is an iterator over the strings of a [`MiniStrVec`](MiniStrVec), created by
+/// [`iter`](MiniStrVec::iter).
+///
+pub struct Strs<'a> {
+  rows: Rows<'a, u8>,
+}
+
+impl MiniStrVec {
+  /// `as_jagged` returns the underlying ragged array of bytes.
+  ///
+  #[must_use]
+  pub fn as_jagged(&self) -> &MiniJagged<u8> {
+    &self.raw
+  }
+
+  /// `clear` removes every string, keeping the allocation.
+  ///
+  pub fn clear(&mut self) {
+    self.raw.clear();
+  }
+
+  /// `get` returns the string at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  #[must_use]
+  pub fn get(&self, idx: usize) -> Option<&str> {
+    self
+      .raw
+      .get_row(idx)
+      .map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+  }
+
+  /// `is_empty` returns whether or not there are any strings.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.raw.is_empty()
+  }
+
+  /// `iter` returns an iterator over the strings.
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Strs<'_> {
+    Strs {
+      rows: self.raw.iter_rows(),
+    }
+  }
+
+  /// `len` returns the number of strings.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.raw.len()
+  }
+
+  /// `new` constructs an empty list of strings.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniStrVec {
+      raw: MiniJagged::new(),
+    }
+  }
+
+  /// `pop` removes the last string and returns it, or `None` if there are no strings.
+  ///
+  pub fn pop(&mut self) -> Option<alloc::string::String> {
+    let last = self.len().checked_sub(1)?;
+    let s = alloc::string::String::from(&self[last]);
+    self.raw.truncate(last);
+    Some(s)
+  }
+
+  /// `push` appends a copy of `s`.
+  ///
+  pub fn push(&mut self, s: &str) {
+    self.raw.push_row(s.bytes());
+  }
+
+  /// `push_str_to_last` appends `s` to the last string.
+  ///
+  /// # Panics
+  ///
+  /// Panics if there are no strings.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut lines = minivec::MiniStrVec::new();
+  /// lines.push("hello");
+  /// lines.push_str_to_last(", world");
+  ///
+  /// assert_eq!(&lines[0], "hello, world");
+  /// ```
+  ///
+  pub fn push_str_to_last(&mut self, s: &str) {
+    self.raw.extend_last_row(s.bytes());
+  }
+
+  /// `truncate` removes every string past the first `len`.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    self.raw.truncate(len);
+  }
+
+  /// `with_capacity` constructs an empty list with room for `strings` strings holding `bytes` bytes in total.
+  ///
+  #[must_use]
+  pub fn with_capacity(strings: usize, bytes: usize) -> Self {
+    MiniStrVec {
+      raw: MiniJagged::with_capacity(strings, bytes),
+    }
+  }
+}
+
+impl core::fmt::Debug for MiniStrVec {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl core::ops::Index<usize> for MiniStrVec {
+  type Output = str;
+
+  fn index(&self, idx: usize) -> &str {
+    unsafe { core::str::from_utf8_unchecked(self.raw.row(idx)) }
+  }
+}
+
+impl<S: AsRef<str>> core::iter::Extend<S> for MiniStrVec {
+  fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+    for s in iter {
+      self.push(s.as_ref());
+    }
+  }
+}
+
+impl<S: AsRef<str>> core::iter::FromIterator<S> for MiniStrVec {
+  fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+    let mut strs = MiniStrVec::new();
+    strs.extend(iter);
+    strs
+  }
+}
+
+impl<'a> core::iter::IntoIterator for &'a MiniStrVec {
+  type Item = &'a str;
+  type IntoIter = Strs<'a>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a> Iterator for Strs<'a> {
+  type Item = &'a str;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .rows
+      .next()
+      .map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.rows.size_hint()
+  }
+}
+
+impl DoubleEndedIterator for Strs<'_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self
+      .rows
+      .next_back()
+      .map(|bytes| unsafe { core::str::from_utf8_unchecked(bytes) })
+  }
+}
+
+impl ExactSizeIterator for Strs<'_> {}
@@ -33,6 +33,33 @@
 //! * [`from_raw_part`](MiniVec::from_raw_part)
 //! * [`drain_vec`](MiniVec::drain_vec)
 //!
+//! This crate also provides other single-pointer containers built on the same header-prefixed allocation strategy:
+//! * [`MiniHashMap`](MiniHashMap) and [`MiniHashSet`](MiniHashSet)
+//! * [`AtomicMiniVec`](AtomicMiniVec), which lets readers share a vector while writers atomically replace it
+//! * [`ConcurrentMiniVec`](ConcurrentMiniVec), an append-only vector which can be pushed to from many threads
+//! * [`BoundedMiniVec`](BoundedMiniVec), whose capacity can never exceed a compile-time limit
+//! * [`MiniBytesMut`](MiniBytesMut) and [`MiniBytes`](MiniBytes), byte buffers which freeze into cheaply sliced shared views (see the `bytes` feature)
+//! * [`MiniCow`](MiniCow), a single-pointer clone-on-write slice which either borrows its elements or owns a `MiniVec`
+//! * [`MiniDynVec`](MiniDynVec), which packs trait objects and other unsized values into a single allocation
+//! * [`ErasedMiniVec`](ErasedMiniVec), whose element type is only described at runtime by a layout and drop and clone functions
+//! * [`MiniGapBuffer`](MiniGapBuffer) and [`MiniGapString`](MiniGapString), which keep their spare capacity at a movable cursor for localized edits
+//! * [`MiniInterner`](MiniInterner), a string interner keeping every string in one byte arena
+//! * [`MiniSmallVec`](MiniSmallVec), which stores a few small elements inline in its pointer word
+//! * [`NonEmptyMiniVec`](NonEmptyMiniVec), which always contains at least one element
+//! * [`MiniJagged`](MiniJagged), a ragged array of rows packed into one allocation, and [`MiniStrVec`](MiniStrVec) for packed strings
+//! * [`MiniMatrix`](MiniMatrix), a dense row-major matrix which keeps its shape in its allocation header
+//! * [`MiniVecPool`](MiniVecPool) and [`SyncMiniVecPool`](SyncMiniVecPool), which recycle the allocations of dropped vectors by capacity class
+//! * [`MiniSegVec`](MiniSegVec), a segmented vector whose elements never move once pushed
+//! * [`MiniSlab`](MiniSlab), a slab allocator handing out stable, optionally generational, keys
+//! * [`soa::RawMiniSoA`](soa::RawMiniSoA), the single-allocation column storage behind `#[derive(MiniSoA)]` struct-of-arrays vectors (see the `derive` feature)
+//! * [`spsc::channel`](spsc::channel), a bounded single-producer/single-consumer queue whose halves share one ring buffer
+//! * [`TaggedMiniVec`](TaggedMiniVec), which stores a small user tag in the spare low bits of its pointer
+//! * [`MiniIndexVec`](MiniIndexVec) and [`MiniVecMap`](MiniVecMap), which are indexed by a user-defined [`Idx`](Idx) type
+//! * [`MiniVecWithHeader`](MiniVecWithHeader), which stores a user-defined header in the same allocation as its elements
+//!
+//! With the `bytemuck` feature enabled, the `cast` module reinterprets vectors of plain old data as vectors of
+//! another such type without copying, reusing the allocation whose header already records its alignment.
+//!
 //! Eventual TODO's:
 //! * add `try_reserve` methods once stable
 //! * add myriad specializations to associated functions such as `FromIterator` once stable
@@ -43,6 +70,33 @@ extern crate alloc;
 
 mod r#impl;
 
+pub mod atomic;
+pub mod bounded;
+pub mod bytes;
+#[cfg(feature = "bytemuck")]
+pub mod cast;
+pub mod concurrent;
+pub mod cow;
+pub mod dyn_vec;
+pub mod erased;
+pub mod gap_buffer;
+pub mod hash_map;
+pub mod hash_set;
+pub mod index_vec;
+pub mod interner;
+pub mod jagged;
+pub mod matrix;
+pub mod non_empty;
+pub mod pool;
+pub mod seg_vec;
+pub mod slab;
+pub mod small_vec;
+pub mod soa;
+pub mod spsc;
+pub mod tagged;
+pub mod vec_map;
+pub mod with_header;
+
 mod as_mut;
 mod as_ref;
 mod borrow;
@@ -62,19 +116,46 @@ mod ord;
 mod partial_eq;
 #[cfg(feature = "serde")]
 mod serde;
+mod sync;
 
 use crate::r#impl::drain::make_drain_iterator;
 use crate::r#impl::drain_filter::make_drain_filter_iterator;
 use crate::r#impl::helpers::{make_layout, max_align, next_aligned, next_capacity};
 use crate::r#impl::splice::make_splice_iterator;
 
+pub use crate::atomic::AtomicMiniVec;
+pub use crate::bounded::BoundedMiniVec;
+pub use crate::bytes::{MiniBytes, MiniBytesMut};
+pub use crate::concurrent::ConcurrentMiniVec;
+pub use crate::cow::MiniCow;
+pub use crate::dyn_vec::MiniDynVec;
+pub use crate::erased::ErasedMiniVec;
+pub use crate::gap_buffer::{MiniGapBuffer, MiniGapString};
+pub use crate::hash_map::MiniHashMap;
+pub use crate::hash_set::MiniHashSet;
+pub use crate::index_vec::{Idx, MiniIndexVec};
+pub use crate::interner::{MiniInterner, Symbol};
+pub use crate::jagged::{MiniJagged, MiniStrVec};
+pub use crate::matrix::MiniMatrix;
+pub use crate::non_empty::NonEmptyMiniVec;
+pub use crate::pool::{MiniVecPool, PooledMiniVec, SyncMiniVecPool};
 pub use crate::r#impl::{Drain, DrainFilter, IntoIter, Splice};
+pub use crate::seg_vec::MiniSegVec;
+pub use crate::slab::{GenKey, MiniSlab, SlabKey};
+pub use crate::small_vec::MiniSmallVec;
+pub use crate::tagged::TaggedMiniVec;
+pub use crate::vec_map::MiniVecMap;
+pub use crate::with_header::MiniVecWithHeader;
+
+#[cfg(feature = "derive")]
+pub use minivec_derive::MiniSoA;
 
 /// `MiniVec` is a space-optimized implementation of `alloc::vec::Vec` that is only the size of a single pointer and
 /// also extends portions of its API, including support for over-aligned allocations. `MiniVec` also aims to bring as
 /// many Nightly features from `Vec` to stable toolchains as is possible. In many cases, it is a drop-in replacement
 /// for the "real" `Vec`.
 ///
+#[repr(transparent)]
 pub struct MiniVec<T> {
   buf: core::ptr::NonNull<u8>,
   phantom: core::marker::PhantomData<T>,
@@ -119,12 +200,48 @@ fn header_clone() {
   assert_eq!(header2.alignment, header.alignment);
 }
 
-static DEFAULT_U8: u8 = 137;
+// Vectors without an allocation point into this table instead of the heap. The sentinel for an alignment `A` is
+// `A` bytes past the start of the table so it's aligned to exactly `A`, which lets empty vectors of any alignment
+// up to `MAX_SENTINEL_ALIGNMENT` remember it without allocating. The table itself is never read from or written to.
+//
+const MAX_SENTINEL_ALIGNMENT: usize = 4096;
+
+#[repr(C, align(4096))]
+struct Sentinels([u8; MAX_SENTINEL_ALIGNMENT]);
+
+static SENTINELS: Sentinels = Sentinels([0; MAX_SENTINEL_ALIGNMENT]);
+
+fn sentinel(alignment: usize) -> core::ptr::NonNull<u8> {
+  debug_assert!(alignment.is_power_of_two());
+
+  let offset = if alignment < MAX_SENTINEL_ALIGNMENT {
+    alignment
+  } else {
+    0
+  };
+
+  let base = core::ptr::addr_of!(SENTINELS).cast::<u8>().cast_mut();
+  unsafe { core::ptr::NonNull::new_unchecked(base.add(offset)) }
+}
+
+fn is_sentinel(ptr: *const u8) -> bool {
+  let base = core::ptr::addr_of!(SENTINELS).cast::<u8>();
+  ptr.addr().wrapping_sub(base.addr()) < MAX_SENTINEL_ALIGNMENT
+}
+
+fn sentinel_alignment(ptr: *const u8) -> usize {
+  debug_assert!(is_sentinel(ptr));
+
+  let base = core::ptr::addr_of!(SENTINELS).cast::<u8>();
+  match ptr.addr() - base.addr() {
+    0 => MAX_SENTINEL_ALIGNMENT,
+    offset => offset,
+  }
+}
 
 impl<T> MiniVec<T> {
-  #[allow(clippy::cast_ptr_alignment)]
   fn is_default(&self) -> bool {
-    core::ptr::eq(self.buf.as_ptr(), &DEFAULT_U8)
+    is_sentinel(self.buf.as_ptr())
   }
 
   fn header(&self) -> &Header {
@@ -148,9 +265,28 @@ impl<T> MiniVec<T> {
     unsafe { self.buf.as_ptr().add(count).cast::<T>() }
   }
 
+  // Returns the capacity the allocation would have as a vector of `U`, provided it's suitably aligned for `U` and its
+  // layout is exactly the one that vector would deallocate with.
+  //
+  fn recycled_capacity<U>(&self) -> Option<usize> {
+    debug_assert!(!self.is_default());
+    debug_assert!(core::mem::size_of::<U>() > 0);
+
+    let Header { cap, alignment, .. } = *self.header();
+    let capacity = cap * core::mem::size_of::<T>() / core::mem::size_of::<U>();
+
+    if alignment < max_align::<U>()
+      || make_layout::<U>(capacity, alignment) != make_layout::<T>(cap, alignment)
+    {
+      return None;
+    }
+
+    Some(capacity)
+  }
+
   fn alignment(&self) -> usize {
-    if self.capacity() == 0 {
-      max_align::<T>()
+    if self.is_default() {
+      core::cmp::max(sentinel_alignment(self.buf.as_ptr()), max_align::<T>())
     } else {
       self.header().alignment
     }
@@ -162,7 +298,21 @@ impl<T> MiniVec<T> {
     let old_capacity = self.capacity();
     let new_capacity = capacity;
 
-    if new_capacity == old_capacity {
+    // a vector without any capacity only needs a sentinel to remember its alignment so any allocation it has can be
+    // released
+    //
+    if new_capacity == 0 && alignment.is_power_of_two() && alignment <= MAX_SENTINEL_ALIGNMENT {
+      if !self.is_default() {
+        unsafe {
+          alloc::alloc::dealloc(self.buf.as_ptr(), make_layout::<T>(old_capacity, alignment));
+        }
+      }
+
+      self.buf = sentinel(alignment);
+      return;
+    }
+
+    if new_capacity == old_capacity && alignment == self.alignment() {
       return;
     }
 
@@ -227,7 +377,7 @@ impl<T> MiniVec<T> {
 
   /// `as_mut_ptr` returns a `*mut T` to the underlying array.
   ///
-  /// * May return a null pointer.
+  /// * Returns a dangling pointer aligned to the vector's alignment if the vector has not allocated.
   /// * May be invalidated by calls to [`reserve()`](MiniVec::reserve)
   /// * Can outlive its backing `MiniVec`
   ///
@@ -246,11 +396,7 @@ impl<T> MiniVec<T> {
   /// ```
   ///
   pub fn as_mut_ptr(&mut self) -> *mut T {
-    if self.is_default() {
-      return core::ptr::null_mut();
-    }
-
-    self.data()
+    self.as_ptr().cast_mut()
   }
 
   /// `as_mut_slice` obtains a mutable reference to a slice that's attached to the backing array.
@@ -271,7 +417,7 @@ impl<T> MiniVec<T> {
 
   /// `as_ptr` obtains a `*const T` to the underlying allocation.
   ///
-  /// * May return a null pointer.
+  /// * Returns a dangling pointer aligned to the vector's alignment if the vector has not allocated.
   /// * May be invalidated by calls to `reserve()`
   /// * Can outlive its backing `MiniVec`
   /// * May allow access to unitialized memory/non-existent objects
@@ -295,7 +441,13 @@ impl<T> MiniVec<T> {
   #[must_use]
   pub fn as_ptr(&self) -> *const T {
     if self.is_default() {
-      return core::ptr::null();
+      // the sentinel is aligned for `T` unless `T` is over-aligned past the end of the sentinel table
+      //
+      return if core::mem::align_of::<T>() <= MAX_SENTINEL_ALIGNMENT {
+        self.buf.as_ptr().cast::<T>()
+      } else {
+        core::ptr::NonNull::dangling().as_ptr()
+      };
     }
 
     self.data()
@@ -428,7 +580,7 @@ impl<T> MiniVec<T> {
       if !matches {
         if read != write {
           unsafe {
-            core::mem::swap(&mut *read, &mut *write);
+            core::ptr::swap(read, write);
           }
         }
         write = unsafe { write.add(1) };
@@ -481,7 +633,7 @@ impl<T> MiniVec<T> {
   /// assert_eq!(other_vec, [4, 5, 6, 7, 8, 9]);
   /// ```
   ///
-  pub fn drain<R>(&mut self, range: R) -> Drain<T>
+  pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
   where
     R: core::ops::RangeBounds<usize>,
   {
@@ -501,21 +653,25 @@ impl<T> MiniVec<T> {
       core::ops::Bound::Unbounded => len,
     };
 
-    if start_idx > end_idx {
-      panic!(
-        "start drain index (is {}) should be <= end drain index (is {})",
-        start_idx, end_idx
-      );
-    }
+    assert!(
+      start_idx <= end_idx,
+      "start drain index (is {}) should be <= end drain index (is {})",
+      start_idx,
+      end_idx
+    );
 
-    if end_idx > len {
-      panic!(
-        "end drain index (is {}) should be <= len (is {})",
-        end_idx, len
-      );
-    }
+    assert!(
+      end_idx <= len,
+      "end drain index (is {}) should be <= len (is {})",
+      end_idx,
+      len
+    );
 
-    let data = self.as_mut_ptr();
+    let data = if self.is_default() {
+      core::ptr::null_mut()
+    } else {
+      self.as_mut_ptr()
+    };
 
     if !data.is_null() {
       unsafe {
@@ -620,6 +776,13 @@ impl<T> MiniVec<T> {
   pub unsafe fn from_raw_part(ptr: *mut T) -> MiniVec<T> {
     debug_assert!(!ptr.is_null());
 
+    if is_sentinel(ptr.cast::<u8>()) {
+      return MiniVec {
+        buf: core::ptr::NonNull::<u8>::new_unchecked(ptr.cast::<u8>()),
+        phantom: core::marker::PhantomData,
+      };
+    }
+
     let header_size = core::mem::size_of::<Header>();
     let aligned = next_aligned(header_size, core::mem::align_of::<T>());
 
@@ -667,6 +830,15 @@ impl<T> MiniVec<T> {
   pub unsafe fn from_raw_parts(ptr: *mut T, length: usize, capacity: usize) -> MiniVec<T> {
     debug_assert!(!ptr.is_null());
 
+    if is_sentinel(ptr.cast::<u8>()) {
+      debug_assert!(length == 0 && capacity == 0);
+
+      return MiniVec {
+        buf: core::ptr::NonNull::<u8>::new_unchecked(ptr.cast::<u8>()),
+        phantom: core::marker::PhantomData,
+      };
+    }
+
     let header_size = core::mem::size_of::<Header>();
     let aligned = next_aligned(header_size, core::mem::align_of::<T>());
 
@@ -703,12 +875,12 @@ impl<T> MiniVec<T> {
   pub fn insert(&mut self, index: usize, element: T) {
     let len = self.len();
 
-    if index > len {
-      panic!(
-        "insertion index (is {}) should be <= len (is {})",
-        index, len
-      );
-    }
+    assert!(
+      index <= len,
+      "insertion index (is {}) should be <= len (is {})",
+      index,
+      len
+    );
 
     if len == self.capacity() {
       self.reserve(1);
@@ -747,6 +919,63 @@ impl<T> MiniVec<T> {
     (v.as_mut_ptr(), v.len(), v.capacity())
   }
 
+  /// `into_recycled` drops every element of the vector and reuses its allocation as an empty vector of `U`, which
+  /// can hold as many `U`s as fit in the bytes that held the `T`s.
+  ///
+  /// This only succeeds when the allocation is suitably aligned for `U` and its layout is exactly the one a
+  /// `MiniVec<U>` of the resulting capacity would allocate, as it's eventually freed with that layout. A vector which
+  /// hasn't allocated always succeeds.
+  ///
+  /// # Errors
+  ///
+  /// Returns the vector unchanged if its allocation can't be reused for `U` or if `U` is zero-sized.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut words = minivec::MiniVec::<u32>::with_capacity(16);
+  /// words.extend([1, 2, 3]);
+  /// let ptr = words.as_ptr();
+  ///
+  /// let bytes = words.into_recycled::<[u8; 4]>().unwrap();
+  /// assert!(bytes.is_empty());
+  /// assert_eq!(bytes.capacity(), 16);
+  /// assert_eq!(bytes.as_ptr().cast::<u32>(), ptr);
+  ///
+  /// assert!(bytes.into_recycled::<u128>().is_err());
+  /// ```
+  ///
+  pub fn into_recycled<U>(mut self) -> Result<MiniVec<U>, MiniVec<T>> {
+    if core::mem::size_of::<U>() == 0 {
+      return Err(self);
+    }
+
+    // an empty vector only carries its alignment, which is kept unless `U` needs more
+    //
+    if self.is_default() {
+      let alignment = core::cmp::max(self.alignment(), max_align::<U>());
+      return match MiniVec::with_alignment(0, alignment) {
+        Ok(vec) => Ok(vec),
+        Err(_) => unreachable!(),
+      };
+    }
+
+    let Some(capacity) = self.recycled_capacity::<U>() else {
+      return Err(self);
+    };
+
+    self.clear();
+
+    let vec = core::mem::ManuallyDrop::new(self);
+    let mut recycled = MiniVec {
+      buf: vec.buf,
+      phantom: core::marker::PhantomData,
+    };
+    recycled.header_mut().cap = capacity;
+
+    Ok(recycled)
+  }
+
   /// `is_empty()` returns whether or not the `MiniVec` has a length greater than 0.
   ///
   /// Logically equivalent to manually writing: `v.len() == 0`.
@@ -792,7 +1021,7 @@ impl<T> MiniVec<T> {
   {
     let len = vec.len();
     let mut vec = core::mem::ManuallyDrop::new(vec);
-    let vec: &mut MiniVec<T> = &mut *vec;
+    let vec: &mut MiniVec<T> = &mut vec;
     unsafe { core::slice::from_raw_parts_mut(vec.as_mut_ptr(), len) }
   }
 
@@ -816,6 +1045,36 @@ impl<T> MiniVec<T> {
     }
   }
 
+  /// `map_in_place` converts every element of the vector with `f`, reusing the vector's allocation for the results
+  /// whenever [`into_recycled`](MiniVec::into_recycled) could, i.e. when a `U` is no larger than a `T`, the
+  /// allocation is aligned for `U` and its layout also fits a vector of `U`. Otherwise, the results are collected into
+  /// a new vector.
+  ///
+  /// Should `f` panic, every element which was and wasn't yet converted is dropped and the allocation is freed.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let ints = minivec::mini_vec![1_u32, 2, 3];
+  /// let ptr = ints.as_ptr();
+  ///
+  /// let floats = ints.map_in_place(|n| n as f32 / 2.0);
+  ///
+  /// assert_eq!(floats, [0.5, 1.0, 1.5]);
+  /// assert_eq!(floats.as_ptr().cast::<u32>(), ptr);
+  /// ```
+  ///
+  #[must_use]
+  pub fn map_in_place<U, F>(self, mut f: F) -> MiniVec<U>
+  where
+    F: FnMut(T) -> U,
+  {
+    match self.try_map(|value| Ok::<U, core::convert::Infallible>(f(value))) {
+      Ok(vec) => vec,
+      Err(never) => match never {},
+    }
+  }
+
   /// `MiniVec::new` constructs an empty `MiniVec`.
   ///
   /// Note: does not allocate any memory.
@@ -829,7 +1088,7 @@ impl<T> MiniVec<T> {
   /// ```
   /// let mut vec = minivec::MiniVec::<i32>::new();
   ///
-  /// assert_eq!(vec.as_mut_ptr(), std::ptr::null_mut());
+  /// assert!(!vec.as_mut_ptr().is_null());
   /// assert_eq!(vec.len(), 0);
   /// assert_eq!(vec.capacity(), 0);
   /// ```
@@ -842,11 +1101,8 @@ impl<T> MiniVec<T> {
       "ZSTs currently not supported"
     );
 
-    let buf =
-      unsafe { core::ptr::NonNull::<u8>::new_unchecked(&DEFAULT_U8 as *const u8 as *mut u8) };
-
     MiniVec {
-      buf,
+      buf: sentinel(max_align::<T>()),
       phantom: core::marker::PhantomData,
     }
   }
@@ -908,7 +1164,7 @@ impl<T> MiniVec<T> {
       core::ptr::write(dst, value);
     };
 
-    let mut header = self.header_mut();
+    let header = self.header_mut();
     header.len += 1;
   }
 
@@ -931,9 +1187,12 @@ impl<T> MiniVec<T> {
   ///
   pub fn remove(&mut self, index: usize) -> T {
     let len = self.len();
-    if index >= len {
-      panic!("removal index (is {}) should be < len (is {})", index, len);
-    }
+    assert!(
+      index < len,
+      "removal index (is {}) should be < len (is {})",
+      index,
+      len
+    );
 
     unsafe {
       let p = self.as_mut_ptr().add(index);
@@ -1139,7 +1398,7 @@ impl<T> MiniVec<T> {
       if should_retain {
         if read != write {
           unsafe {
-            core::mem::swap(&mut *read, &mut *write);
+            core::ptr::swap(read, write);
           }
         }
         write = unsafe { write.add(1) };
@@ -1208,9 +1467,10 @@ impl<T> MiniVec<T> {
       return;
     }
 
-    if capacity < min_capacity {
-      panic!("Tried to shrink to a larger capacity");
-    }
+    assert!(
+      capacity >= min_capacity,
+      "Tried to shrink to a larger capacity"
+    );
 
     self.grow(min_capacity, self.alignment());
   }
@@ -1304,7 +1564,11 @@ impl<T> MiniVec<T> {
   /// assert_eq!(y, &[2, 3, 4]);
   /// ```
   ///
-  pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<<I as IntoIterator>::IntoIter>
+  pub fn splice<R, I>(
+    &mut self,
+    range: R,
+    replace_with: I,
+  ) -> Splice<'_, <I as IntoIterator>::IntoIter>
   where
     I: IntoIterator<Item = T>,
     R: core::ops::RangeBounds<usize>,
@@ -1325,21 +1589,25 @@ impl<T> MiniVec<T> {
       core::ops::Bound::Unbounded => len,
     };
 
-    if start_idx > end_idx {
-      panic!(
-        "start splice index (is {}) should be <= end splice index (is {})",
-        start_idx, end_idx
-      );
-    }
+    assert!(
+      start_idx <= end_idx,
+      "start splice index (is {}) should be <= end splice index (is {})",
+      start_idx,
+      end_idx
+    );
 
-    if end_idx > len {
-      panic!(
-        "end splice index (is {}) should be <= len (is {})",
-        end_idx, len
-      );
-    }
+    assert!(
+      end_idx <= len,
+      "end splice index (is {}) should be <= len (is {})",
+      end_idx,
+      len
+    );
 
-    let data = self.as_mut_ptr();
+    let data = if self.is_default() {
+      core::ptr::null_mut()
+    } else {
+      self.as_mut_ptr()
+    };
 
     if !data.is_null() {
       unsafe {
@@ -1431,12 +1699,15 @@ impl<T> MiniVec<T> {
   /// assert_eq!(tail, [7, 8, 9, 10]);
   /// ```
   ///
-  #[allow(clippy::ptr_as_ptr)]
+  #[allow(clippy::ptr_as_ptr, clippy::return_self_not_must_use)]
   pub fn split_off(&mut self, at: usize) -> MiniVec<T> {
     let len = self.len();
-    if at > len {
-      panic!("`at` split index (is {}) should be <= len (is {})", at, len);
-    }
+    assert!(
+      at <= len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      len
+    );
 
     if len == 0 {
       let other = if self.capacity() > 0 {
@@ -1449,16 +1720,15 @@ impl<T> MiniVec<T> {
     }
 
     if at == 0 {
-      let orig_cap = self.capacity();
+      let (orig_cap, orig_alignment) = (self.capacity(), self.alignment());
 
       let other = MiniVec {
         buf: self.buf,
         phantom: core::marker::PhantomData,
       };
 
-      self.buf =
-        unsafe { core::ptr::NonNull::<u8>::new_unchecked(&DEFAULT_U8 as *const u8 as *mut u8) };
-      self.reserve_exact(orig_cap);
+      self.buf = sentinel(max_align::<T>());
+      self.grow(orig_cap, orig_alignment);
 
       return other;
     }
@@ -1500,12 +1770,12 @@ impl<T> MiniVec<T> {
   ///
   pub fn swap_remove(&mut self, index: usize) -> T {
     let len = self.len();
-    if index >= len {
-      panic!(
-        "swap_remove index (is {}) should be < len (is {})",
-        index, len
-      );
-    }
+    assert!(
+      index < len,
+      "swap_remove index (is {}) should be < len (is {})",
+      index,
+      len
+    );
 
     let src = unsafe { core::ptr::read(self.as_ptr().add(len - 1)) };
     self.header_mut().len -= 1;
@@ -1546,6 +1816,111 @@ impl<T> MiniVec<T> {
       core::ptr::drop_in_place(s);
     }
   }
+  /// `try_map` is the fallible counterpart of [`map_in_place`](MiniVec::map_in_place), stopping at the first element
+  /// for which `f` returns an error. The elements converted so far and the elements not yet converted are then all
+  /// dropped, along with the allocation, and the error is returned.
+  ///
+  /// # Errors
+  ///
+  /// Returns the first error returned by `f`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use core::convert::TryFrom;
+  ///
+  /// let wide = minivec::mini_vec![1_u64, 2, 3];
+  /// let narrow = wide.try_map(u32::try_from).unwrap();
+  /// assert_eq!(narrow, [1, 2, 3]);
+  ///
+  /// let wide = minivec::mini_vec![1_u64, u64::MAX, 3];
+  /// assert!(wide.try_map(u32::try_from).is_err());
+  /// ```
+  ///
+  pub fn try_map<U, E, F>(self, mut f: F) -> Result<MiniVec<U>, E>
+  where
+    F: FnMut(T) -> Result<U, E>,
+  {
+    // drops the converted elements before `mapped` and the unconverted elements after it, the element at `mapped`
+    // having been moved into `f`, and then frees the allocation
+    //
+    struct Guard<T, U> {
+      buf: core::ptr::NonNull<u8>,
+      src: *mut T,
+      dst: *mut U,
+      len: usize,
+      mapped: usize,
+      layout: alloc::alloc::Layout,
+    }
+
+    impl<T, U> Drop for Guard<T, U> {
+      fn drop(&mut self) {
+        unsafe {
+          core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.dst, self.mapped));
+          core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(
+            self.src.add(self.mapped + 1),
+            self.len - self.mapped - 1,
+          ));
+          alloc::alloc::dealloc(self.buf.as_ptr(), self.layout);
+        }
+      }
+    }
+
+    if self.is_default() {
+      let alignment = core::cmp::max(self.alignment(), max_align::<U>());
+      return match MiniVec::with_alignment(0, alignment) {
+        Ok(vec) => Ok(vec),
+        Err(_) => unreachable!(),
+      };
+    }
+
+    let recycled =
+      if core::mem::size_of::<U>() > 0 && core::mem::size_of::<U>() <= core::mem::size_of::<T>() {
+        self.recycled_capacity::<U>()
+      } else {
+        None
+      };
+
+    let Some(capacity) = recycled else {
+      return self.into_iter().map(f).collect();
+    };
+
+    let Header {
+      len,
+      cap,
+      alignment,
+    } = *self.header();
+    let vec = core::mem::ManuallyDrop::new(self);
+
+    let mut guard = Guard {
+      buf: vec.buf,
+      src: vec.data(),
+      dst: vec.data().cast::<U>(),
+      len,
+      mapped: 0,
+      layout: make_layout::<T>(cap, alignment),
+    };
+
+    // a `U` is no larger than a `T` so writing the `idx`th result only overwrites elements that were already read
+    //
+    while guard.mapped < len {
+      let idx = guard.mapped;
+      let value = unsafe { core::ptr::read(guard.src.add(idx)) };
+      let result = f(value)?;
+      unsafe { core::ptr::write(guard.dst.add(idx), result) };
+      guard.mapped += 1;
+    }
+
+    core::mem::forget(guard);
+
+    let mut mapped = MiniVec {
+      buf: vec.buf,
+      phantom: core::marker::PhantomData,
+    };
+    mapped.header_mut().cap = capacity;
+
+    Ok(mapped)
+  }
 
   /// `with_alignment` is similar to its counterpart [`with_capacity`](MiniVec::with_capacity)
   /// except it takes an additional argument: the alignment to use for the allocation.
@@ -1562,6 +1937,9 @@ impl<T> MiniVec<T> {
   /// aligned on a 32 byte boundary. `with_alignment` is intended to make this possible with a
   /// `Vec`-like container.
   ///
+  /// Note: does not allocate any memory when `capacity` is `0` and `alignment` is at most 4096. The vector still
+  /// remembers `alignment` and uses it once it grows.
+  ///
   /// # Errors
   ///
   /// Returns a `Result` that contains either `MiniVec<T>` or a `LayoutErr`.
@@ -1627,7 +2005,7 @@ impl<T> MiniVec<T> {
       return Err(LayoutErr::AlignmentTooSmall);
     }
 
-    if alignment % 2 > 0 {
+    if !alignment.is_multiple_of(2) {
       return Err(LayoutErr::AlignmentNotDivisibleByTwo);
     }
 
@@ -1717,7 +2095,7 @@ impl<T: Clone> MiniVec<T> {
       vec: &'a mut MiniVec<T>,
     }
 
-    impl<'a, T> Drop for PanicGuard<'a, T>
+    impl<T> Drop for PanicGuard<'_, T>
     where
       T: Clone,
     {
@@ -1728,7 +2106,7 @@ impl<T: Clone> MiniVec<T> {
       }
     }
 
-    impl<'a, 'b, T> PanicGuard<'a, T>
+    impl<T> PanicGuard<'_, T>
     where
       T: Clone,
     {
@@ -1762,19 +2140,19 @@ impl<T: Clone> MiniVec<T> {
       core::ops::Bound::Unbounded => len,
     };
 
-    if start_idx > end_idx {
-      panic!(
-        "start extend_from_within index (is {}) should be <= end (is {})",
-        start_idx, end_idx
-      );
-    }
+    assert!(
+      start_idx <= end_idx,
+      "start extend_from_within index (is {}) should be <= end (is {})",
+      start_idx,
+      end_idx
+    );
 
-    if end_idx > len {
-      panic!(
-        "end extend_from_within index (is {}) should be <= len (is {})",
-        end_idx, len
-      );
-    }
+    assert!(
+      end_idx <= len,
+      "end extend_from_within index (is {}) should be <= len (is {})",
+      end_idx,
+      len
+    );
 
     if len == 0 {
       return;
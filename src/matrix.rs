@@ -0,0 +1,723 @@
+//! `matrix` contains [`MiniMatrix`](MiniMatrix), a dense row-major matrix which keeps its shape in its allocation.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::max_align_with;
+use crate::{LayoutErr, MiniVec, MiniVecWithHeader};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Shape {
+  rows: usize,
+  cols: usize,
+}
+
+/// `MiniMatrix` is a dense two-dimensional matrix whose elements are stored row by row in a single allocation. The
+/// number of rows and columns live in the allocation's header next to the length and capacity so the matrix is only
+/// the size of a single pointer, and like [`MiniVec::with_alignment`](MiniVec::with_alignment) the elements can be
+/// over-aligned for use with SIMD.
+///
+/// Elements are addressed by `(row, col)` pairs.
+///
+/// # Example
+///
+/// ```
+/// let mut grid = minivec::MiniMatrix::from_elem(2, 3, 0.0_f64);
+/// grid[(1, 2)] = 1.5;
+///
+/// assert_eq!(grid.shape(), (2, 3));
+/// assert_eq!(grid.row(1), [0.0, 0.0, 1.5]);
+/// assert_eq!(grid.col(2).copied().collect::<Vec<_>>(), [0.0, 1.5]);
+///
+/// let transposed = grid.transpose();
+/// assert_eq!(transposed.shape(), (3, 2));
+/// assert_eq!(transposed[(2, 1)], 1.5);
+/// ```
+///
+pub struct MiniMatrix<T> {
+  data: MiniVecWithHeader<Shape, T>,
+}
+
+/// `Col` is an iterator over the elements of one column of a [`MiniMatrix`](MiniMatrix), created by
+/// [`col`](MiniMatrix::col).
+///
+pub struct Col<'a, T> {
+  inner: core::iter::StepBy<core::slice::Iter<'a, T>>,
+}
+
+/// `ColMut` is an iterator over mutable references to the elements of one column of a [`MiniMatrix`](MiniMatrix),
+/// created by [`col_mut`](MiniMatrix::col_mut).
+///
+pub struct ColMut<'a, T> {
+  inner: core::iter::StepBy<core::slice::IterMut<'a, T>>,
+}
+
+// Truncates `data` back to `len` elements when dropped, so a panic partway through adding elements can't leave more
+// of them behind than the shape in the header accounts for.
+//
+struct Truncate<'a, T> {
+  data: &'a mut MiniVecWithHeader<Shape, T>,
+  len: usize,
+}
+
+impl<T> Drop for Truncate<'_, T> {
+  fn drop(&mut self) {
+    self.data.truncate(self.len);
+  }
+}
+
+impl<T> MiniMatrix<T> {
+  fn dims(&self) -> Shape {
+    self.data.header().copied().unwrap_or_default()
+  }
+
+  fn set_dims(&mut self, rows: usize, cols: usize) {
+    *self.data.header_mut() = Shape { rows, cols };
+  }
+
+  // Constructs an empty `0 x 0` matrix with the same alignment as this one.
+  //
+  fn empty_like(&self, capacity: usize) -> Self {
+    let Ok(matrix) = Self::with_alignment(capacity, self.alignment()) else {
+      unreachable!("a matrix's own alignment is always valid");
+    };
+    matrix
+  }
+
+  fn check_col(&self, col: usize) -> Shape {
+    let dims = self.dims();
+    assert!(
+      col < dims.cols,
+      "column index out of bounds: the cols is {} but the index is {}",
+      dims.cols,
+      col
+    );
+    dims
+  }
+
+  fn check_row(&self, row: usize) -> Shape {
+    let dims = self.dims();
+    assert!(
+      row < dims.rows,
+      "row index out of bounds: the rows is {} but the index is {}",
+      dims.rows,
+      row
+    );
+    dims
+  }
+
+  /// `alignment` returns the alignment of the matrix's elements.
+  ///
+  #[must_use]
+  pub fn alignment(&self) -> usize {
+    self.data.alignment()
+  }
+
+  /// `as_mut_slice` returns every element of the matrix, row after row.
+  ///
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    self.data.as_mut_slice()
+  }
+
+  /// `as_slice` returns every element of the matrix, row after row.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    self.data.as_slice()
+  }
+
+  /// `col` returns an iterator over the elements of column `col`, from the first row to the last.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `col` is out of bounds.
+  ///
+  #[must_use]
+  pub fn col(&self, col: usize) -> Col<'_, T> {
+    let dims = self.check_col(col);
+    Col {
+      inner: self
+        .data
+        .get(col..)
+        .unwrap_or_default()
+        .iter()
+        .step_by(dims.cols),
+    }
+  }
+
+  /// `col_mut` returns an iterator over mutable references to the elements of column `col`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `col` is out of bounds.
+  ///
+  pub fn col_mut(&mut self, col: usize) -> ColMut<'_, T> {
+    let dims = self.check_col(col);
+    ColMut {
+      inner: self
+        .data
+        .get_mut(col..)
+        .unwrap_or_default()
+        .iter_mut()
+        .step_by(dims.cols),
+    }
+  }
+
+  /// `cols` returns the number of columns.
+  ///
+  #[must_use]
+  pub fn cols(&self) -> usize {
+    self.dims().cols
+  }
+
+  /// `from_fn` constructs a `rows` by `cols` matrix whose element at `(row, col)` is `f(row, col)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the number of elements overflows `usize`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let identity = minivec::MiniMatrix::from_fn(2, 2, |row, col| u8::from(row == col));
+  /// assert_eq!(identity.as_slice(), [1, 0, 0, 1]);
+  /// ```
+  ///
+  pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+  where
+    F: FnMut(usize, usize) -> T,
+  {
+    let len = rows.checked_mul(cols).expect("capacity overflow");
+
+    let mut matrix = MiniMatrix {
+      data: MiniVecWithHeader::with_capacity(len),
+    };
+    for row in 0..rows {
+      for col in 0..cols {
+        matrix.data.push(f(row, col));
+      }
+    }
+
+    matrix.set_dims(rows, cols);
+    matrix
+  }
+
+  /// `from_vec` reshapes the row-major elements of `vec` into a `rows` by `cols` matrix, keeping the vector's
+  /// alignment.
+  ///
+  /// # Errors
+  ///
+  /// Returns `vec` back if its length isn't `rows * cols`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let matrix = minivec::MiniMatrix::from_vec(minivec::mini_vec![1, 2, 3, 4, 5, 6], 3, 2).unwrap();
+  /// assert_eq!(matrix.row(2), [5, 6]);
+  ///
+  /// assert!(minivec::MiniMatrix::from_vec(minivec::mini_vec![1, 2, 3], 2, 2).is_err());
+  /// ```
+  ///
+  pub fn from_vec(mut vec: MiniVec<T>, rows: usize, cols: usize) -> Result<Self, MiniVec<T>> {
+    if rows.checked_mul(cols) != Some(vec.len()) {
+      return Err(vec);
+    }
+
+    let alignment = core::cmp::max(vec.alignment(), max_align_with::<Shape, T>());
+    let Ok(mut data) =
+      MiniVecWithHeader::with_header_and_alignment(Shape { rows, cols }, vec.len(), alignment)
+    else {
+      unreachable!("a vector's alignment is always valid");
+    };
+
+    let len = vec.len();
+    if len > 0 {
+      unsafe {
+        core::ptr::copy_nonoverlapping(vec.as_ptr(), data.as_mut_ptr(), len);
+        vec.set_len(0);
+        data.set_len(len);
+      }
+    }
+
+    Ok(MiniMatrix { data })
+  }
+
+  /// `get` returns a reference to the element at `(row, col)`, or `None` if either index is out of bounds.
+  ///
+  #[must_use]
+  pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+    let dims = self.dims();
+    if row >= dims.rows || col >= dims.cols {
+      return None;
+    }
+
+    self.data.get(row * dims.cols + col)
+  }
+
+  /// `get_mut` returns a mutable reference to the element at `(row, col)`, or `None` if either index is out of
+  /// bounds.
+  ///
+  pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+    let dims = self.dims();
+    if row >= dims.rows || col >= dims.cols {
+      return None;
+    }
+
+    self.data.get_mut(row * dims.cols + col)
+  }
+
+  /// `insert_col` inserts the values of `col` as a new column at index `idx`, shifting every later column to the
+  /// right. Inserting a column into an empty `0 x 0` matrix gives it one row per value.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `idx` is greater than the number of columns or if `col` doesn't yield exactly one value per row.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut matrix = minivec::MiniMatrix::from_vec(minivec::mini_vec![1, 3, 4, 6], 2, 2).unwrap();
+  /// matrix.insert_col(1, [2, 5]);
+  ///
+  /// assert_eq!(matrix.as_slice(), [1, 2, 3, 4, 5, 6]);
+  /// ```
+  ///
+  pub fn insert_col<I: IntoIterator<Item = T>>(&mut self, idx: usize, col: I) {
+    let Shape { rows, cols } = self.dims();
+    assert!(
+      idx <= cols,
+      "column index out of bounds: the cols is {} but the index is {}",
+      cols,
+      idx
+    );
+
+    let mut col: MiniVec<T> = col.into_iter().collect();
+    let rows = if rows == 0 && cols == 0 {
+      col.len()
+    } else {
+      assert!(
+        col.len() == rows,
+        "column length mismatch: expected {} values but got {}",
+        rows,
+        col.len()
+      );
+      rows
+    };
+
+    self.data.reserve(rows);
+
+    // rows are shifted back to front so that every row moves into space already vacated by the rows after it
+    //
+    let new_cols = cols + 1;
+    unsafe {
+      let base = self.data.as_mut_ptr();
+      for row in (0..rows).rev() {
+        let old = base.add(row * cols);
+        let new = base.add(row * new_cols);
+
+        core::ptr::copy(old.add(idx), new.add(idx + 1), cols - idx);
+        core::ptr::copy(old, new, idx);
+        new.add(idx).write(core::ptr::read(col.as_ptr().add(row)));
+      }
+
+      if rows > 0 {
+        col.set_len(0);
+        self.data.set_len(rows * new_cols);
+      }
+    }
+
+    self.set_dims(rows, new_cols);
+  }
+
+  /// `into_parts` returns the row-major elements of the matrix in a vector with the same alignment, along with the
+  /// number of rows and columns.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let matrix = minivec::MiniMatrix::from_fn(2, 3, |row, col| row * 3 + col);
+  /// let (vec, rows, cols) = matrix.into_parts();
+  ///
+  /// assert_eq!(vec, [0, 1, 2, 3, 4, 5]);
+  /// assert_eq!((rows, cols), (2, 3));
+  /// ```
+  ///
+  #[must_use]
+  pub fn into_parts(mut self) -> (MiniVec<T>, usize, usize) {
+    let Shape { rows, cols } = self.dims();
+    let len = self.data.len();
+
+    let Ok(mut vec) = MiniVec::with_alignment(len, self.alignment()) else {
+      unreachable!("a matrix's alignment is always valid for a vector");
+    };
+    if len > 0 {
+      unsafe {
+        core::ptr::copy_nonoverlapping(self.data.as_ptr(), vec.as_mut_ptr(), len);
+        self.data.set_len(0);
+        vec.set_len(len);
+      }
+    }
+
+    (vec, rows, cols)
+  }
+
+  /// `is_empty` returns whether or not the matrix has no elements, i.e. either no rows or no columns.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  /// `new` constructs an empty `0 x 0` matrix.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniMatrix {
+      data: MiniVecWithHeader::new(),
+    }
+  }
+
+  /// `push_row` appends the values of `row` as a new last row. Pushing a row onto an empty `0 x 0` matrix sets its
+  /// number of columns.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` doesn't yield exactly one value per column.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut matrix = minivec::MiniMatrix::new();
+  /// matrix.push_row([1, 2]);
+  /// matrix.push_row([3, 4]);
+  ///
+  /// assert_eq!(matrix.shape(), (2, 2));
+  /// assert_eq!(matrix[(1, 0)], 3);
+  /// ```
+  ///
+  pub fn push_row<I: IntoIterator<Item = T>>(&mut self, row: I) {
+    let Shape { rows, cols } = self.dims();
+
+    let start = self.data.len();
+    let guard = Truncate {
+      data: &mut self.data,
+      len: start,
+    };
+    guard.data.extend(row);
+    let pushed = guard.data.len() - start;
+
+    let cols = if rows == 0 && cols == 0 {
+      pushed
+    } else {
+      assert!(
+        pushed == cols,
+        "row length mismatch: expected {} values but got {}",
+        cols,
+        pushed
+      );
+      cols
+    };
+
+    core::mem::forget(guard);
+    self.set_dims(rows + 1, cols);
+  }
+
+  /// `remove_col` removes the column at index `idx` and returns its values, shifting every later column to the left.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `idx` is out of bounds.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut matrix = minivec::MiniMatrix::from_vec(minivec::mini_vec![1, 2, 3, 4, 5, 6], 2, 3).unwrap();
+  ///
+  /// assert_eq!(matrix.remove_col(1), [2, 5]);
+  /// assert_eq!(matrix.as_slice(), [1, 3, 4, 6]);
+  /// ```
+  ///
+  pub fn remove_col(&mut self, idx: usize) -> MiniVec<T> {
+    let Shape { rows, cols } = self.check_col(idx);
+
+    let mut removed = MiniVec::with_capacity(rows);
+    let new_cols = cols - 1;
+    unsafe {
+      let base = self.data.as_mut_ptr();
+      if rows > 0 {
+        self.data.set_len(0);
+      }
+
+      for row in 0..rows {
+        let old = base.add(row * cols);
+        let new = base.add(row * new_cols);
+
+        removed.push(core::ptr::read(old.add(idx)));
+        core::ptr::copy(old, new, idx);
+        core::ptr::copy(old.add(idx + 1), new.add(idx), cols - idx - 1);
+      }
+
+      if rows > 0 {
+        self.data.set_len(rows * new_cols);
+      }
+    }
+
+    self.set_dims(rows, new_cols);
+    removed
+  }
+
+  /// `row` returns the elements of row `row`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` is out of bounds.
+  ///
+  #[must_use]
+  pub fn row(&self, row: usize) -> &[T] {
+    let dims = self.check_row(row);
+    &self.data[row * dims.cols..(row + 1) * dims.cols]
+  }
+
+  /// `row_mut` returns the elements of row `row` mutably.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `row` is out of bounds.
+  ///
+  pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+    let dims = self.check_row(row);
+    &mut self.data[row * dims.cols..(row + 1) * dims.cols]
+  }
+
+  /// `rows` returns the number of rows.
+  ///
+  #[must_use]
+  pub fn rows(&self) -> usize {
+    self.dims().rows
+  }
+
+  /// `shape` returns the number of rows and columns.
+  ///
+  #[must_use]
+  pub fn shape(&self) -> (usize, usize) {
+    let dims = self.dims();
+    (dims.rows, dims.cols)
+  }
+
+  /// `with_alignment` constructs an empty `0 x 0` matrix with room for `capacity` elements, all of which are placed
+  /// on an `alignment`-byte boundary.
+  ///
+  /// # Errors
+  ///
+  /// Returns the same errors as [`MiniVec::with_alignment`](MiniVec::with_alignment).
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut matrix = minivec::MiniMatrix::<f32>::with_alignment(16, 32).unwrap();
+  /// for row in 0..4 {
+  ///     matrix.push_row([row as f32; 4]);
+  /// }
+  ///
+  /// assert_eq!(matrix.as_slice().as_ptr() as usize % 32, 0);
+  /// assert_eq!(matrix.alignment(), 32);
+  /// ```
+  ///
+  pub fn with_alignment(capacity: usize, alignment: usize) -> Result<Self, LayoutErr> {
+    let data = MiniVecWithHeader::with_header_and_alignment(Shape::default(), capacity, alignment)?;
+    Ok(MiniMatrix { data })
+  }
+}
+
+impl<T: Clone> MiniMatrix<T> {
+  /// `from_elem` constructs a `rows` by `cols` matrix filled with clones of `value`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the number of elements overflows `usize`.
+  ///
+  pub fn from_elem(rows: usize, cols: usize, value: T) -> Self {
+    Self::from_fn(rows, cols, |_, _| value.clone())
+  }
+
+  /// `resize` changes the shape of the matrix to `rows` by `cols`. Elements whose position lies within both the old
+  /// and the new shape are kept, the rest are dropped, and new positions are filled with clones of `value`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the number of elements overflows `usize`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut matrix = minivec::MiniMatrix::from_vec(minivec::mini_vec![1, 2, 3, 4], 2, 2).unwrap();
+  /// matrix.resize(3, 1, 0);
+  ///
+  /// assert_eq!(matrix.shape(), (3, 1));
+  /// assert_eq!(matrix.as_slice(), [1, 3, 0]);
+  /// ```
+  ///
+  pub fn resize(&mut self, rows: usize, cols: usize, value: T) {
+    let len = rows.checked_mul(cols).expect("capacity overflow");
+
+    // the new elements are gathered into `self` while `old` is taken apart. A panicking `clone` drops the gathered
+    // elements and leaks the unread ones, leaving behind an empty `0 x 0` matrix rather than a partially moved one
+    //
+    let empty = self.empty_like(len);
+    let mut old = core::mem::replace(self, empty);
+    let guard = Truncate {
+      data: &mut self.data,
+      len: 0,
+    };
+    let Shape {
+      rows: old_rows,
+      cols: old_cols,
+    } = old.dims();
+
+    unsafe {
+      let src = old.data.as_mut_ptr();
+      if !old.data.is_empty() {
+        old.data.set_len(0);
+      }
+
+      for row in 0..old_rows {
+        for col in 0..old_cols {
+          if row >= rows || col >= cols {
+            core::ptr::drop_in_place(src.add(row * old_cols + col));
+          }
+        }
+      }
+
+      for row in 0..rows {
+        for col in 0..cols {
+          let value = if row < old_rows && col < old_cols {
+            core::ptr::read(src.add(row * old_cols + col))
+          } else {
+            value.clone()
+          };
+
+          guard.data.push(value);
+        }
+      }
+    }
+
+    core::mem::forget(guard);
+    self.set_dims(rows, cols);
+  }
+
+  /// `transpose` returns a new matrix whose rows are the columns of this one, with the same alignment.
+  ///
+  #[must_use]
+  pub fn transpose(&self) -> Self {
+    let Shape { rows, cols } = self.dims();
+
+    let mut transposed = self.empty_like(self.data.len());
+    for col in 0..cols {
+      transposed.data.extend(self.col(col).cloned());
+    }
+
+    transposed.set_dims(cols, rows);
+    transposed
+  }
+}
+
+impl<T: Clone> Clone for MiniMatrix<T> {
+  fn clone(&self) -> Self {
+    MiniMatrix {
+      data: self.data.clone(),
+    }
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniMatrix<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list()
+      .entries((0..self.rows()).map(|row| self.row(row)))
+      .finish()
+  }
+}
+
+impl<T> Default for MiniMatrix<T> {
+  fn default() -> Self {
+    MiniMatrix::new()
+  }
+}
+
+impl<T: PartialEq> PartialEq for MiniMatrix<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.shape() == other.shape() && self.as_slice() == other.as_slice()
+  }
+}
+
+impl<T: Eq> Eq for MiniMatrix<T> {}
+
+impl<T> core::ops::Index<(usize, usize)> for MiniMatrix<T> {
+  type Output = T;
+
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    let shape = self.shape();
+    match self.get(row, col) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the shape is {:?} but the index is {:?}",
+        shape,
+        (row, col)
+      ),
+    }
+  }
+}
+
+impl<T> core::ops::IndexMut<(usize, usize)> for MiniMatrix<T> {
+  fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+    let shape = self.shape();
+    match self.get_mut(row, col) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the shape is {:?} but the index is {:?}",
+        shape,
+        (row, col)
+      ),
+    }
+  }
+}
+
+impl<'a, T> Iterator for Col<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<T> DoubleEndedIterator for Col<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.inner.next_back()
+  }
+}
+
+impl<T> ExactSizeIterator for Col<'_, T> {}
+
+impl<'a, T> Iterator for ColMut<'a, T> {
+  type Item = &'a mut T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.inner.size_hint()
+  }
+}
+
+impl<T> DoubleEndedIterator for ColMut<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.inner.next_back()
+  }
+}
+
+impl<T> ExactSizeIterator for ColMut<'_, T> {}
@@ -0,0 +1,423 @@
+//! `non_empty` contains [`NonEmptyMiniVec`](NonEmptyMiniVec), a [`MiniVec`](crate::MiniVec) that always holds at
+//! least one element.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::MiniVec;
+
+use core::convert::TryFrom;
+use core::num::NonZeroUsize;
+
+/// `NonEmptyMiniVec` is a [`MiniVec`](crate::MiniVec) that is guaranteed to contain at least one element. Like
+/// `MiniVec`, it is only the size of a single pointer.
+///
+/// Because the vector can never be empty, accessors such as [`first`](NonEmptyMiniVec::first),
+/// [`last`](NonEmptyMiniVec::last), [`max`](NonEmptyMiniVec::max) and [`reduce`](NonEmptyMiniVec::reduce) return
+/// their results directly instead of wrapping them in an `Option`. Operations that would remove the final element
+/// refuse to do so.
+///
+/// `NonEmptyMiniVec` dereferences to a slice, so all of the read-only and in-place slice methods are available. It
+/// deliberately does not implement `Ord` as `Ord::max` and `Ord::min` would shadow the element-wise
+/// [`max`](NonEmptyMiniVec::max) and [`min`](NonEmptyMiniVec::min).
+///
+/// # Example
+///
+/// ```
+/// use minivec::nonempty_mini_vec;
+///
+/// let mut levels = nonempty_mini_vec![101, 99, 104];
+///
+/// assert_eq!(*levels.first(), 101);
+/// assert_eq!(*levels.max(), 104);
+/// assert_eq!(levels.len().get(), 3);
+///
+/// assert_eq!(levels.pop(), Some(104));
+/// assert_eq!(levels.pop(), Some(99));
+/// assert_eq!(levels.pop(), None);
+/// ```
+///
+pub struct NonEmptyMiniVec<T> {
+  inner: MiniVec<T>,
+}
+
+impl<T> NonEmptyMiniVec<T> {
+  /// `new` constructs a vector containing only `head`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::NonEmptyMiniVec::new("root");
+  /// assert_eq!(vec, ["root"]);
+  /// ```
+  ///
+  pub fn new(head: T) -> Self {
+    let mut inner = MiniVec::with_capacity(1);
+    inner.push(head);
+    NonEmptyMiniVec { inner }
+  }
+
+  /// `from_vec` converts `vec` into a `NonEmptyMiniVec`, returning `None` if `vec` is empty.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::{mini_vec, MiniVec, NonEmptyMiniVec};
+  ///
+  /// assert!(NonEmptyMiniVec::from_vec(mini_vec![1, 2, 3]).is_some());
+  /// assert!(NonEmptyMiniVec::from_vec(MiniVec::<i32>::new()).is_none());
+  /// ```
+  ///
+  #[must_use]
+  pub fn from_vec(vec: MiniVec<T>) -> Option<Self> {
+    if vec.is_empty() {
+      None
+    } else {
+      Some(NonEmptyMiniVec { inner: vec })
+    }
+  }
+
+  /// `as_mini_vec` returns a reference to the underlying `MiniVec`.
+  ///
+  /// There is no mutable counterpart as it would allow the vector to be emptied.
+  ///
+  #[must_use]
+  pub fn as_mini_vec(&self) -> &MiniVec<T> {
+    &self.inner
+  }
+
+  /// `capacity` returns the capacity of the underlying vector.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.inner.capacity()
+  }
+
+  /// `first` returns a reference to the first element of the vector.
+  ///
+  #[must_use]
+  pub fn first(&self) -> &T {
+    &self.inner[0]
+  }
+
+  /// `first_mut` returns a mutable reference to the first element of the vector.
+  ///
+  pub fn first_mut(&mut self) -> &mut T {
+    &mut self.inner[0]
+  }
+
+  /// `insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len`.
+  ///
+  pub fn insert(&mut self, index: usize, element: T) {
+    self.inner.insert(index, element);
+  }
+
+  /// `into_vec` unwraps the underlying `MiniVec`.
+  ///
+  #[must_use]
+  pub fn into_vec(self) -> MiniVec<T> {
+    self.inner
+  }
+
+  /// `last` returns a reference to the last element of the vector.
+  ///
+  #[must_use]
+  pub fn last(&self) -> &T {
+    &self.inner[self.inner.len() - 1]
+  }
+
+  /// `last_mut` returns a mutable reference to the last element of the vector.
+  ///
+  pub fn last_mut(&mut self) -> &mut T {
+    let idx = self.inner.len() - 1;
+    &mut self.inner[idx]
+  }
+
+  /// `len` returns the number of elements in the vector, which is never zero.
+  ///
+  #[must_use]
+  pub fn len(&self) -> NonZeroUsize {
+    unsafe { NonZeroUsize::new_unchecked(self.inner.len()) }
+  }
+
+  /// `max` returns a reference to the maximum element of the vector. If several elements are equally maximum, the
+  /// last one is returned.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::nonempty_mini_vec;
+  ///
+  /// let vec = nonempty_mini_vec![3, 7, 2];
+  /// assert_eq!(*vec.max(), 7);
+  /// ```
+  ///
+  #[must_use]
+  pub fn max(&self) -> &T
+  where
+    T: Ord,
+  {
+    let (head, tail) = self.split_first();
+    tail
+      .iter()
+      .fold(head, |max, x| if x >= max { x } else { max })
+  }
+
+  /// `min` returns a reference to the minimum element of the vector. If several elements are equally minimum, the
+  /// first one is returned.
+  ///
+  #[must_use]
+  pub fn min(&self) -> &T
+  where
+    T: Ord,
+  {
+    let (head, tail) = self.split_first();
+    tail
+      .iter()
+      .fold(head, |min, x| if x < min { x } else { min })
+  }
+
+  /// `pop` removes the last element of the vector and returns it, unless it is the only element remaining in which
+  /// case the vector is left untouched and `None` is returned.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    if self.inner.len() > 1 {
+      self.inner.pop()
+    } else {
+      None
+    }
+  }
+
+  /// `push` appends `value` to the end of the vector.
+  ///
+  pub fn push(&mut self, value: T) {
+    self.inner.push(value);
+  }
+
+  /// `reduce` folds every element of the vector into a single value by repeatedly applying `f`, using the first
+  /// element as the initial accumulator.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use minivec::nonempty_mini_vec;
+  ///
+  /// let segments = nonempty_mini_vec![String::from("usr"), String::from("local"), String::from("bin")];
+  /// let path = segments.reduce(|acc, s| acc + "/" + &s);
+  ///
+  /// assert_eq!(path, "usr/local/bin");
+  /// ```
+  ///
+  pub fn reduce<F>(self, f: F) -> T
+  where
+    F: FnMut(T, T) -> T,
+  {
+    let mut iter = self.inner.into_iter();
+    match iter.next() {
+      Some(head) => iter.fold(head, f),
+      None => unreachable!(),
+    }
+  }
+
+  /// `remove` removes the element at `index` and returns it, shifting every element after it to the left. If it is
+  /// the only element remaining, the vector is left untouched and `None` is returned.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn remove(&mut self, index: usize) -> Option<T> {
+    assert!(
+      index < self.inner.len(),
+      "removal index (is {}) should be < len (is {})",
+      index,
+      self.inner.len()
+    );
+
+    if self.inner.len() > 1 {
+      Some(self.inner.remove(index))
+    } else {
+      None
+    }
+  }
+
+  /// `reserve` reserves space for at least `additional` more elements.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    self.inner.reserve(additional);
+  }
+
+  /// `shrink_to_fit` reduces the capacity of the vector as much as possible.
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    self.inner.shrink_to_fit();
+  }
+
+  /// `split_first` returns the first element of the vector along with the remaining elements.
+  ///
+  #[must_use]
+  pub fn split_first(&self) -> (&T, &[T]) {
+    let (head, tail) = self.inner.split_at(1);
+    (&head[0], tail)
+  }
+
+  /// `split_last` returns the last element of the vector along with the preceding elements.
+  ///
+  #[must_use]
+  pub fn split_last(&self) -> (&T, &[T]) {
+    let (init, last) = self.inner.split_at(self.inner.len() - 1);
+    (&last[0], init)
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest. Has no effect if `len` is greater than the
+  /// vector's current length.
+  ///
+  pub fn truncate(&mut self, len: NonZeroUsize) {
+    self.inner.truncate(len.get());
+  }
+}
+
+impl<T> core::ops::Deref for NonEmptyMiniVec<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
+impl<T> core::ops::DerefMut for NonEmptyMiniVec<T> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.inner
+  }
+}
+
+impl<T> AsRef<[T]> for NonEmptyMiniVec<T> {
+  fn as_ref(&self) -> &[T] {
+    &self.inner
+  }
+}
+
+impl<T> AsMut<[T]> for NonEmptyMiniVec<T> {
+  fn as_mut(&mut self) -> &mut [T] {
+    &mut self.inner
+  }
+}
+
+impl<T: Clone> Clone for NonEmptyMiniVec<T> {
+  fn clone(&self) -> Self {
+    NonEmptyMiniVec {
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for NonEmptyMiniVec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.inner.fmt(f)
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<NonEmptyMiniVec<U>> for NonEmptyMiniVec<T> {
+  fn eq(&self, other: &NonEmptyMiniVec<U>) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for NonEmptyMiniVec<T> {
+  fn eq(&self, other: &[U; N]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for NonEmptyMiniVec<T> {
+  fn eq(&self, other: &[U]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: Eq> Eq for NonEmptyMiniVec<T> {}
+
+impl<T: core::hash::Hash> core::hash::Hash for NonEmptyMiniVec<T> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.inner.hash(state);
+  }
+}
+
+impl<T> core::iter::Extend<T> for NonEmptyMiniVec<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    self.inner.extend(iter);
+  }
+}
+
+impl<'a, T: 'a + Copy> core::iter::Extend<&'a T> for NonEmptyMiniVec<T> {
+  fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+    self.inner.extend(iter);
+  }
+}
+
+impl<T> From<NonEmptyMiniVec<T>> for MiniVec<T> {
+  fn from(vec: NonEmptyMiniVec<T>) -> Self {
+    vec.inner
+  }
+}
+
+impl<T> TryFrom<MiniVec<T>> for NonEmptyMiniVec<T> {
+  type Error = MiniVec<T>;
+
+  fn try_from(vec: MiniVec<T>) -> Result<Self, Self::Error> {
+    if vec.is_empty() {
+      Err(vec)
+    } else {
+      Ok(NonEmptyMiniVec { inner: vec })
+    }
+  }
+}
+
+impl<T> core::iter::IntoIterator for NonEmptyMiniVec<T> {
+  type Item = T;
+  type IntoIter = crate::IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.into_iter()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a NonEmptyMiniVec<T> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.iter()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a mut NonEmptyMiniVec<T> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.inner.iter_mut()
+  }
+}
+
+/// `nonempty_mini_vec!` is a macro similar in spirit to [`mini_vec!`](crate::mini_vec) which constructs a
+/// [`NonEmptyMiniVec`](crate::NonEmptyMiniVec).
+///
+/// At least one element must be supplied:
+/// * `nonempty_mini_vec![val1, val2, val3, ...]`
+///
+#[macro_export]
+macro_rules! nonempty_mini_vec {
+    ($head:expr $(, $rest:expr)* $(,)?) => {
+        {
+            let mut tmp = $crate::NonEmptyMiniVec::new($head);
+            $(
+                tmp.push($rest);
+            )*
+            tmp
+        }
+    };
+}
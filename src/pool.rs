@@ -0,0 +1,385 @@
+//! `pool` contains [`MiniVecPool`](MiniVecPool) and [`SyncMiniVecPool`](SyncMiniVecPool), which recycle the
+//! allocations of dropped vectors, along with the [`PooledMiniVec`](PooledMiniVec) guard they hand out.
+//!
+//! A thread-local pool is a `MiniVecPool` kept in a `thread_local!`:
+//!
+//! ```
+//! use minivec::MiniVecPool;
+//!
+//! thread_local! {
+//!     static BUFFERS: MiniVecPool<u8> = MiniVecPool::new();
+//! }
+//!
+//! let len = BUFFERS.with(|pool| {
+//!     let mut buf = pool.take(4096);
+//!     buf.extend_from_slice(b"response");
+//!     buf.len()
+//! });
+//!
+//! assert_eq!(len, 8);
+//! assert_eq!(BUFFERS.with(MiniVecPool::len), 1);
+//! ```
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::sync::{spin_loop, AtomicBool, Ordering, UnsafeCell};
+use crate::MiniVec;
+
+use core::cell::RefCell;
+use core::mem::ManuallyDrop;
+
+const DEFAULT_MAX_PER_CLASS: usize = 64;
+
+// Free vectors grouped by capacity class, where class `k` holds vectors with a capacity of at least `1 << k`.
+//
+struct Classes<T> {
+  free: MiniVec<MiniVec<MiniVec<T>>>,
+  max_per_class: usize,
+}
+
+impl<T> Classes<T> {
+  fn new(max_per_class: usize) -> Self {
+    Classes {
+      free: MiniVec::new(),
+      max_per_class,
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.free.iter().map(MiniVec::len).sum()
+  }
+
+  // Returns a free vector with room for at least `1 << class` elements, if there is one.
+  //
+  fn pop(&mut self, class: usize) -> Option<MiniVec<T>> {
+    self.free.get_mut(class)?.pop()
+  }
+
+  // Hands `vec` back if its class is full so that it's freed outside of any lock.
+  //
+  fn push(&mut self, vec: MiniVec<T>) -> Option<MiniVec<T>> {
+    let class = (usize::BITS - 1 - vec.capacity().leading_zeros()) as usize;
+    if self.free.len() <= class {
+      self.free.resize_with(class + 1, MiniVec::new);
+    }
+
+    let list = &mut self.free[class];
+    if list.len() >= self.max_per_class {
+      return Some(vec);
+    }
+
+    list.push(vec);
+    None
+  }
+}
+
+fn class_of(capacity: usize) -> usize {
+  let Some(rounded) = capacity.checked_next_power_of_two() else {
+    panic!("capacity overflow");
+  };
+
+  rounded.trailing_zeros() as usize
+}
+
+/// `Recycle` is implemented by the pools which [`PooledMiniVec`](PooledMiniVec)s return their allocations to.
+///
+pub trait Recycle<T> {
+  /// `recycle` clears `vec` and keeps its allocation around to hand out again.
+  ///
+  fn recycle(&self, vec: MiniVec<T>);
+}
+
+/// `PooledMiniVec` is a [`MiniVec`](crate::MiniVec) on loan from a pool, which it derefs to. When dropped, its
+/// elements are dropped and its allocation is returned to the pool.
+///
+pub struct PooledMiniVec<'a, T, P: Recycle<T> + ?Sized = MiniVecPool<T>> {
+  vec: ManuallyDrop<MiniVec<T>>,
+  pool: &'a P,
+}
+
+impl<T, P: Recycle<T> + ?Sized> PooledMiniVec<'_, T, P> {
+  /// `detach` takes the vector out of the pool's hands so that its allocation is freed normally instead of being
+  /// recycled.
+  ///
+  #[must_use]
+  pub fn detach(self) -> MiniVec<T> {
+    let mut this = ManuallyDrop::new(self);
+    unsafe { ManuallyDrop::take(&mut this.vec) }
+  }
+}
+
+impl<T, P: Recycle<T> + ?Sized> Drop for PooledMiniVec<'_, T, P> {
+  fn drop(&mut self) {
+    self
+      .pool
+      .recycle(unsafe { ManuallyDrop::take(&mut self.vec) });
+  }
+}
+
+impl<T, P: Recycle<T> + ?Sized> core::ops::Deref for PooledMiniVec<'_, T, P> {
+  type Target = MiniVec<T>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.vec
+  }
+}
+
+impl<T, P: Recycle<T> + ?Sized> core::ops::DerefMut for PooledMiniVec<'_, T, P> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.vec
+  }
+}
+
+impl<T: core::fmt::Debug, P: Recycle<T> + ?Sized> core::fmt::Debug for PooledMiniVec<'_, T, P> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.vec.fmt(f)
+  }
+}
+
+/// `MiniVecPool` keeps the allocations of dropped vectors around for reuse, grouped by capacity class, so that code
+/// which repeatedly needs similarly sized scratch vectors stops hitting the allocator.
+///
+/// [`take`](MiniVecPool::take) hands out an empty [`PooledMiniVec`](PooledMiniVec) with at least the requested
+/// capacity, which returns its allocation to the pool when dropped. Allocations are reused as is, header and
+/// alignment included. Each capacity class holds at most a fixed number of free vectors, beyond which returned
+/// allocations are freed.
+///
+/// `MiniVecPool` can only be used from one thread at a time; [`SyncMiniVecPool`](SyncMiniVecPool) can be shared
+/// between threads.
+///
+/// # Example
+///
+/// ```
+/// let pool = minivec::MiniVecPool::new();
+///
+/// let mut buf = pool.take(1000);
+/// buf.extend_from_slice(b"hello");
+/// let ptr = buf.as_ptr();
+/// drop(buf);
+///
+/// let buf = pool.take(800);
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.as_ptr(), ptr);
+/// ```
+///
+pub struct MiniVecPool<T> {
+  classes: RefCell<Classes<T>>,
+}
+
+impl<T> MiniVecPool<T> {
+  /// `clear` frees every allocation held by the pool.
+  ///
+  pub fn clear(&self) {
+    let free = core::mem::take(&mut self.classes.borrow_mut().free);
+    drop(free);
+  }
+
+  /// `is_empty` returns whether or not the pool holds any free allocations.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of free allocations held by the pool.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.classes.borrow().len()
+  }
+
+  /// `new` constructs an empty pool which holds up to 64 free allocations per capacity class.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniVecPool::with_max_per_class(DEFAULT_MAX_PER_CLASS)
+  }
+
+  /// `take` returns an empty vector with room for at least `capacity` elements, reusing a free allocation when
+  /// the pool has one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `capacity` rounded up to a power of two overflows `usize`.
+  ///
+  pub fn take(&self, capacity: usize) -> PooledMiniVec<'_, T, Self> {
+    let class = class_of(capacity);
+    let vec = self.classes.borrow_mut().pop(class);
+
+    PooledMiniVec {
+      vec: ManuallyDrop::new(vec.unwrap_or_else(|| MiniVec::with_capacity(1 << class))),
+      pool: self,
+    }
+  }
+
+  /// `with_max_per_class` constructs an empty pool which holds up to `max_per_class` free allocations per capacity
+  /// class.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn with_max_per_class(max_per_class: usize) -> Self {
+    MiniVecPool {
+      classes: RefCell::new(Classes::new(max_per_class)),
+    }
+  }
+}
+
+impl<T> Recycle<T> for MiniVecPool<T> {
+  fn recycle(&self, mut vec: MiniVec<T>) {
+    vec.clear();
+    if vec.capacity() == 0 {
+      return;
+    }
+
+    let rejected = self.classes.borrow_mut().push(vec);
+    drop(rejected);
+  }
+}
+
+impl<T> core::fmt::Debug for MiniVecPool<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("MiniVecPool")
+      .field("len", &self.len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl<T> Default for MiniVecPool<T> {
+  fn default() -> Self {
+    MiniVecPool::new()
+  }
+}
+
+/// `SyncMiniVecPool` is a [`MiniVecPool`](MiniVecPool) which can be shared between threads. Its free lists are
+/// guarded by a spin lock which is only ever held while a vector is pushed onto or popped off of them.
+///
+/// # Example
+///
+/// ```
+/// let pool = minivec::SyncMiniVecPool::<u64>::new();
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             let mut scratch = pool.take(256);
+///             scratch.extend(0..256);
+///             assert_eq!(scratch.iter().sum::<u64>(), 32640);
+///         });
+///     }
+/// });
+///
+/// assert!(!pool.is_empty());
+/// ```
+///
+pub struct SyncMiniVecPool<T> {
+  locked: AtomicBool,
+  classes: UnsafeCell<Classes<T>>,
+}
+
+unsafe impl<T: Send> Send for SyncMiniVecPool<T> {}
+unsafe impl<T: Send> Sync for SyncMiniVecPool<T> {}
+
+impl<T> SyncMiniVecPool<T> {
+  fn with_classes<R>(&self, f: impl FnOnce(&mut Classes<T>) -> R) -> R {
+    while self
+      .locked
+      .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+      .is_err()
+    {
+      spin_loop();
+    }
+
+    let result = self.classes.with_mut(|classes| f(unsafe { &mut *classes }));
+    self.locked.store(false, Ordering::Release);
+    result
+  }
+
+  /// `clear` frees every allocation held by the pool.
+  ///
+  pub fn clear(&self) {
+    let free = self.with_classes(|classes| core::mem::take(&mut classes.free));
+    drop(free);
+  }
+
+  /// `is_empty` returns whether or not the pool holds any free allocations.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of free allocations held by the pool.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.with_classes(|classes| classes.len())
+  }
+
+  /// `new` constructs an empty pool which holds up to 64 free allocations per capacity class.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    SyncMiniVecPool::with_max_per_class(DEFAULT_MAX_PER_CLASS)
+  }
+
+  /// `take` returns an empty vector with room for at least `capacity` elements, reusing a free allocation when
+  /// the pool has one.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `capacity` rounded up to a power of two overflows `usize`.
+  ///
+  pub fn take(&self, capacity: usize) -> PooledMiniVec<'_, T, Self> {
+    let class = class_of(capacity);
+    let vec = self.with_classes(|classes| classes.pop(class));
+
+    PooledMiniVec {
+      vec: ManuallyDrop::new(vec.unwrap_or_else(|| MiniVec::with_capacity(1 << class))),
+      pool: self,
+    }
+  }
+
+  /// `with_max_per_class` constructs an empty pool which holds up to `max_per_class` free allocations per capacity
+  /// class.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn with_max_per_class(max_per_class: usize) -> Self {
+    SyncMiniVecPool {
+      locked: AtomicBool::new(false),
+      classes: UnsafeCell::new(Classes::new(max_per_class)),
+    }
+  }
+}
+
+impl<T> Recycle<T> for SyncMiniVecPool<T> {
+  fn recycle(&self, mut vec: MiniVec<T>) {
+    vec.clear();
+    if vec.capacity() == 0 {
+      return;
+    }
+
+    let rejected = self.with_classes(|classes| classes.push(vec));
+    drop(rejected);
+  }
+}
+
+impl<T> core::fmt::Debug for SyncMiniVecPool<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("SyncMiniVecPool")
+      .field("len", &self.len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl<T> Default for SyncMiniVecPool<T> {
+  fn default() -> Self {
+    SyncMiniVecPool::new()
+  }
+}
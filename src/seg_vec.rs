@@ -0,0 +1,378 @@
+//! `seg_vec` contains [`MiniSegVec`](MiniSegVec), a segmented vector whose elements never move once pushed.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::locate_segment;
+use crate::MiniVec;
+
+use core::cell::UnsafeCell;
+
+// Segment `k` holds `FIRST_SEGMENT_LEN << k` elements.
+//
+const FIRST_SEGMENT_SHIFT: usize = 3;
+const FIRST_SEGMENT_LEN: usize = 1 << FIRST_SEGMENT_SHIFT;
+
+fn segment_len(segment: usize) -> usize {
+  FIRST_SEGMENT_LEN << segment
+}
+
+/// `MiniSegVec` is a growable vector which stores its elements in a table of segments, each a `MiniVec` twice as
+/// large as the last. Growing only ever adds a new segment so existing elements are never moved, making it useful as
+/// an arena which hands out references that stay valid while it keeps growing.
+///
+/// The vector itself is a single pointer to its segment table. [`alloc`](MiniSegVec::alloc) pushes through a shared
+/// reference and hands back a reference which stays valid for as long as the vector is borrowed, so a
+/// `MiniSegVec` can't be shared between threads.
+///
+/// [`clear`](MiniSegVec::clear) keeps every segment allocated so that the vector can be refilled without allocating.
+///
+/// # Example
+///
+/// ```
+/// let arena = minivec::MiniSegVec::new();
+///
+/// let first: &String = arena.alloc(String::from("first"));
+/// for i in 0..1000 {
+///     arena.alloc(i.to_string());
+/// }
+///
+/// // `first` is still valid even though the arena has grown many times
+/// //
+/// assert_eq!(first, "first");
+/// assert_eq!(arena.len(), 1001);
+/// assert_eq!(arena[1000], "999");
+/// ```
+///
+pub struct MiniSegVec<T> {
+  segments: UnsafeCell<MiniVec<MiniVec<T>>>,
+}
+
+/// `Iter` is an iterator over shared references to the elements of a [`MiniSegVec`](MiniSegVec), created by
+/// [`iter`](MiniSegVec::iter).
+///
+pub struct Iter<'a, T> {
+  vec: &'a MiniSegVec<T>,
+  idx: usize,
+  end: usize,
+}
+
+/// `IterMut` is an iterator over mutable references to the elements of a [`MiniSegVec`](MiniSegVec), created by
+/// [`iter_mut`](MiniSegVec::iter_mut).
+///
+pub struct IterMut<'a, T> {
+  inner: core::iter::Flatten<core::slice::IterMut<'a, MiniVec<T>>>,
+}
+
+/// `IntoIter` is an iterator which moves the elements out of a [`MiniSegVec`](MiniSegVec).
+///
+pub struct IntoIter<T> {
+  inner: core::iter::Flatten<crate::IntoIter<MiniVec<T>>>,
+}
+
+impl<T> MiniSegVec<T> {
+  // The table is only ever borrowed for the duration of a single method call. `alloc` may reallocate it, which moves
+  // the segment handles but never the elements they point to.
+  //
+  fn table(&self) -> &MiniVec<MiniVec<T>> {
+    unsafe { &*self.segments.get() }
+  }
+
+  fn table_mut(&mut self) -> &mut MiniVec<MiniVec<T>> {
+    self.segments.get_mut()
+  }
+
+  // Callers must not be holding a borrow of the table itself, only of elements.
+  //
+  unsafe fn push_raw(&self, value: T) -> *mut T {
+    let segments = &mut *self.segments.get();
+
+    let idx = if let Some(idx) = segments.iter().position(|s| s.len() < s.capacity()) {
+      idx
+    } else {
+      segments.push(MiniVec::with_capacity(segment_len(segments.len())));
+      segments.len() - 1
+    };
+
+    let segment = &mut segments[idx];
+    let len = segment.len();
+    segment.push(value);
+
+    segment.as_mut_ptr().add(len)
+  }
+
+  /// `alloc` appends `value` through a shared reference and returns a reference to it. The reference stays valid
+  /// while more elements are allocated as the element is never moved.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let names = minivec::MiniSegVec::new();
+  ///
+  /// let alice: &str = names.alloc(String::from("alice"));
+  /// let bob: &str = names.alloc(String::from("bob"));
+  ///
+  /// let edges = [(alice, bob), (bob, alice)];
+  /// assert_eq!(edges[1], ("bob", "alice"));
+  /// ```
+  ///
+  pub fn alloc(&self, value: T) -> &T {
+    unsafe { &*self.push_raw(value) }
+  }
+
+  /// `capacity` returns the number of elements the vector can hold across all of its allocated segments without
+  /// allocating another one.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.table().iter().map(MiniVec::capacity).sum()
+  }
+
+  /// `clear` drops every element in the vector while keeping its segments allocated for reuse.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec: minivec::MiniSegVec<_> = (0..100).collect();
+  /// let capacity = vec.capacity();
+  ///
+  /// vec.clear();
+  /// assert!(vec.is_empty());
+  /// assert_eq!(vec.capacity(), capacity);
+  /// ```
+  ///
+  pub fn clear(&mut self) {
+    for segment in self.table_mut().iter_mut() {
+      segment.clear();
+    }
+  }
+
+  /// `get` returns a reference to the element at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  #[must_use]
+  pub fn get(&self, idx: usize) -> Option<&T> {
+    if idx >= self.len() {
+      return None;
+    }
+
+    let (segment, offset) = locate_segment(idx, FIRST_SEGMENT_SHIFT);
+    self.table().get(segment)?.get(offset)
+  }
+
+  /// `get_mut` returns a mutable reference to the element at `idx`, or `None` if `idx` is out of bounds.
+  ///
+  pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+    if idx >= self.len() {
+      return None;
+    }
+
+    let (segment, offset) = locate_segment(idx, FIRST_SEGMENT_SHIFT);
+    self.table_mut().get_mut(segment)?.get_mut(offset)
+  }
+
+  /// `is_empty` returns whether or not the vector contains any elements.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.table().first().is_none_or(MiniVec::is_empty)
+  }
+
+  /// `iter` returns an iterator over the elements in the vector. Elements allocated after the iterator was created
+  /// are not visited.
+  ///
+  pub fn iter(&self) -> Iter<'_, T> {
+    Iter {
+      vec: self,
+      idx: 0,
+      end: self.len(),
+    }
+  }
+
+  /// `iter_mut` returns an iterator over mutable references to the elements in the vector.
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+    IterMut {
+      inner: self.table_mut().iter_mut().flatten(),
+    }
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.table().iter().map(MiniVec::len).sum()
+  }
+
+  /// `new` constructs an empty vector.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniSegVec {
+      segments: UnsafeCell::new(MiniVec::new()),
+    }
+  }
+
+  /// `pop` removes the last element of the vector and returns it, or `None` if the vector is empty. The segment it
+  /// lived in stays allocated.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    self
+      .table_mut()
+      .iter_mut()
+      .rev()
+      .find(|s| !s.is_empty())?
+      .pop()
+  }
+
+  /// `push` appends `value` to the vector and returns a mutable reference to it.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniSegVec::new();
+  ///
+  /// *vec.push(1) += 1;
+  /// assert_eq!(vec[0], 2);
+  /// ```
+  ///
+  pub fn push(&mut self, value: T) -> &mut T {
+    unsafe { &mut *self.push_raw(value) }
+  }
+}
+
+impl<T: Clone> Clone for MiniSegVec<T> {
+  fn clone(&self) -> Self {
+    self.iter().cloned().collect()
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniSegVec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T> Default for MiniSegVec<T> {
+  fn default() -> Self {
+    MiniSegVec::new()
+  }
+}
+
+impl<T: PartialEq> PartialEq for MiniSegVec<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for MiniSegVec<T> {}
+
+impl<T> core::iter::Extend<T> for MiniSegVec<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<T> core::iter::FromIterator<T> for MiniSegVec<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut vec = MiniSegVec::new();
+    vec.extend(iter);
+    vec
+  }
+}
+
+impl<T> core::ops::Index<usize> for MiniSegVec<T> {
+  type Output = T;
+
+  fn index(&self, idx: usize) -> &T {
+    let len = self.len();
+    match self.get(idx) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+}
+
+impl<T> core::ops::IndexMut<usize> for MiniSegVec<T> {
+  fn index_mut(&mut self, idx: usize) -> &mut T {
+    let len = self.len();
+    match self.get_mut(idx) {
+      Some(value) => value,
+      None => panic!(
+        "index out of bounds: the len is {} but the index is {}",
+        len, idx
+      ),
+    }
+  }
+}
+
+impl<T> core::iter::IntoIterator for MiniSegVec<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter {
+      inner: self.segments.into_inner().into_iter().flatten(),
+    }
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a MiniSegVec<T> {
+  type Item = &'a T;
+  type IntoIter = Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a mut MiniSegVec<T> {
+  type Item = &'a mut T;
+  type IntoIter = IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.idx == self.end {
+      return None;
+    }
+
+    let value = self.vec.get(self.idx);
+    self.idx += 1;
+    value
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let remaining = self.end - self.idx;
+    (remaining, Some(remaining))
+  }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+  type Item = &'a mut T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.next()
+  }
+}
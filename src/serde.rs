@@ -1,8 +1,19 @@
-use crate::MiniVec;
+use crate::hash_map::StatelessBuildHasher;
+use crate::{
+  BoundedMiniVec, GenKey, Idx, MiniCow, MiniHashMap, MiniHashSet, MiniIndexVec, MiniInterner,
+  MiniJagged, MiniMatrix, MiniSlab, MiniSmallVec, MiniStrVec, MiniVec, MiniVecMap, NonEmptyMiniVec,
+  SlabKey, Symbol,
+};
 
-use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::de::{
+  Deserialize, DeserializeSeed, Deserializer, Error as DeError, IgnoredAny, MapAccess, SeqAccess,
+  Unexpected, Visitor,
+};
 use serde::ser::{Serialize, Serializer};
 
+use core::any::TypeId;
+use core::convert::TryFrom;
+use core::hash::Hash;
 use core::marker::PhantomData;
 use core::{cmp, fmt};
 
@@ -13,7 +24,7 @@ impl<T: Serialize> Serialize for MiniVec<T> {
   }
 }
 
-#[inline(always)]
+#[inline]
 fn map_size_hint(hint: Option<usize>) -> usize {
   match hint {
     Some(hint) => cmp::min(hint, 1024),
@@ -21,12 +32,25 @@ fn map_size_hint(hint: Option<usize>) -> usize {
   }
 }
 
+// Maps and slabs allocate a slot for every index up to their largest key. While deserializing, their slots are
+// limited to `MIN_RESTORED_SLOTS` plus `RESTORED_SLOTS_PER_VALUE` for every value read so far so that a single large
+// key in the input can't force a huge allocation.
+//
+const MIN_RESTORED_SLOTS: usize = 1024;
+const RESTORED_SLOTS_PER_VALUE: usize = 8;
+
+fn restored_slots_limit(values: usize) -> usize {
+  values
+    .saturating_mul(RESTORED_SLOTS_PER_VALUE)
+    .saturating_add(MIN_RESTORED_SLOTS)
+}
+
 //Helper to deserialize in place
 //
 //Taken from serde
 struct InPlaceSeed<'a, T: 'a>(pub &'a mut T);
 
-impl<'a, 'de, T: Deserialize<'de>> DeserializeSeed<'de> for InPlaceSeed<'a, T> {
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for InPlaceSeed<'_, T> {
   type Value = ();
 
   #[inline]
@@ -74,7 +98,7 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for MiniVec<T> {
   ) -> Result<(), D::Error> {
     struct VecInPlaceVisitor<'a, T: 'a>(&'a mut MiniVec<T>);
 
-    impl<'a, 'de, T: Deserialize<'de>> Visitor<'de> for VecInPlaceVisitor<'a, T> {
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for VecInPlaceVisitor<'_, T> {
       type Value = ();
 
       fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -110,27 +134,1004 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for MiniVec<T> {
   }
 }
 
+impl<K, V, S> Serialize for MiniHashMap<K, V, S>
+where
+  K: Serialize,
+  V: Serialize,
+{
+  #[inline]
+  fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+    serializer.collect_map(self)
+  }
+}
+
+impl<'de, K, V, S> Deserialize<'de> for MiniHashMap<K, V, S>
+where
+  K: Deserialize<'de> + Eq + Hash,
+  V: Deserialize<'de>,
+  S: StatelessBuildHasher,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<K, V, S> {
+      marker: PhantomData<MiniHashMap<K, V, S>>,
+    }
+
+    impl<'de, K, V, S> Visitor<'de> for MapVisitor<K, V, S>
+    where
+      K: Deserialize<'de> + Eq + Hash,
+      V: Deserialize<'de>,
+      S: StatelessBuildHasher,
+    {
+      type Value = MiniHashMap<K, V, S>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+      }
+
+      fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = MiniHashMap::default();
+        values.reserve(map_size_hint(map.size_hint()));
+
+        while let Some((key, value)) = map.next_entry()? {
+          values.insert(key, value);
+        }
+
+        Ok(values)
+      }
+    }
+
+    let visitor = MapVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_map(visitor)
+  }
+}
+
+impl<T, S> Serialize for MiniHashSet<T, S>
+where
+  T: Serialize,
+{
+  #[inline]
+  fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+    serializer.collect_seq(self)
+  }
+}
+
+impl<'de, T, S> Deserialize<'de> for MiniHashSet<T, S>
+where
+  T: Deserialize<'de> + Eq + Hash,
+  S: StatelessBuildHasher,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct SetVisitor<T, S> {
+      marker: PhantomData<MiniHashSet<T, S>>,
+    }
+
+    impl<'de, T, S> Visitor<'de> for SetVisitor<T, S>
+    where
+      T: Deserialize<'de> + Eq + Hash,
+      S: StatelessBuildHasher,
+    {
+      type Value = MiniHashSet<T, S>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = MiniHashSet::default();
+        values.reserve(map_size_hint(seq.size_hint()));
+
+        while let Some(value) = seq.next_element()? {
+          values.insert(value);
+        }
+
+        Ok(values)
+      }
+    }
+
+    let visitor = SetVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+  }
+}
+
+impl<I: Idx, T: Serialize> Serialize for MiniIndexVec<I, T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.raw().serialize(serializer)
+  }
+}
+
+impl<'de, I: Idx, T: Deserialize<'de>> Deserialize<'de> for MiniIndexVec<I, T> {
+  #[inline]
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    MiniVec::deserialize(deserializer).map(MiniIndexVec::from_raw)
+  }
+}
+
+impl<I: Idx, V: Serialize> Serialize for MiniVecMap<I, V> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter().map(|(k, v)| (k.index(), v)))
+  }
+}
+
+impl<'de, I: Idx, V: Deserialize<'de>> Deserialize<'de> for MiniVecMap<I, V> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MapVisitor<I, V> {
+      marker: PhantomData<MiniVecMap<I, V>>,
+    }
+
+    impl<'de, I: Idx, V: Deserialize<'de>> Visitor<'de> for MapVisitor<I, V> {
+      type Value = MiniVecMap<I, V>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of indices to values")
+      }
+
+      fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = MiniVecMap::new();
+
+        let mut read = 0_usize;
+        while let Some((key, value)) = map.next_entry::<usize, V>()? {
+          read += 1;
+          if key >= restored_slots_limit(read) {
+            return Err(A::Error::invalid_value(
+              Unexpected::Unsigned(key as u64),
+              &"a map index within the bounds set by the values before it",
+            ));
+          }
+
+          let Some(idx) = I::try_new(key) else {
+            return Err(A::Error::invalid_value(
+              Unexpected::Unsigned(key as u64),
+              &"an index representable by the map's index type",
+            ));
+          };
+
+          if values.insert(idx, value).is_some() {
+            return Err(A::Error::custom("duplicate map index"));
+          }
+        }
+
+        Ok(values)
+      }
+    }
+
+    let visitor = MapVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_map(visitor)
+  }
+}
+
+impl<T: Serialize> Serialize for NonEmptyMiniVec<T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_mini_vec().serialize(serializer)
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NonEmptyMiniVec<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let vec = MiniVec::deserialize(deserializer)?;
+    NonEmptyMiniVec::from_vec(vec)
+      .ok_or_else(|| D::Error::invalid_length(0, &"a sequence with at least one element"))
+  }
+}
+
+impl<T: Serialize, const N: usize> Serialize for BoundedMiniVec<T, N> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_mini_vec().serialize(serializer)
+  }
+}
+
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for BoundedMiniVec<T, N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct BoundedVisitor<T, const N: usize> {
+      marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for BoundedVisitor<T, N> {
+      type Value = BoundedMiniVec<T, N>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of at most {N} elements")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        // reject oversized inputs before allocating anything when the format tells us the length up front
+        //
+        if let Some(len) = seq.size_hint() {
+          if len > N {
+            return Err(A::Error::invalid_length(len, &self));
+          }
+        }
+
+        let mut values = BoundedMiniVec::with_capacity(map_size_hint(seq.size_hint()));
+
+        while !values.is_full() {
+          match seq.next_element()? {
+            Some(value) => values.push(value),
+            None => return Ok(values),
+          }
+        }
+
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+          return Err(A::Error::invalid_length(N + 1, &self));
+        }
+
+        Ok(values)
+      }
+    }
+
+    let visitor = BoundedVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+  }
+}
+
+impl<T: Serialize> Serialize for MiniSmallVec<T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self)
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MiniSmallVec<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct SmallVecVisitor<T> {
+      marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for SmallVecVisitor<T> {
+      type Value = MiniSmallVec<T>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = MiniSmallVec::with_capacity(map_size_hint(seq.size_hint()));
+
+        while let Some(value) = seq.next_element()? {
+          values.push(value);
+        }
+
+        Ok(values)
+      }
+    }
+
+    let visitor = SmallVecVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+  }
+}
+
+impl<T: Serialize> Serialize for MiniJagged<T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter_rows())
+  }
+}
+
+// Deserializes a single row straight into the end of a ragged array
+//
+struct RowSeed<'a, T>(&'a mut MiniJagged<T>);
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for RowSeed<'_, T> {
+  type Value = ();
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_seq(self)
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for RowSeed<'_, T> {
+  type Value = ();
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a sequence")
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    self.0.reserve_values(map_size_hint(seq.size_hint()));
+    self.0.push_row(core::iter::empty());
+
+    while let Some(value) = seq.next_element()? {
+      self.0.extend_last_row(core::iter::once(value));
+    }
+
+    Ok(())
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MiniJagged<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct JaggedVisitor<T> {
+      marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for JaggedVisitor<T> {
+      type Value = MiniJagged<T>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of sequences")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut jagged = MiniJagged::new();
+        jagged.reserve_rows(map_size_hint(seq.size_hint()));
+
+        while seq.next_element_seed(RowSeed(&mut jagged))?.is_some() {}
+
+        Ok(jagged)
+      }
+    }
+
+    let visitor = JaggedVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+  }
+}
+
+impl<T: Serialize> Serialize for MiniMatrix<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeTuple;
+
+    let mut tuple = serializer.serialize_tuple(3)?;
+    tuple.serialize_element(&self.rows())?;
+    tuple.serialize_element(&self.cols())?;
+    tuple.serialize_element(self.as_slice())?;
+    tuple.end()
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MiniMatrix<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct MatrixVisitor<T> {
+      marker: PhantomData<T>,
+    }
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for MatrixVisitor<T> {
+      type Value = MiniMatrix<T>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tuple of the rows, columns and row-major elements of a matrix")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let rows = seq
+          .next_element()?
+          .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let cols = seq
+          .next_element()?
+          .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let values: MiniVec<T> = seq
+          .next_element()?
+          .ok_or_else(|| A::Error::invalid_length(2, &self))?;
+
+        MiniMatrix::from_vec(values, rows, cols)
+          .map_err(|_| A::Error::custom("matrix elements do not match its shape"))
+      }
+    }
+
+    let visitor = MatrixVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_tuple(3, visitor)
+  }
+}
+
+impl Serialize for MiniStrVec {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.iter())
+  }
+}
+
+// Deserializes a single string straight into the end of a `MiniStrVec` without an intermediate `String`
+//
+struct StrSeed<'a>(&'a mut MiniStrVec);
+
+impl<'de> DeserializeSeed<'de> for StrSeed<'_> {
+  type Value = ();
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_str(self)
+  }
+}
+
+impl Visitor<'_> for StrSeed<'_> {
+  type Value = ();
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a string")
+  }
+
+  fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+    self.0.push(v);
+    Ok(())
+  }
+}
+
+impl<'de> Deserialize<'de> for MiniStrVec {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct StrVecVisitor;
+
+    impl<'de> Visitor<'de> for StrVecVisitor {
+      type Value = MiniStrVec;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of strings")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut strs = MiniStrVec::with_capacity(map_size_hint(seq.size_hint()), 0);
+
+        while seq.next_element_seed(StrSeed(&mut strs))?.is_some() {}
+
+        Ok(strs)
+      }
+    }
+
+    deserializer.deserialize_seq(StrVecVisitor)
+  }
+}
+
+impl Serialize for Symbol {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u32(self.as_u32())
+  }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    u32::deserialize(deserializer).map(Symbol::from_u32)
+  }
+}
+
+impl<S> Serialize for MiniInterner<S> {
+  #[inline]
+  fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+    serializer.collect_seq(self.iter().map(|(_, s)| s))
+  }
+}
+
+// Interns a single string, rejecting duplicates as they would shift the symbols of every later string
+//
+struct InternSeed<'a, S>(&'a mut MiniInterner<S>);
+
+impl<'de, S: StatelessBuildHasher> DeserializeSeed<'de> for InternSeed<'_, S> {
+  type Value = ();
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_str(self)
+  }
+}
+
+impl<S: StatelessBuildHasher> Visitor<'_> for InternSeed<'_, S> {
+  type Value = ();
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a string")
+  }
+
+  fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
+    let len = self.0.len();
+    if self.0.intern(v).index() != len {
+      return Err(E::custom(format_args!("duplicate interned string {v:?}")));
+    }
+
+    Ok(())
+  }
+}
+
+impl<'de, S: StatelessBuildHasher> Deserialize<'de> for MiniInterner<S> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct InternerVisitor<S> {
+      marker: PhantomData<fn() -> S>,
+    }
+
+    impl<'de, S: StatelessBuildHasher> Visitor<'de> for InternerVisitor<S> {
+      type Value = MiniInterner<S>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of distinct strings")
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut interner = MiniInterner::default();
+        interner.reserve(map_size_hint(seq.size_hint()), 0);
+
+        while seq.next_element_seed(InternSeed(&mut interner))?.is_some() {}
+
+        Ok(interner)
+      }
+    }
+
+    let visitor = InternerVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_seq(visitor)
+  }
+}
+
+impl Serialize for GenKey {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    (self.index(), self.generation()).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for GenKey {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let (index, generation) = <(usize, u32)>::deserialize(deserializer)?;
+    if u32::try_from(index).is_err() {
+      return Err(D::Error::custom("slab index exceeded u32::MAX"));
+    }
+
+    Ok(SlabKey::new(index, generation))
+  }
+}
+
+impl<T: Serialize, K: SlabKey + Serialize> Serialize for MiniSlab<T, K> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(self.iter())
+  }
+}
+
+impl<'de, T: Deserialize<'de>, K: SlabKey + Deserialize<'de>> Deserialize<'de> for MiniSlab<T, K> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct SlabVisitor<T, K> {
+      marker: PhantomData<MiniSlab<T, K>>,
+    }
+
+    impl<'de, T: Deserialize<'de>, K: SlabKey + Deserialize<'de>> Visitor<'de> for SlabVisitor<T, K> {
+      type Value = MiniSlab<T, K>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of slab keys to values")
+      }
+
+      fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut slab = MiniSlab::default();
+        slab.reserve(map_size_hint(map.size_hint()));
+
+        let mut restored = 0_usize;
+        while let Some((key, value)) = map.next_entry::<K, T>()? {
+          // the vacant slots in front of a key are allocated too, so their number is bounded by the values seen so
+          // far to keep a single large key from forcing a huge allocation
+          //
+          restored += 1;
+          if key.index() >= restored_slots_limit(restored) {
+            return Err(A::Error::invalid_value(
+              Unexpected::Unsigned(key.index() as u64),
+              &"a slab key index within the bounds set by the values before it",
+            ));
+          }
+
+          if slab.restore(key, value).is_err() {
+            return Err(A::Error::custom("duplicate slab key"));
+          }
+        }
+
+        slab.finish_restore();
+        Ok(slab)
+      }
+    }
+
+    let visitor = SlabVisitor {
+      marker: PhantomData,
+    };
+    deserializer.deserialize_map(visitor)
+  }
+}
+
+impl<T: Serialize> Serialize for MiniCow<'_, T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self)
+  }
+}
+
+// A `MiniCow<'de, u8>` asks for bytes so that it can borrow them straight from the input, every other element type
+// is deserialized into an owned vector.
+//
+impl<'de: 'a, 'a, T: Deserialize<'de> + 'static> Deserialize<'de> for MiniCow<'a, T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct CowVisitor<'a, T> {
+      marker: PhantomData<&'a [T]>,
+    }
+
+    impl<'de: 'a, 'a, T: Deserialize<'de> + 'static> Visitor<'de> for CowVisitor<'a, T> {
+      type Value = MiniCow<'a, T>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+      }
+
+      // self-describing formats may hand bytes to any visitor, which can only be reinterpreted as `u8` elements
+      //
+      fn visit_borrowed_bytes<E: DeError>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        if TypeId::of::<T>() != TypeId::of::<u8>() {
+          return Err(E::invalid_type(Unexpected::Bytes(v), &self));
+        }
+
+        let slice = unsafe { core::slice::from_raw_parts(v.as_ptr().cast::<T>(), v.len()) };
+        Ok(MiniCow::borrowed(slice))
+      }
+
+      fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if TypeId::of::<T>() != TypeId::of::<u8>() {
+          return Err(E::invalid_type(Unexpected::Bytes(v), &self));
+        }
+
+        let slice = unsafe { core::slice::from_raw_parts(v.as_ptr().cast::<T>(), v.len()) };
+
+        let mut vec = MiniVec::with_capacity(slice.len());
+        unsafe {
+          core::ptr::copy_nonoverlapping(slice.as_ptr(), vec.as_mut_ptr(), slice.len());
+          vec.set_len(slice.len());
+        }
+        Ok(MiniCow::owned(vec))
+      }
+
+      fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = MiniVec::with_capacity(map_size_hint(seq.size_hint()));
+
+        while let Some(value) = seq.next_element()? {
+          values.push(value);
+        }
+
+        Ok(MiniCow::owned(values))
+      }
+    }
+
+    let visitor = CowVisitor {
+      marker: PhantomData,
+    };
+
+    if TypeId::of::<T>() == TypeId::of::<u8>() {
+      deserializer.deserialize_bytes(visitor)
+    } else {
+      deserializer.deserialize_seq(visitor)
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::MiniVec;
+  use crate::hash_map::DefaultHashBuilder;
+  use crate::{
+    BoundedMiniVec, GenKey, MiniCow, MiniHashMap, MiniHashSet, MiniIndexVec, MiniInterner,
+    MiniJagged, MiniMatrix, MiniSlab, MiniSmallVec, MiniStrVec, MiniVec, MiniVecMap,
+    NonEmptyMiniVec, SlabKey, Symbol,
+  };
 
-  use serde::de::value::{Error as ValueError, SeqDeserializer};
+  use serde::de::value::{Error as ValueError, MapDeserializer, SeqDeserializer};
   use serde::de::Deserialize;
 
   #[test]
   fn should_deserialize() {
     let input = [1u32, 2, 3, 10, 5];
-    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().cloned());
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
     let result = MiniVec::<u32>::deserialize(deserializer).expect("To deserialize");
     assert_eq!(result, input);
 
-    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().cloned());
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
     let mut vec = MiniVec::<u32>::new();
     MiniVec::<u32>::deserialize_in_place(deserializer, &mut vec).expect("To deserialize");
     assert_eq!(vec, input);
 
-    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().cloned());
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
     MiniVec::<u32>::deserialize_in_place(deserializer, &mut vec).expect("To deserialize");
     assert_eq!(vec, input);
   }
+
+  #[test]
+  fn should_deserialize_hash_map() {
+    let input = [(1u32, 10u32), (2, 20), (3, 30)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniHashMap::<u32, u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.len(), input.len());
+    for (k, v) in input {
+      assert_eq!(result[&k], v);
+    }
+  }
+
+  #[test]
+  fn should_deserialize_hash_set() {
+    let input = [1u32, 2, 3, 2, 1];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniHashSet::<u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.len(), 3);
+    assert!(input.iter().all(|x| result.contains(x)));
+  }
+
+  #[test]
+  fn should_deserialize_index_vec() {
+    let input = [1u32, 2, 3];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniIndexVec::<u32, u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.as_slice(), input);
+    assert_eq!(result[2_u32], 3);
+  }
+
+  #[test]
+  fn should_deserialize_vec_map() {
+    let input = [(4usize, 40u32), (1, 10)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniVecMap::<u32, u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[1], 10);
+    assert_eq!(result[4], 40);
+    assert_eq!(result.get(0), None);
+  }
+
+  #[test]
+  fn should_reject_invalid_vec_map_keys() {
+    let input = [(5_000_000_000usize, 1u32)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniVecMap::<u32, u32>::deserialize(deserializer).is_err());
+
+    let input = [(1usize, 1u32), (3, 3), (1, 2)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniVecMap::<u32, u32>::deserialize(deserializer).is_err());
+
+    // keys far past the values read so far are rejected before any slots are allocated for them
+    //
+    let input = [(8_589_934_592usize, 1u32)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniVecMap::<usize, u32>::deserialize(deserializer).is_err());
+
+    let input = [(u32::MAX as usize, 1u32)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniVecMap::<u32, u32>::deserialize(deserializer).is_err());
+
+    let input = [(0usize, 0u32), (1, 1), (1039, 2)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniVecMap::<u32, u32>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(result[1039], 2);
+  }
+
+  #[test]
+  fn should_deserialize_non_empty() {
+    let input = [1u32, 2, 3];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = NonEmptyMiniVec::<u32>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(result, input);
+
+    let deserializer = SeqDeserializer::<_, ValueError>::new(core::iter::empty::<u32>());
+    assert!(NonEmptyMiniVec::<u32>::deserialize(deserializer).is_err());
+  }
+
+  #[test]
+  fn should_deserialize_bounded() {
+    let input = [1u32, 2, 3];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = BoundedMiniVec::<u32, 3>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(result, input);
+    assert_eq!(result.capacity(), 3);
+
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(BoundedMiniVec::<u32, 2>::deserialize(deserializer).is_err());
+
+    // a sequence that doesn't report its length must still be cut off at the limit
+    //
+    let deserializer = SeqDeserializer::<_, ValueError>::new((0u32..).filter(|x| x % 2 == 0));
+    assert!(BoundedMiniVec::<u32, 16>::deserialize(deserializer).is_err());
+  }
+
+  #[test]
+  fn should_deserialize_small_vec() {
+    let input = [1u8, 2, 3];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniSmallVec::<u8>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(result, input);
+
+    let input = [7u64; 32];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniSmallVec::<u64>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(result, input);
+  }
+
+  #[test]
+  fn should_deserialize_slab() {
+    let input = [(3usize, 30u32), (0, 0)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    let mut slab = MiniSlab::<u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(slab.len(), 2);
+    assert_eq!(slab[3], 30);
+    assert_eq!(slab[0], 0);
+    assert_eq!(slab.get(1), None);
+
+    // the gaps are reused before the slab grows
+    //
+    assert_eq!(slab.insert(10), 1);
+    assert_eq!(slab.insert(20), 2);
+    assert_eq!(slab.insert(40), 4);
+
+    let input = [(1usize, 1u32), (1, 2)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniSlab::<u32>::deserialize(deserializer).is_err());
+  }
+
+  #[test]
+  fn should_bound_deserialized_slab_slots() {
+    let input = [(usize::MAX, 1u32)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniSlab::<u32>::deserialize(deserializer).is_err());
+
+    let input = [(1032usize, 1u32)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniSlab::<u32>::deserialize(deserializer).is_err());
+
+    // every value read raises the limit
+    //
+    let input = [(0usize, 0u32), (1, 1), (1039, 2)];
+    let deserializer = MapDeserializer::<_, ValueError>::new(input.iter().copied());
+    let slab = MiniSlab::<u32>::deserialize(deserializer).expect("To deserialize");
+    assert_eq!(slab[1039], 2);
+  }
+
+  #[test]
+  fn should_deserialize_generational_slab() {
+    let deserializer = SeqDeserializer::<_, ValueError>::new([2u32, 7].iter().copied());
+    let key = GenKey::deserialize(deserializer).expect("To deserialize");
+    assert_eq!((key.index(), key.generation()), (2, 7));
+
+    let mut slab = MiniSlab::<u32, GenKey>::default();
+    assert!(slab.restore(key, 5).is_ok());
+    slab.finish_restore();
+
+    assert_eq!(slab.get(key), Some(&5));
+    assert_eq!(slab.get(<GenKey as SlabKey>::new(2, 6)), None);
+
+    // freshly inserted values never reuse a generation handed out before serializing
+    //
+    let fresh = slab.insert(1);
+    assert_eq!(fresh.index(), 0);
+    assert!(fresh.generation() > 7);
+  }
+
+  #[test]
+  fn should_deserialize_jagged() {
+    let rows: [&[u32]; 3] = [&[1, 2], &[], &[3]];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(
+      rows
+        .iter()
+        .map(|row| SeqDeserializer::<_, ValueError>::new(row.iter().copied())),
+    );
+    let result = MiniJagged::<u32>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.len(), 3);
+    assert_eq!(result[0], [1, 2]);
+    assert!(result[1].is_empty());
+    assert_eq!(result[2], [3]);
+  }
+
+  #[test]
+  fn should_deserialize_str_vec() {
+    let input = ["hello", "", "world"];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniStrVec::deserialize(deserializer).expect("To deserialize");
+
+    assert!(result.iter().eq(input.iter().copied()));
+  }
+
+  #[test]
+  fn should_deserialize_matrix() {
+    use serde::de::{Deserializer, IntoDeserializer, Visitor};
+
+    // the tuple mixes lengths with a sequence so its elements need a deserializer of their own
+    //
+    enum Part {
+      Len(usize),
+      Values(&'static [u8]),
+    }
+
+    impl<'de> Deserializer<'de> for Part {
+      type Error = ValueError;
+
+      fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+          Part::Len(len) => visitor.visit_u64(len as u64),
+          Part::Values(values) => visitor.visit_seq(SeqDeserializer::<_, ValueError>::new(
+            values.iter().copied(),
+          )),
+        }
+      }
+
+      serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier ignored_any
+      }
+    }
+
+    impl IntoDeserializer<'_, ValueError> for Part {
+      type Deserializer = Self;
+
+      fn into_deserializer(self) -> Self {
+        self
+      }
+    }
+
+    let parts = [
+      Part::Len(2),
+      Part::Len(3),
+      Part::Values(&[1, 2, 3, 4, 5, 6]),
+    ];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(IntoIterator::into_iter(parts));
+    let result = MiniMatrix::<u8>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.shape(), (2, 3));
+    assert_eq!(result.row(1), [4, 5, 6]);
+
+    let parts = [Part::Len(2), Part::Len(2), Part::Values(&[1, 2, 3])];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(IntoIterator::into_iter(parts));
+    assert!(MiniMatrix::<u8>::deserialize(deserializer).is_err());
+  }
+
+  #[test]
+  fn should_deserialize_cow() {
+    use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer};
+
+    let input = [1u8, 2, 3];
+    let deserializer = BorrowedBytesDeserializer::<ValueError>::new(&input);
+    let result = MiniCow::<u8>::deserialize(deserializer).expect("To deserialize");
+    assert!(result.is_borrowed());
+    assert_eq!(result.as_ptr(), input.as_ptr());
+    assert_eq!(result, input);
+
+    let deserializer = BytesDeserializer::<ValueError>::new(&input);
+    let result = MiniCow::<u8>::deserialize(deserializer).expect("To deserialize");
+    assert!(result.is_owned());
+    assert_eq!(result, input);
+
+    let input = [1u32, 2, 3];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result = MiniCow::<u32>::deserialize(deserializer).expect("To deserialize");
+    assert!(result.is_owned());
+    assert_eq!(result, input);
+
+    // these deserializers answer any request with bytes, which other element types must reject
+    //
+    let input = [1u8, 2, 3, 4];
+    let deserializer = BorrowedBytesDeserializer::<ValueError>::new(&input);
+    assert!(MiniCow::<u32>::deserialize(deserializer).is_err());
+
+    let deserializer = BytesDeserializer::<ValueError>::new(&input);
+    assert!(MiniCow::<u16>::deserialize(deserializer).is_err());
+  }
+
+  #[test]
+  fn should_deserialize_interner() {
+    let input = ["let", "x", "=", "y"];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    let result =
+      MiniInterner::<DefaultHashBuilder>::deserialize(deserializer).expect("To deserialize");
+
+    assert_eq!(result.len(), 4);
+    for (idx, s) in input.iter().enumerate() {
+      assert_eq!(result.get(s).map(Symbol::index), Some(idx));
+    }
+
+    let input = ["a", "b", "a"];
+    let deserializer = SeqDeserializer::<_, ValueError>::new(input.iter().copied());
+    assert!(MiniInterner::<DefaultHashBuilder>::deserialize(deserializer).is_err());
+  }
 }
@@ -0,0 +1,790 @@
+//! `slab` contains [`MiniSlab`](MiniSlab), a slab allocator which hands out stable keys to its values and reuses
+//! the slots of removed values, along with the [`SlabKey`](SlabKey) trait describing its key types.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::MiniVecWithHeader;
+
+use core::convert::TryFrom;
+
+/// `SlabKey` describes a type that can be used as a key into a [`MiniSlab`](MiniSlab).
+///
+/// A key is built from the index of its slot and the generation the slot's value was inserted with. Plain `usize`
+/// keys ignore the generation so a key to a removed value silently refers to whatever value reuses its slot, while
+/// [`GenKey`](GenKey) checks it so that such stale keys are detected.
+///
+pub trait SlabKey: Copy {
+  /// `new` constructs a key for the slot at `index` holding a value inserted with `generation`.
+  ///
+  fn new(index: usize, generation: u32) -> Self;
+
+  /// `index` returns the index of the slot the key refers to.
+  ///
+  fn index(self) -> usize;
+
+  /// `generation` returns the generation the key expects its slot to hold, or `None` if it matches any generation.
+  ///
+  fn generation(self) -> Option<u32>;
+}
+
+impl SlabKey for usize {
+  fn new(index: usize, _generation: u32) -> Self {
+    index
+  }
+
+  fn index(self) -> usize {
+    self
+  }
+
+  fn generation(self) -> Option<u32> {
+    None
+  }
+}
+
+/// `GenKey` is a generational [`SlabKey`](SlabKey) which stops matching once the value it was handed out for has
+/// been removed, even after the value's slot has been reused.
+///
+/// # Example
+///
+/// ```
+/// use minivec::{GenKey, MiniSlab};
+///
+/// let mut slab = MiniSlab::<_, GenKey>::default();
+///
+/// let stale = slab.insert("first");
+/// slab.remove(stale);
+///
+/// let fresh = slab.insert("second");
+/// assert_eq!(fresh.index(), stale.index());
+///
+/// assert_eq!(slab.get(stale), None);
+/// assert_eq!(slab.get(fresh), Some(&"second"));
+/// ```
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GenKey {
+  index: u32,
+  generation: u32,
+}
+
+impl GenKey {
+  /// `index` returns the index of the slot the key refers to.
+  ///
+  #[must_use]
+  pub fn index(self) -> usize {
+    self.index as usize
+  }
+
+  /// `generation` returns the generation of the value the key was handed out for.
+  ///
+  #[must_use]
+  pub fn generation(self) -> u32 {
+    self.generation
+  }
+}
+
+impl SlabKey for GenKey {
+  fn new(index: usize, generation: u32) -> Self {
+    GenKey {
+      index: u32::try_from(index).expect("slab index exceeded u32::MAX"),
+      generation,
+    }
+  }
+
+  fn index(self) -> usize {
+    self.index as usize
+  }
+
+  fn generation(self) -> Option<u32> {
+    Some(self.generation)
+  }
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+  Occupied { generation: u32, value: T },
+  Vacant { next: usize },
+}
+
+// Vacant slots form a free list threaded through their `next` fields, which ends at the first slot past the end of
+// the entries. Every insertion is stamped with a slab-wide generation so that keys to removed values stay stale even
+// after the entries have been truncated.
+//
+#[derive(Clone, Copy, Default)]
+struct SlabHeader {
+  next_free: usize,
+  occupied: usize,
+  generation: u32,
+}
+
+/// `MiniSlab` is a slab allocator which stores values in a [`MiniVecWithHeader`](crate::MiniVecWithHeader) of
+/// slots and hands out keys which stay valid until the value is removed. The slots of removed values are reused by
+/// later insertions. The free list and the number of occupied slots live in the vector's header so the slab is only
+/// the size of a single pointer.
+///
+/// Keys are `usize` by default. Using [`GenKey`](GenKey) instead, with a slab constructed through `Default`, detects
+/// keys to values which have since been removed.
+///
+/// Deserializing allocates every vacant slot in front of the largest key. To keep a single large key in untrusted
+/// input from forcing a huge allocation, a slab is rejected if a key lies beyond 1024 slots plus 8 for every value
+/// read up to and including it.
+///
+/// # Example
+///
+/// ```
+/// let mut connections = minivec::MiniSlab::new();
+///
+/// let a = connections.insert("10.0.0.1");
+/// let b = connections.insert("10.0.0.2");
+///
+/// assert_eq!(connections.remove(a), "10.0.0.1");
+///
+/// // the slot of the removed connection is reused
+/// //
+/// let c = connections.insert("10.0.0.3");
+/// assert_eq!(c, a);
+///
+/// assert_eq!(connections[b], "10.0.0.2");
+/// assert_eq!(connections.len(), 2);
+/// ```
+///
+pub struct MiniSlab<T, K = usize> {
+  entries: MiniVecWithHeader<SlabHeader, Entry<T>>,
+  marker: core::marker::PhantomData<fn(K) -> K>,
+}
+
+/// `VacantEntry` is a handle to the slot the next value inserted into a [`MiniSlab`](MiniSlab) will occupy, created
+/// by [`vacant_entry`](MiniSlab::vacant_entry). It makes the key available before the value is constructed.
+///
+pub struct VacantEntry<'a, T, K> {
+  slab: &'a mut MiniSlab<T, K>,
+}
+
+/// `Iter` is an iterator over the keys and values of a [`MiniSlab`](MiniSlab), created by
+/// [`iter`](MiniSlab::iter).
+///
+pub struct Iter<'a, T, K> {
+  inner: core::iter::Enumerate<core::slice::Iter<'a, Entry<T>>>,
+  marker: core::marker::PhantomData<fn(K) -> K>,
+}
+
+/// `IterMut` is an iterator over the keys and mutable values of a [`MiniSlab`](MiniSlab), created by
+/// [`iter_mut`](MiniSlab::iter_mut).
+///
+pub struct IterMut<'a, T, K> {
+  inner: core::iter::Enumerate<core::slice::IterMut<'a, Entry<T>>>,
+  marker: core::marker::PhantomData<fn(K) -> K>,
+}
+
+/// `IntoIter` is an iterator which moves the keys and values out of a [`MiniSlab`](MiniSlab).
+///
+pub struct IntoIter<T, K> {
+  entries: MiniVecWithHeader<SlabHeader, Entry<T>>,
+  idx: usize,
+  len: usize,
+  marker: core::marker::PhantomData<fn(K) -> K>,
+}
+
+/// `Drain` is an iterator which removes and returns every value of a [`MiniSlab`](MiniSlab), created by
+/// [`drain`](MiniSlab::drain).
+///
+pub struct Drain<'a, T> {
+  entries: *mut Entry<T>,
+  idx: usize,
+  len: usize,
+  marker: core::marker::PhantomData<&'a mut Entry<T>>,
+}
+
+// Rebuilds the free list when dropped so that the slab stays consistent even if a user callback panics part way
+// through rearranging the entries.
+//
+struct Relink<'a, T, K>(&'a mut MiniSlab<T, K>);
+
+impl<T, K> Drop for Relink<'_, T, K> {
+  fn drop(&mut self) {
+    self.0.relink();
+  }
+}
+
+impl<T, K> MiniSlab<T, K> {
+  fn meta(&self) -> SlabHeader {
+    self.entries.header().copied().unwrap_or_default()
+  }
+
+  fn next_generation(&mut self) -> u32 {
+    let header = self.entries.header_mut();
+    let generation = header.generation;
+    header.generation = generation.wrapping_add(1);
+    generation
+  }
+
+  fn relink(&mut self) {
+    let mut next = self.entries.len();
+    let mut occupied = 0;
+
+    for idx in (0..self.entries.len()).rev() {
+      match &mut self.entries[idx] {
+        Entry::Occupied { .. } => occupied += 1,
+        Entry::Vacant { next: link } => {
+          *link = next;
+          next = idx;
+        }
+      }
+    }
+
+    if self.entries.header().is_some() {
+      let header = self.entries.header_mut();
+      header.next_free = next;
+      header.occupied = occupied;
+    }
+  }
+
+  /// `capacity` returns the number of slots the slab can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.entries.capacity()
+  }
+
+  /// `clear` removes every value from the slab, keeping its allocation.
+  ///
+  pub fn clear(&mut self) {
+    self.entries.clear();
+    self.relink();
+  }
+
+  /// `drain` returns an iterator which removes every value from the slab, keeping its allocation. Values which
+  /// aren't consumed are dropped along with the iterator.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut slab: minivec::MiniSlab<_> = minivec::MiniSlab::new();
+  /// slab.insert(1);
+  /// slab.insert(2);
+  ///
+  /// assert_eq!(slab.drain().sum::<i32>(), 3);
+  /// assert!(slab.is_empty());
+  /// ```
+  ///
+  pub fn drain(&mut self) -> Drain<'_, T> {
+    let len = self.entries.len();
+
+    // the slab is emptied up front so that leaking the iterator leaks the values rather than leaving the slab
+    // pointing at entries which have been moved out
+    //
+    unsafe { self.entries.set_len(0) };
+    self.relink();
+
+    Drain {
+      entries: self.entries.as_mut_ptr(),
+      idx: 0,
+      len,
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `is_empty` returns whether or not the slab holds any values.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of values in the slab.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.meta().occupied
+  }
+
+  /// `reserve` makes room for at least `additional` more values than the slab currently holds.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    let vacant = self.entries.len() - self.len();
+    self.entries.reserve(additional.saturating_sub(vacant));
+  }
+}
+
+impl<T> MiniSlab<T> {
+  /// `new` constructs an empty slab.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniSlab {
+      entries: MiniVecWithHeader::new(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `with_capacity` constructs an empty slab with room for `capacity` values.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniSlab {
+      entries: MiniVecWithHeader::with_capacity(capacity),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<T, K: SlabKey> MiniSlab<T, K> {
+  fn insert_at(&mut self, index: usize, value: T) -> K {
+    let generation = self.entries.header().map_or(0, |h| h.generation);
+    let key = K::new(index, generation);
+    self.next_generation();
+
+    let entry = Entry::Occupied { generation, value };
+
+    let next_free = if index == self.entries.len() {
+      self.entries.push(entry);
+      index + 1
+    } else {
+      match core::mem::replace(&mut self.entries[index], entry) {
+        Entry::Vacant { next } => next,
+        Entry::Occupied { .. } => unreachable!(),
+      }
+    };
+
+    let header = self.entries.header_mut();
+    header.next_free = next_free;
+    header.occupied += 1;
+
+    key
+  }
+
+  /// `compact` moves values from the end of the slab into vacant slots until every slot is occupied, then shrinks
+  /// the allocation to fit. `rekey` is called with each moved value along with its old and new keys so that any
+  /// references to it can be updated.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut slab = minivec::MiniSlab::new();
+  /// let keys: Vec<usize> = (0..4).map(|i| slab.insert(i)).collect();
+  ///
+  /// slab.remove(keys[0]);
+  /// slab.remove(keys[1]);
+  ///
+  /// let mut moves = Vec::new();
+  /// slab.compact(|_, from, to| moves.push((from, to)));
+  ///
+  /// assert_eq!(moves, [(2, 0), (3, 1)]);
+  /// assert_eq!(slab[0], 2);
+  /// assert_eq!(slab.capacity(), 2);
+  /// ```
+  ///
+  pub fn compact<F>(&mut self, mut rekey: F)
+  where
+    F: FnMut(&mut T, K, K),
+  {
+    let occupied = self.len();
+    let len = self.entries.len();
+
+    let guard = Relink(self);
+    let slab = &mut *guard.0;
+
+    let mut front = 0;
+    for back in occupied..len {
+      if let Entry::Vacant { .. } = slab.entries[back] {
+        continue;
+      }
+
+      while let Entry::Occupied { .. } = slab.entries[front] {
+        front += 1;
+      }
+
+      let generation = slab.next_generation();
+      if let Entry::Occupied {
+        generation: old,
+        value,
+      } = &mut slab.entries[back]
+      {
+        rekey(value, K::new(back, *old), K::new(front, generation));
+      }
+
+      if let Entry::Occupied { value, .. } =
+        core::mem::replace(&mut slab.entries[back], Entry::Vacant { next: 0 })
+      {
+        slab.entries[front] = Entry::Occupied { generation, value };
+      }
+    }
+
+    slab.entries.truncate(occupied);
+    slab.entries.shrink_to_fit();
+  }
+
+  /// `contains` returns whether or not `key` refers to a value in the slab.
+  ///
+  #[must_use]
+  pub fn contains(&self, key: K) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// `get` returns a reference to the value `key` refers to, or `None` if there is no such value.
+  ///
+  #[must_use]
+  pub fn get(&self, key: K) -> Option<&T> {
+    match self.entries.get(key.index())? {
+      Entry::Occupied { generation, value }
+        if key.generation().is_none_or(|g| g == *generation) =>
+      {
+        Some(value)
+      }
+      _ => None,
+    }
+  }
+
+  /// `get_mut` returns a mutable reference to the value `key` refers to, or `None` if there is no such value.
+  ///
+  pub fn get_mut(&mut self, key: K) -> Option<&mut T> {
+    match self.entries.get_mut(key.index())? {
+      Entry::Occupied { generation, value }
+        if key.generation().is_none_or(|g| g == *generation) =>
+      {
+        Some(value)
+      }
+      _ => None,
+    }
+  }
+
+  // Places `value` at the slot `key` refers to, growing the slab with vacant slots as needed. The free list must be
+  // rebuilt with `relink` afterwards. Returns `value` back if the slot is already occupied.
+  //
+  #[cfg(feature = "serde")]
+  pub(crate) fn restore(&mut self, key: K, value: T) -> Result<(), T> {
+    let index = key.index();
+    let generation = key.generation().unwrap_or(0);
+
+    while self.entries.len() <= index {
+      self.entries.push(Entry::Vacant { next: 0 });
+    }
+
+    if let Entry::Occupied { .. } = self.entries[index] {
+      return Err(value);
+    }
+    self.entries[index] = Entry::Occupied { generation, value };
+
+    let header = self.entries.header_mut();
+    if generation >= header.generation {
+      header.generation = generation.wrapping_add(1);
+    }
+
+    Ok(())
+  }
+
+  #[cfg(feature = "serde")]
+  pub(crate) fn finish_restore(&mut self) {
+    self.relink();
+  }
+
+  /// `insert` stores `value` in the slab and returns its key.
+  ///
+  pub fn insert(&mut self, value: T) -> K {
+    let index = self.meta().next_free;
+    self.insert_at(index, value)
+  }
+
+  /// `iter` returns an iterator over the keys and values of the slab, in order of their slots.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut slab = minivec::MiniSlab::new();
+  /// let a = slab.insert("a");
+  /// let b = slab.insert("b");
+  /// slab.remove(a);
+  ///
+  /// assert_eq!(slab.iter().collect::<Vec<_>>(), [(b, &"b")]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_, T, K> {
+    Iter {
+      inner: self.entries.iter().enumerate(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `iter_mut` returns an iterator over the keys and mutable values of the slab, in order of their slots.
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, T, K> {
+    IterMut {
+      inner: self.entries.iter_mut().enumerate(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `remove` removes the value `key` refers to and returns it.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `key` doesn't refer to a value in the slab.
+  ///
+  pub fn remove(&mut self, key: K) -> T {
+    match self.try_remove(key) {
+      Some(value) => value,
+      None => panic!("invalid slab key"),
+    }
+  }
+
+  /// `retain` removes every value for which `f` returns `false`. The keys of the remaining values are unchanged.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut slab = minivec::MiniSlab::new();
+  /// for x in 1..=6 {
+  ///     slab.insert(x);
+  /// }
+  ///
+  /// slab.retain(|_, x| *x % 2 == 0);
+  /// assert_eq!(slab.iter().collect::<Vec<_>>(), [(1, &2), (3, &4), (5, &6)]);
+  /// ```
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(K, &mut T) -> bool,
+  {
+    for idx in 0..self.entries.len() {
+      let keep = match &mut self.entries[idx] {
+        Entry::Occupied { generation, value } => f(K::new(idx, *generation), value),
+        Entry::Vacant { .. } => true,
+      };
+
+      if !keep {
+        drop(self.take(idx));
+      }
+    }
+  }
+
+  fn take(&mut self, idx: usize) -> T {
+    let next_free = self.meta().next_free;
+
+    let entry = core::mem::replace(&mut self.entries[idx], Entry::Vacant { next: next_free });
+
+    let header = self.entries.header_mut();
+    header.next_free = idx;
+    header.occupied -= 1;
+
+    match entry {
+      Entry::Occupied { value, .. } => value,
+      Entry::Vacant { .. } => unreachable!(),
+    }
+  }
+
+  /// `try_remove` removes the value `key` refers to and returns it, or returns `None` if there is no such value.
+  ///
+  pub fn try_remove(&mut self, key: K) -> Option<T> {
+    if self.contains(key) {
+      Some(self.take(key.index()))
+    } else {
+      None
+    }
+  }
+
+  /// `vacant_entry` returns a handle to the slot the next inserted value will occupy.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// struct Connection {
+  ///     id: usize,
+  /// }
+  ///
+  /// let mut slab = minivec::MiniSlab::new();
+  ///
+  /// let entry = slab.vacant_entry();
+  /// let id = entry.key();
+  /// entry.insert(Connection { id });
+  ///
+  /// assert_eq!(slab[id].id, id);
+  /// ```
+  ///
+  pub fn vacant_entry(&mut self) -> VacantEntry<'_, T, K> {
+    VacantEntry { slab: self }
+  }
+}
+
+impl<T, K: SlabKey> VacantEntry<'_, T, K> {
+  /// `key` returns the key the value will be stored under.
+  ///
+  #[must_use]
+  pub fn key(&self) -> K {
+    let meta = self.slab.meta();
+    K::new(meta.next_free, meta.generation)
+  }
+}
+
+impl<'a, T, K: SlabKey> VacantEntry<'a, T, K> {
+  /// `insert` stores `value` in the slot and returns a mutable reference to it.
+  ///
+  pub fn insert(self, value: T) -> &'a mut T {
+    let key = self.slab.insert(value);
+    match self.slab.get_mut(key) {
+      Some(value) => value,
+      None => unreachable!(),
+    }
+  }
+}
+
+impl<T: Clone, K> Clone for MiniSlab<T, K> {
+  fn clone(&self) -> Self {
+    MiniSlab {
+      entries: self.entries.clone(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<T: core::fmt::Debug, K: SlabKey + core::fmt::Debug> core::fmt::Debug for MiniSlab<T, K> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+impl<T, K> Default for MiniSlab<T, K> {
+  fn default() -> Self {
+    MiniSlab {
+      entries: MiniVecWithHeader::new(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<T, K: SlabKey> core::ops::Index<K> for MiniSlab<T, K> {
+  type Output = T;
+
+  fn index(&self, key: K) -> &T {
+    match self.get(key) {
+      Some(value) => value,
+      None => panic!("invalid slab key"),
+    }
+  }
+}
+
+impl<T, K: SlabKey> core::ops::IndexMut<K> for MiniSlab<T, K> {
+  fn index_mut(&mut self, key: K) -> &mut T {
+    match self.get_mut(key) {
+      Some(value) => value,
+      None => panic!("invalid slab key"),
+    }
+  }
+}
+
+impl<T, K: SlabKey> core::iter::IntoIterator for MiniSlab<T, K> {
+  type Item = (K, T);
+  type IntoIter = IntoIter<T, K>;
+
+  fn into_iter(mut self) -> Self::IntoIter {
+    let len = self.entries.len();
+    unsafe { self.entries.set_len(0) };
+
+    let entries = core::mem::take(&mut self.entries);
+
+    IntoIter {
+      entries,
+      idx: 0,
+      len,
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<'a, T, K: SlabKey> core::iter::IntoIterator for &'a MiniSlab<T, K> {
+  type Item = (K, &'a T);
+  type IntoIter = Iter<'a, T, K>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T, K: SlabKey> core::iter::IntoIterator for &'a mut MiniSlab<T, K> {
+  type Item = (K, &'a mut T);
+  type IntoIter = IterMut<'a, T, K>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+impl<'a, T, K: SlabKey> Iterator for Iter<'a, T, K> {
+  type Item = (K, &'a T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.find_map(|(idx, entry)| match entry {
+      Entry::Occupied { generation, value } => Some((K::new(idx, *generation), value)),
+      Entry::Vacant { .. } => None,
+    })
+  }
+}
+
+impl<'a, T, K: SlabKey> Iterator for IterMut<'a, T, K> {
+  type Item = (K, &'a mut T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.inner.find_map(|(idx, entry)| match entry {
+      Entry::Occupied { generation, value } => Some((K::new(idx, *generation), value)),
+      Entry::Vacant { .. } => None,
+    })
+  }
+}
+
+impl<T, K: SlabKey> Iterator for IntoIter<T, K> {
+  type Item = (K, T);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.idx < self.len {
+      let idx = self.idx;
+      self.idx += 1;
+
+      if let Entry::Occupied { generation, value } =
+        unsafe { self.entries.as_ptr().add(idx).read() }
+      {
+        return Some((K::new(idx, generation), value));
+      }
+    }
+
+    None
+  }
+}
+
+impl<T, K> Drop for IntoIter<T, K> {
+  fn drop(&mut self) {
+    while self.idx < self.len {
+      let idx = self.idx;
+      self.idx += 1;
+
+      unsafe { core::ptr::drop_in_place(self.entries.as_mut_ptr().add(idx)) };
+    }
+  }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.idx < self.len {
+      let idx = self.idx;
+      self.idx += 1;
+
+      if let Entry::Occupied { value, .. } = unsafe { self.entries.add(idx).read() } {
+        return Some(value);
+      }
+    }
+
+    None
+  }
+}
+
+impl<T> Drop for Drain<'_, T> {
+  fn drop(&mut self) {
+    self.for_each(drop);
+  }
+}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for Drain<'_, T> {}
+unsafe impl<T: core::marker::Sync> core::marker::Sync for Drain<'_, T> {}
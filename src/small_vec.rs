@@ -0,0 +1,1109 @@
+//! `small_vec` contains [`MiniSmallVec`](MiniSmallVec), a [`MiniVec`](crate::MiniVec) that stores a handful of
+//! small elements directly inside its pointer word, along with its owning iterator.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::{next_aligned, next_capacity, resolve_range};
+use crate::{Drain, DrainFilter, MiniVec, Splice};
+
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr::NonNull;
+
+const WORD: usize = core::mem::size_of::<NonNull<u8>>();
+
+// the tag byte is the one holding the least significant bits of the pointer, which are always zero for a heap
+// allocation as the `Header` is at least `usize`-aligned
+//
+const TAG_OFFSET: usize = if cfg!(target_endian = "little") {
+  0
+} else {
+  WORD - 1
+};
+
+const INLINE_TAG: u8 = 1;
+
+// the remaining bits of the tag byte store the inline length
+//
+const MAX_INLINE_LEN: usize = (u8::MAX >> 1) as usize;
+
+const _: () = assert!(core::mem::align_of::<usize>() >= 2);
+
+/// `MiniSmallVec` is a [`MiniVec`](crate::MiniVec) that is only the size of a single pointer but stores its elements
+/// inline, inside of the pointer word itself, when there are few enough of them and they are small enough to fit.
+///
+/// The low bit of the word is used as a tag: heap allocations are always at least `usize`-aligned so a set bit can
+/// only mean the elements are stored inline. The remaining bits of the tag byte store the inline length and the rest
+/// of the word stores the elements.
+///
+/// The inline capacity is [`INLINE_CAPACITY`](MiniSmallVec::INLINE_CAPACITY) and depends on the size and alignment
+/// of `T`. On 64-bit targets, a `MiniSmallVec<u8>` holds up to 7 elements inline, a `MiniSmallVec<u16>` 3 and a
+/// `MiniSmallVec<u32>` 1. Types that are too large are always stored on the heap, making `MiniSmallVec` behave like
+/// a plain `MiniVec`.
+///
+/// Once a `MiniSmallVec` outgrows its inline storage it "spills" to a normal `MiniVec` allocation.
+/// [`shrink_to_fit`](MiniSmallVec::shrink_to_fit) moves the elements back inline when they fit again.
+///
+/// # Example
+///
+/// ```
+/// let mut vec = minivec::MiniSmallVec::<u8>::new();
+/// assert_eq!(core::mem::size_of_val(&vec), core::mem::size_of::<usize>());
+///
+/// vec.push(1);
+/// vec.push(2);
+/// assert!(!vec.spilled());
+///
+/// vec.extend_from_slice(&[0; 32]);
+/// assert!(vec.spilled());
+///
+/// vec.truncate(2);
+/// vec.shrink_to_fit();
+/// assert!(!vec.spilled());
+/// assert_eq!(vec, [1, 2]);
+/// ```
+///
+#[repr(transparent)]
+pub struct MiniSmallVec<T> {
+  buf: MaybeUninit<NonNull<u8>>,
+  phantom: core::marker::PhantomData<T>,
+}
+
+// Used by `dedup_by` and `retain` to commit the new length once they're done with the elements, even if their
+// callback panics, in which case the elements that haven't been visited yet are moved down behind the kept ones.
+// `ptr` is only derived after the guard has taken `vec`, and `vec` isn't touched again until `ptr` is no longer
+// used, since reborrowing the vector would invalidate a pointer into inline storage.
+//
+struct Compact<'a, T> {
+  vec: &'a mut MiniSmallVec<T>,
+  ptr: *mut T,
+  len: usize,
+  read: usize,
+  kept: usize,
+}
+
+impl<T> Drop for Compact<'_, T> {
+  fn drop(&mut self) {
+    let unvisited = self.len - self.read;
+    unsafe {
+      if unvisited > 0 && self.read != self.kept {
+        core::ptr::copy(self.ptr.add(self.read), self.ptr.add(self.kept), unvisited);
+      }
+      self.vec.set_len(self.kept + unvisited);
+    }
+  }
+}
+
+impl<T> MiniSmallVec<T> {
+  const INLINE_OFFSET: usize = if cfg!(target_endian = "little") {
+    next_aligned(1, core::mem::align_of::<T>())
+  } else {
+    0
+  };
+
+  const INLINE_END: usize = if cfg!(target_endian = "little") {
+    WORD
+  } else {
+    WORD - 1
+  };
+
+  /// `INLINE_CAPACITY` is the number of elements a `MiniSmallVec<T>` can store without allocating.
+  ///
+  pub const INLINE_CAPACITY: usize = {
+    let size = core::mem::size_of::<T>();
+    let align = core::mem::align_of::<T>();
+
+    if size == 0
+      || align > core::mem::align_of::<NonNull<u8>>()
+      || Self::INLINE_OFFSET >= Self::INLINE_END
+    {
+      0
+    } else {
+      let cap = (Self::INLINE_END - Self::INLINE_OFFSET) / size;
+      if cap > MAX_INLINE_LEN {
+        MAX_INLINE_LEN
+      } else {
+        cap
+      }
+    }
+  };
+
+  fn word_ptr(&self) -> *const u8 {
+    self.buf.as_ptr().cast::<u8>()
+  }
+
+  fn word_mut_ptr(&mut self) -> *mut u8 {
+    self.buf.as_mut_ptr().cast::<u8>()
+  }
+
+  fn tag(&self) -> u8 {
+    unsafe { *self.word_ptr().add(TAG_OFFSET) }
+  }
+
+  fn is_inline(&self) -> bool {
+    self.tag() & INLINE_TAG == INLINE_TAG
+  }
+
+  fn inline_ptr(&self) -> *const T {
+    unsafe { self.word_ptr().add(Self::INLINE_OFFSET).cast::<T>() }
+  }
+
+  fn inline_mut_ptr(&mut self) -> *mut T {
+    unsafe { self.word_mut_ptr().add(Self::INLINE_OFFSET).cast::<T>() }
+  }
+
+  fn heap(&self) -> &MiniVec<T> {
+    debug_assert!(!self.is_inline());
+    unsafe { &*self.buf.as_ptr().cast::<MiniVec<T>>() }
+  }
+
+  fn heap_mut(&mut self) -> &mut MiniVec<T> {
+    debug_assert!(!self.is_inline());
+    unsafe { &mut *self.buf.as_mut_ptr().cast::<MiniVec<T>>() }
+  }
+
+  fn from_heap(vec: MiniVec<T>) -> Self {
+    let vec = ManuallyDrop::new(vec);
+    MiniSmallVec {
+      buf: MaybeUninit::new(vec.buf),
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  #[allow(clippy::cast_possible_truncation)]
+  unsafe fn set_inline_len(&mut self, len: usize) {
+    debug_assert!(len <= Self::INLINE_CAPACITY);
+    *self.word_mut_ptr().add(TAG_OFFSET) = ((len as u8) << 1) | INLINE_TAG;
+  }
+
+  fn spill(&mut self, capacity: usize) {
+    debug_assert!(self.is_inline());
+
+    let len = self.len();
+    let mut vec = MiniVec::<T>::with_capacity(core::cmp::max(capacity, core::cmp::max(len, 1)));
+
+    unsafe {
+      core::ptr::copy_nonoverlapping(self.inline_ptr(), vec.as_mut_ptr(), len);
+      vec.set_len(len);
+    }
+
+    self.buf = MaybeUninit::new(ManuallyDrop::new(vec).buf);
+  }
+
+  fn spill_in_place(&mut self) -> &mut MiniVec<T> {
+    if self.is_inline() {
+      self.spill(self.len());
+    }
+
+    self.heap_mut()
+  }
+
+  fn unspill(&mut self) {
+    debug_assert!(!self.is_inline());
+    debug_assert!(self.len() <= Self::INLINE_CAPACITY);
+
+    let mut vec = unsafe { core::ptr::read(self.heap()) };
+    let len = vec.len();
+
+    unsafe { core::ptr::write(self, MiniSmallVec::new()) };
+
+    unsafe {
+      core::ptr::copy_nonoverlapping(vec.as_ptr(), self.inline_mut_ptr(), len);
+      vec.set_len(0);
+      self.set_inline_len(len);
+    }
+  }
+
+  /// `append` moves every element from `other` to the back of `self`. `other.is_empty()` is `true` once this
+  /// operation completes.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniSmallVec::from(&[1_u8, 2][..]);
+  /// let mut other = minivec::MiniSmallVec::from(&[3_u8][..]);
+  ///
+  /// vec.append(&mut other);
+  /// assert_eq!(vec, [1, 2, 3]);
+  /// assert!(other.is_empty());
+  /// ```
+  ///
+  pub fn append(&mut self, other: &mut MiniSmallVec<T>) {
+    let (len, count) = (self.len(), other.len());
+    self.reserve(count);
+
+    unsafe {
+      other.set_len(0);
+      core::ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(len), count);
+      self.set_len(len + count);
+    }
+  }
+
+  /// `as_mut_ptr` returns a mutable pointer to the first element of the vector, wherever it is stored.
+  ///
+  /// Note: for inline vectors, the pointer refers to the `MiniSmallVec` itself and is invalidated when it is moved.
+  ///
+  pub fn as_mut_ptr(&mut self) -> *mut T {
+    if self.is_inline() {
+      self.inline_mut_ptr()
+    } else {
+      self.heap_mut().as_mut_ptr()
+    }
+  }
+
+  /// `as_mut_slice` returns a mutable slice over the elements of the vector.
+  ///
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    let len = self.len();
+    unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+  }
+
+  /// `as_ptr` returns a pointer to the first element of the vector, wherever it is stored.
+  ///
+  /// Note: for inline vectors, the pointer refers to the `MiniSmallVec` itself and is invalidated when it is moved.
+  ///
+  #[must_use]
+  pub fn as_ptr(&self) -> *const T {
+    if self.is_inline() {
+      self.inline_ptr()
+    } else {
+      self.heap().as_ptr()
+    }
+  }
+
+  /// `as_slice` returns a slice over the elements of the vector.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
+  }
+
+  /// `capacity` returns the number of elements the vector can hold without allocating or reallocating.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::MiniSmallVec::<u8>::new();
+  /// assert_eq!(vec.capacity(), minivec::MiniSmallVec::<u8>::INLINE_CAPACITY);
+  /// ```
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    if self.is_inline() {
+      Self::INLINE_CAPACITY
+    } else {
+      self.heap().capacity()
+    }
+  }
+
+  /// `clear` drops every element of the vector. The storage is left untouched.
+  ///
+  pub fn clear(&mut self) {
+    self.truncate(0);
+  }
+
+  /// `dedup_by` removes consecutive elements for which `pred(current, previous)` returns `true`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniSmallVec::from(&[1_u8, 1, 2, 3, 3, 3][..]);
+  /// vec.dedup_by(|x, y| x == y);
+  ///
+  /// assert_eq!(vec, [1, 2, 3]);
+  /// ```
+  ///
+  pub fn dedup_by<F>(&mut self, mut pred: F)
+  where
+    F: FnMut(&mut T, &mut T) -> bool,
+  {
+    if self.len() <= 1 {
+      return;
+    }
+
+    let mut compact = Compact {
+      vec: self,
+      ptr: core::ptr::null_mut(),
+      len: 0,
+      read: 1,
+      kept: 1,
+    };
+    compact.len = compact.vec.len();
+    compact.ptr = compact.vec.as_mut_ptr();
+
+    while compact.read < compact.len {
+      unsafe {
+        let current = compact.ptr.add(compact.read);
+        let previous = compact.ptr.add(compact.kept - 1);
+
+        if pred(&mut *current, &mut *previous) {
+          compact.read += 1;
+          core::ptr::drop_in_place(current);
+        } else {
+          if compact.read != compact.kept {
+            core::ptr::copy_nonoverlapping(current, compact.ptr.add(compact.kept), 1);
+          }
+          compact.read += 1;
+          compact.kept += 1;
+        }
+      }
+    }
+  }
+
+  /// `dedup_by_key` removes consecutive elements that map to the same key.
+  ///
+  pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+  where
+    F: FnMut(&mut T) -> K,
+    K: PartialEq,
+  {
+    self.dedup_by(|a, b| key(a) == key(b));
+  }
+
+  /// `drain` removes the elements in the supplied range from the vector, returning them as an iterator.
+  ///
+  /// Note: an inline vector is spilled to the heap first so that the shared [`Drain`](crate::Drain) iterator can be
+  /// used.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+  where
+    R: core::ops::RangeBounds<usize>,
+  {
+    self.spill_in_place().drain(range)
+  }
+
+  /// `drain_filter` removes every element for which `pred` returns `true`, returning them as an iterator.
+  ///
+  /// Note: an inline vector is spilled to the heap first so that the shared [`DrainFilter`](crate::DrainFilter)
+  /// iterator can be used.
+  ///
+  pub fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<'_, T, F>
+  where
+    F: FnMut(&mut T) -> bool,
+  {
+    self.spill_in_place().drain_filter(pred)
+  }
+
+  /// `insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len`.
+  ///
+  pub fn insert(&mut self, index: usize, element: T) {
+    let len = self.len();
+
+    assert!(
+      index <= len,
+      "insertion index (is {}) should be <= len (is {})",
+      index,
+      len
+    );
+
+    self.reserve(1);
+
+    unsafe {
+      let p = self.as_mut_ptr().add(index);
+      core::ptr::copy(p, p.add(1), len - index);
+      core::ptr::write(p, element);
+      self.set_len(len + 1);
+    }
+  }
+
+  /// `into_vec` converts the vector into a `MiniVec`, allocating if the elements are stored inline.
+  ///
+  #[must_use]
+  pub fn into_vec(self) -> MiniVec<T> {
+    let this = ManuallyDrop::new(self);
+
+    if !this.is_inline() {
+      return unsafe { core::ptr::read(this.heap()) };
+    }
+
+    let len = this.len();
+    let mut vec = MiniVec::with_capacity(len);
+
+    unsafe {
+      core::ptr::copy_nonoverlapping(this.inline_ptr(), vec.as_mut_ptr(), len);
+      vec.set_len(len);
+    }
+
+    vec
+  }
+
+  /// `is_empty` returns whether or not the vector has a length of `0`.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    if self.is_inline() {
+      (self.tag() >> 1) as usize
+    } else {
+      self.heap().len()
+    }
+  }
+
+  /// `new` constructs an empty vector using its inline storage.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> MiniSmallVec<T> {
+    let mut buf = MaybeUninit::<NonNull<u8>>::zeroed();
+    unsafe { *buf.as_mut_ptr().cast::<u8>().add(TAG_OFFSET) = INLINE_TAG };
+
+    MiniSmallVec {
+      buf,
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  /// `pop` removes the last element of the vector and returns it, or `None` if the vector is empty.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    let len = self.len();
+    if len == 0 {
+      return None;
+    }
+
+    unsafe {
+      self.set_len(len - 1);
+      Some(core::ptr::read(self.as_ptr().add(len - 1)))
+    }
+  }
+
+  /// `push` appends `value` to the end of the vector, spilling to the heap if the inline storage is full.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniSmallVec::new();
+  /// vec.push(1_u32);
+  ///
+  /// assert_eq!(vec, [1]);
+  /// ```
+  ///
+  pub fn push(&mut self, value: T) {
+    let len = self.len();
+    if len == self.capacity() {
+      self.reserve(1);
+    }
+
+    unsafe {
+      core::ptr::write(self.as_mut_ptr().add(len), value);
+      self.set_len(len + 1);
+    }
+  }
+
+  /// `remove` removes the element at `index` and returns it, shifting every element after it to the left.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn remove(&mut self, index: usize) -> T {
+    let len = self.len();
+    assert!(
+      index < len,
+      "removal index (is {}) should be < len (is {})",
+      index,
+      len
+    );
+
+    unsafe {
+      let p = self.as_mut_ptr().add(index);
+      let x = core::ptr::read(p);
+      core::ptr::copy(p.add(1), p, len - index - 1);
+      self.set_len(len - 1);
+      x
+    }
+  }
+
+  /// `remove_item` removes the first element equal to `item`, returning it.
+  ///
+  pub fn remove_item<V>(&mut self, item: &V) -> Option<T>
+  where
+    T: PartialEq<V>,
+  {
+    let idx = self.iter().position(|x| *x == *item)?;
+    Some(self.remove(idx))
+  }
+
+  /// `reserve` ensures there is room for at least `additional` more elements, spilling to the heap if the inline
+  /// storage is too small.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    let len = self.len();
+    let required = len.checked_add(additional).expect("capacity overflow");
+
+    if self.is_inline() {
+      if required > Self::INLINE_CAPACITY {
+        let amortized = next_capacity::<T>(Self::INLINE_CAPACITY);
+        self.spill(core::cmp::max(required, amortized));
+      }
+    } else {
+      self.heap_mut().reserve(additional);
+    }
+  }
+
+  /// `reserve_exact` ensures there is room for exactly `additional` more elements, spilling to the heap if the
+  /// inline storage is too small.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve_exact(&mut self, additional: usize) {
+    let len = self.len();
+    let required = len.checked_add(additional).expect("capacity overflow");
+
+    if self.is_inline() {
+      if required > Self::INLINE_CAPACITY {
+        self.spill(required);
+      }
+    } else {
+      self.heap_mut().reserve_exact(additional);
+    }
+  }
+
+  /// `resize_with` invokes `f` to append elements until the length of the vector becomes `new_len`, or truncates it
+  /// if it's longer.
+  ///
+  pub fn resize_with<F>(&mut self, new_len: usize, mut f: F)
+  where
+    F: FnMut() -> T,
+  {
+    let len = self.len();
+    if new_len <= len {
+      self.truncate(new_len);
+      return;
+    }
+
+    self.reserve(new_len - len);
+    for _ in len..new_len {
+      self.push(f());
+    }
+  }
+
+  /// `retain` removes every element for which `f` returns `false`.
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    let mut compact = Compact {
+      vec: self,
+      ptr: core::ptr::null_mut(),
+      len: 0,
+      read: 0,
+      kept: 0,
+    };
+    compact.len = compact.vec.len();
+    compact.ptr = compact.vec.as_mut_ptr();
+
+    while compact.read < compact.len {
+      unsafe {
+        let current = compact.ptr.add(compact.read);
+
+        if f(&*current) {
+          if compact.read != compact.kept {
+            core::ptr::copy_nonoverlapping(current, compact.ptr.add(compact.kept), 1);
+          }
+          compact.read += 1;
+          compact.kept += 1;
+        } else {
+          compact.read += 1;
+          core::ptr::drop_in_place(current);
+        }
+      }
+    }
+  }
+
+  /// `set_len` sets the length of the vector.
+  ///
+  /// # Safety
+  ///
+  /// `len` must be less than or equal to the capacity and the first `len` elements must be initialized.
+  ///
+  pub unsafe fn set_len(&mut self, len: usize) {
+    if self.is_inline() {
+      self.set_inline_len(len);
+    } else {
+      self.heap_mut().set_len(len);
+    }
+  }
+
+  /// `shrink_to` reduces the capacity of the vector to be no less than `min_capacity`, moving the elements back
+  /// inline if they fit.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `min_capacity` is greater than the current capacity of a spilled vector.
+  ///
+  pub fn shrink_to(&mut self, min_capacity: usize) {
+    if self.is_inline() {
+      return;
+    }
+
+    if core::cmp::max(min_capacity, self.len()) <= Self::INLINE_CAPACITY {
+      self.unspill();
+    } else {
+      self.heap_mut().shrink_to(min_capacity);
+    }
+  }
+
+  /// `shrink_to_fit` reduces the capacity of the vector as much as possible, moving the elements back inline if they
+  /// fit.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniSmallVec::<u8>::with_capacity(128);
+  /// assert!(vec.spilled());
+  ///
+  /// vec.push(1);
+  /// vec.shrink_to_fit();
+  /// assert!(!vec.spilled());
+  /// ```
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    self.shrink_to(0);
+  }
+
+  /// `splice` replaces the elements in `range` with those yielded by `replace_with`, returning the removed elements
+  /// as an iterator.
+  ///
+  /// Note: an inline vector is spilled to the heap first so that the shared [`Splice`](crate::Splice) iterator can
+  /// be used.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  pub fn splice<R, I>(
+    &mut self,
+    range: R,
+    replace_with: I,
+  ) -> Splice<'_, <I as IntoIterator>::IntoIter>
+  where
+    I: IntoIterator<Item = T>,
+    R: core::ops::RangeBounds<usize>,
+  {
+    self.spill_in_place().splice(range, replace_with)
+  }
+
+  /// `spilled` returns whether or not the elements of the vector are stored on the heap.
+  ///
+  #[must_use]
+  pub fn spilled(&self) -> bool {
+    !self.is_inline()
+  }
+
+  /// `split_off` moves the elements `[at, len)` into a new vector, leaving `self` with the elements `[0, at)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `at > len`.
+  ///
+  #[must_use]
+  pub fn split_off(&mut self, at: usize) -> MiniSmallVec<T> {
+    let len = self.len();
+    assert!(
+      at <= len,
+      "`at` split index (is {}) should be <= len (is {})",
+      at,
+      len
+    );
+
+    let count = len - at;
+    let mut other = MiniSmallVec::with_capacity(count);
+
+    unsafe {
+      self.set_len(at);
+      core::ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), count);
+      other.set_len(count);
+    }
+
+    other
+  }
+
+  /// `swap_remove` removes the element at `index` and returns it, replacing it with the last element of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn swap_remove(&mut self, index: usize) -> T {
+    let len = self.len();
+    assert!(
+      index < len,
+      "swap_remove index (is {}) should be < len (is {})",
+      index,
+      len
+    );
+
+    unsafe {
+      let last = core::ptr::read(self.as_ptr().add(len - 1));
+      self.set_len(len - 1);
+      core::ptr::replace(self.as_mut_ptr().add(index), last)
+    }
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest. Has no effect if `len` is greater than or
+  /// equal to the vector's current length.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    let old_len = self.len();
+    if len >= old_len {
+      return;
+    }
+
+    unsafe {
+      self.set_len(len);
+      let tail = core::ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+      core::ptr::drop_in_place(tail);
+    }
+  }
+
+  /// `with_capacity` constructs an empty vector with room for at least `capacity` elements. The vector only
+  /// allocates if `capacity` exceeds [`INLINE_CAPACITY`](MiniSmallVec::INLINE_CAPACITY).
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> MiniSmallVec<T> {
+    let mut vec = MiniSmallVec::new();
+    vec.reserve_exact(capacity);
+    vec
+  }
+}
+
+impl<T: Clone> MiniSmallVec<T> {
+  /// `extend_from_slice` clones every element of `elems` onto the end of the vector.
+  ///
+  pub fn extend_from_slice(&mut self, elems: &[T]) {
+    self.reserve(elems.len());
+    for x in elems {
+      self.push(x.clone());
+    }
+  }
+
+  /// `extend_from_within` clones the elements in `range` onto the end of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the range is invalid or out of bounds.
+  ///
+  pub fn extend_from_within<R>(&mut self, range: R)
+  where
+    R: core::ops::RangeBounds<usize>,
+  {
+    let (start_idx, end_idx) = resolve_range(&range, self.len());
+    self.reserve(end_idx - start_idx);
+
+    for idx in start_idx..end_idx {
+      let x = self[idx].clone();
+      self.push(x);
+    }
+  }
+
+  /// `resize` clones `value` onto the end of the vector until its length becomes `new_len`, or truncates it if it's
+  /// longer.
+  ///
+  pub fn resize(&mut self, new_len: usize, value: T) {
+    self.resize_with(new_len, || value.clone());
+  }
+}
+
+impl<T: PartialEq> MiniSmallVec<T> {
+  /// `dedup` removes consecutive repeated elements.
+  ///
+  pub fn dedup(&mut self) {
+    self.dedup_by(|x, y| x == y);
+  }
+}
+
+impl<T> Drop for MiniSmallVec<T> {
+  fn drop(&mut self) {
+    if self.is_inline() {
+      unsafe { core::ptr::drop_in_place(self.as_mut_slice()) };
+    } else {
+      unsafe { core::ptr::drop_in_place(self.heap_mut()) };
+    }
+  }
+}
+
+impl<T> core::ops::Deref for MiniSmallVec<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl<T> core::ops::DerefMut for MiniSmallVec<T> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.as_mut_slice()
+  }
+}
+
+impl<T> AsRef<[T]> for MiniSmallVec<T> {
+  fn as_ref(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<T> AsMut<[T]> for MiniSmallVec<T> {
+  fn as_mut(&mut self) -> &mut [T] {
+    self.as_mut_slice()
+  }
+}
+
+impl<T> core::borrow::Borrow<[T]> for MiniSmallVec<T> {
+  fn borrow(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<T> core::borrow::BorrowMut<[T]> for MiniSmallVec<T> {
+  fn borrow_mut(&mut self) -> &mut [T] {
+    self.as_mut_slice()
+  }
+}
+
+impl<T: Clone> Clone for MiniSmallVec<T> {
+  fn clone(&self) -> Self {
+    let mut vec = MiniSmallVec::with_capacity(self.len());
+    vec.extend_from_slice(self);
+    vec
+  }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for MiniSmallVec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    self.as_slice().fmt(f)
+  }
+}
+
+impl<T> Default for MiniSmallVec<T> {
+  fn default() -> Self {
+    MiniSmallVec::new()
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<MiniSmallVec<U>> for MiniSmallVec<T> {
+  fn eq(&self, other: &MiniSmallVec<U>) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<MiniVec<U>> for MiniSmallVec<T> {
+  fn eq(&self, other: &MiniVec<U>) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for MiniSmallVec<T> {
+  fn eq(&self, other: &[U]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for MiniSmallVec<T> {
+  fn eq(&self, other: &[U; N]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: Eq> Eq for MiniSmallVec<T> {}
+
+impl<T: PartialOrd> PartialOrd for MiniSmallVec<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    self.as_slice().partial_cmp(other.as_slice())
+  }
+}
+
+impl<T: Ord> Ord for MiniSmallVec<T> {
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    self.as_slice().cmp(other.as_slice())
+  }
+}
+
+impl<T: core::hash::Hash> core::hash::Hash for MiniSmallVec<T> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.as_slice().hash(state);
+  }
+}
+
+impl<T> core::iter::Extend<T> for MiniSmallVec<T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    let iter = iter.into_iter();
+
+    let (lower, _) = iter.size_hint();
+    self.reserve(lower);
+
+    for x in iter {
+      self.push(x);
+    }
+  }
+}
+
+impl<'a, T: 'a + Copy> core::iter::Extend<&'a T> for MiniSmallVec<T> {
+  fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+    self.extend(iter.into_iter().copied());
+  }
+}
+
+impl<T> core::iter::FromIterator<T> for MiniSmallVec<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut vec = MiniSmallVec::new();
+    vec.extend(iter);
+    vec
+  }
+}
+
+impl<T> From<MiniVec<T>> for MiniSmallVec<T> {
+  fn from(vec: MiniVec<T>) -> Self {
+    if vec.is_default() {
+      // an unallocated `MiniVec` only points at a sentinel, so there is no allocation to take over and the vector
+      // starts out inline instead
+      //
+      return MiniSmallVec::new();
+    }
+
+    MiniSmallVec::from_heap(vec)
+  }
+}
+
+impl<T: Clone> From<&[T]> for MiniSmallVec<T> {
+  fn from(elems: &[T]) -> Self {
+    let mut vec = MiniSmallVec::with_capacity(elems.len());
+    vec.extend_from_slice(elems);
+    vec
+  }
+}
+
+impl<T, const N: usize> From<[T; N]> for MiniSmallVec<T> {
+  fn from(elems: [T; N]) -> Self {
+    let mut vec = MiniSmallVec::with_capacity(N);
+    vec.extend(core::iter::IntoIterator::into_iter(elems));
+    vec
+  }
+}
+
+impl<T> From<MiniSmallVec<T>> for MiniVec<T> {
+  fn from(vec: MiniSmallVec<T>) -> Self {
+    vec.into_vec()
+  }
+}
+
+impl<T> core::iter::IntoIterator for MiniSmallVec<T> {
+  type Item = T;
+  type IntoIter = IntoIter<T>;
+
+  fn into_iter(mut self) -> Self::IntoIter {
+    let end = self.len();
+    unsafe { self.set_len(0) };
+
+    IntoIter {
+      vec: self,
+      pos: 0,
+      end,
+    }
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a MiniSmallVec<T> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T> core::iter::IntoIterator for &'a mut MiniSmallVec<T> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for MiniSmallVec<T> {}
+unsafe impl<T: core::marker::Sync> core::marker::Sync for MiniSmallVec<T> {}
+
+/// `IntoIter` is an iterator that consumes a [`MiniSmallVec`](MiniSmallVec) and yields its elements by value.
+///
+pub struct IntoIter<T> {
+  vec: MiniSmallVec<T>,
+  pos: usize,
+  end: usize,
+}
+
+impl<T> IntoIter<T> {
+  /// `as_slice` returns a slice over the elements that have yet to be yielded.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    unsafe { core::slice::from_raw_parts(self.vec.as_ptr().add(self.pos), self.end - self.pos) }
+  }
+}
+
+impl<T> Iterator for IntoIter<T> {
+  type Item = T;
+
+  fn next(&mut self) -> Option<T> {
+    if self.pos == self.end {
+      return None;
+    }
+
+    let x = unsafe { core::ptr::read(self.vec.as_ptr().add(self.pos)) };
+    self.pos += 1;
+    Some(x)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.end - self.pos;
+    (len, Some(len))
+  }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+  fn next_back(&mut self) -> Option<T> {
+    if self.pos == self.end {
+      return None;
+    }
+
+    self.end -= 1;
+    Some(unsafe { core::ptr::read(self.vec.as_ptr().add(self.end)) })
+  }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> core::iter::FusedIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+  fn drop(&mut self) {
+    let remaining = core::ptr::slice_from_raw_parts_mut(
+      unsafe { self.vec.as_mut_ptr().add(self.pos) },
+      self.end - self.pos,
+    );
+
+    self.pos = self.end;
+    unsafe { core::ptr::drop_in_place(remaining) };
+  }
+}
@@ -0,0 +1,397 @@
+//! `soa` contains [`RawMiniSoA`](RawMiniSoA), the single-allocation column storage behind the vectors generated by
+//! `#[derive(MiniSoA)]`.
+//!
+//! The derive lives in the `minivec-derive` crate and is re-exported as `minivec::MiniSoA` when the `derive` feature
+//! is enabled. For a struct such as
+//!
+//! ```ignore
+//! #[derive(minivec::MiniSoA)]
+//! #[mini_soa(serde)]
+//! pub struct Particle {
+//!     pub x: f32,
+//!     pub y: f32,
+//! }
+//! ```
+//!
+//! it generates a `ParticleMiniVec` storing every field in its own column, along with `ParticleRef` and
+//! `ParticleRefMut` views of a single row and `ParticleIter` and `ParticleIterMut` iterators. The vector offers
+//! `push`, `pop`, `get`, `get_mut`, per-column slices named after the fields (`x()`, `x_mut()`, ...), `iter`,
+//! `iter_mut`, `swap`, `swap_remove`, `truncate`, `clear` and `sort_by`/`sort_by_key`, which reorder every column.
+//! The optional `#[mini_soa(serde)]` attribute also generates `Serialize` and `Deserialize` impls, which require the
+//! `serde` feature of this crate.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::next_aligned;
+use crate::MiniVec;
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+/// `SoAColumns` describes the columns of a struct-of-arrays row type, one [`Layout`](core::alloc::Layout) per field.
+/// It is implemented by `#[derive(MiniSoA)]`.
+///
+/// # Safety
+///
+/// Code reading and writing the columns of a [`RawMiniSoA`](RawMiniSoA) relies on `LAYOUTS` exactly matching the
+/// types stored in each column.
+///
+pub unsafe trait SoAColumns {
+  /// `LAYOUTS` holds the layout of every column, in declaration order.
+  ///
+  const LAYOUTS: &'static [Layout];
+}
+
+#[derive(Clone, Copy)]
+struct SoAHeader {
+  len: usize,
+  cap: usize,
+}
+
+/// `RawMiniSoA` is a single-pointer buffer holding one column per entry of `C::LAYOUTS`, all sharing one length and
+/// capacity kept at the front of the allocation. It never drops the values in its columns, which is left to the
+/// typed vectors built on top of it.
+///
+pub struct RawMiniSoA<C: SoAColumns> {
+  buf: NonNull<u8>,
+  marker: core::marker::PhantomData<C>,
+}
+
+impl<C: SoAColumns> RawMiniSoA<C> {
+  fn alignment() -> usize {
+    C::LAYOUTS
+      .iter()
+      .map(Layout::align)
+      .fold(core::mem::align_of::<SoAHeader>(), core::cmp::max)
+  }
+
+  fn is_allocated(&self) -> bool {
+    !crate::is_sentinel(self.buf.as_ptr())
+  }
+
+  fn header(&self) -> SoAHeader {
+    if !self.is_allocated() {
+      return SoAHeader { len: 0, cap: 0 };
+    }
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      *self.buf.as_ptr().cast::<SoAHeader>()
+    }
+  }
+
+  fn header_mut(&mut self) -> &mut SoAHeader {
+    debug_assert!(self.is_allocated());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &mut *self.buf.as_ptr().cast::<SoAHeader>()
+    }
+  }
+
+  // Returns the offset of column `idx` in an allocation with room for `capacity` rows, or the total size of the
+  // allocation when `idx` is the number of columns.
+  //
+  fn offset(capacity: usize, idx: usize) -> Option<usize> {
+    C::LAYOUTS[..idx]
+      .iter()
+      .try_fold(core::mem::size_of::<SoAHeader>(), |offset, layout| {
+        next_aligned(offset, layout.align()).checked_add(layout.size().checked_mul(capacity)?)
+      })
+      .map(|end| match C::LAYOUTS.get(idx) {
+        Some(layout) => next_aligned(end, layout.align()),
+        None => end,
+      })
+  }
+
+  fn layout(capacity: usize) -> Layout {
+    let Some(size) = Self::offset(capacity, C::LAYOUTS.len()) else {
+      panic!("capacity overflow");
+    };
+
+    let Ok(layout) = Layout::from_size_align(size, Self::alignment()) else {
+      panic!("capacity overflow");
+    };
+
+    layout
+  }
+
+  fn grow(&mut self, capacity: usize) {
+    let header = self.header();
+    debug_assert!(capacity >= header.len);
+
+    let new_layout = Self::layout(capacity);
+    let new_buf = unsafe { alloc::alloc::alloc(new_layout) };
+    if new_buf.is_null() {
+      alloc::alloc::handle_alloc_error(new_layout);
+    }
+
+    if self.is_allocated() {
+      for (idx, layout) in C::LAYOUTS.iter().enumerate() {
+        let (Some(old), Some(new)) = (Self::offset(header.cap, idx), Self::offset(capacity, idx))
+        else {
+          unreachable!("both capacities were already checked for overflow");
+        };
+
+        unsafe {
+          core::ptr::copy_nonoverlapping(
+            self.buf.as_ptr().add(old),
+            new_buf.add(new),
+            header.len * layout.size(),
+          );
+        }
+      }
+
+      unsafe { alloc::alloc::dealloc(self.buf.as_ptr(), Self::layout(header.cap)) };
+    }
+
+    self.buf = unsafe { NonNull::new_unchecked(new_buf) };
+    *self.header_mut() = SoAHeader {
+      len: header.len,
+      cap: capacity,
+    };
+  }
+
+  /// `capacity` returns the number of rows the buffer can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.header().cap
+  }
+
+  /// `column` returns a pointer to the first element of column `idx`. The pointer is dangling but suitably aligned
+  /// if the buffer has not allocated.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `idx` is not a column index.
+  ///
+  #[must_use]
+  pub fn column(&self, idx: usize) -> *mut u8 {
+    let layout = C::LAYOUTS[idx];
+    if !self.is_allocated() {
+      return core::ptr::without_provenance_mut(layout.align());
+    }
+
+    let Some(offset) = Self::offset(self.capacity(), idx) else {
+      unreachable!("the allocated capacity was already checked for overflow");
+    };
+
+    unsafe { self.buf.as_ptr().add(offset) }
+  }
+
+  /// `is_empty` returns whether or not the buffer holds any rows.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of rows in the buffer.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.header().len
+  }
+
+  /// `new` constructs an empty buffer.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    RawMiniSoA {
+      buf: crate::sentinel(Self::alignment()),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `reserve` ensures there is room for at least `additional` more rows, growing the capacity geometrically.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    let header = self.header();
+    if header.cap - header.len >= additional {
+      return;
+    }
+
+    let Some(required) = header.len.checked_add(additional) else {
+      panic!("capacity overflow");
+    };
+
+    let doubled = if header.cap == 0 {
+      4
+    } else {
+      header.cap.saturating_mul(2)
+    };
+    self.grow(core::cmp::max(required, doubled));
+  }
+
+  /// `reserve_exact` ensures there is room for at least `additional` more rows without over-allocating.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the new capacity overflows `usize`.
+  ///
+  pub fn reserve_exact(&mut self, additional: usize) {
+    let header = self.header();
+    if header.cap - header.len >= additional {
+      return;
+    }
+
+    let Some(required) = header.len.checked_add(additional) else {
+      panic!("capacity overflow");
+    };
+
+    self.grow(required);
+  }
+
+  /// `set_len` sets the number of rows in the buffer.
+  ///
+  /// # Safety
+  ///
+  /// `len` must not exceed the capacity and the first `len` rows of every column must be initialized.
+  ///
+  pub unsafe fn set_len(&mut self, len: usize) {
+    debug_assert!(len <= self.capacity());
+    if self.is_allocated() {
+      self.header_mut().len = len;
+    }
+  }
+
+  /// `sort_by` stably reorders the rows of every column so that `compare`, which is handed the buffer along with
+  /// pairs of row indices, sees them in ascending order.
+  ///
+  pub fn sort_by<F>(&mut self, mut compare: F)
+  where
+    F: FnMut(&Self, usize, usize) -> core::cmp::Ordering,
+  {
+    let len = self.len();
+    let mut order: MiniVec<usize> = (0..len).collect();
+    order.sort_by(|&a, &b| compare(self, a, b));
+
+    // row `idx` of the result is row `order[idx]` of the input, which is applied one cycle at a time, marking every
+    // placed row by pointing it at itself
+    //
+    for start in 0..len {
+      let mut idx = start;
+      while order[idx] != start {
+        let next = order[idx];
+        self.swap(idx, next);
+        order[idx] = idx;
+        idx = next;
+      }
+      order[idx] = idx;
+    }
+  }
+
+  /// `swap` swaps rows `a` and `b` in every column.
+  ///
+  /// # Panics
+  ///
+  /// Panics if either index is out of bounds.
+  ///
+  pub fn swap(&mut self, a: usize, b: usize) {
+    let len = self.len();
+    assert!(
+      a < len && b < len,
+      "swap indices (are {} and {}) should be < len (is {})",
+      a,
+      b,
+      len
+    );
+
+    if a == b {
+      return;
+    }
+
+    for (idx, layout) in C::LAYOUTS.iter().enumerate() {
+      let column = self.column(idx);
+      unsafe {
+        core::ptr::swap_nonoverlapping(
+          column.add(a * layout.size()),
+          column.add(b * layout.size()),
+          layout.size(),
+        );
+      }
+    }
+  }
+
+  /// `with_capacity` constructs an empty buffer with room for `capacity` rows.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    let mut buf = RawMiniSoA::new();
+    buf.reserve_exact(capacity);
+    buf
+  }
+}
+
+impl<C: SoAColumns> Drop for RawMiniSoA<C> {
+  fn drop(&mut self) {
+    if self.is_allocated() {
+      unsafe { alloc::alloc::dealloc(self.buf.as_ptr(), Self::layout(self.capacity())) };
+    }
+  }
+}
+
+impl<C: SoAColumns> Default for RawMiniSoA<C> {
+  fn default() -> Self {
+    RawMiniSoA::new()
+  }
+}
+
+unsafe impl<C: SoAColumns + Send> Send for RawMiniSoA<C> {}
+unsafe impl<C: SoAColumns + Sync> Sync for RawMiniSoA<C> {}
+
+/// `FieldSeed` deserializes a struct field name into its index in `names`, or `None` for unknown fields. It is used
+/// by the `Deserialize` impls generated by `#[derive(MiniSoA)]`.
+///
+#[cfg(feature = "serde")]
+pub struct FieldSeed(pub &'static [&'static str]);
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::DeserializeSeed<'de> for FieldSeed {
+  type Value = Option<usize>;
+
+  fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    deserializer.deserialize_identifier(self)
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Visitor<'_> for FieldSeed {
+  type Value = Option<usize>;
+
+  fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.write_str("a field name")
+  }
+
+  fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+    Ok(
+      <usize as core::convert::TryFrom<u64>>::try_from(value)
+        .ok()
+        .filter(|&idx| idx < self.0.len()),
+    )
+  }
+
+  fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+    Ok(self.0.iter().position(|&name| name == value))
+  }
+
+  fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+    Ok(self.0.iter().position(|&name| name.as_bytes() == value))
+  }
+}
+
+// Lets generated serde impls name the `serde` crate without their own crate depending on it.
+//
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
@@ -0,0 +1,534 @@
+//! `spsc` contains a bounded single-producer/single-consumer queue built on a ring buffer which lives in a single
+//! header-prefixed allocation, in the same style as a [`MiniVec`](crate::MiniVec).
+//!
+//! [`channel`](channel) returns a [`Producer`](Producer) and a [`Consumer`](Consumer) which each hold one pointer to
+//! the shared allocation. Neither half ever blocks or takes a lock, making the queue suitable for real-time
+//! threads such as audio callbacks.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::{data_offset, make_layout_with, max_align_with, next_aligned};
+use crate::sync::{AtomicUsize, Ordering, UnsafeCell};
+use crate::{Header, LayoutErr};
+
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+
+// The producer and the consumer each hammer on their own index so they're kept on separate cache lines to avoid
+// false sharing.
+//
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+// The ring state follows the usual `Header`, which stores the capacity and alignment of the allocation while its
+// length goes unused. `head` is only written by the consumer and `tail` only by the producer, both counting every
+// element ever popped and pushed respectively so that the number of elements in flight is always `tail - head`.
+//
+// The counters wrap around at `usize::MAX`, so the ring allocates a power of two number of slots, the capacity
+// rounded up, and maps a counter to its slot with a mask. Any other slot count would make the slot an index maps to
+// jump when its counter wraps.
+//
+struct Ring {
+  head: CachePadded<AtomicUsize>,
+  tail: CachePadded<AtomicUsize>,
+  handles: AtomicUsize,
+}
+
+type Slot<T> = UnsafeCell<MaybeUninit<T>>;
+
+// `RawRing` is the pointer shared by both halves of the channel.
+//
+struct RawRing<T> {
+  buf: NonNull<u8>,
+  phantom: core::marker::PhantomData<T>,
+}
+
+impl<T> RawRing<T> {
+  fn header(&self) -> &Header {
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &*self.buf.as_ptr().cast::<Header>()
+    }
+  }
+
+  fn ring(&self) -> &Ring {
+    let offset = next_aligned(
+      core::mem::size_of::<Header>(),
+      core::mem::align_of::<Ring>(),
+    );
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &*self.buf.as_ptr().add(offset).cast::<Ring>()
+    }
+  }
+
+  fn slot(&self, idx: usize) -> &Slot<T> {
+    let offset = data_offset::<Ring>(self.header().alignment);
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &*self
+        .buf
+        .as_ptr()
+        .add(offset)
+        .cast::<Slot<T>>()
+        .add(idx & (self.slots() - 1))
+    }
+  }
+
+  fn allocate(capacity: usize, alignment: usize) -> Self {
+    let slots = capacity
+      .checked_next_power_of_two()
+      .expect("capacity overflow");
+    let layout = make_layout_with::<Ring, Slot<T>>(slots, alignment);
+
+    let buf = unsafe { alloc::alloc::alloc(layout) };
+    if buf.is_null() {
+      alloc::alloc::handle_alloc_error(layout);
+    }
+
+    let raw = RawRing {
+      buf: unsafe { NonNull::new_unchecked(buf) },
+      phantom: core::marker::PhantomData,
+    };
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      buf.cast::<Header>().write(Header {
+        len: 0,
+        cap: capacity,
+        alignment,
+      });
+
+      let ring = buf.add(next_aligned(
+        core::mem::size_of::<Header>(),
+        core::mem::align_of::<Ring>(),
+      ));
+      ring.cast::<Ring>().write(Ring {
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+        handles: AtomicUsize::new(2),
+      });
+
+      let data = buf.add(data_offset::<Ring>(alignment)).cast::<Slot<T>>();
+      for idx in 0..slots {
+        data.add(idx).write(UnsafeCell::new(MaybeUninit::uninit()));
+      }
+    }
+
+    raw
+  }
+
+  fn capacity(&self) -> usize {
+    self.header().cap
+  }
+
+  fn slots(&self) -> usize {
+    self.capacity().next_power_of_two()
+  }
+
+  fn len(&self) -> usize {
+    let ring = self.ring();
+
+    let head = ring.head.0.load(Ordering::Acquire);
+    let tail = ring.tail.0.load(Ordering::Acquire);
+    tail.wrapping_sub(head)
+  }
+
+  // Called by each half as it's dropped. Whichever half goes last drops the elements still in flight and frees the
+  // allocation.
+  //
+  fn release(&self) {
+    let ring = self.ring();
+    if ring.handles.fetch_sub(1, Ordering::AcqRel) != 1 {
+      return;
+    }
+
+    let head = ring.head.0.load(Ordering::Relaxed);
+    let tail = ring.tail.0.load(Ordering::Relaxed);
+
+    let mut idx = head;
+    while idx != tail {
+      self
+        .slot(idx)
+        .with_mut(|p| unsafe { core::ptr::drop_in_place((*p).as_mut_ptr()) });
+      idx = idx.wrapping_add(1);
+    }
+
+    let slots = self.slots();
+    let alignment = self.header().alignment;
+    let layout = make_layout_with::<Ring, Slot<T>>(slots, alignment);
+
+    unsafe {
+      for idx in 0..slots {
+        core::ptr::drop_in_place(core::ptr::from_ref(self.slot(idx)).cast_mut());
+      }
+
+      core::ptr::drop_in_place(core::ptr::from_ref(ring).cast_mut());
+      alloc::alloc::dealloc(self.buf.as_ptr(), layout);
+    }
+  }
+}
+
+/// `Producer` is the sending half of a channel created by [`channel`](channel).
+///
+pub struct Producer<T> {
+  raw: RawRing<T>,
+}
+
+/// `Consumer` is the receiving half of a channel created by [`channel`](channel).
+///
+pub struct Consumer<T> {
+  raw: RawRing<T>,
+}
+
+/// `channel` creates a bounded single-producer/single-consumer queue which can hold up to `capacity` elements at a
+/// time and returns its two halves. Room is allocated for `capacity` rounded up to the next power of two.
+///
+/// # Panics
+///
+/// Panics if `capacity` can't be rounded up to a power of two.
+///
+/// # Example
+///
+/// ```
+/// let (mut tx, mut rx) = minivec::spsc::channel::<i32>(4);
+///
+/// let consumer = std::thread::spawn(move || {
+///     let mut sum = 0;
+///     let mut received = 0;
+///     while received < 100 {
+///         if let Some(x) = rx.pop() {
+///             sum += x;
+///             received += 1;
+///         }
+///     }
+///     sum
+/// });
+///
+/// for i in 0..100 {
+///     let mut value = i;
+///     while let Err(rejected) = tx.push(value) {
+///         value = rejected;
+///     }
+/// }
+///
+/// assert_eq!(consumer.join().unwrap(), (0..100).sum());
+/// ```
+///
+#[must_use]
+pub fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+  match channel_with_alignment(capacity, max_align_with::<Ring, Slot<T>>()) {
+    Ok(halves) => halves,
+    Err(_) => unreachable!(),
+  }
+}
+
+/// `channel_with_alignment` creates a channel like [`channel`](channel) but aligns the first element slot to
+/// `alignment`, in the same manner as [`MiniVec::with_alignment`](crate::MiniVec::with_alignment).
+///
+/// # Errors
+///
+/// Returns a [`LayoutErr`](crate::LayoutErr) if `alignment` is smaller than the cache-line alignment of the ring's
+/// header or than the alignment of `T`, or if it isn't a power of two.
+///
+/// # Example
+///
+/// ```
+/// let (mut tx, _rx) = minivec::spsc::channel_with_alignment::<f32>(64, 128).unwrap();
+/// tx.push_slice(&[1.0, 2.0]);
+///
+/// assert!(minivec::spsc::channel_with_alignment::<f32>(64, 4).is_err());
+/// ```
+///
+pub fn channel_with_alignment<T>(
+  capacity: usize,
+  alignment: usize,
+) -> Result<(Producer<T>, Consumer<T>), LayoutErr> {
+  if alignment < max_align_with::<Ring, Slot<T>>() {
+    return Err(LayoutErr::AlignmentTooSmall);
+  }
+
+  if !alignment.is_power_of_two() {
+    return Err(LayoutErr::AlignmentNotDivisibleByTwo);
+  }
+
+  let producer = RawRing::allocate(capacity, alignment);
+  let consumer = RawRing {
+    buf: producer.buf,
+    phantom: core::marker::PhantomData,
+  };
+
+  Ok((Producer { raw: producer }, Consumer { raw: consumer }))
+}
+
+impl<T> Producer<T> {
+  /// `capacity` returns the maximum number of elements the channel can hold at once.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.raw.capacity()
+  }
+
+  /// `is_full` returns whether or not a [`push`](Producer::push) would currently be rejected.
+  ///
+  #[must_use]
+  pub fn is_full(&self) -> bool {
+    self.len() == self.capacity()
+  }
+
+  /// `len` returns the number of elements which have been pushed but not yet popped. The consumer may pop more
+  /// elements concurrently so this is only a lower bound on the free space.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.raw.len()
+  }
+
+  /// `is_empty` returns whether or not every pushed element has been popped.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `push` appends `value` to the queue.
+  ///
+  /// # Errors
+  ///
+  /// Returns `Err(value)` if the queue is full.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let (mut tx, mut rx) = minivec::spsc::channel(1);
+  ///
+  /// assert_eq!(tx.push("a"), Ok(()));
+  /// assert_eq!(tx.push("b"), Err("b"));
+  ///
+  /// assert_eq!(rx.pop(), Some("a"));
+  /// assert_eq!(tx.push("b"), Ok(()));
+  /// ```
+  ///
+  pub fn push(&mut self, value: T) -> Result<(), T> {
+    let ring = self.raw.ring();
+
+    let tail = ring.tail.0.load(Ordering::Relaxed);
+    let head = ring.head.0.load(Ordering::Acquire);
+    if tail.wrapping_sub(head) == self.capacity() {
+      return Err(value);
+    }
+
+    self
+      .raw
+      .slot(tail)
+      .with_mut(|p| unsafe { (*p).as_mut_ptr().write(value) });
+    ring.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+
+    Ok(())
+  }
+
+  /// `push_slice` copies as many elements from the front of `values` as currently fit into the queue, publishing
+  /// them to the consumer all at once, and returns how many were pushed.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let (mut tx, mut rx) = minivec::spsc::channel(4);
+  ///
+  /// assert_eq!(tx.push_slice(&[1, 2, 3, 4, 5, 6]), 4);
+  /// assert_eq!(tx.push_slice(&[5, 6]), 0);
+  ///
+  /// let mut out = [0; 3];
+  /// assert_eq!(rx.pop_into_slice(&mut out), 3);
+  /// assert_eq!(out, [1, 2, 3]);
+  /// ```
+  ///
+  pub fn push_slice(&mut self, values: &[T]) -> usize
+  where
+    T: Copy,
+  {
+    let ring = self.raw.ring();
+
+    let tail = ring.tail.0.load(Ordering::Relaxed);
+    let head = ring.head.0.load(Ordering::Acquire);
+
+    let free = self.capacity() - tail.wrapping_sub(head);
+    let count = core::cmp::min(free, values.len());
+
+    for (offset, &value) in values[..count].iter().enumerate() {
+      self
+        .raw
+        .slot(tail.wrapping_add(offset))
+        .with_mut(|p| unsafe { (*p).as_mut_ptr().write(value) });
+    }
+
+    ring
+      .tail
+      .0
+      .store(tail.wrapping_add(count), Ordering::Release);
+
+    count
+  }
+}
+
+impl<T> Consumer<T> {
+  /// `capacity` returns the maximum number of elements the channel can hold at once.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.raw.capacity()
+  }
+
+  /// `len` returns the number of elements which are ready to be popped. The producer may push more elements
+  /// concurrently so this is only a lower bound.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.raw.len()
+  }
+
+  /// `is_empty` returns whether or not a [`pop`](Consumer::pop) would currently return `None`.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `pop` removes and returns the oldest element in the queue, or `None` if the queue is empty.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let (mut tx, mut rx) = minivec::spsc::channel(2);
+  /// assert_eq!(rx.pop(), None);
+  ///
+  /// tx.push(1).unwrap();
+  /// tx.push(2).unwrap();
+  ///
+  /// assert_eq!(rx.pop(), Some(1));
+  /// assert_eq!(rx.pop(), Some(2));
+  /// assert_eq!(rx.pop(), None);
+  /// ```
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    let ring = self.raw.ring();
+
+    let head = ring.head.0.load(Ordering::Relaxed);
+    let tail = ring.tail.0.load(Ordering::Acquire);
+    if head == tail {
+      return None;
+    }
+
+    let value = self
+      .raw
+      .slot(head)
+      .with(|p| unsafe { (*p).as_ptr().read() });
+    ring.head.0.store(head.wrapping_add(1), Ordering::Release);
+
+    Some(value)
+  }
+
+  /// `pop_into_slice` moves as many of the oldest elements as are available and fit into `out`, releasing their
+  /// slots back to the producer all at once, and returns how many were popped.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let (mut tx, mut rx) = minivec::spsc::channel(8);
+  /// tx.push_slice(b"hello");
+  ///
+  /// let mut out = [0u8; 8];
+  /// let count = rx.pop_into_slice(&mut out);
+  ///
+  /// assert_eq!(&out[..count], b"hello");
+  /// ```
+  ///
+  pub fn pop_into_slice(&mut self, out: &mut [T]) -> usize
+  where
+    T: Copy,
+  {
+    let ring = self.raw.ring();
+
+    let head = ring.head.0.load(Ordering::Relaxed);
+    let tail = ring.tail.0.load(Ordering::Acquire);
+
+    let count = core::cmp::min(tail.wrapping_sub(head), out.len());
+
+    for (offset, dst) in out[..count].iter_mut().enumerate() {
+      *dst = self
+        .raw
+        .slot(head.wrapping_add(offset))
+        .with(|p| unsafe { (*p).as_ptr().read() });
+    }
+
+    ring
+      .head
+      .0
+      .store(head.wrapping_add(count), Ordering::Release);
+
+    count
+  }
+}
+
+impl<T> Drop for Producer<T> {
+  fn drop(&mut self) {
+    self.raw.release();
+  }
+}
+
+impl<T> Drop for Consumer<T> {
+  fn drop(&mut self) {
+    self.raw.release();
+  }
+}
+
+impl<T> core::fmt::Debug for Producer<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("Producer")
+      .field("capacity", &self.capacity())
+      .field("len", &self.len())
+      .finish()
+  }
+}
+
+impl<T> core::fmt::Debug for Consumer<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("Consumer")
+      .field("capacity", &self.capacity())
+      .field("len", &self.len())
+      .finish()
+  }
+}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for Producer<T> {}
+unsafe impl<T: core::marker::Send> core::marker::Sync for Producer<T> {}
+
+unsafe impl<T: core::marker::Send> core::marker::Send for Consumer<T> {}
+unsafe impl<T: core::marker::Send> core::marker::Sync for Consumer<T> {}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn indices_wrap_around_usize() {
+    let (mut tx, mut rx) = channel::<usize>(3);
+
+    let ring = tx.raw.ring();
+    ring.head.0.store(usize::MAX - 1, Ordering::Relaxed);
+    ring.tail.0.store(usize::MAX - 1, Ordering::Relaxed);
+
+    for round in 0..4 {
+      assert_eq!(tx.push_slice(&[round, round + 1, round + 2]), 3);
+      assert!(tx.is_full());
+
+      assert_eq!(rx.pop(), Some(round));
+      let mut out = [0; 2];
+      assert_eq!(rx.pop_into_slice(&mut out), 2);
+      assert_eq!(out, [round + 1, round + 2]);
+      assert!(rx.is_empty());
+    }
+  }
+}
@@ -0,0 +1,41 @@
+// Synchronization primitives used by the concurrent containers. Building with `--cfg loom` swaps them for `loom`'s
+// model-checked versions.
+//
+
+#[cfg(loom)]
+pub use loom::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(not(loom))]
+pub use core::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(loom)]
+pub use loom::cell::UnsafeCell;
+
+// Transparent so that containers can hand a buffer of cells over as a buffer of their contents.
+//
+#[cfg(not(loom))]
+#[repr(transparent)]
+pub struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+  pub fn new(value: T) -> Self {
+    UnsafeCell(core::cell::UnsafeCell::new(value))
+  }
+
+  pub fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+    f(self.0.get())
+  }
+
+  pub fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+    f(self.0.get())
+  }
+}
+
+pub fn spin_loop() {
+  #[cfg(loom)]
+  loom::thread::yield_now();
+
+  #[cfg(not(loom))]
+  core::hint::spin_loop();
+}
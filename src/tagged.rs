@@ -0,0 +1,499 @@
+//! `tagged` contains [`TaggedMiniVec`](TaggedMiniVec), a [`MiniVec`](crate::MiniVec) which stores a small user
+//! tag in the always-zero low bits of its pointer.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::{Header, MiniVec};
+
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+/// `TaggedMiniVec` is a [`MiniVec`](crate::MiniVec) which packs a `BITS`-bit tag into the low bits of its pointer.
+/// It is still only the size of a single pointer.
+///
+/// Every `MiniVec` pointer is aligned to at least the larger of `align_of::<T>()` and the alignment of its internal
+/// header (capped at 4096 for empty vectors), including over-aligned vectors created with
+/// [`with_alignment`](crate::MiniVec::with_alignment), so those bits are always zero. Using more bits than that
+/// guarantees is a compile-time error.
+///
+/// The common vector operations are available directly. Everything else in the `MiniVec` API is available through
+/// [`with_mut`](TaggedMiniVec::with_mut) which lends out the untagged vector and re-applies the tag afterwards.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Debug, PartialEq)]
+/// enum State {
+///     Open = 0,
+///     Closed = 1,
+/// }
+///
+/// let mut vec = minivec::TaggedMiniVec::<u32, 2>::new();
+/// vec.push(1);
+/// vec.push(2);
+///
+/// vec.set_tag(State::Closed as usize);
+/// assert_eq!(vec.tag(), 1);
+/// assert_eq!(vec, [1, 2]);
+///
+/// vec.with_mut(|v| v.drain(..1).for_each(drop));
+/// assert_eq!(vec, [2]);
+/// assert_eq!(vec.tag(), State::Closed as usize);
+/// ```
+///
+/// Tags wider than the alignment allows are rejected at compile time:
+///
+/// ```compile_fail
+/// let vec = minivec::TaggedMiniVec::<u8, 8>::new();
+/// ```
+///
+#[repr(transparent)]
+pub struct TaggedMiniVec<T, const BITS: u8> {
+  buf: NonNull<u8>,
+  phantom: core::marker::PhantomData<T>,
+}
+
+struct Retag<'a, T, const BITS: u8> {
+  vec: ManuallyDrop<MiniVec<T>>,
+  tagged: &'a mut TaggedMiniVec<T, BITS>,
+}
+
+impl<T, const BITS: u8> Drop for Retag<'_, T, BITS> {
+  fn drop(&mut self) {
+    let tag = self.tagged.tag();
+    self.tagged.buf = TaggedMiniVec::<T, BITS>::pack(&self.vec, tag);
+  }
+}
+
+impl<T, const BITS: u8> TaggedMiniVec<T, BITS> {
+  const MASK: usize = {
+    let min_align = if core::mem::align_of::<T>() > core::mem::align_of::<Header>() {
+      core::mem::align_of::<T>()
+    } else {
+      core::mem::align_of::<Header>()
+    };
+
+    // empty vectors point into the sentinel table which is only aligned so far
+    //
+    let min_align = if min_align > crate::MAX_SENTINEL_ALIGNMENT {
+      crate::MAX_SENTINEL_ALIGNMENT
+    } else {
+      min_align
+    };
+
+    assert!(
+      (BITS as u32) <= min_align.trailing_zeros(),
+      "BITS exceeds the number of low pointer bits guaranteed to be zero"
+    );
+
+    (1_usize << BITS) - 1
+  };
+
+  fn pack(vec: &MiniVec<T>, tag: usize) -> NonNull<u8> {
+    let tagged = vec.buf.as_ptr().map_addr(|addr| addr | tag);
+    unsafe { NonNull::new_unchecked(tagged) }
+  }
+
+  fn untagged(&self) -> ManuallyDrop<MiniVec<T>> {
+    let ptr = self.buf.as_ptr().map_addr(|addr| addr & !Self::MASK);
+
+    ManuallyDrop::new(MiniVec {
+      buf: unsafe { NonNull::new_unchecked(ptr) },
+      phantom: core::marker::PhantomData,
+    })
+  }
+
+  /// `as_mut_ptr` returns a mutable pointer to the first element of the vector.
+  ///
+  pub fn as_mut_ptr(&mut self) -> *mut T {
+    self.untagged().as_mut_ptr()
+  }
+
+  /// `as_mut_slice` returns a mutable slice over the elements of the vector.
+  ///
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    let len = self.len();
+    unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+  }
+
+  /// `as_ptr` returns a pointer to the first element of the vector.
+  ///
+  #[must_use]
+  pub fn as_ptr(&self) -> *const T {
+    self.untagged().as_ptr()
+  }
+
+  /// `as_slice` returns a slice over the elements of the vector.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
+  }
+
+  /// `capacity` returns the number of elements the vector can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.untagged().capacity()
+  }
+
+  /// `clear` removes every element from the vector. The tag is unaffected.
+  ///
+  pub fn clear(&mut self) {
+    self.with_mut(MiniVec::clear);
+  }
+
+  /// `from_vec` wraps `vec`, tagging it with `tag`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `tag` does not fit in `BITS` bits.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::TaggedMiniVec::<_, 3>::from_vec(minivec::mini_vec![1, 2, 3], 5);
+  ///
+  /// assert_eq!(vec.tag(), 5);
+  /// assert_eq!(vec, [1, 2, 3]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn from_vec(vec: MiniVec<T>, tag: usize) -> Self {
+    assert!(
+      tag <= Self::MASK,
+      "tag (is {}) does not fit in {} bits",
+      tag,
+      BITS
+    );
+
+    let vec = ManuallyDrop::new(vec);
+    TaggedMiniVec {
+      buf: Self::pack(&vec, tag),
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  /// `insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len`.
+  ///
+  pub fn insert(&mut self, index: usize, element: T) {
+    self.with_mut(|vec| vec.insert(index, element));
+  }
+
+  /// `into_parts` splits the vector into the untagged `MiniVec` and its tag.
+  ///
+  #[must_use]
+  pub fn into_parts(self) -> (MiniVec<T>, usize) {
+    let tag = self.tag();
+    let vec = ManuallyDrop::into_inner(self.untagged());
+    core::mem::forget(self);
+    (vec, tag)
+  }
+
+  /// `into_vec` unwraps the vector, discarding the tag.
+  ///
+  #[must_use]
+  pub fn into_vec(self) -> MiniVec<T> {
+    self.into_parts().0
+  }
+
+  /// `is_empty` returns whether or not the vector has a length of `0`.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.untagged().is_empty()
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.untagged().len()
+  }
+
+  /// `new` constructs an empty vector with a tag of `0`.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    Self::from_vec(MiniVec::new(), 0)
+  }
+
+  /// `pop` removes the last element of the vector and returns it, or `None` if the vector is empty.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    self.with_mut(MiniVec::pop)
+  }
+
+  /// `push` appends `value` to the end of the vector. The tag is unaffected.
+  ///
+  pub fn push(&mut self, value: T) {
+    self.with_mut(|vec| vec.push(value));
+  }
+
+  /// `remove` removes the element at `index` and returns it, shifting every element after it to the left.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn remove(&mut self, index: usize) -> T {
+    self.with_mut(|vec| vec.remove(index))
+  }
+
+  /// `reserve` reserves space for at least `additional` more elements.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    self.with_mut(|vec| vec.reserve(additional));
+  }
+
+  /// `reserve_exact` reserves space for exactly `additional` more elements.
+  ///
+  pub fn reserve_exact(&mut self, additional: usize) {
+    self.with_mut(|vec| vec.reserve_exact(additional));
+  }
+
+  /// `retain` removes every element for which `f` returns `false`.
+  ///
+  pub fn retain<F>(&mut self, f: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    self.with_mut(|vec| vec.retain(f));
+  }
+
+  /// `set_tag` replaces the tag of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `tag` does not fit in `BITS` bits.
+  ///
+  pub fn set_tag(&mut self, tag: usize) {
+    assert!(
+      tag <= Self::MASK,
+      "tag (is {}) does not fit in {} bits",
+      tag,
+      BITS
+    );
+
+    let tagged = self
+      .buf
+      .as_ptr()
+      .map_addr(|addr| (addr & !Self::MASK) | tag);
+
+    self.buf = unsafe { NonNull::new_unchecked(tagged) };
+  }
+
+  /// `shrink_to_fit` reduces the capacity of the vector as much as possible.
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    self.with_mut(MiniVec::shrink_to_fit);
+  }
+
+  /// `swap_remove` removes the element at `index` and returns it, replacing it with the last element of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn swap_remove(&mut self, index: usize) -> T {
+    self.with_mut(|vec| vec.swap_remove(index))
+  }
+
+  /// `tag` returns the tag currently stored in the vector.
+  ///
+  #[must_use]
+  pub fn tag(&self) -> usize {
+    self.buf.as_ptr().addr() & Self::MASK
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    self.with_mut(|vec| vec.truncate(len));
+  }
+
+  /// `with_capacity` constructs an empty vector with space for `capacity` elements and a tag of `0`.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self::from_vec(MiniVec::with_capacity(capacity), 0)
+  }
+
+  /// `with_mut` lends the untagged `MiniVec` to `f`, giving access to the entire `MiniVec` API. The tag is
+  /// re-applied once `f` returns, even if it panics.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::TaggedMiniVec::<_, 3>::from_vec(minivec::mini_vec![3, 1, 2, 1], 4);
+  ///
+  /// let removed = vec.with_mut(|v| {
+  ///     v.dedup();
+  ///     v.splice(1..2, vec![9, 9]).collect::<Vec<_>>()
+  /// });
+  ///
+  /// assert_eq!(removed, [1]);
+  /// assert_eq!(vec, [3, 9, 9, 2, 1]);
+  /// assert_eq!(vec.tag(), 4);
+  /// ```
+  ///
+  pub fn with_mut<F, R>(&mut self, f: F) -> R
+  where
+    F: FnOnce(&mut MiniVec<T>) -> R,
+  {
+    let mut guard = Retag {
+      vec: self.untagged(),
+      tagged: self,
+    };
+
+    f(&mut guard.vec)
+  }
+}
+
+impl<T: Clone, const BITS: u8> TaggedMiniVec<T, BITS> {
+  /// `extend_from_slice` clones every element of `elems` onto the end of the vector.
+  ///
+  pub fn extend_from_slice(&mut self, elems: &[T]) {
+    self.with_mut(|vec| vec.extend_from_slice(elems));
+  }
+
+  /// `resize` clones `value` onto the end of the vector until its length becomes `new_len`, or truncates it if it's
+  /// longer.
+  ///
+  pub fn resize(&mut self, new_len: usize, value: T) {
+    self.with_mut(|vec| vec.resize(new_len, value));
+  }
+}
+
+impl<T, const BITS: u8> Drop for TaggedMiniVec<T, BITS> {
+  fn drop(&mut self) {
+    drop(ManuallyDrop::into_inner(self.untagged()));
+  }
+}
+
+impl<T, const BITS: u8> core::ops::Deref for TaggedMiniVec<T, BITS> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl<T, const BITS: u8> core::ops::DerefMut for TaggedMiniVec<T, BITS> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.as_mut_slice()
+  }
+}
+
+impl<T, const BITS: u8> AsRef<[T]> for TaggedMiniVec<T, BITS> {
+  fn as_ref(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<T, const BITS: u8> AsMut<[T]> for TaggedMiniVec<T, BITS> {
+  fn as_mut(&mut self) -> &mut [T] {
+    self.as_mut_slice()
+  }
+}
+
+impl<T: Clone, const BITS: u8> Clone for TaggedMiniVec<T, BITS> {
+  fn clone(&self) -> Self {
+    let vec = MiniVec::clone(&self.untagged());
+    Self::from_vec(vec, self.tag())
+  }
+}
+
+impl<T: core::fmt::Debug, const BITS: u8> core::fmt::Debug for TaggedMiniVec<T, BITS> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("TaggedMiniVec")
+      .field("tag", &self.tag())
+      .field("elements", &self.as_slice())
+      .finish()
+  }
+}
+
+impl<T, const BITS: u8> Default for TaggedMiniVec<T, BITS> {
+  fn default() -> Self {
+    TaggedMiniVec::new()
+  }
+}
+
+impl<T: PartialEq<U>, U, const BITS: u8> PartialEq<TaggedMiniVec<U, BITS>>
+  for TaggedMiniVec<T, BITS>
+{
+  fn eq(&self, other: &TaggedMiniVec<U, BITS>) -> bool {
+    self.tag() == other.tag() && self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const BITS: u8> PartialEq<[U]> for TaggedMiniVec<T, BITS> {
+  fn eq(&self, other: &[U]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: PartialEq<U>, U, const BITS: u8, const N: usize> PartialEq<[U; N]>
+  for TaggedMiniVec<T, BITS>
+{
+  fn eq(&self, other: &[U; N]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<T: Eq, const BITS: u8> Eq for TaggedMiniVec<T, BITS> {}
+
+impl<T: core::hash::Hash, const BITS: u8> core::hash::Hash for TaggedMiniVec<T, BITS> {
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.tag().hash(state);
+    self.as_slice().hash(state);
+  }
+}
+
+impl<T, const BITS: u8> core::iter::Extend<T> for TaggedMiniVec<T, BITS> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    self.with_mut(|vec| vec.extend(iter));
+  }
+}
+
+impl<T, const BITS: u8> From<MiniVec<T>> for TaggedMiniVec<T, BITS> {
+  fn from(vec: MiniVec<T>) -> Self {
+    Self::from_vec(vec, 0)
+  }
+}
+
+impl<T, const BITS: u8> core::iter::IntoIterator for TaggedMiniVec<T, BITS> {
+  type Item = T;
+  type IntoIter = crate::IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.into_vec().into_iter()
+  }
+}
+
+impl<'a, T, const BITS: u8> core::iter::IntoIterator for &'a TaggedMiniVec<T, BITS> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, T, const BITS: u8> core::iter::IntoIterator for &'a mut TaggedMiniVec<T, BITS> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+unsafe impl<T: core::marker::Send, const BITS: u8> core::marker::Send for TaggedMiniVec<T, BITS> {}
+unsafe impl<T: core::marker::Sync, const BITS: u8> core::marker::Sync for TaggedMiniVec<T, BITS> {}
@@ -0,0 +1,644 @@
+//! `vec_map` contains [`MiniVecMap`](MiniVecMap), a sparse map from dense indices to values backed by a single
+//! [`MiniVec`](crate::MiniVec), along with its iterator and entry types.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::index_vec::Idx;
+use crate::MiniVec;
+
+/// `MiniVecMap` is a map keyed by a dense index type `I`, stored as a `MiniVec<Option<V>>` where the position of a
+/// slot is its key. It is only the size of a single pointer.
+///
+/// Lookups, insertions and removals are all constant time. Because the backing vector is only as long as the largest
+/// key in the map, `MiniVecMap` is best suited to keys that are densely packed, such as those handed out by a
+/// [`MiniIndexVec`](crate::MiniIndexVec).
+///
+/// Trailing vacant slots are trimmed on removal so [`is_empty`](MiniVecMap::is_empty) is constant time. The number of
+/// values isn't stored anywhere though, so [`len`](MiniVecMap::len) takes time proportional to the largest key.
+///
+/// Deserializing allocates a slot for every key up to the largest one in the input. To keep a single large key in
+/// untrusted input from forcing a huge allocation, a map is rejected if a key lies beyond 1024 slots plus 8 for every
+/// value read up to and including it.
+///
+pub struct MiniVecMap<I, V> {
+  raw: MiniVec<Option<V>>,
+  marker: core::marker::PhantomData<fn(&I)>,
+}
+
+impl<I, V> MiniVecMap<I, V> {
+  fn trim(&mut self) {
+    while let Some(None) = self.raw.last() {
+      self.raw.pop();
+    }
+  }
+}
+
+impl<I: Idx, V> MiniVecMap<I, V> {
+  /// `MiniVecMap::new` constructs an empty map.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map = minivec::MiniVecMap::<u32, &str>::new();
+  /// assert!(map.is_empty());
+  /// ```
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    MiniVecMap::default()
+  }
+
+  /// `with_capacity` constructs an empty map with space for keys up to, but not including, `capacity`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let map = minivec::MiniVecMap::<u32, &str>::with_capacity(16);
+  /// assert_eq!(map.capacity(), 16);
+  /// ```
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    MiniVecMap {
+      raw: MiniVec::with_capacity(capacity),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `capacity` returns the number of slots the map can hold before it needs to reallocate.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    self.raw.capacity()
+  }
+
+  /// `clear` removes every key-value pair from the map.
+  ///
+  pub fn clear(&mut self) {
+    self.raw.clear();
+  }
+
+  /// `contains_key` returns whether or not the map contains a value for `key`.
+  ///
+  pub fn contains_key(&self, key: I) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// `entry` returns the [`Entry`](Entry) for the supplied key, allowing for in-place manipulation of the map.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut counts = minivec::MiniVecMap::<u32, u32>::new();
+  ///
+  /// for x in [1_u32, 3, 1, 1] {
+  ///     *counts.entry(x).or_insert(0) += 1;
+  /// }
+  ///
+  /// assert_eq!(counts[1], 3);
+  /// assert_eq!(counts[3], 1);
+  /// assert_eq!(counts.get(2), None);
+  /// ```
+  ///
+  pub fn entry(&mut self, key: I) -> Entry<'_, I, V> {
+    let idx = key.index();
+    if matches!(self.raw.get(idx), Some(Some(_))) {
+      Entry::Occupied(OccupiedEntry { map: self, idx })
+    } else {
+      Entry::Vacant(VacantEntry { map: self, idx })
+    }
+  }
+
+  /// `get` returns a reference to the value stored for `key`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniVecMap::<u32, &str>::new();
+  /// map.insert(3, "c");
+  ///
+  /// assert_eq!(map.get(3), Some(&"c"));
+  /// assert_eq!(map.get(0), None);
+  /// ```
+  ///
+  pub fn get(&self, key: I) -> Option<&V> {
+    self.raw.get(key.index()).and_then(Option::as_ref)
+  }
+
+  /// `get_mut` returns a mutable reference to the value stored for `key`.
+  ///
+  pub fn get_mut(&mut self, key: I) -> Option<&mut V> {
+    self.raw.get_mut(key.index()).and_then(Option::as_mut)
+  }
+
+  /// `insert` stores `value` for `key`, returning the previous value if one was present.
+  ///
+  /// The backing vector is extended with vacant slots if `key` lies past its end.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the backing vector can't be extended to hold `key`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniVecMap::<u32, &str>::new();
+  ///
+  /// assert_eq!(map.insert(5, "a"), None);
+  /// assert_eq!(map.insert(5, "b"), Some("a"));
+  /// assert_eq!(map[5], "b");
+  /// ```
+  ///
+  pub fn insert(&mut self, key: I, value: V) -> Option<V> {
+    let idx = key.index();
+    if idx >= self.raw.len() {
+      let len = idx.checked_add(1).expect("capacity overflow");
+      self.raw.resize_with(len, || None);
+    }
+
+    self.raw[idx].replace(value)
+  }
+
+  /// `is_empty` returns whether or not the map contains any values.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.raw.is_empty()
+  }
+
+  /// `iter` returns an iterator over every key-value pair in the map, in ascending key order.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniVecMap::<u32, &str>::new();
+  /// map.insert(4, "d");
+  /// map.insert(1, "a");
+  ///
+  /// assert_eq!(map.iter().collect::<Vec<_>>(), [(1, &"a"), (4, &"d")]);
+  /// ```
+  ///
+  #[must_use]
+  pub fn iter(&self) -> Iter<'_, I, V> {
+    Iter {
+      inner: self.raw.iter().enumerate(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `iter_mut` returns an iterator over every key-value pair in the map, in ascending key order, with mutable
+  /// references to the values.
+  ///
+  pub fn iter_mut(&mut self) -> IterMut<'_, I, V> {
+    IterMut {
+      inner: self.raw.iter_mut().enumerate(),
+      marker: core::marker::PhantomData,
+    }
+  }
+
+  /// `keys` returns an iterator over every key in the map, in ascending order.
+  ///
+  #[must_use]
+  pub fn keys(&self) -> impl DoubleEndedIterator<Item = I> + '_ {
+    self.iter().map(|(k, _)| k)
+  }
+
+  /// `len` returns the number of values in the map.
+  ///
+  /// Note: this counts the values by visiting every slot in the backing vector, so it takes time proportional to the
+  /// largest key rather than constant time. Prefer [`is_empty`](MiniVecMap::is_empty) to check for emptiness.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniVecMap::<u32, &str>::new();
+  /// map.insert(7, "a");
+  ///
+  /// assert_eq!(map.len(), 1);
+  /// ```
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.raw.iter().filter(|x| x.is_some()).count()
+  }
+
+  /// `remove` removes the value stored for `key` and returns it.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut map = minivec::MiniVecMap::<u32, &str>::new();
+  /// map.insert(7, "a");
+  ///
+  /// assert_eq!(map.remove(7), Some("a"));
+  /// assert_eq!(map.remove(7), None);
+  /// assert!(map.is_empty());
+  /// ```
+  ///
+  pub fn remove(&mut self, key: I) -> Option<V> {
+    let value = self.raw.get_mut(key.index())?.take();
+    self.trim();
+    value
+  }
+
+  /// `retain` removes every key-value pair for which `f(key, &mut value)` returns `false`.
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(I, &mut V) -> bool,
+  {
+    for (idx, slot) in self.raw.iter_mut().enumerate() {
+      if let Some(value) = slot {
+        if !f(I::new(idx), value) {
+          *slot = None;
+        }
+      }
+    }
+
+    self.trim();
+  }
+
+  /// `values` returns an iterator over every value in the map, in ascending key order.
+  ///
+  pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + '_ {
+    self.raw.iter().filter_map(Option::as_ref)
+  }
+
+  /// `values_mut` returns an iterator over mutable references to every value in the map, in ascending key order.
+  ///
+  pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + '_ {
+    self.raw.iter_mut().filter_map(Option::as_mut)
+  }
+}
+
+impl<I: Idx, V> core::ops::Index<I> for MiniVecMap<I, V> {
+  type Output = V;
+
+  fn index(&self, key: I) -> &V {
+    self.get(key).expect("no entry found for key in MiniVecMap")
+  }
+}
+
+impl<I: Idx, V> core::ops::IndexMut<I> for MiniVecMap<I, V> {
+  fn index_mut(&mut self, key: I) -> &mut V {
+    self
+      .get_mut(key)
+      .expect("no entry found for key in MiniVecMap")
+  }
+}
+
+impl<I, V: Clone> Clone for MiniVecMap<I, V> {
+  fn clone(&self) -> Self {
+    MiniVecMap {
+      raw: self.raw.clone(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, V> core::fmt::Debug for MiniVecMap<I, V>
+where
+  I: Idx + core::fmt::Debug,
+  V: core::fmt::Debug,
+{
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+impl<I, V> Default for MiniVecMap<I, V> {
+  fn default() -> Self {
+    MiniVecMap {
+      raw: MiniVec::new(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<I, V: PartialEq> PartialEq for MiniVecMap<I, V> {
+  fn eq(&self, other: &Self) -> bool {
+    self.raw == other.raw
+  }
+}
+
+impl<I, V: Eq> Eq for MiniVecMap<I, V> {}
+
+impl<I: Idx, V> core::iter::Extend<(I, V)> for MiniVecMap<I, V> {
+  fn extend<It>(&mut self, iter: It)
+  where
+    It: IntoIterator<Item = (I, V)>,
+  {
+    for (k, v) in iter {
+      self.insert(k, v);
+    }
+  }
+}
+
+impl<I: Idx, V> core::iter::FromIterator<(I, V)> for MiniVecMap<I, V> {
+  fn from_iter<It>(iter: It) -> Self
+  where
+    It: IntoIterator<Item = (I, V)>,
+  {
+    let mut map = MiniVecMap::new();
+    map.extend(iter);
+    map
+  }
+}
+
+impl<I: Idx, V> core::iter::IntoIterator for MiniVecMap<I, V> {
+  type Item = (I, V);
+  type IntoIter = IntoIter<I, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    IntoIter {
+      inner: self.raw.into_iter().enumerate(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+impl<'a, I: Idx, V> core::iter::IntoIterator for &'a MiniVecMap<I, V> {
+  type Item = (I, &'a V);
+  type IntoIter = Iter<'a, I, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, I: Idx, V> core::iter::IntoIterator for &'a mut MiniVecMap<I, V> {
+  type Item = (I, &'a mut V);
+  type IntoIter = IterMut<'a, I, V>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+/// `Entry` is a view into a single slot of a [`MiniVecMap`](MiniVecMap), which is either occupied or vacant.
+///
+pub enum Entry<'a, I, V> {
+  /// An occupied slot.
+  ///
+  Occupied(OccupiedEntry<'a, I, V>),
+  /// A vacant slot.
+  ///
+  Vacant(VacantEntry<'a, I, V>),
+}
+
+/// `OccupiedEntry` is a view into a slot of a [`MiniVecMap`](MiniVecMap) that contains a value.
+///
+pub struct OccupiedEntry<'a, I, V> {
+  map: &'a mut MiniVecMap<I, V>,
+  idx: usize,
+}
+
+/// `VacantEntry` is a view into a slot of a [`MiniVecMap`](MiniVecMap) that doesn't contain a value.
+///
+pub struct VacantEntry<'a, I, V> {
+  map: &'a mut MiniVecMap<I, V>,
+  idx: usize,
+}
+
+impl<'a, I: Idx, V> Entry<'a, I, V> {
+  /// `and_modify` invokes `f` with the current value if the entry is occupied.
+  ///
+  #[must_use]
+  pub fn and_modify<F>(self, f: F) -> Self
+  where
+    F: FnOnce(&mut V),
+  {
+    match self {
+      Entry::Occupied(mut entry) => {
+        f(entry.get_mut());
+        Entry::Occupied(entry)
+      }
+      Entry::Vacant(entry) => Entry::Vacant(entry),
+    }
+  }
+
+  /// `key` returns the key of the entry.
+  ///
+  #[must_use]
+  pub fn key(&self) -> I {
+    match self {
+      Entry::Occupied(entry) => entry.key(),
+      Entry::Vacant(entry) => entry.key(),
+    }
+  }
+
+  /// `or_default` inserts `V::default()` if the entry is vacant and returns a mutable reference to the value.
+  ///
+  pub fn or_default(self) -> &'a mut V
+  where
+    V: Default,
+  {
+    self.or_insert_with(V::default)
+  }
+
+  /// `or_insert` inserts `default` if the entry is vacant and returns a mutable reference to the value.
+  ///
+  pub fn or_insert(self, default: V) -> &'a mut V {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default),
+    }
+  }
+
+  /// `or_insert_with` inserts the result of `default()` if the entry is vacant and returns a mutable reference to
+  /// the value.
+  ///
+  pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+  where
+    F: FnOnce() -> V,
+  {
+    match self {
+      Entry::Occupied(entry) => entry.into_mut(),
+      Entry::Vacant(entry) => entry.insert(default()),
+    }
+  }
+}
+
+fn occupied<V>(slot: Option<&V>) -> &V {
+  match slot {
+    Some(value) => value,
+    None => unreachable!(),
+  }
+}
+
+fn occupied_mut<V>(slot: Option<&mut V>) -> &mut V {
+  match slot {
+    Some(value) => value,
+    None => unreachable!(),
+  }
+}
+
+impl<'a, I: Idx, V> OccupiedEntry<'a, I, V> {
+  /// `get` returns a reference to the value in the entry.
+  ///
+  #[must_use]
+  pub fn get(&self) -> &V {
+    occupied(self.map.raw[self.idx].as_ref())
+  }
+
+  /// `get_mut` returns a mutable reference to the value in the entry.
+  ///
+  pub fn get_mut(&mut self) -> &mut V {
+    occupied_mut(self.map.raw[self.idx].as_mut())
+  }
+
+  /// `insert` replaces the value in the entry, returning the old value.
+  ///
+  pub fn insert(&mut self, value: V) -> V {
+    core::mem::replace(self.get_mut(), value)
+  }
+
+  /// `into_mut` converts the entry into a mutable reference to its value with the lifetime of the map.
+  ///
+  #[must_use]
+  pub fn into_mut(self) -> &'a mut V {
+    occupied_mut(self.map.raw[self.idx].as_mut())
+  }
+
+  /// `key` returns the key of the entry.
+  ///
+  #[must_use]
+  pub fn key(&self) -> I {
+    I::new(self.idx)
+  }
+
+  /// `remove` takes the value out of the map and returns it.
+  ///
+  #[must_use]
+  pub fn remove(self) -> V {
+    let value = self.map.raw[self.idx].take();
+    self.map.trim();
+
+    match value {
+      Some(value) => value,
+      None => unreachable!(),
+    }
+  }
+}
+
+impl<'a, I: Idx, V> VacantEntry<'a, I, V> {
+  /// `insert` places `value` in the entry's slot and returns a mutable reference to it.
+  ///
+  pub fn insert(self, value: V) -> &'a mut V {
+    let VacantEntry { map, idx } = self;
+    map.insert(I::new(idx), value);
+    occupied_mut(map.raw[idx].as_mut())
+  }
+
+  /// `key` returns the key of the entry.
+  ///
+  #[must_use]
+  pub fn key(&self) -> I {
+    I::new(self.idx)
+  }
+}
+
+/// `Iter` is an iterator over the key-value pairs of a [`MiniVecMap`](MiniVecMap).
+///
+pub struct Iter<'a, I, V> {
+  inner: core::iter::Enumerate<core::slice::Iter<'a, Option<V>>>,
+  marker: core::marker::PhantomData<fn(&I)>,
+}
+
+impl<'a, I: Idx, V> Iterator for Iter<'a, I, V> {
+  type Item = (I, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .find_map(|(idx, slot)| slot.as_ref().map(|v| (I::new(idx), v)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, self.inner.size_hint().1)
+  }
+}
+
+impl<I: Idx, V> DoubleEndedIterator for Iter<'_, I, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .rev()
+      .find_map(|(idx, slot)| slot.as_ref().map(|v| (I::new(idx), v)))
+  }
+}
+
+impl<I: Idx, V> core::iter::FusedIterator for Iter<'_, I, V> {}
+
+impl<I, V> Clone for Iter<'_, I, V> {
+  fn clone(&self) -> Self {
+    Iter {
+      inner: self.inner.clone(),
+      marker: core::marker::PhantomData,
+    }
+  }
+}
+
+/// `IterMut` is an iterator over the key-value pairs of a [`MiniVecMap`](MiniVecMap) which yields mutable references
+/// to the values.
+///
+pub struct IterMut<'a, I, V> {
+  inner: core::iter::Enumerate<core::slice::IterMut<'a, Option<V>>>,
+  marker: core::marker::PhantomData<fn(&I)>,
+}
+
+impl<'a, I: Idx, V> Iterator for IterMut<'a, I, V> {
+  type Item = (I, &'a mut V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .find_map(|(idx, slot)| slot.as_mut().map(|v| (I::new(idx), v)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, self.inner.size_hint().1)
+  }
+}
+
+impl<I: Idx, V> DoubleEndedIterator for IterMut<'_, I, V> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .rev()
+      .find_map(|(idx, slot)| slot.as_mut().map(|v| (I::new(idx), v)))
+  }
+}
+
+impl<I: Idx, V> core::iter::FusedIterator for IterMut<'_, I, V> {}
+
+/// `IntoIter` is an iterator that consumes a [`MiniVecMap`](MiniVecMap) and yields its key-value pairs in ascending
+/// key order.
+///
+pub struct IntoIter<I, V> {
+  inner: core::iter::Enumerate<crate::IntoIter<Option<V>>>,
+  marker: core::marker::PhantomData<fn(&I)>,
+}
+
+impl<I: Idx, V> Iterator for IntoIter<I, V> {
+  type Item = (I, V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .find_map(|(idx, slot)| slot.map(|v| (I::new(idx), v)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, self.inner.size_hint().1)
+  }
+}
+
+impl<I: Idx, V> core::iter::FusedIterator for IntoIter<I, V> {}
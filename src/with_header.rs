@@ -0,0 +1,704 @@
+//! `with_header` contains [`MiniVecWithHeader`](MiniVecWithHeader), a single-pointer vector which stores a user
+//! header in its allocation alongside the length and capacity.
+//!
+#![allow(clippy::module_name_repetitions)]
+
+use crate::r#impl::helpers::{
+  data_offset, make_layout_with, max_align_with, next_aligned, next_capacity,
+};
+use crate::{Header, LayoutErr};
+
+use core::ptr::NonNull;
+
+// An unallocated vector has nowhere to keep its user header so it instead points to a promoted `fn() -> H` which
+// builds the header once the vector first allocates. The low bit of the pointer is set to tell the two states apart
+// as real allocations are always aligned to at least `align_of::<Header>()`.
+//
+const UNALLOCATED: usize = 1;
+
+trait HeaderInit: Default {
+  const INIT: *const fn() -> Self;
+}
+
+impl<H: Default> HeaderInit for H {
+  const INIT: *const fn() -> Self = &(Self::default as fn() -> Self);
+}
+
+/// `MiniVecWithHeader` is a vector which places a user-defined header `H` in the same allocation as its elements,
+/// directly after the internal length and capacity. This makes it possible to attach per-vector metadata such as a
+/// generation counter or a cached hash while still only being the size of a single pointer.
+///
+/// When `H: Default`, [`new`](MiniVecWithHeader::new) constructs a vector which does not allocate. Its header is
+/// then implicitly `H::default()` and is only materialized once the vector allocates or the header is borrowed
+/// mutably. Vectors constructed with [`with_header`](MiniVecWithHeader::with_header) always allocate so that the
+/// header has somewhere to live.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Default, Debug)]
+/// struct Meta {
+///     generation: u32,
+/// }
+///
+/// let mut vec = minivec::MiniVecWithHeader::<Meta, i32>::new();
+/// assert!(vec.header().is_none());
+///
+/// vec.push(1);
+/// vec.push(2);
+/// vec.header_mut().generation += 1;
+///
+/// assert_eq!(vec.header().unwrap().generation, 1);
+/// assert_eq!(vec, [1, 2]);
+/// assert_eq!(core::mem::size_of_val(&vec), core::mem::size_of::<*const ()>());
+/// ```
+///
+pub struct MiniVecWithHeader<H, T> {
+  buf: NonNull<u8>,
+  phantom: core::marker::PhantomData<(H, T)>,
+}
+
+impl<H, T> MiniVecWithHeader<H, T> {
+  fn is_allocated(&self) -> bool {
+    self.buf.as_ptr().addr() & UNALLOCATED == 0
+  }
+
+  fn init(&self) -> fn() -> H {
+    debug_assert!(!self.is_allocated());
+
+    let init = self.buf.as_ptr().map_addr(|addr| addr & !UNALLOCATED);
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      *init.cast::<fn() -> H>()
+    }
+  }
+
+  fn prefix(&self) -> &Header {
+    debug_assert!(self.is_allocated());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &*self.buf.as_ptr().cast::<Header>()
+    }
+  }
+
+  fn prefix_mut(&mut self) -> &mut Header {
+    debug_assert!(self.is_allocated());
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      &mut *self.buf.as_ptr().cast::<Header>()
+    }
+  }
+
+  fn user_header(&self) -> *mut H {
+    debug_assert!(self.is_allocated());
+
+    let offset = next_aligned(core::mem::size_of::<Header>(), core::mem::align_of::<H>());
+    unsafe { self.buf.as_ptr().add(offset).cast::<H>() }
+  }
+
+  fn data(&self) -> *mut T {
+    debug_assert!(self.is_allocated());
+
+    let offset = data_offset::<H>(self.prefix().alignment);
+    unsafe { self.buf.as_ptr().add(offset).cast::<T>() }
+  }
+
+  fn allocate(header: H, capacity: usize, alignment: usize) -> Self {
+    let layout = make_layout_with::<H, T>(capacity, alignment);
+
+    let buf = unsafe { alloc::alloc::alloc(layout) };
+    if buf.is_null() {
+      alloc::alloc::handle_alloc_error(layout);
+    }
+
+    let vec = MiniVecWithHeader {
+      buf: unsafe { NonNull::new_unchecked(buf) },
+      phantom: core::marker::PhantomData,
+    };
+
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+      core::ptr::write(
+        buf.cast::<Header>(),
+        Header {
+          len: 0,
+          cap: capacity,
+          alignment,
+        },
+      );
+
+      core::ptr::write(vec.user_header(), header);
+    }
+
+    vec
+  }
+
+  fn grow(&mut self, capacity: usize) {
+    debug_assert!(capacity >= self.len());
+
+    if !self.is_allocated() {
+      let header = self.init()();
+      *self = Self::allocate(header, capacity, max_align_with::<H, T>());
+      return;
+    }
+
+    let old_capacity = self.capacity();
+    if capacity == old_capacity {
+      return;
+    }
+
+    let alignment = self.prefix().alignment;
+    let old_layout = make_layout_with::<H, T>(old_capacity, alignment);
+    let new_layout = make_layout_with::<H, T>(capacity, alignment);
+
+    let buf = unsafe { alloc::alloc::realloc(self.buf.as_ptr(), old_layout, new_layout.size()) };
+    if buf.is_null() {
+      alloc::alloc::handle_alloc_error(new_layout);
+    }
+
+    self.buf = unsafe { NonNull::new_unchecked(buf) };
+    self.prefix_mut().cap = capacity;
+  }
+
+  /// `as_mut_ptr` returns a mutable pointer to the first element of the vector. The pointer is dangling if the
+  /// vector has not allocated.
+  ///
+  pub fn as_mut_ptr(&mut self) -> *mut T {
+    self.as_ptr().cast_mut()
+  }
+
+  /// `as_mut_slice` returns a mutable slice over the elements of the vector.
+  ///
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    let len = self.len();
+    unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+  }
+
+  /// `as_ptr` returns a pointer to the first element of the vector. The pointer is dangling if the vector has not
+  /// allocated.
+  ///
+  #[must_use]
+  pub fn as_ptr(&self) -> *const T {
+    if self.is_allocated() {
+      self.data()
+    } else {
+      NonNull::dangling().as_ptr()
+    }
+  }
+
+  /// `as_slice` returns a slice over the elements of the vector.
+  ///
+  #[must_use]
+  pub fn as_slice(&self) -> &[T] {
+    unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
+  }
+
+  /// `alignment` returns the alignment of the element storage, which is only ever larger than that of `H` and `T`
+  /// for vectors built by [`with_header_and_alignment`](MiniVecWithHeader::with_header_and_alignment).
+  ///
+  #[must_use]
+  pub fn alignment(&self) -> usize {
+    if self.is_allocated() {
+      self.prefix().alignment
+    } else {
+      max_align_with::<H, T>()
+    }
+  }
+
+  /// `capacity` returns the number of elements the vector can hold without reallocating.
+  ///
+  #[must_use]
+  pub fn capacity(&self) -> usize {
+    if self.is_allocated() {
+      self.prefix().cap
+    } else {
+      0
+    }
+  }
+
+  /// `clear` removes every element from the vector. The header is unaffected.
+  ///
+  pub fn clear(&mut self) {
+    self.truncate(0);
+  }
+
+  /// `header` returns a reference to the user header, or `None` if the vector has not allocated yet in which case
+  /// its header is implicitly `H::default()`.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let vec = minivec::MiniVecWithHeader::<_, u8>::with_header("hello");
+  /// assert_eq!(vec.header(), Some(&"hello"));
+  ///
+  /// let vec = minivec::MiniVecWithHeader::<&str, u8>::new();
+  /// assert_eq!(vec.header(), None);
+  /// ```
+  ///
+  #[must_use]
+  pub fn header(&self) -> Option<&H> {
+    if self.is_allocated() {
+      Some(unsafe { &*self.user_header() })
+    } else {
+      None
+    }
+  }
+
+  /// `header_mut` returns a mutable reference to the user header, allocating room for it first if the vector has
+  /// not allocated yet.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniVecWithHeader::<u64, u8>::new();
+  /// *vec.header_mut() = 1337;
+  ///
+  /// assert_eq!(vec.header(), Some(&1337));
+  /// assert_eq!(vec.capacity(), 0);
+  /// ```
+  ///
+  pub fn header_mut(&mut self) -> &mut H {
+    if !self.is_allocated() {
+      self.grow(0);
+    }
+
+    unsafe { &mut *self.user_header() }
+  }
+
+  /// `insert` places `element` at `index`, shifting every element after it to the right.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index > len`.
+  ///
+  pub fn insert(&mut self, index: usize, element: T) {
+    let len = self.len();
+    assert!(
+      index <= len,
+      "insertion index (is {}) should be <= len (is {})",
+      index,
+      len
+    );
+
+    self.reserve(1);
+
+    unsafe {
+      let p = self.as_mut_ptr().add(index);
+      core::ptr::copy(p, p.add(1), len - index);
+      core::ptr::write(p, element);
+      self.set_len(len + 1);
+    }
+  }
+
+  /// `is_empty` returns whether or not the vector has a length of `0`.
+  ///
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// `len` returns the number of elements in the vector.
+  ///
+  #[must_use]
+  pub fn len(&self) -> usize {
+    if self.is_allocated() {
+      self.prefix().len
+    } else {
+      0
+    }
+  }
+
+  /// `pop` removes the last element of the vector and returns it, or `None` if the vector is empty.
+  ///
+  pub fn pop(&mut self) -> Option<T> {
+    let len = self.len();
+    if len == 0 {
+      return None;
+    }
+
+    unsafe {
+      self.set_len(len - 1);
+      Some(core::ptr::read(self.as_ptr().add(len - 1)))
+    }
+  }
+
+  /// `push` appends `value` to the end of the vector.
+  ///
+  pub fn push(&mut self, value: T) {
+    let len = self.len();
+    self.reserve(1);
+
+    unsafe {
+      core::ptr::write(self.as_mut_ptr().add(len), value);
+      self.set_len(len + 1);
+    }
+  }
+
+  /// `remove` removes the element at `index` and returns it, shifting every element after it to the left.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn remove(&mut self, index: usize) -> T {
+    let len = self.len();
+    assert!(
+      index < len,
+      "removal index (is {}) should be < len (is {})",
+      index,
+      len
+    );
+
+    unsafe {
+      let p = self.as_mut_ptr().add(index);
+      let value = core::ptr::read(p);
+      core::ptr::copy(p.add(1), p, len - index - 1);
+      self.set_len(len - 1);
+      value
+    }
+  }
+
+  /// `reserve` ensures there is room for at least `additional` more elements, growing the capacity geometrically.
+  ///
+  pub fn reserve(&mut self, additional: usize) {
+    let capacity = self.capacity();
+    let total_required = self.len() + additional;
+
+    if self.is_allocated() && total_required <= capacity {
+      return;
+    }
+
+    let mut new_capacity = next_capacity::<T>(capacity);
+    while new_capacity < total_required {
+      new_capacity = next_capacity::<T>(new_capacity);
+    }
+
+    self.grow(new_capacity);
+  }
+
+  /// `reserve_exact` ensures the capacity of the vector is at least `len() + additional`, allocating exactly that
+  /// much if it needs to grow.
+  ///
+  pub fn reserve_exact(&mut self, additional: usize) {
+    let total_required = self.len() + additional;
+
+    if self.is_allocated() && total_required <= self.capacity() {
+      return;
+    }
+
+    self.grow(total_required);
+  }
+
+  /// `retain` removes every element for which `f` returns `false`.
+  ///
+  pub fn retain<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&T) -> bool,
+  {
+    let len = self.len();
+    let mut kept = 0;
+
+    unsafe {
+      // elements are leaked rather than double-dropped should `f` panic
+      //
+      self.set_len(0);
+
+      let p = self.as_mut_ptr();
+      for idx in 0..len {
+        let cur = p.add(idx);
+        if f(&*cur) {
+          if idx != kept {
+            core::ptr::copy_nonoverlapping(cur, p.add(kept), 1);
+          }
+          kept += 1;
+        } else {
+          core::ptr::drop_in_place(cur);
+        }
+      }
+
+      self.set_len(kept);
+    }
+  }
+
+  /// `set_len` directly sets the length of the vector.
+  ///
+  /// # Safety
+  ///
+  /// `len` must not exceed the capacity of the vector and every element in `0..len` must be initialized.
+  ///
+  pub unsafe fn set_len(&mut self, len: usize) {
+    debug_assert!(len <= self.capacity());
+
+    if self.is_allocated() {
+      self.prefix_mut().len = len;
+    }
+  }
+
+  /// `shrink_to_fit` reduces the capacity of the vector to its length. An allocated vector keeps its header-only
+  /// allocation even when empty so that its header is preserved.
+  ///
+  pub fn shrink_to_fit(&mut self) {
+    if self.is_allocated() {
+      self.grow(self.len());
+    }
+  }
+
+  /// `swap_remove` removes the element at `index` and returns it, replacing it with the last element of the vector.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `index` is out of bounds.
+  ///
+  pub fn swap_remove(&mut self, index: usize) -> T {
+    let len = self.len();
+    assert!(
+      index < len,
+      "swap_remove index (is {}) should be < len (is {})",
+      index,
+      len
+    );
+
+    self.as_mut_slice().swap(index, len - 1);
+    self.pop().expect("vector is non-empty")
+  }
+
+  /// `truncate` shortens the vector to `len` elements, dropping the rest. Has no effect if `len` is greater than the
+  /// vector's current length.
+  ///
+  pub fn truncate(&mut self, len: usize) {
+    let old_len = self.len();
+    if len >= old_len {
+      return;
+    }
+
+    unsafe {
+      self.set_len(len);
+
+      let tail = core::ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), old_len - len);
+      core::ptr::drop_in_place(tail);
+    }
+  }
+
+  /// `with_header` constructs an empty vector holding `header`.
+  ///
+  /// Note: allocates room for the header but not for any elements.
+  ///
+  #[must_use]
+  pub fn with_header(header: H) -> Self {
+    Self::allocate(header, 0, max_align_with::<H, T>())
+  }
+
+  /// `with_header_and_capacity` constructs an empty vector holding `header` with room for `capacity` elements.
+  ///
+  #[must_use]
+  pub fn with_header_and_capacity(header: H, capacity: usize) -> Self {
+    Self::allocate(header, capacity, max_align_with::<H, T>())
+  }
+
+  /// `with_header_and_alignment` constructs an empty vector holding `header` with room for `capacity` elements,
+  /// whose elements start on an `alignment`-byte boundary. The alignment is kept across reallocations.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LayoutErr::AlignmentTooSmall`](LayoutErr::AlignmentTooSmall) if `alignment` is smaller than the
+  /// alignment required by the header or the elements and
+  /// [`LayoutErr::AlignmentNotDivisibleByTwo`](LayoutErr::AlignmentNotDivisibleByTwo) if it isn't even.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// let mut vec = minivec::MiniVecWithHeader::<u8, f32>::with_header_and_alignment(7, 8, 64).unwrap();
+  /// vec.extend_from_slice(&[1.0; 32]);
+  ///
+  /// assert_eq!(vec.as_ptr() as usize % 64, 0);
+  /// assert_eq!(vec.header(), Some(&7));
+  /// ```
+  ///
+  pub fn with_header_and_alignment(
+    header: H,
+    capacity: usize,
+    alignment: usize,
+  ) -> Result<Self, LayoutErr> {
+    if alignment < max_align_with::<H, T>() {
+      return Err(LayoutErr::AlignmentTooSmall);
+    }
+
+    if !alignment.is_multiple_of(2) {
+      return Err(LayoutErr::AlignmentNotDivisibleByTwo);
+    }
+
+    Ok(Self::allocate(header, capacity, alignment))
+  }
+}
+
+impl<H: Default, T> MiniVecWithHeader<H, T> {
+  /// `new` constructs an empty vector whose header is `H::default()`.
+  ///
+  /// Note: does not allocate any memory.
+  ///
+  #[must_use]
+  pub fn new() -> Self {
+    let init: *const fn() -> H = <H as HeaderInit>::INIT;
+    let buf = init
+      .cast::<u8>()
+      .cast_mut()
+      .map_addr(|addr| addr | UNALLOCATED);
+
+    MiniVecWithHeader {
+      buf: unsafe { NonNull::new_unchecked(buf) },
+      phantom: core::marker::PhantomData,
+    }
+  }
+
+  /// `with_capacity` constructs an empty vector with a default header and room for `capacity` elements.
+  ///
+  #[must_use]
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self::allocate(H::default(), capacity, max_align_with::<H, T>())
+  }
+}
+
+impl<H, T: Clone> MiniVecWithHeader<H, T> {
+  /// `extend_from_slice` clones every element of `elems` onto the end of the vector.
+  ///
+  pub fn extend_from_slice(&mut self, elems: &[T]) {
+    self.reserve(elems.len());
+    for elem in elems {
+      self.push(elem.clone());
+    }
+  }
+}
+
+impl<H, T> Drop for MiniVecWithHeader<H, T> {
+  fn drop(&mut self) {
+    if !self.is_allocated() {
+      return;
+    }
+
+    let layout = make_layout_with::<H, T>(self.capacity(), self.prefix().alignment);
+
+    unsafe {
+      core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.data(), self.len()));
+      core::ptr::drop_in_place(self.user_header());
+      alloc::alloc::dealloc(self.buf.as_ptr(), layout);
+    }
+  }
+}
+
+impl<H, T> core::ops::Deref for MiniVecWithHeader<H, T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_slice()
+  }
+}
+
+impl<H, T> core::ops::DerefMut for MiniVecWithHeader<H, T> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    self.as_mut_slice()
+  }
+}
+
+impl<H, T> AsRef<[T]> for MiniVecWithHeader<H, T> {
+  fn as_ref(&self) -> &[T] {
+    self.as_slice()
+  }
+}
+
+impl<H, T> AsMut<[T]> for MiniVecWithHeader<H, T> {
+  fn as_mut(&mut self) -> &mut [T] {
+    self.as_mut_slice()
+  }
+}
+
+impl<H: Clone, T: Clone> Clone for MiniVecWithHeader<H, T> {
+  fn clone(&self) -> Self {
+    match self.header() {
+      Some(header) => {
+        let mut vec = Self::allocate(header.clone(), self.len(), self.prefix().alignment);
+        vec.extend_from_slice(self);
+        vec
+      }
+      None => MiniVecWithHeader {
+        buf: self.buf,
+        phantom: core::marker::PhantomData,
+      },
+    }
+  }
+}
+
+impl<H: core::fmt::Debug, T: core::fmt::Debug> core::fmt::Debug for MiniVecWithHeader<H, T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("MiniVecWithHeader")
+      .field("header", &self.header())
+      .field("elements", &self.as_slice())
+      .finish()
+  }
+}
+
+impl<H: Default, T> Default for MiniVecWithHeader<H, T> {
+  fn default() -> Self {
+    MiniVecWithHeader::new()
+  }
+}
+
+impl<H, T: PartialEq<U>, U> PartialEq<[U]> for MiniVecWithHeader<H, T> {
+  fn eq(&self, other: &[U]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<H, T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for MiniVecWithHeader<H, T> {
+  fn eq(&self, other: &[U; N]) -> bool {
+    self[..] == other[..]
+  }
+}
+
+impl<H, T> core::iter::Extend<T> for MiniVecWithHeader<H, T> {
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    let iter = iter.into_iter();
+    self.reserve(iter.size_hint().0);
+
+    for value in iter {
+      self.push(value);
+    }
+  }
+}
+
+impl<H: Default, T> core::iter::FromIterator<T> for MiniVecWithHeader<H, T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut vec = MiniVecWithHeader::new();
+    vec.extend(iter);
+    vec
+  }
+}
+
+impl<'a, H, T> core::iter::IntoIterator for &'a MiniVecWithHeader<H, T> {
+  type Item = &'a T;
+  type IntoIter = core::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<'a, H, T> core::iter::IntoIterator for &'a mut MiniVecWithHeader<H, T> {
+  type Item = &'a mut T;
+  type IntoIter = core::slice::IterMut<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter_mut()
+  }
+}
+
+unsafe impl<H: core::marker::Send, T: core::marker::Send> core::marker::Send
+  for MiniVecWithHeader<H, T>
+{
+}
+unsafe impl<H: core::marker::Sync, T: core::marker::Sync> core::marker::Sync
+  for MiniVecWithHeader<H, T>
+{
+}
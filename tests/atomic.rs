@@ -0,0 +1,273 @@
+extern crate minivec;
+
+mod common;
+
+// The model tests run with: RUSTFLAGS="--cfg loom" cargo test --test atomic --release
+
+#[cfg(not(loom))]
+mod threads {
+  use minivec::{mini_vec, AtomicMiniVec, MiniVec};
+
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  use crate::common::SyncDropCounter;
+
+  #[test]
+  fn atomic_load_store_swap() {
+    let vec = AtomicMiniVec::new(mini_vec![1, 2, 3]);
+    assert_eq!(*vec.load(), [1, 2, 3]);
+
+    vec.store(mini_vec![4]);
+    assert_eq!(*vec.load(), [4]);
+
+    let old = vec.swap(MiniVec::new());
+    assert_eq!(*old, [4]);
+    assert!(vec.load().is_empty());
+    drop(old);
+
+    vec.store(mini_vec![5, 6]);
+    assert_eq!(vec.into_inner(), [5, 6]);
+  }
+
+  #[test]
+  fn atomic_compare_exchange() {
+    let vec = AtomicMiniVec::new(mini_vec![1]);
+
+    let current = vec.load();
+    let mut next = (*current).clone();
+    next.push(2);
+    assert_eq!(*vec.compare_exchange(current, next).unwrap(), [1]);
+
+    // a guard from before the exchange is stale, even though it's been dropped by the time we try
+    //
+    let stale_ptr = vec.load().as_ptr();
+    vec.store(mini_vec![3]);
+    let current = vec.load();
+    assert_ne!(current.as_ptr(), stale_ptr);
+
+    let other = AtomicMiniVec::new(mini_vec![9]);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      let _ = other.compare_exchange(current, mini_vec![0]);
+    }));
+    assert!(result.is_err());
+    assert_eq!(*other.load(), [9]);
+  }
+
+  #[test]
+  fn atomic_compare_exchange_fails_when_replaced() {
+    let vec = Arc::new(AtomicMiniVec::new(mini_vec![1]));
+
+    let current = vec.load();
+
+    let writer = {
+      let vec = vec.clone();
+      std::thread::spawn(move || (*vec.swap(mini_vec![2])).clone())
+    };
+
+    // the writer doesn't wait for `current` to be dropped
+    //
+    assert_eq!(writer.join().unwrap(), [1]);
+    assert_eq!(*current, [1]);
+
+    let rejected = vec.compare_exchange(current, mini_vec![3]).unwrap_err();
+    assert_eq!(rejected, [3]);
+    assert_eq!(*vec.load(), [2]);
+  }
+
+  #[test]
+  fn atomic_guard_outlives_replacement() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let vec = Arc::new(AtomicMiniVec::new(mini_vec![SyncDropCounter {
+      count: count.clone()
+    }]));
+
+    let guard = vec.load();
+
+    let writer = {
+      let vec = vec.clone();
+      let count = count.clone();
+      std::thread::spawn(move || {
+        vec.store(mini_vec![SyncDropCounter { count }]);
+      })
+    };
+
+    writer.join().unwrap();
+    assert_eq!(guard.len(), 1);
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+
+    drop(guard);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+
+    drop(vec);
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+  }
+
+  #[test]
+  fn atomic_last_guard_drops_replaced_vector() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let vec = AtomicMiniVec::new(mini_vec![SyncDropCounter {
+      count: count.clone()
+    }]);
+
+    let first = vec.load();
+    let second = vec.load();
+
+    // replacing the vector while this thread holds guards to it must not wait on them
+    //
+    vec.store(MiniVec::new());
+    let old = vec.swap(mini_vec![SyncDropCounter {
+      count: count.clone()
+    }]);
+    assert!(old.is_empty());
+    drop(old);
+
+    drop(second);
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+    assert_eq!(first.len(), 1);
+
+    drop(first);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+
+    let current = vec.load();
+    let swapped = vec.swap(MiniVec::new());
+    drop(current);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+    assert_eq!(swapped.len(), 1);
+
+    drop(swapped);
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+  }
+
+  #[test]
+  fn atomic_readers_and_writers() {
+    let vec = AtomicMiniVec::new((0..16).collect::<MiniVec<usize>>());
+
+    std::thread::scope(|s| {
+      for _ in 0..4 {
+        s.spawn(|| {
+          for _ in 0..1000 {
+            let guard = vec.load();
+            let first = guard[0];
+            assert!(guard.iter().enumerate().all(|(i, &x)| x == first + i));
+          }
+        });
+      }
+
+      for w in 0..2 {
+        let vec = &vec;
+        s.spawn(move || {
+          for i in 0..100 {
+            let base = (w * 100 + i) * 16;
+            vec.store((base..base + 16).collect());
+          }
+        });
+      }
+    });
+
+    assert_eq!(vec.load().len(), 16);
+  }
+}
+
+#[cfg(loom)]
+mod model {
+  use minivec::{mini_vec, AtomicMiniVec};
+
+  use loom::sync::atomic::{AtomicUsize, Ordering};
+  use loom::sync::Arc;
+  use loom::thread;
+
+  struct DropCounter {
+    id: usize,
+    count: Arc<AtomicUsize>,
+  }
+
+  impl Drop for DropCounter {
+    fn drop(&mut self) {
+      self.count.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  #[test]
+  fn loom_atomic_replaced_vector_outlives_reader() {
+    loom::model(|| {
+      let count = Arc::new(AtomicUsize::new(0));
+      let vec = Arc::new(AtomicMiniVec::new(mini_vec![DropCounter {
+        id: 1,
+        count: count.clone()
+      }]));
+
+      let reader = {
+        let vec = vec.clone();
+        let count = count.clone();
+        thread::spawn(move || {
+          let guard = vec.load();
+          if guard[0].id == 1 {
+            assert_eq!(count.load(Ordering::Relaxed), 0);
+          }
+        })
+      };
+
+      vec.store(mini_vec![DropCounter {
+        id: 2,
+        count: count.clone()
+      }]);
+
+      reader.join().unwrap();
+      assert_eq!(count.load(Ordering::Relaxed), 1);
+    });
+  }
+
+  #[test]
+  fn loom_atomic_reader_sees_whole_vector() {
+    loom::model(|| {
+      let vec = Arc::new(AtomicMiniVec::new(mini_vec![1, 1]));
+
+      let reader = {
+        let vec = vec.clone();
+        thread::spawn(move || {
+          let guard = vec.load();
+          assert!(*guard == [1, 1] || *guard == [2, 2]);
+        })
+      };
+
+      let old = vec.swap(mini_vec![2, 2]);
+      assert_eq!(*old, [1, 1]);
+      drop(old);
+
+      reader.join().unwrap();
+      assert_eq!(*vec.load(), [2, 2]);
+    });
+  }
+
+  #[test]
+  fn loom_atomic_competing_writers() {
+    loom::model(|| {
+      let vec = Arc::new(AtomicMiniVec::new(mini_vec![0]));
+
+      let writer = {
+        let vec = vec.clone();
+        thread::spawn(move || {
+          let current = vec.load();
+          let next = mini_vec![current[0] + 1];
+          vec.compare_exchange(current, next).is_ok()
+        })
+      };
+
+      let current = vec.load();
+      let next = mini_vec![current[0] + 10];
+      let ours = vec.compare_exchange(current, next).is_ok();
+
+      let theirs = writer.join().unwrap();
+      assert!(ours || theirs);
+
+      let value = vec.load()[0];
+      match (ours, theirs) {
+        (true, true) => assert!(value == 11),
+        (true, false) => assert!(value == 10),
+        (false, true) => assert!(value == 1),
+        (false, false) => unreachable!(),
+      }
+    });
+  }
+}
@@ -0,0 +1,139 @@
+extern crate minivec;
+
+use minivec::{mini_vec, BoundedMiniVec, MiniVec};
+
+use std::convert::TryFrom;
+
+#[test]
+fn bounded_push_stops_at_limit() {
+  let mut vec = BoundedMiniVec::<i32, 5>::new();
+  assert_eq!(vec.capacity(), 0);
+  assert_eq!(vec.limit(), 5);
+
+  for i in 0..5 {
+    assert!(vec.capacity() <= 5);
+    vec.try_push(i).unwrap();
+  }
+
+  assert!(vec.is_full());
+  assert_eq!(vec.remaining(), 0);
+  assert_eq!(vec.capacity(), 5);
+  assert_eq!(vec.try_push(5).unwrap_err().element(), 5);
+  assert_eq!(vec.try_insert(0, 6).unwrap_err().element(), 6);
+  assert_eq!(vec, [0, 1, 2, 3, 4]);
+
+  assert_eq!(vec.pop(), Some(4));
+  vec.insert(0, -1);
+  assert_eq!(vec, [-1, 0, 1, 2, 3]);
+  assert_eq!(vec.capacity(), 5);
+}
+
+#[test]
+#[should_panic(expected = "capacity limit exceeded")]
+fn bounded_push_panics_when_full() {
+  let mut vec = BoundedMiniVec::<i32, 1>::new();
+  vec.push(1);
+  vec.push(2);
+}
+
+#[test]
+fn bounded_extend_and_resize() {
+  let mut vec = BoundedMiniVec::<u8, 10>::with_capacity(2);
+  assert_eq!(vec.capacity(), 2);
+
+  vec.extend(vec![1, 2, 3]);
+  assert!(vec.try_extend_from_slice(&[0; 8]).is_err());
+  assert_eq!(vec.len(), 3);
+
+  vec.extend_from_slice(&[4, 5]);
+  vec.extend_from_within(1..4);
+  assert!(vec.try_extend_from_within(..3).is_err());
+  assert_eq!(vec, [1, 2, 3, 4, 5, 2, 3, 4]);
+
+  assert!(vec.try_resize(11, 0).is_err());
+  vec.resize(10, 0);
+  assert_eq!(vec, [1, 2, 3, 4, 5, 2, 3, 4, 0, 0]);
+  assert_eq!(vec.capacity(), 10);
+
+  vec.resize(2, 0);
+  assert_eq!(vec, [1, 2]);
+
+  assert!(vec.try_reserve(9).is_err());
+  vec.try_reserve(8).unwrap();
+  assert_eq!(vec.capacity(), 10);
+
+  let err = vec.try_extend(10..).unwrap_err();
+  assert_eq!(err.element(), 18);
+  assert!(vec.is_full());
+}
+
+#[test]
+fn bounded_splice() {
+  let mut vec = BoundedMiniVec::<String, 4>::new();
+  vec.extend(["a", "b", "c"].iter().map(|s| s.to_string()));
+
+  let err = vec
+    .try_splice(
+      ..1,
+      vec![String::from("x"), String::from("y"), String::from("z")],
+    )
+    .err()
+    .unwrap();
+  assert_eq!(err.element().len(), 3);
+  assert_eq!(vec, ["a", "b", "c"]);
+
+  let removed: Vec<_> = vec
+    .splice(..1, vec![String::from("x"), String::from("y")])
+    .collect();
+  assert_eq!(removed, ["a"]);
+  assert_eq!(vec, ["x", "y", "b", "c"]);
+  assert_eq!(vec.capacity(), 4);
+
+  let removed: Vec<_> = vec.splice(1..3, None).collect();
+  assert_eq!(removed, ["y", "b"]);
+  assert_eq!(vec, ["x", "c"]);
+}
+
+#[test]
+fn bounded_splice_unbounded_replacement() {
+  let mut vec = BoundedMiniVec::<u32, 8>::new();
+  vec.extend_from_slice(&[1, 2, 3, 4]);
+
+  let err = vec.try_splice(1..3, std::iter::repeat(7)).err().unwrap();
+  assert_eq!(err.element(), [7; 7]);
+  assert_eq!(vec, [1, 2, 3, 4]);
+  assert_eq!(vec.capacity(), 4);
+
+  let mut counter = 0..;
+  let removed: Vec<_> = vec
+    .try_splice(.., counter.by_ref().take(8))
+    .unwrap()
+    .collect();
+  assert_eq!(removed, [1, 2, 3, 4]);
+  assert_eq!(vec, [0, 1, 2, 3, 4, 5, 6, 7]);
+  assert_eq!(vec.capacity(), 8);
+  assert_eq!(counter.next(), Some(8));
+}
+
+#[test]
+fn bounded_conversions() {
+  let vec = BoundedMiniVec::<i32, 4>::try_from(mini_vec![1, 2, 3, 4, 5]).unwrap_err();
+  assert_eq!(vec, [1, 2, 3, 4, 5]);
+
+  let mut big = MiniVec::with_capacity(64);
+  big.push(1);
+  let mut vec = BoundedMiniVec::<i32, 4>::try_from(big).unwrap();
+  assert_eq!(vec.capacity(), 4);
+
+  vec.retain(|&x| x != 1);
+  assert!(vec.is_empty());
+
+  vec.extend(vec![3, 1, 2]);
+  vec.sort_unstable();
+  assert_eq!(vec.clone(), vec);
+  assert_eq!(vec.drain(..2).collect::<Vec<_>>(), [1, 2]);
+  assert_eq!(vec.swap_remove(0), 3);
+
+  let vec: MiniVec<_> = vec.into();
+  assert!(vec.is_empty());
+}
@@ -0,0 +1,247 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniBytes, MiniBytesMut, MiniVec};
+
+use std::fmt::Write;
+
+#[test]
+fn bytes_mut_split() {
+  let mut buf = MiniBytesMut::with_capacity(16);
+  buf.extend_from_slice(b"hello, world");
+  let ptr = buf.as_ptr();
+
+  let mut head = buf.split_to(5);
+  assert_eq!(head, b"hello");
+  assert_eq!(buf, b", world");
+  assert_eq!(head.as_ptr(), ptr);
+  assert_eq!(buf.as_ptr(), ptr.wrapping_add(5));
+  assert_eq!(head.capacity(), 5);
+
+  let mut tail = buf.split_off(2);
+  assert_eq!(tail, b"world");
+  assert_eq!(buf, b", ");
+  assert_eq!(tail.as_ptr(), ptr.wrapping_add(7));
+
+  head[0] = b'H';
+  tail[0] = b'W';
+  buf[1] = b'_';
+  assert_eq!(head, b"Hello");
+  assert_eq!(buf, b",_");
+  assert_eq!(tail, b"World");
+
+  assert!(buf.split_to(0).is_empty());
+  assert!(buf.split_off(2).is_empty());
+  assert_eq!(buf.split_to(2), b",_");
+  assert!(buf.is_empty());
+
+  let frozen = tail.freeze();
+  assert_eq!(frozen, b"World");
+  assert_eq!(frozen.as_ptr(), ptr.wrapping_add(7));
+}
+
+#[test]
+fn bytes_mut_split_then_grow() {
+  let mut buf = MiniBytesMut::from(&b"abcdef"[..]);
+  let ptr = buf.as_ptr();
+
+  let mut head = buf.split_to(2);
+  head.extend_from_slice(b"XY");
+  assert_eq!(head, b"abXY");
+  assert_ne!(head.as_ptr(), ptr);
+  assert_eq!(buf, b"cdef");
+
+  // with the other half gone, growing reuses the shared allocation
+  //
+  drop(head);
+  buf.reserve(1);
+  assert_eq!(buf.as_ptr(), ptr);
+  assert!(buf.capacity() >= 6);
+  buf.extend_from_slice(b"g");
+  assert_eq!(buf, b"cdefg");
+
+  let mut tail = buf.split_off(3);
+  tail.resize(1, 0);
+  assert_eq!(tail, b"f");
+  tail.resize(3, b'!');
+  assert_eq!(tail, b"f!!");
+
+  let vec: MiniVec<u8> = buf.into();
+  assert_eq!(vec, *b"cde");
+
+  let mut other = MiniBytesMut::from("xyz");
+  let rest = other.split_off(1);
+  other.clear();
+  assert!(other.is_empty());
+  assert_eq!(rest.clone(), b"yz");
+  assert_eq!(MiniVec::from(rest), *b"yz");
+}
+
+#[test]
+#[should_panic(expected = "`at` split index (is 4) should be <= len (is 3)")]
+fn bytes_mut_split_out_of_bounds() {
+  let mut buf = MiniBytesMut::from("abc");
+  let _ = buf.split_to(4);
+}
+
+#[test]
+fn bytes_mut_edits() {
+  let mut buf: MiniBytesMut = (b'a'..=b'c').collect();
+  buf.extend(b"de");
+  buf.extend([b'f']);
+  write!(buf, "-{}", 42).unwrap();
+  assert_eq!(buf, b"abcdef-42");
+
+  buf[0] = b'A';
+  buf.truncate(3);
+  buf.resize(5, b'.');
+  assert_eq!(buf, b"Abc..");
+  assert_eq!(format!("{:?}", buf), "b\"Abc..\"");
+
+  let vec: MiniVec<u8> = buf.clone().into();
+  assert_eq!(vec, *b"Abc..");
+
+  buf.clear();
+  assert!(buf.is_empty());
+  assert_eq!(buf, MiniBytesMut::default());
+}
+
+#[test]
+fn bytes_freeze_and_slice() {
+  let buf = MiniBytesMut::from(mini_vec![0_u8, 1, 2, 3, 4, 5, 6, 7]);
+  let ptr = buf.as_ptr();
+
+  let bytes = buf.freeze();
+  assert_eq!(bytes.as_ptr(), ptr);
+  assert_eq!(bytes.len(), 8);
+
+  let middle = bytes.slice(2..6);
+  assert_eq!(middle, [2, 3, 4, 5]);
+  assert_eq!(middle.as_ptr(), ptr.wrapping_add(2));
+
+  let inner = middle.slice(1..=1);
+  assert_eq!(inner, [3]);
+  assert_eq!(inner.as_ptr(), ptr.wrapping_add(3));
+
+  drop(bytes);
+  drop(middle);
+  assert_eq!(inner, [3]);
+
+  assert!(MiniBytesMut::new().freeze().is_empty());
+}
+
+#[test]
+fn bytes_split() {
+  let mut bytes = MiniBytes::copy_from_slice(b"key=value;rest");
+  let ptr = bytes.as_ptr();
+
+  let key = bytes.split_to(3);
+  assert_eq!(key, b"key");
+  assert_eq!(bytes.as_ptr(), ptr.wrapping_add(3));
+
+  let rest = bytes.split_off(7);
+  assert_eq!(bytes, b"=value;");
+  assert_eq!(rest, b"rest");
+  assert_eq!(rest.as_ptr(), ptr.wrapping_add(10));
+
+  bytes.truncate(6);
+  assert_eq!(bytes, b"=value");
+  bytes.clear();
+  assert!(bytes.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "end idx 4 is out of bounds for length 3")]
+fn bytes_slice_out_of_bounds() {
+  let bytes = MiniBytes::from_static(b"abc");
+  let _ = bytes.slice(1..4);
+}
+
+#[test]
+fn bytes_static() {
+  static DATA: &[u8] = b"static";
+
+  let bytes = MiniBytes::from_static(DATA);
+  assert_eq!(bytes.as_ptr(), DATA.as_ptr());
+  assert_eq!(bytes.slice(1..3), b"ta");
+  assert_eq!(bytes, MiniBytes::from("static"));
+  assert_eq!(format!("{:?}", MiniBytes::from("a\n")), "b\"a\\n\"");
+
+  let bytes = bytes.try_into_mut().unwrap_err();
+  assert_eq!(bytes, b"static");
+  assert!(MiniBytes::new().is_empty());
+}
+
+#[test]
+fn bytes_try_into_mut() {
+  let mut bytes = MiniBytes::from(mini_vec![1_u8, 2, 3, 4, 5]);
+  let ptr = bytes.as_ptr();
+
+  let head = bytes.split_to(1);
+  let bytes = bytes.try_into_mut().unwrap_err();
+  drop(head);
+
+  let view = bytes.slice(1..3);
+  let view = view.try_into_mut().unwrap_err();
+  drop(bytes);
+
+  let bytes = view.clone();
+  drop(view);
+
+  let buf = bytes.try_into_mut().unwrap();
+  assert_eq!(buf, [3, 4]);
+  assert_eq!(buf.as_ptr(), ptr);
+}
+
+#[test]
+fn bytes_shared_across_threads() {
+  let bytes = MiniBytes::copy_from_slice(&[7; 1024]);
+
+  std::thread::scope(|s| {
+    for idx in 0..4 {
+      let view = bytes.slice(idx * 256..(idx + 1) * 256);
+      s.spawn(move || assert!(view.iter().all(|&b| b == 7)));
+    }
+  });
+
+  assert_eq!(bytes.len(), 1024);
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_buf() {
+  use bytes::{Buf, BufMut};
+
+  let mut buf = MiniBytesMut::new();
+  buf.put_u16(0x0102);
+  buf.put_slice(b"abc");
+  buf.put_bytes(b'z', 100);
+  assert_eq!(buf.len(), 105);
+  assert_eq!(&buf[..5], b"\x01\x02abc");
+
+  let mut bytes = buf.freeze();
+  assert_eq!(bytes.get_u16(), 0x0102);
+  assert_eq!(bytes.remaining(), 103);
+
+  let abc = bytes.copy_to_bytes(3);
+  assert_eq!(abc, b"abc"[..]);
+  assert_eq!(bytes.chunk(), [b'z'; 100]);
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_conversions() {
+  let bytes = MiniBytes::copy_from_slice(b"shared");
+  let ptr = bytes.as_ptr();
+
+  let converted = bytes::Bytes::from(bytes.slice(1..));
+  assert_eq!(converted, b"hared"[..]);
+  assert_eq!(converted.as_ptr(), ptr.wrapping_add(1));
+  drop(bytes);
+  assert_eq!(converted.slice(1..3), b"ar"[..]);
+
+  let converted = bytes::Bytes::from(MiniBytes::from_static(b"static"));
+  assert_eq!(converted, b"static"[..]);
+
+  let back = MiniBytes::from(bytes::Bytes::from_static(b"back"));
+  assert_eq!(back, b"back");
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "bytemuck")]
+
+extern crate minivec;
+
+use bytemuck::checked::CheckedCastError;
+use bytemuck::PodCastError;
+use minivec::cast::{cast_vec, try_cast_vec};
+use minivec::{mini_vec, MiniVec};
+
+#[test]
+fn cast_vec_reuses_allocation() {
+  let mut words = MiniVec::<u32>::with_capacity(6);
+  words.extend([1, 2, 3, 4]);
+  let ptr = words.as_ptr();
+
+  let mut pairs: MiniVec<[u32; 2]> = cast_vec(words).unwrap();
+  assert_eq!(pairs, [[1, 2], [3, 4]]);
+  assert_eq!(pairs.capacity(), 3);
+  assert_eq!(pairs.as_ptr().cast::<u32>(), ptr);
+
+  pairs.push([5, 6]);
+  pairs.push([7, 8]);
+  assert_eq!(pairs.len(), 4);
+
+  let bytes: MiniVec<u8> = cast_vec(pairs).unwrap();
+  assert_eq!(bytes.len(), 32);
+  assert_eq!(&bytes[..4], 1_u32.to_ne_bytes());
+
+  let empty: MiniVec<u64> = cast_vec(MiniVec::<u8>::new()).unwrap();
+  assert!(empty.is_empty());
+  assert_eq!(empty.capacity(), 0);
+}
+
+#[test]
+fn cast_vec_errors() {
+  let bytes = mini_vec![1_u8, 2, 3];
+  let (e, bytes) = cast_vec::<u8, u16>(bytes).unwrap_err();
+  assert_eq!(e, PodCastError::OutputSliceWouldHaveSlop);
+  assert_eq!(bytes, [1, 2, 3]);
+
+  let mut halves = MiniVec::<u16>::with_capacity(3);
+  halves.push(1);
+  halves.push(2);
+  let (e, halves) = cast_vec::<u16, u32>(halves).unwrap_err();
+  assert_eq!(e, PodCastError::OutputSliceWouldHaveSlop);
+  assert_eq!(halves, [1, 2]);
+
+  let bytes = mini_vec![0_u8; 16];
+  let (e, _) = cast_vec::<u8, u128>(bytes).unwrap_err();
+  assert_eq!(e, PodCastError::TargetAlignmentGreaterAndInputNotAligned);
+
+  let (e, _) = cast_vec::<u8, ()>(mini_vec![0_u8]).unwrap_err();
+  assert_eq!(e, PodCastError::SizeMismatch);
+}
+
+#[test]
+fn cast_vec_over_aligned() {
+  let mut bytes = MiniVec::<u8>::with_alignment(48, 16).unwrap();
+  bytes.extend_from_slice(&[0xff; 32]);
+
+  let wide: MiniVec<u128> = cast_vec(bytes).unwrap();
+  assert_eq!(wide, [u128::MAX; 2]);
+  assert_eq!(wide.capacity(), 3);
+
+  let mut empty: MiniVec<u16> = cast_vec(MiniVec::<u8>::with_alignment(0, 64).unwrap()).unwrap();
+  assert_eq!(empty.capacity(), 0);
+  empty.push(1);
+  assert_eq!(empty.as_ptr() as usize % 64, 0);
+}
+
+#[test]
+fn try_cast_vec_validates() {
+  let chars = try_cast_vec::<u32, char>(mini_vec![0x61, 0x62]).unwrap();
+  assert_eq!(chars, ['a', 'b']);
+
+  let (e, words) = try_cast_vec::<u32, char>(mini_vec![0x61, 0xd800]).unwrap_err();
+  assert_eq!(e, CheckedCastError::InvalidBitPattern);
+  assert_eq!(words, [0x61, 0xd800]);
+
+  let (e, _) = try_cast_vec::<u8, char>(mini_vec![0x61]).unwrap_err();
+  assert_eq!(
+    e,
+    CheckedCastError::PodCastError(PodCastError::OutputSliceWouldHaveSlop)
+  );
+}
+
+#[test]
+fn bytes_views() {
+  let mut vec = mini_vec![0_u16; 3];
+  vec.as_bytes_mut().copy_from_slice(&[1, 0, 2, 0, 3, 0]);
+  assert_eq!(vec.as_bytes(), [1, 0, 2, 0, 3, 0]);
+  assert_eq!(vec.iter().copied().map(u16::from_le).sum::<u16>(), 6);
+
+  assert!(MiniVec::<u16>::new().as_bytes().is_empty());
+}
+
+#[test]
+fn from_bytes() {
+  let bytes: Vec<u8> = (0..33).collect();
+
+  let vec = MiniVec::<u64>::from_bytes(&bytes[1..]).unwrap();
+  assert_eq!(vec.len(), 4);
+  assert_eq!(vec.as_ptr() as usize % 8, 0);
+  assert_eq!(vec.as_bytes(), &bytes[1..]);
+
+  assert_eq!(
+    MiniVec::<u64>::from_bytes(&bytes).unwrap_err(),
+    PodCastError::OutputSliceWouldHaveSlop
+  );
+  assert!(MiniVec::<u64>::from_bytes(&[]).unwrap().is_empty());
+}
@@ -0,0 +1,33 @@
+// Fixtures shared by the integration tests. Every test binary compiles this module but only uses some of it.
+//
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Bumps `count` when dropped, so tests can check that every element is dropped exactly once.
+//
+#[derive(Clone)]
+pub struct DropCounter {
+  pub count: Rc<Cell<usize>>,
+}
+
+impl Drop for DropCounter {
+  fn drop(&mut self) {
+    self.count.set(self.count.get() + 1);
+  }
+}
+
+// A `DropCounter` which can be sent to other threads.
+//
+pub struct SyncDropCounter {
+  pub count: Arc<AtomicUsize>,
+}
+
+impl Drop for SyncDropCounter {
+  fn drop(&mut self) {
+    self.count.fetch_add(1, Ordering::Relaxed);
+  }
+}
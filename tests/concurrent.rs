@@ -0,0 +1,209 @@
+extern crate minivec;
+
+mod common;
+
+// The model tests run with: RUSTFLAGS="--cfg loom" cargo test --test concurrent --release
+
+#[cfg(not(loom))]
+mod threads {
+  use minivec::ConcurrentMiniVec;
+
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  use crate::common::SyncDropCounter;
+
+  #[test]
+  fn concurrent_push_single_thread() {
+    let vec = ConcurrentMiniVec::new();
+    assert!(vec.is_empty());
+    assert_eq!(vec.get(0), None);
+    assert_eq!(vec.get(usize::MAX), None);
+
+    for i in 0..1000 {
+      assert_eq!(vec.push(i), i);
+    }
+
+    assert_eq!(vec.len(), 1000);
+    assert_eq!(vec.get(999), Some(&999));
+    assert_eq!(vec.get(1000), None);
+    assert_eq!(vec.get(usize::MAX), None);
+    assert!(vec.iter().copied().eq(0..1000));
+
+    assert!(vec.into_inner().into_iter().eq(0..1000));
+  }
+
+  #[test]
+  fn concurrent_push_many_threads() {
+    let vec = ConcurrentMiniVec::new();
+    let num_threads = 8;
+    let per_thread = 2000;
+
+    std::thread::scope(|s| {
+      for t in 0..num_threads {
+        let vec = &vec;
+        s.spawn(move || {
+          for i in 0..per_thread {
+            let value = t * per_thread + i;
+            let idx = vec.push(value);
+            assert_eq!(vec.get(idx), Some(&value));
+          }
+        });
+      }
+
+      s.spawn(|| {
+        // readers only ever see fully written elements
+        //
+        for _ in 0..100 {
+          let count = vec
+            .iter()
+            .filter(|&&x| x < num_threads * per_thread)
+            .count();
+          assert!(count <= vec.len());
+        }
+      });
+    });
+
+    assert_eq!(vec.len(), num_threads * per_thread);
+
+    let mut values = vec.into_inner();
+    values.sort_unstable();
+    assert!(values.into_iter().eq(0..num_threads * per_thread));
+  }
+
+  #[test]
+  fn concurrent_references_stay_valid() {
+    let vec = ConcurrentMiniVec::new();
+    vec.push(String::from("first"));
+
+    let first = vec.get(0).unwrap();
+    let ptr = first.as_ptr();
+
+    for i in 0..10_000 {
+      vec.push(i.to_string());
+    }
+
+    assert_eq!(first, "first");
+    assert_eq!(vec.get(0).unwrap().as_ptr(), ptr);
+  }
+
+  #[test]
+  fn concurrent_drops_elements() {
+    let count = Arc::new(AtomicUsize::new(0));
+
+    {
+      let vec: ConcurrentMiniVec<_> = (0..100)
+        .map(|_| SyncDropCounter {
+          count: count.clone(),
+        })
+        .collect();
+
+      assert_eq!(vec.len(), 100);
+    }
+
+    assert_eq!(count.load(Ordering::Relaxed), 100);
+
+    let vec: ConcurrentMiniVec<_> = (0..50)
+      .map(|_| SyncDropCounter {
+        count: count.clone(),
+      })
+      .collect();
+
+    let inner = vec.into_inner();
+    assert_eq!(count.load(Ordering::Relaxed), 100);
+
+    drop(inner);
+    assert_eq!(count.load(Ordering::Relaxed), 150);
+  }
+
+  #[test]
+  fn concurrent_into_inner_moves_elements() {
+    let vec = ConcurrentMiniVec::new();
+    for i in 0..5 {
+      vec.push(i.to_string());
+    }
+
+    let ptr: *const String = vec.get(0).unwrap();
+    let inner = vec.into_inner();
+    assert_eq!(inner.as_ptr(), ptr);
+    assert_eq!(inner, ["0", "1", "2", "3", "4"]);
+    assert_eq!(inner.capacity(), 8);
+
+    let vec: ConcurrentMiniVec<_> = (0..1000).map(|i: u64| i.to_string()).collect();
+    let ptr = vec.get(999).unwrap().as_ptr();
+
+    let inner = vec.into_inner();
+    assert_eq!(inner.len(), 1000);
+    assert_eq!(inner.capacity(), 1000);
+    assert_eq!(inner[999].as_ptr(), ptr);
+    assert!(inner.iter().map(|s| s.parse::<u64>().unwrap()).eq(0..1000));
+
+    assert!(ConcurrentMiniVec::<String>::new().into_inner().is_empty());
+  }
+
+  #[test]
+  fn concurrent_debug() {
+    let vec: ConcurrentMiniVec<_> = (1..=3).collect();
+    assert_eq!(format!("{:?}", vec), "[1, 2, 3]");
+  }
+}
+
+#[cfg(loom)]
+mod model {
+  use minivec::ConcurrentMiniVec;
+
+  use loom::sync::Arc;
+  use loom::thread;
+
+  #[test]
+  fn loom_concurrent_push() {
+    loom::model(|| {
+      let vec = Arc::new(ConcurrentMiniVec::new());
+
+      let handles: Vec<_> = (0..2)
+        .map(|t| {
+          let vec = vec.clone();
+          thread::spawn(move || {
+            let a = vec.push(t * 10);
+            let b = vec.push(t * 10 + 1);
+
+            assert_eq!(vec.get(a), Some(&(t * 10)));
+            assert_eq!(vec.get(b), Some(&(t * 10 + 1)));
+          })
+        })
+        .collect();
+
+      for handle in handles {
+        handle.join().unwrap();
+      }
+
+      let vec = Arc::try_unwrap(vec).ok().unwrap();
+      let mut values = vec.into_inner();
+      values.sort_unstable();
+
+      assert_eq!(values, [0, 1, 10, 11]);
+    });
+  }
+
+  #[test]
+  fn loom_concurrent_read_while_pushing() {
+    loom::model(|| {
+      let vec = Arc::new(ConcurrentMiniVec::new());
+
+      let writer = {
+        let vec = vec.clone();
+        thread::spawn(move || {
+          vec.push(String::from("a"));
+          vec.push(String::from("b"));
+          vec.push(String::from("c"));
+        })
+      };
+
+      let seen: Vec<String> = vec.iter().cloned().collect();
+      assert!(["a", "b", "c"].starts_with(&seen.iter().map(String::as_str).collect::<Vec<_>>()));
+
+      writer.join().unwrap();
+      assert_eq!(vec.iter().count(), 3);
+    });
+  }
+}
@@ -0,0 +1,150 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniCow, MiniVec};
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn cow_borrowed() {
+  let data = [1, 2, 3];
+  let cow = MiniCow::borrowed(&data[..]);
+
+  assert!(cow.is_borrowed());
+  assert!(!cow.is_owned());
+  assert_eq!(cow.as_ptr(), data.as_ptr());
+  assert_eq!(cow.len(), 3);
+  assert_eq!(cow, [1, 2, 3]);
+  assert_eq!(format!("{:?}", cow), "[1, 2, 3]");
+
+  let empty = MiniCow::<i32>::borrowed(&[]);
+  assert!(empty.is_owned());
+  assert!(empty.is_empty());
+  assert_eq!(empty, MiniCow::default());
+}
+
+#[test]
+fn cow_owned() {
+  let vec = mini_vec![1, 2, 3];
+  let ptr = vec.as_ptr();
+
+  let cow = MiniCow::owned(vec);
+  assert!(cow.is_owned());
+  assert_eq!(cow.as_ptr(), ptr);
+
+  let vec = cow.into_owned();
+  assert_eq!(vec.as_ptr(), ptr);
+  assert_eq!(vec, [1, 2, 3]);
+}
+
+#[test]
+fn cow_to_mut() {
+  let data = mini_vec![String::from("a"), String::from("b")];
+  let mut cow = MiniCow::from(&data);
+  assert!(cow.is_borrowed());
+
+  cow.to_mut().push(String::from("c"));
+  assert!(cow.is_owned());
+  assert_eq!(cow, ["a", "b", "c"]);
+  assert_eq!(data, ["a", "b"]);
+
+  let ptr = cow.as_ptr();
+  cow.to_mut()[0].push('!');
+  assert_eq!(cow.as_ptr(), ptr);
+  assert_eq!(cow[0], "a!");
+}
+
+#[test]
+fn cow_clone() {
+  let data = [4, 5, 6];
+  let borrowed = MiniCow::borrowed(&data[..]);
+  let clone = borrowed.clone();
+  assert!(clone.is_borrowed());
+  assert_eq!(clone.as_ptr(), data.as_ptr());
+
+  let owned: MiniCow<'_, i32> = MiniCow::owned(mini_vec![4, 5, 6]);
+  let clone = owned.clone();
+  assert!(clone.is_owned());
+  assert_ne!(clone.as_ptr(), owned.as_ptr());
+  assert_eq!(clone, borrowed);
+}
+
+#[test]
+fn cow_conversions() {
+  let data = vec![1_u8, 2];
+
+  let cow = MiniCow::from(Cow::Borrowed(&data[..]));
+  assert!(cow.is_borrowed());
+
+  let cow: MiniCow<'_, u8> = MiniCow::from(Cow::Owned(data.clone()));
+  assert!(cow.is_owned());
+
+  let vec: MiniVec<u8> = MiniCow::from(&data[..]).into();
+  assert_eq!(vec, [1, 2]);
+
+  let mut set = HashSet::new();
+  set.insert(MiniCow::from(mini_vec![1_u8, 2]));
+  assert!(set.contains(&[1_u8, 2][..]));
+  assert!(set.contains(&MiniCow::borrowed(&data[..])));
+
+  assert!(MiniCow::borrowed(&[1, 2][..]) < MiniCow::borrowed(&[1, 3][..]));
+  assert_eq!((&MiniCow::borrowed(&data[..])).into_iter().count(), 2);
+}
+
+#[test]
+fn cow_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let data: Vec<DropCounter> = (0..3)
+    .map(|_| DropCounter {
+      count: count.clone(),
+    })
+    .collect();
+
+  drop(MiniCow::borrowed(&data[..]));
+  assert_eq!(count.get(), 0);
+
+  let mut cow = MiniCow::borrowed(&data[..]);
+  cow.to_mut().pop();
+  assert_eq!(count.get(), 1);
+
+  drop(cow);
+  assert_eq!(count.get(), 3);
+
+  drop(data);
+  assert_eq!(count.get(), 6);
+}
+
+#[test]
+fn cow_to_mut_panicking_clone() {
+  struct PanicOnClone(i32);
+
+  impl Clone for PanicOnClone {
+    fn clone(&self) -> Self {
+      if self.0 == 2 {
+        panic!("clone failed");
+      }
+      PanicOnClone(self.0)
+    }
+  }
+
+  let data = [PanicOnClone(1), PanicOnClone(2)];
+  let mut cow = MiniCow::borrowed(&data[..]);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    cow.to_mut();
+  }));
+  assert!(result.is_err());
+
+  // the cow still borrows `data` and is dropped exactly once
+  //
+  assert!(cow.is_borrowed());
+  assert_eq!(cow.len(), 2);
+  assert_eq!(cow[1].0, 2);
+  drop(cow);
+}
@@ -0,0 +1,246 @@
+extern crate minivec;
+
+use minivec::{mini_dyn_push, MiniDynVec};
+
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+trait Handler {
+  fn handle(&mut self, input: u32) -> u32;
+}
+
+struct Add(u32);
+
+struct Mul(u64);
+
+struct Counter {
+  calls: u8,
+}
+
+impl Handler for Add {
+  fn handle(&mut self, input: u32) -> u32 {
+    input + self.0
+  }
+}
+
+impl Handler for Mul {
+  fn handle(&mut self, input: u32) -> u32 {
+    input * self.0 as u32
+  }
+}
+
+impl Handler for Counter {
+  fn handle(&mut self, input: u32) -> u32 {
+    self.calls += 1;
+    input
+  }
+}
+
+impl Handler for DropCounter {
+  fn handle(&mut self, input: u32) -> u32 {
+    input
+  }
+}
+
+#[allow(dead_code)]
+#[repr(align(64))]
+#[derive(Debug)]
+struct Aligned(u8);
+
+#[test]
+fn dyn_vec_new_does_not_allocate() {
+  let vec = MiniDynVec::<dyn Handler>::new();
+  assert!(vec.is_empty());
+  assert_eq!(vec.len(), 0);
+  assert_eq!(vec.byte_capacity(), 0);
+  assert!(vec.last().is_none());
+  assert_eq!(vec.iter().count(), 0);
+}
+
+#[test]
+fn dyn_vec_push_and_iter() {
+  let mut handlers = MiniDynVec::<dyn Handler>::new();
+  mini_dyn_push!(handlers, Add(3));
+  mini_dyn_push!(handlers, Counter { calls: 0 });
+  mini_dyn_push!(handlers, Mul(4));
+  mini_dyn_push!(handlers, Counter { calls: 0 });
+
+  assert_eq!(handlers.len(), 4);
+
+  let mut value = 1;
+  for handler in handlers.iter_mut() {
+    value = handler.handle(value);
+  }
+  assert_eq!(value, 16);
+
+  let value = (&mut handlers)
+    .into_iter()
+    .fold(0, |value, handler| handler.handle(value));
+  assert_eq!(value, 12);
+
+  assert_eq!(handlers.iter().len(), 4);
+  assert_eq!(handlers.last_mut().unwrap().handle(5), 5);
+}
+
+#[test]
+fn dyn_vec_debug() {
+  let mut values = MiniDynVec::<dyn Debug>::new();
+  mini_dyn_push!(values, 1_u8);
+  mini_dyn_push!(values, "two");
+  mini_dyn_push!(values, [3_u64, 4]);
+  mini_dyn_push!(values, ());
+
+  assert_eq!(format!("{:?}", values), r#"[1, "two", [3, 4], ()]"#);
+}
+
+#[test]
+fn dyn_vec_slices() {
+  let mut slices = MiniDynVec::<[u16]>::new();
+  mini_dyn_push!(slices, [1_u16, 2, 3]);
+  mini_dyn_push!(slices, [0_u16; 0]);
+  mini_dyn_push!(slices, [4_u16]);
+
+  let lens: Vec<usize> = slices.iter().map(<[u16]>::len).collect();
+  assert_eq!(lens, [3, 0, 1]);
+
+  for slice in &mut slices {
+    slice.reverse();
+  }
+  assert_eq!(slices.iter().next().unwrap(), [3, 2, 1]);
+}
+
+#[test]
+fn dyn_vec_over_aligned() {
+  let mut values = MiniDynVec::<dyn Debug>::new();
+  mini_dyn_push!(values, 1_u8);
+  mini_dyn_push!(values, String::from("before"));
+  mini_dyn_push!(values, Aligned(7));
+  mini_dyn_push!(values, 2_u16);
+
+  for value in values.iter() {
+    let addr = value as *const dyn Debug as *const u8 as usize;
+    assert_eq!(addr % std::mem::align_of_val(value), 0);
+  }
+
+  assert_eq!(format!("{:?}", values), r#"[1, "before", Aligned(7), 2]"#);
+}
+
+#[test]
+fn dyn_vec_pop() {
+  let mut values = MiniDynVec::<dyn Debug>::new();
+  assert!(!values.pop());
+
+  mini_dyn_push!(values, 1);
+  mini_dyn_push!(values, String::from("two"));
+  mini_dyn_push!(values, 3.0);
+
+  assert!(values.pop());
+  assert_eq!(format!("{:?}", values.last()), r#"Some("two")"#);
+
+  mini_dyn_push!(values, 'c');
+  assert_eq!(format!("{:?}", values), r#"[1, "two", 'c']"#);
+
+  assert!(values.pop());
+  assert!(values.pop());
+  assert!(values.pop());
+  assert!(!values.pop());
+  assert!(values.is_empty());
+  assert!(values.byte_capacity() > 0);
+}
+
+#[test]
+fn dyn_vec_retain() {
+  let mut values = MiniDynVec::<dyn Debug>::new();
+  for i in 0..20_u32 {
+    if i % 3 == 0 {
+      mini_dyn_push!(values, Aligned(i as u8));
+    } else if i % 3 == 1 {
+      mini_dyn_push!(values, String::from("x").repeat(i as usize));
+    } else {
+      mini_dyn_push!(values, i);
+    }
+  }
+
+  values.retain(|value| format!("{:?}", value).len() % 2 == 0);
+
+  let expected: Vec<String> = (0..20_u32)
+    .map(|i| {
+      if i % 3 == 0 {
+        format!("{:?}", Aligned(i as u8))
+      } else if i % 3 == 1 {
+        format!("{:?}", "x".repeat(i as usize))
+      } else {
+        format!("{:?}", i)
+      }
+    })
+    .filter(|repr| repr.len() % 2 == 0)
+    .collect();
+
+  let actual: Vec<String> = values.iter().map(|value| format!("{:?}", value)).collect();
+  assert_eq!(actual, expected);
+
+  for value in values.iter() {
+    let addr = value as *const dyn Debug as *const u8 as usize;
+    assert_eq!(addr % std::mem::align_of_val(value), 0);
+  }
+
+  values.retain(|_| false);
+  assert!(values.is_empty());
+  assert!(values.last().is_none());
+}
+
+#[test]
+fn dyn_vec_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let mut handlers = MiniDynVec::<dyn Handler>::new();
+  for _ in 0..6 {
+    mini_dyn_push!(
+      handlers,
+      DropCounter {
+        count: count.clone(),
+      }
+    );
+    mini_dyn_push!(handlers, Add(1));
+  }
+
+  assert!(handlers.pop());
+  assert!(handlers.pop());
+  assert_eq!(count.get(), 1);
+
+  let mut keep = false;
+  handlers.retain(|_| {
+    keep = !keep;
+    keep
+  });
+  assert_eq!(count.get(), 1);
+  assert_eq!(handlers.len(), 5);
+
+  handlers.clear();
+  assert_eq!(count.get(), 6);
+  assert!(handlers.is_empty());
+
+  mini_dyn_push!(
+    handlers,
+    DropCounter {
+      count: count.clone(),
+    }
+  );
+  drop(handlers);
+  assert_eq!(count.get(), 7);
+}
+
+#[test]
+fn dyn_vec_with_byte_capacity() {
+  let mut values = MiniDynVec::<dyn Debug>::with_byte_capacity(256);
+  let capacity = values.byte_capacity();
+  assert!(capacity >= 256);
+
+  mini_dyn_push!(values, 1_u64);
+  mini_dyn_push!(values, 2_u64);
+  assert_eq!(values.byte_capacity(), capacity);
+}
@@ -0,0 +1,234 @@
+extern crate minivec;
+
+use minivec::{mini_vec, ErasedMiniVec, LayoutErr, MiniVec};
+
+use std::alloc::Layout;
+use std::any::TypeId;
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+unsafe fn push<T>(vec: &mut ErasedMiniVec, value: T) {
+  let value = std::mem::ManuallyDrop::new(value);
+  vec.push_raw((&*value as *const T).cast());
+}
+
+#[test]
+fn erased_new() {
+  let vec = ErasedMiniVec::new::<u64>();
+  assert!(vec.is_empty());
+  assert_eq!(vec.len(), 0);
+  assert_eq!(vec.layout(), Layout::new::<u64>());
+  assert_eq!(vec.element_type_id(), Some(TypeId::of::<u64>()));
+  assert!(vec.is::<u64>());
+  assert!(!vec.is::<i64>());
+  assert_eq!(vec.downcast_ref::<u64>(), Some(&[][..]));
+  assert!(vec.get_raw(0).is_none());
+  assert_eq!(
+    format!("{:?}", vec).split('{').next(),
+    Some("ErasedMiniVec ")
+  );
+}
+
+#[test]
+fn erased_push_and_get() {
+  let mut vec = ErasedMiniVec::new::<String>();
+  for word in ["zero", "one", "two"] {
+    unsafe { push(&mut vec, String::from(word)) };
+  }
+
+  assert_eq!(vec.len(), 3);
+  assert!(vec.capacity() >= 3);
+
+  let one = unsafe { &*vec.get_raw(1).unwrap().cast::<String>() };
+  assert_eq!(one, "one");
+
+  unsafe { (*vec.get_raw_mut(2).unwrap().cast::<String>()).push('!') };
+  assert_eq!(
+    vec.downcast_ref::<String>().unwrap(),
+    ["zero", "one", "two!"]
+  );
+
+  vec.downcast_mut::<String>().unwrap().reverse();
+  assert_eq!(
+    vec.downcast_ref::<String>().unwrap(),
+    ["two!", "one", "zero"]
+  );
+  assert!(vec.downcast_mut::<&str>().is_none());
+}
+
+#[test]
+fn erased_swap_remove() {
+  let mut vec = ErasedMiniVec::from(mini_vec![
+    String::from("a"),
+    String::from("b"),
+    String::from("c"),
+    String::from("d")
+  ]);
+
+  let mut out = std::mem::MaybeUninit::<String>::uninit();
+  unsafe { vec.swap_remove_raw(0, out.as_mut_ptr().cast()) };
+  assert_eq!(unsafe { out.assume_init() }, "a");
+  assert_eq!(vec.downcast_ref::<String>().unwrap(), ["d", "b", "c"]);
+
+  vec.swap_remove_and_drop(1);
+  assert_eq!(vec.downcast_ref::<String>().unwrap(), ["d", "c"]);
+
+  vec.swap_remove_and_drop(1);
+  assert_eq!(vec.downcast_ref::<String>().unwrap(), ["d"]);
+}
+
+#[test]
+#[should_panic(expected = "swap_remove index (is 2) should be < len (is 2)")]
+fn erased_swap_remove_out_of_bounds() {
+  let mut vec = ErasedMiniVec::from(mini_vec![1, 2]);
+  vec.swap_remove_and_drop(2);
+}
+
+#[test]
+fn erased_round_trip() {
+  let vec = mini_vec![1_u32, 2, 3];
+  let erased = ErasedMiniVec::from(vec);
+
+  let erased = erased.into_typed::<i32>().unwrap_err();
+  let vec: MiniVec<u32> = erased.into_typed().unwrap();
+  assert_eq!(vec, [1, 2, 3]);
+
+  let empty: MiniVec<u8> = ErasedMiniVec::new::<u8>().into_typed().unwrap();
+  assert!(empty.is_empty());
+
+  let aligned = MiniVec::<f32>::with_alignment(4, 128).unwrap();
+  let erased = ErasedMiniVec::from(aligned);
+  assert_eq!(erased.alignment(), 128);
+  assert_eq!(
+    erased.into_typed::<f32>().unwrap().as_ptr() as usize % 128,
+    0
+  );
+}
+
+#[test]
+fn erased_from_layout() {
+  let layout = Layout::from_size_align(6, 2).unwrap();
+  let mut vec = unsafe { ErasedMiniVec::from_layout(layout, None, None) };
+
+  unsafe {
+    vec.push_raw([1_u8, 2, 3, 4, 5, 6].as_ptr());
+    vec.push_raw([7_u8, 8, 9, 10, 11, 12].as_ptr());
+  }
+
+  assert_eq!(vec.len(), 2);
+  assert!(vec.element_type_id().is_none());
+  assert!(vec.downcast_ref::<[u8; 6]>().is_none());
+  assert_eq!(unsafe { vec.as_bytes() }, (1..=12).collect::<Vec<u8>>());
+  assert!(vec.try_clone().is_none());
+
+  let mut out = [0_u8; 6];
+  unsafe { vec.swap_remove_raw(0, out.as_mut_ptr()) };
+  assert_eq!(out, [1, 2, 3, 4, 5, 6]);
+  assert_eq!(unsafe { vec.as_bytes() }, [7, 8, 9, 10, 11, 12]);
+}
+
+#[test]
+fn erased_zero_sized() {
+  let mut vec = ErasedMiniVec::new::<()>();
+  for _ in 0..10 {
+    unsafe { push(&mut vec, ()) };
+  }
+
+  assert_eq!(vec.len(), 10);
+  assert_eq!(vec.capacity(), usize::MAX);
+  assert_eq!(vec.downcast_ref::<()>().unwrap().len(), 10);
+
+  vec.swap_remove_and_drop(3);
+  assert_eq!(vec.len(), 9);
+
+  let vec = vec.into_typed::<()>().unwrap_err();
+  assert!(vec.is::<()>());
+  assert_eq!(vec.len(), 9);
+}
+
+#[test]
+fn erased_alignment() {
+  let mut vec = ErasedMiniVec::new::<f32>();
+  for idx in 0..5 {
+    unsafe { push(&mut vec, idx as f32) };
+  }
+
+  let mut vec = vec.with_alignment(64).unwrap();
+  assert_eq!(vec.alignment(), 64);
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+  assert_eq!(
+    vec.downcast_ref::<f32>().unwrap(),
+    [0.0, 1.0, 2.0, 3.0, 4.0]
+  );
+
+  vec.reserve(100);
+  assert!(vec.capacity() >= 105);
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+
+  assert!(matches!(
+    ErasedMiniVec::new::<u8>().with_alignment(4),
+    Err((LayoutErr::AlignmentTooSmall, _))
+  ));
+
+  let Err((err, vec)) = vec.with_alignment(48) else {
+    panic!("48 is not a power of two");
+  };
+  assert!(matches!(err, LayoutErr::AlignmentNotDivisibleByTwo));
+  assert_eq!(vec.alignment(), 64);
+  assert_eq!(vec.len(), 5);
+  assert_eq!(vec.downcast_ref::<f32>().unwrap()[4], 4.0);
+}
+
+#[test]
+fn erased_try_clone() {
+  let mut vec = ErasedMiniVec::new_cloneable::<String>();
+  unsafe {
+    push(&mut vec, String::from("x"));
+    push(&mut vec, String::from("y"));
+  }
+
+  let clone = vec.try_clone().unwrap();
+  assert_eq!(clone.downcast_ref::<String>().unwrap(), ["x", "y"]);
+  assert_eq!(clone.alignment(), vec.alignment());
+
+  assert!(ErasedMiniVec::from(mini_vec![1]).try_clone().is_none());
+}
+
+#[test]
+fn erased_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  let mut vec = ErasedMiniVec::new_cloneable::<DropCounter>();
+  for _ in 0..5 {
+    unsafe { push(&mut vec, make()) };
+  }
+
+  vec.swap_remove_and_drop(0);
+  assert_eq!(count.get(), 1);
+
+  let clone = vec.try_clone().unwrap();
+  drop(clone);
+  assert_eq!(count.get(), 5);
+
+  let vec = vec.with_alignment(128).unwrap();
+  assert_eq!(count.get(), 5);
+
+  let typed: MiniVec<DropCounter> = vec.into_typed().unwrap();
+  assert_eq!(count.get(), 5);
+
+  let mut vec = ErasedMiniVec::from(typed);
+  vec.clear();
+  assert_eq!(count.get(), 9);
+  assert!(vec.is_empty());
+
+  unsafe { push(&mut vec, make()) };
+  drop(vec);
+  assert_eq!(count.get(), 10);
+}
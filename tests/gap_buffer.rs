@@ -0,0 +1,273 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniGapBuffer, MiniGapString, MiniVec};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn gap_buffer_new_does_not_allocate() {
+  let mut buf = MiniGapBuffer::<u32>::new();
+  assert!(buf.is_empty());
+  assert_eq!(buf.capacity(), 0);
+  assert_eq!(buf.cursor(), 0);
+  assert_eq!(buf.as_slices(), (&[][..], &[][..]));
+  assert_eq!(buf.delete_before(), None);
+  assert_eq!(buf.delete_after(), None);
+
+  buf.move_cursor(0);
+  assert_eq!(buf.capacity(), 0);
+}
+
+#[test]
+fn gap_buffer_edits_at_cursor() {
+  let mut buf = MiniGapBuffer::new();
+  for i in 0..100 {
+    buf.insert_at_cursor(i);
+  }
+
+  buf.move_cursor(50);
+  assert_eq!(buf.cursor(), 50);
+  for i in 0..10 {
+    buf.insert_at_cursor(1000 + i);
+  }
+
+  assert_eq!(buf.len(), 110);
+  assert_eq!(buf[49], 49);
+  assert_eq!(buf[50], 1000);
+  assert_eq!(buf[60], 50);
+  assert_eq!(buf.get(110), None);
+
+  assert_eq!(buf.delete_before(), Some(1009));
+  assert_eq!(buf.delete_after(), Some(50));
+  assert_eq!(buf.as_slices().0.len(), 59);
+  assert_eq!(buf.as_slices().1[0], 51);
+
+  buf.move_cursor(0);
+  assert_eq!(buf.delete_before(), None);
+  buf.move_cursor(buf.len());
+  assert_eq!(buf.delete_after(), None);
+
+  let expected: Vec<i32> = (0..50).chain(1000..1009).chain(51..100).collect();
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn gap_buffer_growth_keeps_elements_after_cursor() {
+  let mut buf: MiniGapBuffer<String> = (0..4).map(|i| i.to_string()).collect();
+  buf.move_cursor(1);
+
+  for i in 0..100 {
+    buf.insert_at_cursor(format!("x{}", i));
+  }
+
+  assert_eq!(buf.len(), 104);
+  assert_eq!(buf[0], "0");
+  assert_eq!(buf[101], "1");
+  assert_eq!(buf[103], "3");
+  assert_eq!(buf.as_slices().1, ["1", "2", "3"]);
+}
+
+#[test]
+#[should_panic(expected = "cursor position (is 4) should be <= len (is 3)")]
+fn gap_buffer_cursor_out_of_bounds() {
+  let mut buf: MiniGapBuffer<_> = (0..3).collect();
+  buf.move_cursor(4);
+}
+
+#[test]
+fn gap_buffer_make_contiguous() {
+  let mut buf: MiniGapBuffer<_> = (0..6).collect();
+  buf.move_cursor(2);
+
+  let slice = buf.make_contiguous();
+  slice.reverse();
+  assert_eq!(buf.cursor(), 6);
+  assert_eq!(buf.as_slices(), (&[5, 4, 3, 2, 1, 0][..], &[][..]));
+
+  for value in &mut buf {
+    *value += 1;
+  }
+  buf.move_cursor(3);
+  let (front, back) = buf.as_mut_slices();
+  front[0] = 0;
+  back[2] = 0;
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), [0, 5, 4, 3, 2, 0]);
+}
+
+#[test]
+fn gap_buffer_splice() {
+  let mut buf: MiniGapBuffer<_> = (0..10).collect();
+
+  assert_eq!(buf.splice(2..5, vec![20, 30]), [2, 3, 4]);
+  assert_eq!(buf.cursor(), 4);
+  assert_eq!(
+    buf.iter().copied().collect::<Vec<_>>(),
+    [0, 1, 20, 30, 5, 6, 7, 8, 9]
+  );
+
+  assert!(buf.splice(.., None).len() == 9);
+  assert!(buf.is_empty());
+}
+
+#[test]
+fn gap_buffer_vec_conversions() {
+  let buf = MiniGapBuffer::from(mini_vec![1, 2, 3]);
+  assert_eq!(buf.cursor(), 3);
+  assert!(buf.capacity() >= 3);
+
+  let mut buf = buf;
+  buf.move_cursor(1);
+  buf.insert_at_cursor(10);
+
+  let vec: MiniVec<_> = buf.into();
+  assert_eq!(vec, [1, 10, 2, 3]);
+
+  assert!(MiniGapBuffer::<u8>::new().into_vec().is_empty());
+}
+
+#[test]
+fn gap_buffer_vec_conversions_reuse_allocation() {
+  let mut vec = MiniVec::<u64>::with_capacity(8);
+  vec.extend_from_slice(&[1, 2, 3]);
+  let ptr = vec.as_ptr();
+
+  let mut buf = MiniGapBuffer::from(vec);
+  assert_eq!(buf.cursor(), 3);
+  assert_eq!(buf.capacity(), 7);
+
+  buf.move_cursor(1);
+  buf.insert_at_cursor(10);
+  assert_eq!(buf.as_slices(), (&[1, 10][..], &[2, 3][..]));
+  assert_eq!(buf.as_slices().0.as_ptr(), ptr);
+
+  let vec = buf.into_vec();
+  assert_eq!(vec, [1, 10, 2, 3]);
+  assert_eq!(vec.as_ptr(), ptr);
+  assert_eq!(vec.capacity(), 8);
+
+  let mut aligned = MiniVec::<u8>::with_alignment(4, 64).unwrap();
+  aligned.extend_from_slice(b"abcd");
+  let mut buf = MiniGapBuffer::from(aligned);
+  buf.move_cursor(2);
+  buf.insert_at_cursor(b'-');
+  let vec = buf.into_vec();
+  assert_eq!(vec, *b"ab-cd");
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+}
+
+#[test]
+fn gap_buffer_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  let mut buf: MiniGapBuffer<_> = (0..6).map(|_| make()).collect();
+  buf.move_cursor(3);
+
+  drop(buf.delete_after());
+  drop(buf.delete_before());
+  assert_eq!(count.get(), 2);
+
+  drop(buf.splice(0..1, None));
+  assert_eq!(count.get(), 3);
+
+  buf.clear();
+  assert_eq!(count.get(), 6);
+
+  buf.extend((0..4).map(|_| make()));
+  buf.move_cursor(1);
+  drop(buf);
+  assert_eq!(count.get(), 10);
+}
+
+#[test]
+fn gap_buffer_clone_debug_and_eq() {
+  let mut buf: MiniGapBuffer<_> = (0..4).collect();
+  buf.move_cursor(1);
+
+  let clone = buf.clone();
+  assert_eq!(clone.cursor(), 1);
+  assert_eq!(clone, buf);
+  assert_eq!(format!("{:?}", clone), "[0, 1, 2, 3]");
+
+  let mut other: MiniGapBuffer<_> = (0..4).collect();
+  assert_eq!(other, buf);
+  other[3] = 7;
+  assert_ne!(other, buf);
+}
+
+#[test]
+fn gap_string_edits() {
+  let mut text = MiniGapString::new();
+  text.insert_str("héllo wörld");
+  assert_eq!(text, "héllo wörld");
+  assert_eq!(text.len(), "héllo wörld".len());
+
+  text.move_cursor(7);
+  text.insert('🦀');
+  text.insert(' ');
+  assert_eq!(text.as_strs(), ("héllo 🦀 ", "wörld"));
+
+  assert_eq!(text.delete_before(), Some(' '));
+  assert_eq!(text.delete_before(), Some('🦀'));
+  assert_eq!(text.delete_after(), Some('w'));
+  assert_eq!(text.delete_after(), Some('ö'));
+  assert_eq!(text.to_string(), "héllo rld");
+
+  text.move_cursor(0);
+  assert_eq!(text.delete_before(), None);
+  assert_eq!(text.chars().rev().collect::<String>(), "dlr olléh");
+
+  assert_eq!(text.make_contiguous(), "héllo rld");
+  assert_eq!(text.cursor(), text.len());
+  assert_eq!(text.delete_after(), None);
+}
+
+#[test]
+#[should_panic(expected = "cursor position 2 is not a char boundary")]
+fn gap_string_cursor_inside_char() {
+  let mut text = MiniGapString::from("héllo");
+  text.move_cursor(2);
+}
+
+#[test]
+fn gap_string_splice() {
+  let mut text = MiniGapString::from("hello world");
+  text.splice(6.., "wörld!");
+  assert_eq!(text, "hello wörld!");
+
+  text.splice(..5, "goodbye");
+  assert_eq!(text.as_strs(), ("goodbye", " wörld!"));
+}
+
+#[test]
+#[should_panic(expected = "does not lie on char boundaries")]
+fn gap_string_splice_inside_char() {
+  let mut text = MiniGapString::from("wörld");
+  text.splice(0..2, "");
+}
+
+#[test]
+fn gap_string_clone_debug_and_eq() {
+  let mut text = MiniGapString::from("a\"b");
+  text.move_cursor(1);
+
+  assert_eq!(format!("{:?}", text), r#""a\"b""#);
+  assert_eq!(format!("{}", text), "a\"b");
+  assert_eq!(text.clone(), text);
+  assert_ne!(text, "a\"c");
+
+  let mut other = MiniGapString::default();
+  other.extend(vec!["a", "\"b"]);
+  assert_eq!(other, text);
+
+  other.clear();
+  other.extend("xyz".chars());
+  assert_eq!(other, "xyz");
+}
@@ -0,0 +1,325 @@
+extern crate minivec;
+
+use minivec::hash_map::Entry;
+use minivec::{MiniHashMap, MiniHashSet};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn hash_map_default_constructed() {
+  let map = MiniHashMap::<i32, i32>::new();
+  assert_eq!(map.len(), 0);
+  assert_eq!(map.capacity(), 0);
+  assert!(map.is_empty());
+  assert_eq!(map.get(&1), None);
+  assert_eq!(map.iter().count(), 0);
+
+  let mut map: MiniHashMap<i32, i32> = Default::default();
+  assert_eq!(map.remove(&1), None);
+  map.clear();
+  map.retain(|_, _| false);
+  assert_eq!(map.drain().count(), 0);
+  assert_eq!(map.into_iter().count(), 0);
+}
+
+#[test]
+fn hash_map_insert_get_remove() {
+  let mut map = MiniHashMap::new();
+
+  for i in 0..1000 {
+    assert_eq!(map.insert(i, i * 2), None);
+  }
+
+  assert_eq!(map.len(), 1000);
+  assert!(map.capacity() >= 1000);
+
+  for i in 0..1000 {
+    assert_eq!(map.get(&i), Some(&(i * 2)));
+    assert_eq!(map.insert(i, i * 3), Some(i * 2));
+  }
+
+  assert_eq!(map.get(&1000), None);
+
+  for i in (0..1000).step_by(2) {
+    assert_eq!(map.remove(&i), Some(i * 3));
+  }
+
+  assert_eq!(map.len(), 500);
+
+  for i in 0..1000 {
+    assert_eq!(map.contains_key(&i), i % 2 == 1);
+  }
+}
+
+#[test]
+fn hash_map_tombstone_churn() {
+  let mut map = MiniHashMap::<u64, u64>::with_capacity(16);
+  let cap = map.capacity();
+
+  // repeatedly inserting and removing fresh keys fills the table with tombstones which must eventually be
+  // cleaned up without the table growing
+  //
+  for i in 0..10_000 {
+    map.insert(i, i);
+    assert_eq!(map.remove(&i), Some(i));
+  }
+
+  assert!(map.is_empty());
+  assert_eq!(map.capacity(), cap);
+}
+
+#[test]
+fn hash_map_borrowed_lookup() {
+  let mut map = MiniHashMap::new();
+  map.insert(String::from("hello"), 1);
+  map.insert(String::from("world"), 2);
+
+  assert_eq!(map["hello"], 1);
+  assert_eq!(map.get("world"), Some(&2));
+  assert_eq!(map.remove_entry("hello"), Some((String::from("hello"), 1)));
+  assert!(!map.contains_key("hello"));
+}
+
+#[test]
+#[should_panic(expected = "key not found")]
+fn hash_map_index_panics() {
+  let map: MiniHashMap<i32, i32> = [(1, 1)].into();
+  let _ = map[&2];
+}
+
+#[test]
+fn hash_map_entry() {
+  let mut map = MiniHashMap::new();
+
+  for word in "the quick brown fox jumps over the lazy dog the end".split(' ') {
+    *map.entry(word).or_insert(0) += 1;
+  }
+
+  assert_eq!(map["the"], 3);
+  assert_eq!(map["fox"], 1);
+
+  match map.entry("fox") {
+    Entry::Occupied(mut entry) => {
+      assert_eq!(entry.key(), &"fox");
+      assert_eq!(entry.insert(7), 1);
+      assert_eq!(*entry.get(), 7);
+    }
+    Entry::Vacant(_) => unreachable!(),
+  }
+
+  match map.entry("cat") {
+    Entry::Occupied(_) => unreachable!(),
+    Entry::Vacant(entry) => {
+      assert_eq!(entry.key(), &"cat");
+      *entry.insert(5) += 1;
+    }
+  }
+
+  assert_eq!(map["cat"], 6);
+
+  if let Entry::Occupied(entry) = map.entry("the") {
+    assert_eq!(entry.remove_entry(), ("the", 3));
+  }
+
+  assert!(!map.contains_key("the"));
+  assert_eq!(*map.entry("dog").and_modify(|x| *x += 10).or_default(), 11);
+}
+
+#[test]
+fn hash_map_retain() {
+  let count = Rc::new(Cell::new(0));
+
+  let mut map = MiniHashMap::new();
+  for i in 0..100 {
+    map.insert(
+      i,
+      DropCounter {
+        count: count.clone(),
+      },
+    );
+  }
+
+  map.retain(|k, _| k % 3 == 0);
+  assert_eq!(map.len(), 34);
+  assert_eq!(count.get(), 66);
+  assert!(map.keys().all(|k| k % 3 == 0));
+
+  drop(map);
+  assert_eq!(count.get(), 100);
+}
+
+#[test]
+fn hash_map_drain() {
+  let count = Rc::new(Cell::new(0));
+
+  let mut map = MiniHashMap::new();
+  for i in 0..64 {
+    map.insert(
+      i,
+      DropCounter {
+        count: count.clone(),
+      },
+    );
+  }
+
+  let cap = map.capacity();
+
+  {
+    let mut drain = map.drain();
+    assert_eq!(drain.len(), 64);
+    drain.next();
+    drain.next();
+  }
+
+  assert_eq!(count.get(), 64);
+  assert!(map.is_empty());
+  assert_eq!(map.capacity(), cap);
+
+  map.insert(
+    1,
+    DropCounter {
+      count: count.clone(),
+    },
+  );
+  assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn hash_map_into_iter() {
+  let count = Rc::new(Cell::new(0));
+
+  let mut map = MiniHashMap::new();
+  for i in 0..64 {
+    map.insert(
+      i,
+      DropCounter {
+        count: count.clone(),
+      },
+    );
+  }
+
+  let mut iter = map.into_iter();
+  assert_eq!(iter.len(), 64);
+  for _ in 0..10 {
+    iter.next();
+  }
+  assert_eq!(iter.len(), 54);
+  assert_eq!(count.get(), 10);
+
+  drop(iter);
+  assert_eq!(count.get(), 64);
+
+  let map: MiniHashMap<_, _> = (0..10).map(|x| (x, x)).collect();
+  let mut pairs: Vec<_> = map.into_iter().collect();
+  pairs.sort_unstable();
+  assert_eq!(pairs, (0..10).map(|x| (x, x)).collect::<Vec<_>>());
+}
+
+#[test]
+fn hash_map_iter_mut() {
+  let mut map: MiniHashMap<_, _> = (0..100).map(|x| (x, x)).collect();
+
+  for (k, v) in &mut map {
+    *v += k;
+  }
+
+  assert!(map.iter().all(|(k, v)| *v == 2 * k));
+  assert_eq!(map.values().sum::<i32>(), 2 * (0..100).sum::<i32>());
+}
+
+#[test]
+fn hash_map_clone_and_eq() {
+  let map: MiniHashMap<_, _> = (0..100).map(|x| (x, x.to_string())).collect();
+  let mut other = map.clone();
+
+  assert_eq!(map, other);
+
+  other.insert(0, String::from("zero"));
+  assert_ne!(map, other);
+
+  other.remove(&0);
+  assert_ne!(map, other);
+}
+
+#[test]
+fn hash_map_extend_and_shrink() {
+  let other: MiniHashMap<_, _> = [(1, 1), (2, 2)].into();
+
+  let mut map = MiniHashMap::new();
+  map.extend(&other);
+  map.extend(vec![(3, 3), (1, 10)]);
+
+  assert_eq!(map.len(), 3);
+  assert_eq!(map[&1], 10);
+
+  map.reserve(1000);
+  assert!(map.capacity() >= 1003);
+
+  map.shrink_to_fit();
+  assert!(map.capacity() < 1000);
+  assert_eq!(map[&3], 3);
+
+  map.clear();
+  map.shrink_to_fit();
+  assert_eq!(map.capacity(), 0);
+}
+
+#[test]
+fn hash_map_debug() {
+  let map: MiniHashMap<_, _> = [(1, "a")].into();
+  assert_eq!(format!("{:?}", map), r#"{1: "a"}"#);
+}
+
+#[test]
+fn hash_set_basic() {
+  let mut set = MiniHashSet::new();
+  assert_eq!(set.capacity(), 0);
+
+  for i in 0..100 {
+    assert!(set.insert(i));
+  }
+
+  assert!(!set.insert(5));
+  assert_eq!(set.len(), 100);
+  assert!(set.contains(&99));
+  assert_eq!(set.get(&42), Some(&42));
+
+  assert!(set.remove(&42));
+  assert_eq!(set.take(&43), Some(43));
+  assert_eq!(set.len(), 98);
+
+  set.retain(|x| x % 2 == 0);
+  assert_eq!(set.len(), 49);
+
+  let mut xs: Vec<_> = set.drain().collect();
+  xs.sort_unstable();
+  assert_eq!(xs.len(), 49);
+  assert_eq!(xs[0], 0);
+  assert!(set.is_empty());
+}
+
+#[test]
+fn hash_set_relations() {
+  let a: MiniHashSet<_> = [1, 2, 3].into();
+  let b: MiniHashSet<_> = [1, 2, 3, 4].into();
+  let c: MiniHashSet<_> = [5, 6].into();
+
+  assert!(a.is_subset(&b));
+  assert!(b.is_superset(&a));
+  assert!(!b.is_subset(&a));
+  assert!(a.is_disjoint(&c));
+  assert!(!a.is_disjoint(&b));
+
+  let d: MiniHashSet<_> = b.iter().copied().filter(|&x| x != 4).collect();
+  assert_eq!(a, d);
+  assert_eq!(a.clone(), a);
+
+  let mut xs: Vec<_> = b.into_iter().collect();
+  xs.sort_unstable();
+  assert_eq!(xs, [1, 2, 3, 4]);
+}
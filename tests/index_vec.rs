@@ -0,0 +1,181 @@
+extern crate minivec;
+
+use minivec::vec_map::Entry;
+use minivec::{Idx, MiniIndexVec, MiniVec, MiniVecMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct NodeId(u32);
+
+impl Idx for NodeId {
+  fn new(idx: usize) -> Self {
+    NodeId(idx as u32)
+  }
+
+  fn index(self) -> usize {
+    self.0 as usize
+  }
+}
+
+#[test]
+fn index_vec_push_and_index() {
+  let mut nodes = MiniIndexVec::<NodeId, String>::new();
+  assert!(nodes.is_empty());
+  assert_eq!(nodes.next_index(), NodeId(0));
+
+  let a = nodes.push(String::from("a"));
+  let b = nodes.push(String::from("b"));
+  let c = nodes.push(String::from("c"));
+
+  assert_eq!((a, b, c), (NodeId(0), NodeId(1), NodeId(2)));
+  assert_eq!(nodes.len(), 3);
+  assert_eq!(nodes.last_index(), Some(c));
+  assert_eq!(nodes[b], "b");
+  assert_eq!(nodes.get(NodeId(3)), None);
+
+  nodes[a].push('!');
+  *nodes.get_mut(c).unwrap() = String::from("z");
+  nodes.swap(a, c);
+
+  assert_eq!(nodes.as_slice(), ["z", "b", "a!"]);
+  assert_eq!(nodes.pop(), Some(String::from("a!")));
+  assert_eq!(nodes.len(), 2);
+}
+
+#[test]
+fn index_vec_iteration() {
+  let mut nodes: MiniIndexVec<NodeId, i32> = vec![10, 20, 30].into_iter().collect();
+
+  assert_eq!(
+    nodes.indices().collect::<Vec<_>>(),
+    [NodeId(0), NodeId(1), NodeId(2)]
+  );
+  assert_eq!(
+    nodes.iter_enumerated().collect::<Vec<_>>(),
+    [(NodeId(0), &10), (NodeId(1), &20), (NodeId(2), &30)]
+  );
+
+  for (idx, x) in nodes.iter_enumerated_mut() {
+    *x += idx.0 as i32;
+  }
+
+  assert_eq!(nodes.iter().copied().collect::<Vec<_>>(), [10, 21, 32]);
+  assert_eq!(
+    nodes.indices().rev().map(|i| nodes[i]).collect::<Vec<_>>(),
+    [32, 21, 10]
+  );
+
+  let (lhs, rhs) = nodes.split_at(NodeId(1));
+  assert_eq!(lhs, [10]);
+  assert_eq!(rhs, [21, 32]);
+
+  let (lhs, rhs) = nodes.split_at_mut(NodeId(2));
+  lhs[0] = rhs[0];
+  assert_eq!(nodes[NodeId(0)], 32);
+
+  let raw: MiniVec<i32> = nodes.clone().into();
+  assert_eq!(raw, [32, 21, 32]);
+  assert_eq!(MiniIndexVec::<NodeId, _>::from(raw), nodes);
+}
+
+#[test]
+fn vec_map_insert_remove() {
+  let mut map = MiniVecMap::<NodeId, &str>::new();
+  assert!(map.is_empty());
+
+  assert_eq!(map.insert(NodeId(3), "c"), None);
+  assert_eq!(map.insert(NodeId(1), "a"), None);
+  assert_eq!(map.insert(NodeId(3), "cc"), Some("c"));
+
+  assert_eq!(map.len(), 2);
+  assert!(map.capacity() >= 4);
+  assert!(map.contains_key(NodeId(1)));
+  assert!(!map.contains_key(NodeId(2)));
+  assert!(!map.contains_key(NodeId(100)));
+  assert_eq!(map[NodeId(3)], "cc");
+
+  assert_eq!(
+    map.iter().collect::<Vec<_>>(),
+    [(NodeId(1), &"a"), (NodeId(3), &"cc")]
+  );
+  assert_eq!(map.keys().rev().collect::<Vec<_>>(), [NodeId(3), NodeId(1)]);
+
+  assert_eq!(map.remove(NodeId(3)), Some("cc"));
+  assert_eq!(map.remove(NodeId(3)), None);
+  assert_eq!(map.remove(NodeId(1)), Some("a"));
+  assert!(map.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "no entry found")]
+fn vec_map_index_panics() {
+  let mut map = MiniVecMap::<NodeId, i32>::new();
+  map.insert(NodeId(2), 2);
+  let _ = map[NodeId(1)];
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn vec_map_insert_max_key_panics() {
+  let mut map = MiniVecMap::<usize, u8>::new();
+  map.insert(usize::MAX, 1);
+}
+
+#[test]
+fn vec_map_entry() {
+  let mut map = MiniVecMap::<usize, i32>::new();
+
+  for x in [4, 1, 4, 4, 0].iter().copied() {
+    *map.entry(x).or_default() += 1;
+  }
+
+  assert_eq!(map[4], 3);
+  assert_eq!(map[1], 1);
+  assert_eq!(map[0], 1);
+
+  match map.entry(4) {
+    Entry::Occupied(mut entry) => {
+      assert_eq!(entry.key(), 4);
+      assert_eq!(entry.insert(7), 3);
+      assert_eq!(entry.remove(), 7);
+    }
+    Entry::Vacant(_) => unreachable!(),
+  }
+
+  assert_eq!(map.len(), 2);
+
+  match map.entry(2) {
+    Entry::Occupied(_) => unreachable!(),
+    Entry::Vacant(entry) => {
+      assert_eq!(entry.key(), 2);
+      *entry.insert(5) += 1;
+    }
+  }
+
+  assert_eq!(map[2], 6);
+  assert_eq!(*map.entry(0).and_modify(|x| *x += 10).or_insert(0), 11);
+}
+
+#[test]
+fn vec_map_retain_and_iter_mut() {
+  let mut map: MiniVecMap<usize, usize> = (0..10).map(|x| (x * 2, x)).collect();
+  assert_eq!(map.len(), 10);
+
+  for (k, v) in &mut map {
+    *v += k;
+  }
+
+  assert!(map.iter().all(|(k, v)| *v == k + k / 2));
+
+  map.retain(|k, _| k < 6);
+  assert_eq!(map.keys().collect::<Vec<_>>(), [0, 2, 4]);
+  assert_eq!(map.values().copied().collect::<Vec<_>>(), [0, 3, 6]);
+
+  let other = map.clone();
+  assert_eq!(map, other);
+  assert_eq!(format!("{:?}", other), "{0: 0, 2: 3, 4: 6}");
+
+  assert_eq!(
+    map.into_iter().collect::<Vec<_>>(),
+    [(0, 0), (2, 3), (4, 6)]
+  );
+}
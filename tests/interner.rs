@@ -0,0 +1,100 @@
+extern crate minivec;
+
+use minivec::{MiniInterner, Symbol};
+
+#[test]
+fn interner_is_three_words() {
+  assert_eq!(
+    std::mem::size_of::<MiniInterner>(),
+    3 * std::mem::size_of::<*const ()>()
+  );
+}
+
+#[test]
+fn interner_new() {
+  let interner = MiniInterner::new();
+  assert!(interner.is_empty());
+  assert_eq!(interner.len(), 0);
+  assert_eq!(interner.byte_len(), 0);
+  assert_eq!(interner.get("a"), None);
+  assert_eq!(interner.try_resolve(Symbol::from_u32(0)), None);
+  assert_eq!(interner.iter().count(), 0);
+  assert_eq!(format!("{:?}", interner), "{}");
+}
+
+#[test]
+fn interner_intern() {
+  let mut interner = MiniInterner::new();
+
+  let empty = interner.intern("");
+  let hello = interner.intern("hello");
+  let world = interner.intern("world");
+
+  assert_eq!(empty.as_u32(), 0);
+  assert_eq!(hello.index(), 1);
+  assert_eq!(u32::from(world), 2);
+
+  assert_eq!(interner.intern("hello"), hello);
+  assert_eq!(interner.intern(""), empty);
+  assert_eq!(interner.len(), 3);
+  assert_eq!(interner.byte_len(), 10);
+
+  assert_eq!(interner.resolve(empty), "");
+  assert_eq!(&interner[world], "world");
+  assert_eq!(interner.get("world"), Some(world));
+  assert_eq!(interner.get("hell"), None);
+  assert_eq!(
+    format!("{:?}", interner),
+    r#"{0: "", 1: "hello", 2: "world"}"#
+  );
+}
+
+#[test]
+#[should_panic(expected = "symbol (is 1) should be < len (is 1)")]
+fn interner_resolve_out_of_bounds() {
+  let mut interner = MiniInterner::new();
+  interner.intern("only");
+  let _ = interner.resolve(Symbol::from_u32(1));
+}
+
+#[test]
+fn interner_many() {
+  let mut interner = MiniInterner::with_capacity(16, 64);
+  let words: Vec<String> = (0..10_000).map(|n| format!("ident_{}", n)).collect();
+
+  let symbols: Vec<Symbol> = words.iter().map(|w| interner.intern(w)).collect();
+  assert_eq!(interner.len(), words.len());
+
+  for (word, &symbol) in words.iter().zip(&symbols) {
+    assert_eq!(interner.get(word), Some(symbol));
+    assert_eq!(interner.resolve(symbol), word);
+  }
+
+  for word in &words {
+    interner.intern(word);
+  }
+  assert_eq!(interner.len(), words.len());
+}
+
+#[test]
+fn interner_iter() {
+  let interner: MiniInterner = "a b c b a d".split(' ').collect();
+
+  let pairs: Vec<(u32, &str)> = interner.iter().map(|(s, w)| (s.as_u32(), w)).collect();
+  assert_eq!(pairs, [(0, "a"), (1, "b"), (2, "c"), (3, "d")]);
+  assert_eq!((&interner).into_iter().len(), 4);
+}
+
+#[test]
+fn interner_clear() {
+  let mut interner = MiniInterner::new();
+  interner.extend(["x", "y", "z"]);
+
+  let clone = interner.clone();
+  interner.clear();
+  assert!(interner.is_empty());
+  assert_eq!(interner.get("x"), None);
+
+  assert_eq!(interner.intern("z").index(), 0);
+  assert_eq!(clone.get("z").map(Symbol::index), Some(2));
+}
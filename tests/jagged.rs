@@ -0,0 +1,249 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniJagged, MiniStrVec, MiniVec};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn jagged_new_does_not_allocate() {
+  let jagged = MiniJagged::<u64>::new();
+  assert!(jagged.is_empty());
+  assert_eq!(jagged.row_capacity(), 0);
+  assert_eq!(jagged.value_capacity(), 0);
+  assert_eq!(jagged.get_row(0), None);
+  assert!(jagged.values().is_empty());
+  assert_eq!(jagged.iter_rows().next(), None);
+}
+
+#[test]
+fn jagged_push_rows() {
+  let mut jagged = MiniJagged::new();
+  for i in 0..200_usize {
+    jagged.push_row(0..i % 7);
+  }
+
+  assert_eq!(jagged.len(), 200);
+  for i in 0..200 {
+    assert_eq!(jagged[i], (0..i % 7).collect::<Vec<_>>()[..]);
+  }
+
+  let total: usize = (0..200).map(|i| i % 7).sum();
+  assert_eq!(jagged.values().len(), total);
+  assert!(jagged.row_capacity() >= 200);
+  assert!(jagged.value_capacity() >= total);
+}
+
+#[test]
+fn jagged_values_survive_offset_growth() {
+  #[repr(align(32))]
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  struct Wide(u8);
+
+  // every new row grows the offsets, which moves the values of the earlier rows
+  //
+  let mut jagged = MiniJagged::with_capacity(0, 64);
+  for i in 0..20 {
+    jagged.push_row([Wide(i), Wide(i + 1)]);
+  }
+
+  for (i, row) in jagged.iter_rows().enumerate() {
+    assert_eq!(row, [Wide(i as u8), Wide(i as u8 + 1)]);
+    assert_eq!(row.as_ptr() as usize % 32, 0);
+  }
+}
+
+#[test]
+fn jagged_extend_last_row() {
+  let mut jagged = MiniJagged::new();
+  jagged.push_row([1]);
+  jagged.push_row([2]);
+  jagged.extend_last_row([3, 4]);
+  jagged.extend_last_row(std::iter::empty());
+
+  assert_eq!(jagged[0], [1]);
+  assert_eq!(jagged[1], [2, 3, 4]);
+  assert_eq!(jagged.values(), [1, 2, 3, 4]);
+}
+
+#[test]
+#[should_panic(expected = "no row to extend")]
+fn jagged_extend_last_row_without_rows() {
+  let mut jagged = MiniJagged::new();
+  jagged.extend_last_row([1]);
+}
+
+#[test]
+#[should_panic(expected = "row index out of bounds")]
+fn jagged_row_out_of_bounds() {
+  let mut jagged = MiniJagged::new();
+  jagged.push_row([1]);
+  let _ = jagged.row(1);
+}
+
+#[test]
+fn jagged_row_mut() {
+  let mut jagged: MiniJagged<_> = vec![vec![1, 2], vec![3]].into_iter().collect();
+
+  jagged.row_mut(0).reverse();
+  jagged[1][0] = 30;
+  for value in jagged.values_mut() {
+    *value += 1;
+  }
+
+  assert_eq!(jagged[0], [3, 2]);
+  assert_eq!(jagged[1], [31]);
+}
+
+#[test]
+fn jagged_pop_and_truncate() {
+  let mut jagged = MiniJagged::new();
+  jagged.push_row([String::from("a"), String::from("b")]);
+  jagged.push_row([]);
+  jagged.push_row([String::from("c")]);
+
+  assert_eq!(jagged.pop_row().unwrap(), ["c"]);
+  assert_eq!(jagged.pop_row().unwrap(), MiniVec::<String>::new());
+  assert_eq!(jagged.len(), 1);
+
+  jagged.push_row([String::from("d")]);
+  assert_eq!(jagged[1], ["d"]);
+
+  jagged.truncate(1);
+  assert_eq!(jagged.values(), ["a", "b"]);
+
+  let capacity = jagged.value_capacity();
+  jagged.clear();
+  assert!(jagged.is_empty());
+  assert_eq!(jagged.value_capacity(), capacity);
+  assert_eq!(jagged.pop_row(), None);
+}
+
+#[test]
+fn jagged_iter_rows() {
+  let jagged: MiniJagged<_> = vec![vec![1], vec![2, 3], vec![]].into_iter().collect();
+
+  let rows = jagged.iter_rows();
+  assert_eq!(rows.len(), 3);
+  assert_eq!(rows.rev().collect::<Vec<_>>(), [&[][..], &[2, 3], &[1]]);
+}
+
+#[test]
+fn jagged_from_nested_mini_vec() {
+  let nested = mini_vec![mini_vec![1, 2, 3], mini_vec![], mini_vec![4]];
+  let jagged = MiniJagged::from(nested.clone());
+
+  assert_eq!(jagged.len(), nested.len());
+  for (row, expected) in jagged.iter_rows().zip(nested.iter()) {
+    assert_eq!(row, &expected[..]);
+  }
+  assert_eq!(jagged.row_capacity(), 3);
+  assert_eq!(jagged.value_capacity(), 4);
+}
+
+#[test]
+fn jagged_drops_values() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  let mut jagged = MiniJagged::new();
+  jagged.push_row([make(), make()]);
+  jagged.push_row([make()]);
+  jagged.push_row([make(), make(), make()]);
+
+  jagged.truncate(2);
+  assert_eq!(count.get(), 3);
+
+  drop(jagged.pop_row());
+  assert_eq!(count.get(), 4);
+
+  drop(jagged);
+  assert_eq!(count.get(), 6);
+}
+
+#[test]
+fn jagged_panicking_row_is_discarded() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  let mut jagged = MiniJagged::new();
+  jagged.push_row([make()]);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    jagged.push_row((0..3).map(|i| if i == 2 { panic!("boom") } else { make() }));
+  }));
+  assert!(result.is_err());
+  assert_eq!(count.get(), 2);
+
+  jagged.push_row([make()]);
+  assert_eq!(jagged.len(), 2);
+  assert_eq!(jagged[1].len(), 1);
+  assert_eq!(jagged.values().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "ZSTs currently not supported")]
+fn jagged_zero_sized() {
+  let _ = MiniJagged::<()>::new();
+}
+
+#[test]
+fn jagged_clone_debug_and_eq() {
+  let jagged: MiniJagged<_> = vec![vec![1, 2], vec![]].into_iter().collect();
+  let clone = jagged.clone();
+
+  assert_eq!(jagged, clone);
+  assert_eq!(format!("{:?}", clone), "[[1, 2], []]");
+
+  let other: MiniJagged<_> = vec![vec![1], vec![2]].into_iter().collect();
+  assert_ne!(jagged, other);
+  assert_eq!(jagged.values(), [1, 2]);
+  assert_eq!(other.values(), [1, 2]);
+}
+
+#[test]
+fn str_vec_push_and_get() {
+  let mut strs = MiniStrVec::new();
+  strs.push("héllo");
+  strs.push("");
+  strs.push("wörld");
+
+  assert_eq!(strs.len(), 3);
+  assert_eq!(&strs[0], "héllo");
+  assert_eq!(strs.get(1), Some(""));
+  assert_eq!(strs.get(3), None);
+  assert_eq!(strs.as_jagged().values(), "héllowörld".as_bytes());
+
+  strs.push_str_to_last("!");
+  assert_eq!(&strs[2], "wörld!");
+
+  assert_eq!(strs.pop().as_deref(), Some("wörld!"));
+  strs.truncate(1);
+  assert_eq!(strs.iter().collect::<Vec<_>>(), ["héllo"]);
+
+  strs.clear();
+  assert!(strs.is_empty());
+  assert_eq!(strs.pop(), None);
+}
+
+#[test]
+fn str_vec_collect_debug_and_eq() {
+  let strs: MiniStrVec = ["a", "bc"].iter().collect();
+  let clone = strs.clone();
+
+  assert_eq!(strs, clone);
+  assert_eq!(format!("{:?}", strs), r#"["a", "bc"]"#);
+  assert_eq!(strs.iter().rev().collect::<Vec<_>>(), ["bc", "a"]);
+
+  let mut other = MiniStrVec::default();
+  other.extend(vec![String::from("ab"), String::from("c")]);
+  assert_ne!(strs, other);
+}
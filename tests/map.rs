@@ -0,0 +1,119 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniVec};
+
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[derive(Debug, PartialEq)]
+struct Wrapper<T>(T);
+
+#[test]
+fn map_in_place_reuses_allocation() {
+  let mut vec = MiniVec::<u64>::with_capacity(8);
+  vec.extend(0..5);
+  let ptr = vec.as_ptr();
+
+  let wrapped = vec.map_in_place(Wrapper);
+  assert_eq!(wrapped.as_ptr().cast::<u64>(), ptr);
+  assert_eq!(wrapped.capacity(), 8);
+  assert_eq!(wrapped[4], Wrapper(4));
+
+  let mut halves = wrapped.map_in_place(|Wrapper(n)| n as u32);
+  assert_eq!(halves.as_ptr().cast::<u64>(), ptr);
+  assert_eq!(halves.capacity(), 16);
+  assert_eq!(halves, [0, 1, 2, 3, 4]);
+
+  halves.extend(5..16);
+  assert_eq!(halves.capacity(), 16);
+  assert_eq!(halves[15], 15);
+}
+
+#[test]
+fn map_in_place_falls_back_to_collect() {
+  let bytes = mini_vec![1_u8, 2, 3];
+  let wide = bytes.map_in_place(u64::from);
+  assert_eq!(wide, [1, 2, 3]);
+
+  let strings = mini_vec![1, 2, 3].map_in_place(|n: i32| n.to_string());
+  assert_eq!(strings, ["1", "2", "3"]);
+
+  let empty = MiniVec::<u32>::new().map_in_place(|n| n as f32);
+  assert!(empty.is_empty());
+  assert_eq!(empty.capacity(), 0);
+
+  let reserved = MiniVec::<u32>::with_capacity(4).map_in_place(|n| n as f32);
+  assert!(reserved.is_empty());
+  assert_eq!(reserved.capacity(), 4);
+
+  // an empty vector keeps its alignment
+  //
+  let aligned = MiniVec::<u32>::with_alignment(0, 128).unwrap();
+  let mut aligned = aligned.map_in_place(|n| n as f32);
+  aligned.push(1.0);
+  assert_eq!(aligned.as_ptr() as usize % 128, 0);
+}
+
+#[test]
+fn try_map() {
+  let vec = mini_vec![1_i64, 2, 3];
+  let ptr = vec.as_ptr();
+
+  let narrowed = vec.try_map(i32::try_from).unwrap();
+  assert_eq!(narrowed, [1, 2, 3]);
+  assert_eq!(narrowed.as_ptr().cast::<i64>(), ptr);
+
+  let vec = mini_vec![1_i64, -2, 3];
+  assert!(vec.try_map(u32::try_from).is_err());
+}
+
+#[test]
+fn try_map_drops_on_error() {
+  let count = Rc::new(Cell::new(0));
+  let vec: MiniVec<_> = (0..6)
+    .map(|_| DropCounter {
+      count: count.clone(),
+    })
+    .collect();
+
+  let mut seen = 0;
+  let result = vec.try_map(|counter| {
+    seen += 1;
+    if seen == 3 {
+      Err("third")
+    } else {
+      Ok(counter)
+    }
+  });
+
+  assert_eq!(result.err(), Some("third"));
+  assert_eq!(count.get(), 6);
+}
+
+#[test]
+fn map_in_place_drops_on_panic() {
+  let count = Rc::new(Cell::new(0));
+  let vec: MiniVec<_> = (0..6)
+    .map(|_| DropCounter {
+      count: count.clone(),
+    })
+    .collect();
+
+  let mut seen = 0;
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    vec.map_in_place(|counter| {
+      seen += 1;
+      assert!(seen < 4, "fourth");
+      Wrapper(counter)
+    })
+  }));
+
+  assert!(result.is_err());
+  assert_eq!(count.get(), 6);
+}
@@ -0,0 +1,283 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniMatrix, MiniVec};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn matrix_new_does_not_allocate() {
+  let matrix = MiniMatrix::<f64>::new();
+  assert_eq!(matrix.shape(), (0, 0));
+  assert!(matrix.is_empty());
+  assert!(matrix.as_slice().is_empty());
+  assert_eq!(matrix.get(0, 0), None);
+}
+
+#[test]
+fn matrix_indexing() {
+  let mut matrix = MiniMatrix::from_fn(3, 4, |row, col| row * 10 + col);
+
+  assert_eq!(matrix.shape(), (3, 4));
+  assert_eq!(matrix.rows(), 3);
+  assert_eq!(matrix.cols(), 4);
+  assert_eq!(matrix[(2, 3)], 23);
+  assert_eq!(matrix.get(3, 0), None);
+  assert_eq!(matrix.get(0, 4), None);
+
+  matrix[(1, 1)] = 100;
+  *matrix.get_mut(0, 0).unwrap() = 7;
+  matrix.row_mut(2).reverse();
+
+  assert_eq!(matrix.row(0), [7, 1, 2, 3]);
+  assert_eq!(matrix.row(1), [10, 100, 12, 13]);
+  assert_eq!(matrix.row(2), [23, 22, 21, 20]);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds: the shape is (2, 2) but the index is (0, 2)")]
+fn matrix_index_out_of_bounds() {
+  let matrix = MiniMatrix::from_elem(2, 2, 0);
+  let _ = matrix[(0, 2)];
+}
+
+#[test]
+fn matrix_columns() {
+  let mut matrix = MiniMatrix::from_fn(3, 2, |row, col| row * 2 + col);
+
+  let col = matrix.col(1);
+  assert_eq!(col.len(), 3);
+  assert_eq!(col.rev().copied().collect::<Vec<_>>(), [5, 3, 1]);
+
+  for value in matrix.col_mut(0) {
+    *value *= 10;
+  }
+  assert_eq!(matrix.as_slice(), [0, 1, 20, 3, 40, 5]);
+
+  let empty = MiniMatrix::<i32>::from_fn(0, 3, |_, _| unreachable!());
+  assert_eq!(empty.col(2).count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "column index out of bounds")]
+fn matrix_column_out_of_bounds() {
+  let matrix = MiniMatrix::from_elem(2, 2, 0);
+  let _ = matrix.col(2);
+}
+
+#[test]
+fn matrix_push_row() {
+  let mut matrix = MiniMatrix::new();
+  matrix.push_row(vec![1, 2, 3]);
+  matrix.push_row(4..7);
+
+  assert_eq!(matrix.shape(), (2, 3));
+  assert_eq!(matrix.as_slice(), [1, 2, 3, 4, 5, 6]);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| matrix.push_row([7, 8])));
+  assert!(result.is_err());
+  assert_eq!(matrix.shape(), (2, 3));
+  assert_eq!(matrix.as_slice(), [1, 2, 3, 4, 5, 6]);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    matrix.push_row((7..10).map(|x| {
+      if x < 9 {
+        x
+      } else {
+        panic!("iterator panicked")
+      }
+    }))
+  }));
+  assert!(result.is_err());
+  assert_eq!(matrix.shape(), (2, 3));
+  assert_eq!(matrix.as_slice(), [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn matrix_insert_and_remove_cols() {
+  let mut matrix = MiniMatrix::from_vec(mini_vec![1_i32, 2, 3, 4, 5, 6], 2, 3).unwrap();
+
+  matrix.insert_col(0, [0, 3]);
+  matrix.insert_col(4, [9, 9]);
+  matrix.insert_col(2, [-1, -2]);
+  assert_eq!(matrix.shape(), (2, 6));
+  assert_eq!(matrix.row(0), [0, 1, -1, 2, 3, 9]);
+  assert_eq!(matrix.row(1), [3, 4, -2, 5, 6, 9]);
+
+  assert_eq!(matrix.remove_col(5), [9, 9]);
+  assert_eq!(matrix.remove_col(0), [0, 3]);
+  assert_eq!(matrix.remove_col(1), [-1, -2]);
+  assert_eq!(
+    matrix,
+    MiniMatrix::from_fn(2, 3, |row, col| (row * 3 + col + 1) as i32)
+  );
+
+  for _ in 0..3 {
+    matrix.remove_col(0);
+  }
+  assert_eq!(matrix.shape(), (2, 0));
+  assert!(matrix.is_empty());
+
+  let mut column = MiniMatrix::new();
+  column.insert_col(0, vec![String::from("a"), String::from("b")]);
+  column.insert_col(1, vec![String::from("c"), String::from("d")]);
+  assert_eq!(column.shape(), (2, 2));
+  assert_eq!(column.row(1), ["b", "d"]);
+}
+
+#[test]
+#[should_panic(expected = "column length mismatch")]
+fn matrix_insert_col_length_mismatch() {
+  let mut matrix = MiniMatrix::from_elem(2, 2, 0);
+  matrix.insert_col(1, [1, 2, 3]);
+}
+
+#[test]
+fn matrix_transpose() {
+  let matrix = MiniMatrix::from_fn(2, 3, |row, col| (row, col));
+  let transposed = matrix.transpose();
+
+  assert_eq!(transposed.shape(), (3, 2));
+  for row in 0..2 {
+    for col in 0..3 {
+      assert_eq!(matrix[(row, col)], transposed[(col, row)]);
+    }
+  }
+  assert_eq!(transposed.transpose(), matrix);
+
+  let tall = MiniMatrix::<u8>::from_fn(0, 4, |_, _| 0);
+  assert_eq!(tall.transpose().shape(), (4, 0));
+}
+
+#[test]
+fn matrix_resize() {
+  let mut matrix = MiniMatrix::from_fn(2, 3, |row, col| row * 3 + col);
+
+  matrix.resize(3, 4, 0);
+  assert_eq!(matrix.row(0), [0, 1, 2, 0]);
+  assert_eq!(matrix.row(1), [3, 4, 5, 0]);
+  assert_eq!(matrix.row(2), [0, 0, 0, 0]);
+
+  matrix.resize(1, 2, 0);
+  assert_eq!(matrix.as_slice(), [0, 1]);
+
+  matrix.resize(0, 0, 0);
+  assert!(matrix.is_empty());
+}
+
+#[test]
+fn matrix_resize_panicking_clone() {
+  #[derive(Debug, PartialEq)]
+  struct Fragile(u8);
+
+  impl Clone for Fragile {
+    fn clone(&self) -> Self {
+      panic!("clone panicked");
+    }
+  }
+
+  let mut matrix = MiniMatrix::from_fn(2, 2, |row, col| Fragile((row * 2 + col) as u8));
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    matrix.resize(3, 3, Fragile(9));
+  }));
+  assert!(result.is_err());
+  assert_eq!(matrix.shape(), (0, 0));
+  assert!(matrix.as_slice().is_empty());
+
+  matrix.push_row([Fragile(1)]);
+  assert_eq!(matrix.as_slice(), [Fragile(1)]);
+}
+
+#[test]
+fn matrix_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let make = |_, _| DropCounter {
+    count: count.clone(),
+  };
+
+  let mut matrix = MiniMatrix::from_fn(3, 3, make);
+
+  drop(matrix.remove_col(1));
+  assert_eq!(count.get(), 3);
+
+  matrix.resize(
+    1,
+    1,
+    DropCounter {
+      count: count.clone(),
+    },
+  );
+  // five discarded elements plus the unused fill value
+  //
+  assert_eq!(count.get(), 9);
+
+  drop(matrix);
+  assert_eq!(count.get(), 10);
+}
+
+#[test]
+fn matrix_alignment() {
+  let mut matrix = MiniMatrix::<f32>::with_alignment(0, 64).unwrap();
+  for row in 0..10 {
+    matrix.push_row([row as f32; 5]);
+  }
+
+  assert_eq!(matrix.alignment(), 64);
+  assert_eq!(matrix.as_slice().as_ptr() as usize % 64, 0);
+
+  let transposed = matrix.transpose();
+  assert_eq!(transposed.alignment(), 64);
+  assert_eq!(transposed.as_slice().as_ptr() as usize % 64, 0);
+
+  let clone = matrix.clone();
+  assert_eq!(clone.alignment(), 64);
+  assert_eq!(clone, matrix);
+
+  matrix.resize(20, 20, 1.0);
+  assert_eq!(matrix.as_slice().as_ptr() as usize % 64, 0);
+
+  let (vec, rows, cols) = matrix.into_parts();
+  assert_eq!((rows, cols), (20, 20));
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+
+  assert!(matches!(
+    MiniMatrix::<f64>::with_alignment(4, 4),
+    Err(minivec::LayoutErr::AlignmentTooSmall)
+  ));
+}
+
+#[test]
+fn matrix_vec_round_trip() {
+  let mut vec = MiniVec::<f32>::with_alignment(6, 128).unwrap();
+  vec.extend_from_slice(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+  let matrix = MiniMatrix::from_vec(vec, 3, 2).unwrap();
+  assert_eq!(matrix.alignment(), 128);
+  assert_eq!(matrix.col(1).copied().collect::<Vec<_>>(), [2.0, 4.0, 6.0]);
+
+  let (vec, rows, cols) = matrix.into_parts();
+  assert_eq!(vec, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+  assert_eq!((rows, cols), (3, 2));
+
+  let rejected = MiniMatrix::from_vec(vec, 4, 2).unwrap_err();
+  assert_eq!(rejected.len(), 6);
+
+  let (vec, rows, cols) = MiniMatrix::<u8>::new().into_parts();
+  assert!(vec.is_empty());
+  assert_eq!((rows, cols), (0, 0));
+}
+
+#[test]
+fn matrix_debug_and_eq() {
+  let matrix = MiniMatrix::from_vec(mini_vec![1, 2, 3, 4], 2, 2).unwrap();
+  assert_eq!(format!("{:?}", matrix), "[[1, 2], [3, 4]]");
+
+  let reshaped = MiniMatrix::from_vec(mini_vec![1, 2, 3, 4], 1, 4).unwrap();
+  assert_ne!(matrix, reshaped);
+  assert_eq!(MiniMatrix::<u8>::default(), MiniMatrix::new());
+}
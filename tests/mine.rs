@@ -973,7 +973,8 @@ fn minivec_truncate() {
 #[test]
 fn minivec_macro() {
   let vec: MiniVec<i32> = mini_vec!();
-  assert_eq!(vec.as_ptr(), core::ptr::null());
+  assert!(!vec.as_ptr().is_null());
+  assert_eq!(vec.as_ptr() as usize % core::mem::align_of::<i32>(), 0);
   assert_eq!(vec.len(), 0);
   assert_eq!(vec.capacity(), 0);
 
@@ -1080,6 +1081,62 @@ fn minivec_page_aligned() {
   assert_eq!(vec.as_mut_ptr() as usize % alignment, 0);
 }
 
+#[test]
+fn minivec_empty_keeps_alignment() {
+  for &alignment in &[8, 16, 64, 512, 4096, 8192] {
+    let mut vec = MiniVec::<u8>::with_alignment(0, alignment).unwrap();
+    assert_eq!(vec.capacity(), 0);
+    assert_eq!(vec.as_ptr() as usize % alignment, 0);
+    assert!(vec.spare_capacity_mut().is_empty());
+
+    let (init, uninit) = vec.split_at_spare_mut();
+    assert!(init.is_empty());
+    assert!(uninit.is_empty());
+
+    vec.push(1);
+    assert_eq!(vec.as_ptr() as usize % alignment, 0);
+
+    vec.clear();
+    vec.shrink_to_fit();
+    assert_eq!(vec.capacity(), 0);
+    assert_eq!(vec.as_ptr() as usize % alignment, 0);
+
+    vec.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(vec.as_ptr() as usize % alignment, 0);
+    assert_eq!(vec, [1, 2, 3]);
+  }
+}
+
+#[test]
+fn minivec_empty_raw_parts() {
+  let vec = MiniVec::<u64>::with_alignment(0, 256).unwrap();
+
+  let (ptr, len, cap) = vec.into_raw_parts();
+  assert_eq!((len, cap), (0, 0));
+  assert_eq!(ptr as usize % 256, 0);
+
+  let mut vec = unsafe { MiniVec::from_raw_parts(ptr, len, cap) };
+  vec.push(1337);
+  assert_eq!(vec.as_ptr() as usize % 256, 0);
+  assert_eq!(vec, [1337]);
+
+  let empty: &mut [u64] = MiniVec::leak(MiniVec::new());
+  assert!(empty.is_empty());
+}
+
+#[test]
+fn minivec_split_off_keeps_alignment() {
+  let mut vec = MiniVec::<u8>::with_alignment(16, 128).unwrap();
+  vec.extend_from_slice(b"hello");
+
+  let other = vec.split_off(0);
+  assert_eq!(other, *b"hello");
+  assert_eq!(vec.capacity(), 16);
+
+  vec.push(1);
+  assert_eq!(vec.as_ptr() as usize % 128, 0);
+}
+
 #[test]
 fn minivec_split_at_spare_mut() {
   // empty case
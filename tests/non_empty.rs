@@ -0,0 +1,81 @@
+extern crate minivec;
+
+use minivec::{mini_vec, nonempty_mini_vec, MiniVec, NonEmptyMiniVec};
+
+use std::convert::TryFrom;
+use std::num::NonZeroUsize;
+
+#[test]
+fn non_empty_construction() {
+  let vec = NonEmptyMiniVec::new(1);
+  assert_eq!(vec, [1]);
+  assert_eq!(vec.len().get(), 1);
+  assert_eq!(vec.first(), vec.last());
+
+  let vec = nonempty_mini_vec![1, 2, 3,];
+  assert_eq!(vec, [1, 2, 3]);
+
+  assert!(NonEmptyMiniVec::from_vec(MiniVec::<i32>::new()).is_none());
+  assert_eq!(
+    NonEmptyMiniVec::from_vec(mini_vec![4, 5])
+      .unwrap()
+      .into_vec(),
+    [4, 5]
+  );
+
+  assert_eq!(
+    NonEmptyMiniVec::try_from(MiniVec::<i32>::new()).unwrap_err(),
+    MiniVec::<i32>::new()
+  );
+  let vec: MiniVec<_> = NonEmptyMiniVec::try_from(mini_vec![7]).unwrap().into();
+  assert_eq!(vec, [7]);
+}
+
+#[test]
+fn non_empty_accessors() {
+  let mut vec = nonempty_mini_vec![3, 9, 1, 9, 4];
+
+  assert_eq!(*vec.first(), 3);
+  assert_eq!(*vec.last(), 4);
+  assert_eq!(*vec.max(), 9);
+  assert_eq!(*vec.min(), 1);
+  assert_eq!(vec.split_first(), (&3, &[9, 1, 9, 4][..]));
+  assert_eq!(vec.split_last(), (&4, &[3, 9, 1, 9][..]));
+
+  *vec.first_mut() = 10;
+  *vec.last_mut() = 0;
+  assert_eq!(vec, [10, 9, 1, 9, 0]);
+
+  vec.sort_unstable();
+  assert_eq!(vec, [0, 1, 9, 9, 10]);
+  assert_eq!(vec.iter().sum::<i32>(), 29);
+
+  assert_eq!(vec.clone().reduce(|a, b| a + b), 29);
+  assert_eq!(NonEmptyMiniVec::new(5).reduce(|a, b| a * b), 5);
+}
+
+#[test]
+fn non_empty_refuses_to_empty() {
+  let mut vec = nonempty_mini_vec![String::from("a"), String::from("b")];
+
+  assert_eq!(vec.pop(), Some(String::from("b")));
+  assert_eq!(vec.pop(), None);
+  assert_eq!(vec.remove(0), None);
+  assert_eq!(vec, [String::from("a")]);
+
+  vec.push(String::from("c"));
+  vec.insert(0, String::from("z"));
+  vec.extend(vec![String::from("d"), String::from("e")]);
+  assert_eq!(vec.len().get(), 5);
+  assert_eq!(vec.remove(1), Some(String::from("a")));
+
+  vec.truncate(NonZeroUsize::new(1).unwrap());
+  assert_eq!(vec, [String::from("z")]);
+}
+
+#[test]
+#[should_panic(expected = "removal index")]
+fn non_empty_remove_out_of_bounds() {
+  let mut vec = nonempty_mini_vec![1];
+  vec.remove(1);
+}
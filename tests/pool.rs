@@ -0,0 +1,180 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniVec, MiniVecPool, SyncMiniVecPool};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn pool_reuses_allocations() {
+  let pool = MiniVecPool::<u8>::new();
+  assert!(pool.is_empty());
+
+  let mut buf = pool.take(100);
+  assert!(buf.capacity() >= 100);
+  buf.extend_from_slice(&[1; 100]);
+  let ptr = buf.as_ptr();
+  drop(buf);
+  assert_eq!(pool.len(), 1);
+
+  let buf = pool.take(65);
+  assert!(buf.is_empty());
+  assert_eq!(buf.as_ptr(), ptr);
+  assert!(pool.is_empty());
+
+  let other = pool.take(65);
+  assert_ne!(other.as_ptr(), ptr);
+  drop((buf, other));
+  assert_eq!(pool.len(), 2);
+
+  let small = pool.take(64);
+  assert_ne!(small.as_ptr(), ptr);
+  assert_eq!(small.capacity(), 64);
+}
+
+#[test]
+fn pool_grown_vectors_change_class() {
+  let pool = MiniVecPool::<u32>::new();
+
+  let mut buf = pool.take(4);
+  buf.extend(0..1000);
+  let capacity = buf.capacity();
+  drop(buf);
+
+  let buf = pool.take(4);
+  assert_eq!(buf.capacity(), 4);
+  drop(buf);
+
+  let buf = pool.take(capacity);
+  assert_eq!(buf.capacity(), capacity);
+  assert_eq!(pool.len(), 1);
+}
+
+#[test]
+fn pool_max_per_class() {
+  let pool = MiniVecPool::<u8>::with_max_per_class(2);
+
+  let bufs: Vec<_> = (0..5).map(|_| pool.take(16)).collect();
+  drop(bufs);
+  assert_eq!(pool.len(), 2);
+
+  drop(pool.take(0));
+  assert_eq!(pool.len(), 3);
+
+  pool.clear();
+  assert!(pool.is_empty());
+  assert_eq!(format!("{:?}", pool), "MiniVecPool { len: 0, .. }");
+}
+
+#[test]
+fn pool_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let pool = MiniVecPool::new();
+
+  let mut buf = pool.take(8);
+  for _ in 0..3 {
+    buf.push(DropCounter {
+      count: count.clone(),
+    });
+  }
+  drop(buf);
+  assert_eq!(count.get(), 3);
+
+  let mut buf = pool.take(8);
+  buf.push(DropCounter {
+    count: count.clone(),
+  });
+  let detached: MiniVec<DropCounter> = buf.detach();
+  assert!(pool.is_empty());
+
+  drop(detached);
+  assert_eq!(count.get(), 4);
+  assert!(pool.is_empty());
+}
+
+#[test]
+fn sync_pool_across_threads() {
+  let pool = SyncMiniVecPool::<u64>::with_max_per_class(8);
+
+  std::thread::scope(|s| {
+    for _ in 0..4 {
+      s.spawn(|| {
+        for n in 0..100 {
+          let mut buf = pool.take(n % 16);
+          buf.extend(0..n as u64);
+          assert_eq!(buf.len(), n);
+        }
+      });
+    }
+  });
+
+  assert!(!pool.is_empty());
+  assert!(pool.len() <= 8 * 8);
+
+  let buf = pool.take(16);
+  assert!(buf.is_empty());
+  drop(buf);
+
+  pool.clear();
+  assert!(pool.is_empty());
+}
+
+#[test]
+fn into_recycled() {
+  let mut vec = MiniVec::<u64>::with_capacity(10);
+  vec.extend([1, 2, 3]);
+  let ptr = vec.as_ptr();
+
+  let halves = vec.into_recycled::<u32>().unwrap();
+  assert!(halves.is_empty());
+  assert_eq!(halves.capacity(), 20);
+  assert_eq!(halves.as_ptr().cast::<u64>(), ptr);
+
+  let mut pairs = halves.into_recycled::<(u32, u32)>().unwrap();
+  assert_eq!(pairs.capacity(), 10);
+  pairs.extend((0..15).map(|n| (n, n)));
+  assert_eq!(pairs[14], (14, 14));
+
+  let odd = MiniVec::<u8>::with_capacity(12);
+  let odd = odd.into_recycled::<u64>().unwrap_err();
+  assert_eq!(odd.capacity(), 12);
+
+  let bytes = mini_vec![1_u8; 16];
+  let bytes = bytes.into_recycled::<u128>().unwrap_err();
+  assert_eq!(bytes, [1; 16]);
+
+  let aligned = MiniVec::<u8>::with_alignment(16, 16).unwrap();
+  let wide = aligned.into_recycled::<u128>().unwrap();
+  assert_eq!(wide.capacity(), 1);
+
+  let empty = MiniVec::<u8>::new().into_recycled::<String>().unwrap();
+  assert_eq!(empty.capacity(), 0);
+
+  // an empty vector keeps its alignment
+  //
+  let aligned = MiniVec::<u8>::with_alignment(0, 256).unwrap();
+  let mut aligned = aligned.into_recycled::<u32>().unwrap();
+  aligned.push(1);
+  assert_eq!(aligned.as_ptr() as usize % 256, 0);
+  assert!(mini_vec![1_u8].into_recycled::<()>().is_err());
+}
+
+#[test]
+fn into_recycled_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let vec: MiniVec<_> = (0..4)
+    .map(|_| DropCounter {
+      count: count.clone(),
+    })
+    .collect();
+
+  let recycled = vec
+    .into_recycled::<usize>()
+    .unwrap_or_else(|_| panic!("usize should fit in the allocation"));
+  assert_eq!(count.get(), 4);
+  assert!(recycled.capacity() >= 4);
+}
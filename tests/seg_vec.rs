@@ -0,0 +1,182 @@
+extern crate minivec;
+
+use minivec::MiniSegVec;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn seg_vec_new_does_not_allocate() {
+  let vec = MiniSegVec::<i32>::new();
+  assert!(vec.is_empty());
+  assert_eq!(vec.len(), 0);
+  assert_eq!(vec.capacity(), 0);
+  assert_eq!(vec.get(0), None);
+  assert_eq!(vec.iter().next(), None);
+}
+
+#[test]
+fn seg_vec_get_out_of_range() {
+  let mut vec = MiniSegVec::<i32>::new();
+  assert_eq!(vec.get(usize::MAX), None);
+  assert_eq!(vec.get_mut(usize::MAX), None);
+
+  vec.push(1);
+  assert_eq!(vec.get(1), None);
+  assert_eq!(vec.get(usize::MAX), None);
+  assert_eq!(vec.get_mut(usize::MAX), None);
+  assert_eq!(vec.get(usize::MAX - 7), None);
+  assert_eq!(vec.get_mut(0), Some(&mut 1));
+}
+
+#[test]
+fn seg_vec_push_and_index() {
+  let mut vec = MiniSegVec::new();
+  for i in 0..1000 {
+    assert_eq!(*vec.push(i), i);
+  }
+
+  assert_eq!(vec.len(), 1000);
+  assert!(vec.capacity() >= 1000);
+
+  for i in 0..1000 {
+    assert_eq!(vec[i], i);
+    assert_eq!(vec.get(i), Some(&i));
+  }
+  assert_eq!(vec.get(1000), None);
+
+  vec[10] = 42;
+  *vec.get_mut(11).unwrap() += 1;
+  assert_eq!(vec[10], 42);
+  assert_eq!(vec[11], 12);
+
+  assert!(vec.iter().eq(vec.clone().iter()));
+  assert_eq!(vec.iter().len(), 1000);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn seg_vec_index_out_of_bounds() {
+  let vec: MiniSegVec<_> = (0..3).collect();
+  let _ = vec[3];
+}
+
+#[test]
+fn seg_vec_elements_never_move() {
+  let mut vec = MiniSegVec::new();
+  let first = vec.push(String::from("first")) as *mut String;
+
+  for i in 0..10_000 {
+    vec.push(i.to_string());
+  }
+
+  assert_eq!(&vec[0] as *const String, first.cast_const());
+  assert_eq!(vec[0], "first");
+}
+
+#[test]
+fn seg_vec_alloc_through_shared_reference() {
+  let arena = MiniSegVec::new();
+
+  let refs: Vec<&usize> = (0..500).map(|i| arena.alloc(i)).collect();
+
+  // references handed out earlier stay valid while the arena keeps growing
+  //
+  for (i, r) in refs.iter().enumerate() {
+    assert_eq!(**r, i);
+  }
+
+  let mut iter = arena.iter();
+  assert_eq!(iter.next(), Some(&0));
+
+  // allocating mid-iteration is allowed and isn't observed by the iterator
+  //
+  arena.alloc(500);
+  assert_eq!(iter.count(), 499);
+  assert_eq!(arena.len(), 501);
+}
+
+#[test]
+fn seg_vec_clear_keeps_segments() {
+  let mut vec: MiniSegVec<_> = (0..100).collect();
+  let capacity = vec.capacity();
+
+  vec.clear();
+  assert!(vec.is_empty());
+  assert_eq!(vec.capacity(), capacity);
+
+  vec.extend(0..capacity);
+  assert_eq!(vec.capacity(), capacity);
+  assert!(vec.iter().copied().eq(0..capacity));
+}
+
+#[test]
+fn seg_vec_pop() {
+  let mut vec: MiniSegVec<_> = (0..20).collect();
+
+  for i in (0..20).rev() {
+    assert_eq!(vec.pop(), Some(i));
+    assert_eq!(vec.len(), i);
+  }
+  assert_eq!(vec.pop(), None);
+
+  vec.push(7);
+  assert_eq!(vec[0], 7);
+}
+
+#[test]
+fn seg_vec_iterators() {
+  let mut vec: MiniSegVec<_> = (0..50).collect();
+
+  for x in &mut vec {
+    *x *= 2;
+  }
+
+  assert!((&vec).into_iter().copied().eq((0..50).map(|x| x * 2)));
+  assert!(vec.into_iter().eq((0..50).map(|x| x * 2)));
+}
+
+#[test]
+fn seg_vec_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  {
+    let mut vec = MiniSegVec::new();
+    for _ in 0..30 {
+      vec.push(make());
+    }
+
+    drop(vec.pop());
+    assert_eq!(count.get(), 1);
+
+    vec.clear();
+    assert_eq!(count.get(), 30);
+
+    vec.push(make());
+  }
+  assert_eq!(count.get(), 31);
+
+  let vec: MiniSegVec<_> = (0..30).map(|_| make()).collect();
+  let mut iter = vec.into_iter();
+  drop(iter.next());
+  assert_eq!(count.get(), 32);
+
+  drop(iter);
+  assert_eq!(count.get(), 61);
+}
+
+#[test]
+fn seg_vec_debug_and_eq() {
+  let vec: MiniSegVec<_> = (1..=3).collect();
+  assert_eq!(format!("{:?}", vec), "[1, 2, 3]");
+  assert_eq!(vec, (1..=3).collect());
+  assert_ne!(vec, (1..=4).collect());
+}
@@ -0,0 +1,67 @@
+extern crate minivec;
+
+use minivec::MiniVec;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.with(|count| count.set(count.get() + 1));
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+  ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn empty_aligned_vectors_do_not_allocate() {
+  let before = allocations();
+
+  let vecs: [MiniVec<f32>; 5] = [
+    MiniVec::new(),
+    MiniVec::with_alignment(0, 16).unwrap(),
+    MiniVec::with_alignment(0, 64).unwrap(),
+    MiniVec::with_alignment(0, 1024).unwrap(),
+    MiniVec::with_alignment(0, 4096).unwrap(),
+  ];
+
+  assert_eq!(allocations(), before);
+
+  for vec in &vecs {
+    assert_eq!(vec.capacity(), 0);
+    assert!(vec.is_empty());
+  }
+}
+
+#[test]
+fn shrinking_to_zero_releases_the_allocation() {
+  let mut vec = MiniVec::<u32>::with_alignment(32, 64).unwrap();
+  vec.push(1);
+  vec.clear();
+  vec.shrink_to_fit();
+
+  let before = allocations();
+  let clone = vec.clone();
+  assert_eq!(allocations(), before);
+  assert_eq!(clone.capacity(), 0);
+
+  vec.push(2);
+  assert_eq!(allocations(), before + 1);
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+}
@@ -0,0 +1,120 @@
+extern crate minivec;
+extern crate minivec_derive;
+
+use minivec::{
+  BoundedMiniVec, ConcurrentMiniVec, ErasedMiniVec, GenKey, MiniBytes, MiniBytesMut, MiniCow,
+  MiniDynVec, MiniGapBuffer, MiniGapString, MiniHashMap, MiniHashSet, MiniIndexVec, MiniJagged,
+  MiniMatrix, MiniSegVec, MiniSlab, MiniSmallVec, MiniStrVec, MiniVec, MiniVecMap,
+  MiniVecWithHeader, NonEmptyMiniVec, TaggedMiniVec,
+};
+use minivec_derive::MiniSoA;
+
+use std::mem::size_of;
+
+#[derive(MiniSoA)]
+struct Particle {
+  x: f32,
+  y: f32,
+  id: u8,
+}
+
+#[test]
+fn collections_are_pointer_sized() {
+  let word = size_of::<*const ()>();
+
+  let sizes = [
+    ("MiniVec<String>", size_of::<MiniVec<String>>(), 1),
+    (
+      "BoundedMiniVec<String, 64>",
+      size_of::<BoundedMiniVec<String, 64>>(),
+      1,
+    ),
+    (
+      "ConcurrentMiniVec<String>",
+      size_of::<ConcurrentMiniVec<String>>(),
+      1,
+    ),
+    ("MiniCow<u64>", size_of::<MiniCow<'_, u64>>(), 1),
+    (
+      "Option<MiniCow<u64>>",
+      size_of::<Option<MiniCow<'_, u64>>>(),
+      1,
+    ),
+    (
+      "MiniDynVec<dyn Debug>",
+      size_of::<MiniDynVec<dyn std::fmt::Debug>>(),
+      1,
+    ),
+    ("ErasedMiniVec", size_of::<ErasedMiniVec>(), 1),
+    ("MiniGapBuffer<u8>", size_of::<MiniGapBuffer<u8>>(), 1),
+    ("MiniGapString", size_of::<MiniGapString>(), 1),
+    (
+      "MiniHashMap<String, u64>",
+      size_of::<MiniHashMap<String, u64>>(),
+      1,
+    ),
+    (
+      "Option<MiniHashMap<String, u64>>",
+      size_of::<Option<MiniHashMap<String, u64>>>(),
+      1,
+    ),
+    ("MiniHashSet<String>", size_of::<MiniHashSet<String>>(), 1),
+    (
+      "MiniIndexVec<u32, String>",
+      size_of::<MiniIndexVec<u32, String>>(),
+      1,
+    ),
+    (
+      "MiniVecMap<u32, String>",
+      size_of::<MiniVecMap<u32, String>>(),
+      1,
+    ),
+    ("MiniJagged<String>", size_of::<MiniJagged<String>>(), 1),
+    ("MiniStrVec", size_of::<MiniStrVec>(), 1),
+    ("MiniMatrix<f64>", size_of::<MiniMatrix<f64>>(), 1),
+    (
+      "NonEmptyMiniVec<String>",
+      size_of::<NonEmptyMiniVec<String>>(),
+      1,
+    ),
+    (
+      "Option<NonEmptyMiniVec<String>>",
+      size_of::<Option<NonEmptyMiniVec<String>>>(),
+      1,
+    ),
+    ("MiniSegVec<String>", size_of::<MiniSegVec<String>>(), 1),
+    ("MiniSlab<String>", size_of::<MiniSlab<String>>(), 1),
+    (
+      "MiniSlab<String, GenKey>",
+      size_of::<MiniSlab<String, GenKey>>(),
+      1,
+    ),
+    ("MiniSmallVec<u8>", size_of::<MiniSmallVec<u8>>(), 1),
+    ("MiniSmallVec<String>", size_of::<MiniSmallVec<String>>(), 1),
+    ("ParticleMiniVec", size_of::<ParticleMiniVec>(), 1),
+    ("TaggedMiniVec<u8, 3>", size_of::<TaggedMiniVec<u8, 3>>(), 1),
+    (
+      "Option<TaggedMiniVec<u8, 3>>",
+      size_of::<Option<TaggedMiniVec<u8, 3>>>(),
+      1,
+    ),
+    (
+      "MiniVecWithHeader<String, u8>",
+      size_of::<MiniVecWithHeader<String, u8>>(),
+      1,
+    ),
+    ("MiniBytesMut", size_of::<MiniBytesMut>(), 3),
+    ("MiniBytes", size_of::<MiniBytes>(), 3),
+    ("Option<MiniBytes>", size_of::<Option<MiniBytes>>(), 3),
+  ];
+
+  for &(name, size, words) in sizes.iter() {
+    assert_eq!(
+      size,
+      words * word,
+      "{} should be {} word(s) wide",
+      name,
+      words
+    );
+  }
+}
@@ -0,0 +1,266 @@
+extern crate minivec;
+
+use minivec::{GenKey, MiniSlab};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn slab_new_does_not_allocate() {
+  let slab = MiniSlab::<i32>::new();
+  assert!(slab.is_empty());
+  assert_eq!(slab.capacity(), 0);
+  assert_eq!(slab.get(0), None);
+  assert_eq!(slab.iter().next(), None);
+}
+
+#[test]
+fn slab_insert_remove_reuses_slots() {
+  let mut slab = MiniSlab::new();
+
+  let keys: Vec<usize> = (0..10).map(|i| slab.insert(i * 10)).collect();
+  assert_eq!(keys, (0..10).collect::<Vec<_>>());
+  assert_eq!(slab.len(), 10);
+
+  assert_eq!(slab.remove(3), 30);
+  assert_eq!(slab.remove(7), 70);
+  assert_eq!(slab.try_remove(7), None);
+  assert!(!slab.contains(3));
+  assert_eq!(slab.len(), 8);
+
+  // the most recently freed slot is reused first
+  //
+  assert_eq!(slab.insert(700), 7);
+  assert_eq!(slab.insert(300), 3);
+  assert_eq!(slab.insert(100), 10);
+
+  assert_eq!(slab[7], 700);
+  slab[3] += 1;
+  *slab.get_mut(10).unwrap() += 1;
+  assert_eq!(slab[3], 301);
+  assert_eq!(slab[10], 101);
+}
+
+#[test]
+#[should_panic(expected = "invalid slab key")]
+fn slab_remove_invalid_key() {
+  let mut slab = MiniSlab::new();
+  let key = slab.insert(1);
+  slab.remove(key);
+  slab.remove(key);
+}
+
+#[test]
+fn slab_generational_keys() {
+  let mut slab = MiniSlab::<_, GenKey>::default();
+
+  let a = slab.insert("a");
+  let b = slab.insert("b");
+  assert_ne!(a.generation(), b.generation());
+
+  assert_eq!(slab.remove(a), "a");
+  let c = slab.insert("c");
+
+  assert_eq!(c.index(), a.index());
+  assert!(!slab.contains(a));
+  assert_eq!(slab.get(a), None);
+  assert_eq!(slab.try_remove(a), None);
+  assert_eq!(slab[c], "c");
+
+  // stale keys stay stale even after the slab is cleared and refilled
+  //
+  slab.clear();
+  let d = slab.insert("d");
+  let e = slab.insert("e");
+
+  assert_eq!((d.index(), e.index()), (c.index(), b.index()));
+  assert_eq!(slab.get(c), None);
+  assert_eq!(slab.get(b), None);
+  assert_eq!(slab[d], "d");
+  assert_eq!(slab[e], "e");
+}
+
+#[test]
+fn slab_vacant_entry() {
+  let mut slab = MiniSlab::<(GenKey, &str), GenKey>::default();
+
+  let entry = slab.vacant_entry();
+  let first = entry.key();
+  entry.insert((first, "first"));
+  slab.remove(first);
+
+  let entry = slab.vacant_entry();
+  let key = entry.key();
+  let value = entry.insert((key, "second"));
+  value.1 = "changed";
+
+  assert_eq!(key.index(), first.index());
+  assert_eq!(slab[key], (key, "changed"));
+  assert_eq!(slab.get(first), None);
+}
+
+#[test]
+fn slab_retain_keeps_keys() {
+  let mut slab = MiniSlab::new();
+  for i in 0..10 {
+    slab.insert(i);
+  }
+
+  slab.retain(|key, value| {
+    *value *= 10;
+    key % 3 == 0
+  });
+
+  assert_eq!(
+    slab.iter().collect::<Vec<_>>(),
+    [(0, &0), (3, &30), (6, &60), (9, &90)]
+  );
+  assert_eq!(slab.len(), 4);
+
+  // removed slots are reused
+  //
+  let key = slab.insert(1);
+  assert!(key < 10 && key % 3 != 0);
+}
+
+#[test]
+fn slab_iterators() {
+  let mut slab = MiniSlab::new();
+  for i in 0..6 {
+    slab.insert(i);
+  }
+  slab.remove(1);
+  slab.remove(4);
+
+  for (key, value) in &mut slab {
+    *value += key * 100;
+  }
+
+  assert_eq!(
+    (&slab).into_iter().collect::<Vec<_>>(),
+    [(0, &0), (2, &202), (3, &303), (5, &505)]
+  );
+  assert_eq!(
+    slab.into_iter().collect::<Vec<_>>(),
+    [(0, 0), (2, 202), (3, 303), (5, 505)]
+  );
+}
+
+#[test]
+fn slab_drain() {
+  let mut slab = MiniSlab::new();
+  for i in 0..5 {
+    slab.insert(i);
+  }
+  slab.remove(2);
+  let capacity = slab.capacity();
+
+  assert_eq!(slab.drain().collect::<Vec<_>>(), [0, 1, 3, 4]);
+  assert!(slab.is_empty());
+  assert_eq!(slab.capacity(), capacity);
+  assert_eq!(slab.insert(9), 0);
+}
+
+#[test]
+fn slab_compact() {
+  let mut slab = MiniSlab::<_, GenKey>::default();
+  let keys: Vec<GenKey> = (0..8).map(|i| slab.insert(i)).collect();
+
+  for &key in &keys[..4] {
+    slab.remove(key);
+  }
+  slab.remove(keys[6]);
+
+  let mut moves = Vec::new();
+  slab.compact(|value, from, to| moves.push((*value, from, to)));
+
+  assert_eq!(moves.len(), 3);
+  assert_eq!(slab.len(), 3);
+  assert_eq!(slab.capacity(), 3);
+
+  for (value, from, to) in moves {
+    assert_eq!(from, keys[value]);
+    assert!(to.index() < 3);
+    assert_eq!(slab.get(from), None);
+    assert_eq!(slab[to], value);
+  }
+
+  assert_eq!(slab.insert(100).index(), 3);
+}
+
+#[test]
+fn slab_compact_panic_keeps_slab_consistent() {
+  let mut slab = MiniSlab::new();
+  for i in 0..6 {
+    slab.insert(i);
+  }
+  slab.remove(0);
+  slab.remove(1);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    slab.compact(|value, _, _| {
+      if *value == 5 {
+        panic!("rekey failed");
+      }
+    });
+  }));
+  assert!(result.is_err());
+
+  let mut values: Vec<i32> = slab.iter().map(|(_, &v)| v).collect();
+  values.sort_unstable();
+  assert_eq!(values, [2, 3, 4, 5]);
+  assert_eq!(slab.len(), 4);
+
+  let key = slab.insert(6);
+  assert_eq!(slab[key], 6);
+  assert_eq!(slab.len(), 5);
+}
+
+#[test]
+fn slab_drops_values() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  let mut slab = MiniSlab::new();
+  for _ in 0..10 {
+    slab.insert(make());
+  }
+
+  drop(slab.remove(0));
+  assert_eq!(count.get(), 1);
+
+  slab.retain(|key, _| key % 2 == 0);
+  assert_eq!(count.get(), 6);
+
+  drop(slab.drain().next());
+  assert_eq!(count.get(), 10);
+
+  for _ in 0..4 {
+    slab.insert(make());
+  }
+
+  let mut iter = slab.into_iter();
+  drop(iter.next());
+  assert_eq!(count.get(), 11);
+
+  drop(iter);
+  assert_eq!(count.get(), 14);
+}
+
+#[test]
+fn slab_clone_and_debug() {
+  let mut slab = MiniSlab::new();
+  slab.insert("a");
+  slab.insert("b");
+  slab.remove(0);
+
+  let clone = slab.clone();
+  assert_eq!(format!("{:?}", clone), r#"{1: "b"}"#);
+  assert_eq!(format!("{:?}", slab), format!("{:?}", clone));
+}
@@ -0,0 +1,227 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniSmallVec, MiniVec};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn small_vec_inline_capacity() {
+  assert_eq!(MiniSmallVec::<u8>::INLINE_CAPACITY, 7);
+  assert_eq!(MiniSmallVec::<u16>::INLINE_CAPACITY, 3);
+  assert_eq!(MiniSmallVec::<u32>::INLINE_CAPACITY, 1);
+  assert_eq!(MiniSmallVec::<[u8; 3]>::INLINE_CAPACITY, 2);
+  assert_eq!(MiniSmallVec::<u64>::INLINE_CAPACITY, 0);
+  assert_eq!(MiniSmallVec::<String>::INLINE_CAPACITY, 0);
+  assert_eq!(MiniSmallVec::<()>::INLINE_CAPACITY, 0);
+}
+
+#[test]
+fn small_vec_spill_and_unspill() {
+  let cap = MiniSmallVec::<u8>::INLINE_CAPACITY;
+
+  let mut vec = MiniSmallVec::<u8>::new();
+  assert!(vec.is_empty());
+  assert!(!vec.spilled());
+
+  for i in 0..cap {
+    vec.push(i as u8);
+    assert!(!vec.spilled());
+  }
+
+  assert_eq!(vec.len(), cap);
+  vec.push(100);
+  assert!(vec.spilled());
+  assert_eq!(vec.len(), cap + 1);
+  assert_eq!(vec[cap], 100);
+  assert_eq!(
+    vec[..cap],
+    (0..cap).map(|i| i as u8).collect::<Vec<_>>()[..]
+  );
+
+  assert_eq!(vec.pop(), Some(100));
+  vec.shrink_to_fit();
+  assert_eq!(vec.spilled(), cap == 0);
+  assert_eq!(vec.len(), cap);
+
+  let vec: MiniSmallVec<u64> = (0..100).collect();
+  assert!(vec.spilled());
+  assert_eq!(vec.iter().sum::<u64>(), 4950);
+}
+
+#[test]
+fn small_vec_mutation() {
+  let mut vec = MiniSmallVec::<u16>::new();
+  vec.push(1);
+  vec.insert(0, 0);
+  vec.insert(2, 2);
+  assert_eq!(vec, [0, 1, 2]);
+
+  vec.insert(1, 10);
+  assert!(vec.spilled() || MiniSmallVec::<u16>::INLINE_CAPACITY >= 4);
+  assert_eq!(vec, [0, 10, 1, 2]);
+
+  assert_eq!(vec.remove(1), 10);
+  assert_eq!(vec.swap_remove(0), 0);
+  assert_eq!(vec, [2, 1]);
+
+  vec.shrink_to_fit();
+  vec.resize(5, 7);
+  assert_eq!(vec, [2, 1, 7, 7, 7]);
+
+  vec.dedup();
+  assert_eq!(vec, [2, 1, 7]);
+
+  vec.retain(|&x| x != 1);
+  assert_eq!(vec, [2, 7]);
+
+  vec.extend_from_within(..);
+  assert_eq!(vec, [2, 7, 2, 7]);
+
+  let tail = vec.split_off(1);
+  assert_eq!(vec, [2]);
+  assert_eq!(tail, [7, 2, 7]);
+
+  let mut other = tail.clone();
+  vec.append(&mut other);
+  assert!(other.is_empty());
+  assert_eq!(vec, [2, 7, 2, 7]);
+  assert_eq!(vec.remove_item(&7), Some(7));
+
+  vec.sort_unstable();
+  assert_eq!(vec, [2, 2, 7]);
+
+  assert_eq!(vec.drain(1..).collect::<Vec<_>>(), [2, 7]);
+  assert_eq!(vec, [2]);
+
+  let removed: Vec<_> = vec.splice(.., vec![4, 5]).collect();
+  assert_eq!(removed, [2]);
+  assert_eq!(vec, [4, 5]);
+
+  vec.clear();
+  assert!(vec.is_empty());
+}
+
+#[test]
+fn small_vec_inline_dedup_and_retain() {
+  let mut vec = MiniSmallVec::from(&[1_u8, 1, 2, 2, 2, 3][..]);
+  assert!(!vec.spilled());
+
+  vec.dedup();
+  assert!(!vec.spilled());
+  assert_eq!(vec, [1, 2, 3]);
+
+  vec.retain(|&x| x != 2);
+  assert!(!vec.spilled());
+  assert_eq!(vec, [1, 3]);
+
+  vec.dedup_by_key(|x| *x / 4);
+  assert_eq!(vec, [1]);
+
+  vec.retain(|_| false);
+  assert!(vec.is_empty());
+  assert!(!vec.spilled());
+}
+
+#[test]
+fn small_vec_retain_panic_keeps_unvisited() {
+  let mut vec = MiniSmallVec::from(&[1_u8, 2, 3, 4, 5][..]);
+  assert!(!vec.spilled());
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    vec.retain(|&x| {
+      assert!(x != 4);
+      x % 2 == 1
+    });
+  }));
+  assert!(result.is_err());
+  assert_eq!(vec, [1, 3, 4, 5]);
+
+  let mut vec = MiniSmallVec::from(&[1_u8, 1, 2, 2, 3][..]);
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    vec.dedup_by(|x, y| {
+      assert!(*x != 3);
+      x == y
+    });
+  }));
+  assert!(result.is_err());
+  assert_eq!(vec, [1, 2, 3]);
+}
+
+#[test]
+fn small_vec_drops() {
+  let count = Rc::new(Cell::new(0));
+  let make = || DropCounter {
+    count: count.clone(),
+  };
+
+  {
+    let mut vec = MiniSmallVec::new();
+    for _ in 0..10 {
+      vec.push(make());
+    }
+
+    vec.truncate(6);
+    assert_eq!(count.get(), 4);
+
+    drop(vec.pop());
+    assert_eq!(count.get(), 5);
+
+    let mut iter = vec.into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert_eq!(iter.len(), 3);
+    assert_eq!(count.get(), 7);
+  }
+
+  assert_eq!(count.get(), 10);
+}
+
+#[test]
+fn small_vec_into_iter_inline() {
+  let vec = MiniSmallVec::from([1_u8, 2, 3]);
+  assert_eq!(vec.into_iter().rev().collect::<Vec<_>>(), [3, 2, 1]);
+
+  let vec = MiniSmallVec::from([String::from("a"), String::from("b")]);
+  let mut iter = vec.into_iter();
+  assert_eq!(iter.as_slice(), ["a", "b"]);
+  assert_eq!(iter.next().as_deref(), Some("a"));
+}
+
+#[test]
+fn small_vec_conversions() {
+  let vec: MiniSmallVec<u8> = MiniVec::new().into();
+  assert!(!vec.spilled());
+
+  let vec: MiniSmallVec<u8> = mini_vec![1, 2].into();
+  assert!(vec.spilled());
+  assert_eq!(vec, mini_vec![1, 2]);
+
+  let inline = MiniSmallVec::from(&[1_u8, 2][..]);
+  assert!(!inline.spilled());
+  assert_eq!(inline, vec);
+
+  let back: MiniVec<u8> = inline.into();
+  assert_eq!(back, [1, 2]);
+
+  let heap: MiniVec<u8> = vec.into_vec();
+  assert_eq!(heap, [1, 2]);
+
+  let a = MiniSmallVec::from([1_u8, 2]);
+  let b = MiniSmallVec::from([1_u8, 3]);
+  assert!(a < b);
+  assert_eq!(format!("{:?}", a), "[1, 2]");
+
+  let mut vec = MiniSmallVec::<u8>::with_capacity(64);
+  assert!(vec.spilled());
+  assert!(vec.capacity() >= 64);
+  vec.shrink_to(32);
+  assert_eq!(vec.capacity(), 32);
+  vec.shrink_to(0);
+  assert!(!vec.spilled());
+}
@@ -0,0 +1,329 @@
+extern crate minivec;
+extern crate minivec_derive;
+
+use minivec::mini_vec;
+use minivec_derive::MiniSoA;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[derive(MiniSoA, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", mini_soa(serde))]
+pub struct Particle {
+  pub x: f32,
+  pub y: f32,
+  pub id: u8,
+}
+
+#[derive(MiniSoA)]
+struct Order {
+  price: u64,
+  label: String,
+  flag: (),
+}
+
+#[repr(align(64))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Wide(u32);
+
+#[derive(MiniSoA)]
+struct Mixed {
+  small: u8,
+  wide: Wide,
+}
+
+#[derive(MiniSoA)]
+struct Tracked {
+  value: DropCounter,
+  tag: u16,
+}
+
+fn particles() -> ParticleMiniVec {
+  (0..5_u8)
+    .map(|id| Particle {
+      x: f32::from(id),
+      y: f32::from(id) * 10.0,
+      id,
+    })
+    .collect()
+}
+
+#[test]
+fn soa_new_does_not_allocate() {
+  let particles = ParticleMiniVec::new();
+  assert!(particles.is_empty());
+  assert_eq!(particles.capacity(), 0);
+  assert!(particles.x().is_empty());
+  assert!(particles.get(0).is_none());
+  assert_eq!(particles.iter().count(), 0);
+}
+
+#[test]
+fn soa_push_and_columns() {
+  let mut particles = particles();
+  assert_eq!(particles.len(), 5);
+  assert_eq!(particles.x(), [0.0, 1.0, 2.0, 3.0, 4.0]);
+  assert_eq!(particles.y(), [0.0, 10.0, 20.0, 30.0, 40.0]);
+  assert_eq!(particles.id(), [0, 1, 2, 3, 4]);
+
+  for x in particles.x_mut() {
+    *x += 0.5;
+  }
+
+  let row = particles.get(2).unwrap();
+  assert_eq!((*row.x, *row.y, *row.id), (2.5, 20.0, 2));
+
+  let row = particles.get_mut(4).unwrap();
+  *row.y = -1.0;
+  assert_eq!(particles.y()[4], -1.0);
+
+  particles.push(Particle {
+    x: 9.0,
+    y: 9.0,
+    id: 9,
+  });
+  assert_eq!(particles.id(), [0, 1, 2, 3, 4, 9]);
+}
+
+#[test]
+fn soa_iter() {
+  let mut particles = particles();
+
+  let ids: Vec<u8> = particles.iter().map(|row| *row.id).collect();
+  assert_eq!(ids, [0, 1, 2, 3, 4]);
+  assert_eq!(particles.iter().len(), 5);
+
+  for row in &mut particles {
+    *row.x += *row.y;
+  }
+  assert_eq!(particles.x(), [0.0, 11.0, 22.0, 33.0, 44.0]);
+
+  let sum: f32 = (&particles).into_iter().map(|row| *row.x).sum();
+  assert_eq!(sum, 110.0);
+}
+
+#[test]
+fn soa_pop_and_swap_remove() {
+  let mut particles = particles();
+
+  assert_eq!(
+    particles.swap_remove(1),
+    Particle {
+      x: 1.0,
+      y: 10.0,
+      id: 1
+    }
+  );
+  assert_eq!(particles.id(), [0, 4, 2, 3]);
+
+  assert_eq!(particles.pop().map(|p| p.id), Some(3));
+  assert_eq!(particles.swap_remove(2).id, 2);
+  assert_eq!(particles.id(), [0, 4]);
+
+  particles.swap(0, 1);
+  assert_eq!(particles.x(), [4.0, 0.0]);
+
+  particles.clear();
+  assert!(particles.pop().is_none());
+}
+
+#[test]
+#[should_panic(expected = "swap_remove index (is 2) should be < len (is 2)")]
+fn soa_swap_remove_out_of_bounds() {
+  let mut particles = particles();
+  particles.truncate(2);
+  particles.swap_remove(2);
+}
+
+#[test]
+fn soa_sort() {
+  let mut orders = OrderMiniVec::new();
+  for (price, label) in [(30, "c"), (10, "a"), (20, "b"), (10, "d")] {
+    orders.push(Order {
+      price,
+      label: label.to_string(),
+      flag: (),
+    });
+  }
+
+  orders.sort_by_key(|order| *order.price);
+  assert_eq!(orders.price(), [10, 10, 20, 30]);
+  assert_eq!(orders.label(), ["a", "d", "b", "c"]);
+  assert_eq!(orders.flag().len(), 4);
+
+  orders.sort_by(|a, b| b.label.cmp(a.label));
+  assert_eq!(orders.label(), ["d", "c", "b", "a"]);
+  assert_eq!(orders.price(), [10, 30, 20, 10]);
+
+  let mut many: ParticleMiniVec = (0..100_u8)
+    .map(|id| Particle {
+      x: (u32::from(id) * 37 % 100) as f32,
+      y: 0.0,
+      id,
+    })
+    .collect();
+  many.sort_by(|a, b| a.x.partial_cmp(b.x).unwrap());
+  assert!(many.x().windows(2).all(|pair| pair[0] < pair[1]));
+  assert!(many
+    .iter()
+    .all(|row| *row.x == (u32::from(*row.id) * 37 % 100) as f32));
+}
+
+#[test]
+fn soa_alignment() {
+  let mut mixed = MixedMiniVec::with_capacity(3);
+  assert_eq!(mixed.capacity(), 3);
+
+  for idx in 0..20_u8 {
+    mixed.push(Mixed {
+      small: idx,
+      wide: Wide(u32::from(idx) * 2),
+    });
+    assert_eq!(mixed.wide().as_ptr() as usize % 64, 0);
+  }
+
+  assert_eq!(mixed.small()[19], 19);
+  assert_eq!(mixed.wide()[19], Wide(38));
+}
+
+#[test]
+fn soa_from_columns() {
+  let particles =
+    ParticleMiniVec::from_columns(mini_vec![1.0, 2.0], mini_vec![3.0, 4.0], mini_vec![7, 8])
+      .unwrap();
+  assert_eq!(particles.len(), 2);
+  assert_eq!(particles.y(), [3.0, 4.0]);
+  assert_eq!(particles.get(1).map(|row| *row.id), Some(8));
+
+  let uneven = ParticleMiniVec::from_columns(mini_vec![1.0], mini_vec![], mini_vec![1]);
+  assert!(uneven.is_none());
+}
+
+#[test]
+fn soa_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+  let make = |tag| Tracked {
+    value: DropCounter {
+      count: count.clone(),
+    },
+    tag,
+  };
+
+  let mut tracked: TrackedMiniVec = (0..10).map(make).collect();
+
+  drop(tracked.pop());
+  drop(tracked.swap_remove(0));
+  assert_eq!(count.get(), 2);
+  assert_eq!(tracked.tag()[0], 8);
+
+  tracked.truncate(5);
+  assert_eq!(count.get(), 5);
+
+  tracked.extend((0..3).map(make));
+  assert_eq!(tracked.len(), 8);
+
+  drop(tracked);
+  assert_eq!(count.get(), 13);
+}
+
+#[derive(MiniSoA)]
+#[cfg_attr(feature = "serde", mini_soa(serde))]
+pub struct Edge {
+  pub vec: u32,
+  pub map: f32,
+  pub seq: u8,
+  pub key: u16,
+}
+
+#[test]
+fn soa_fields_named_like_locals() {
+  let edges = EdgeMiniVec::from_columns(
+    mini_vec![1, 2],
+    mini_vec![0.5, 1.5],
+    mini_vec![3, 4],
+    mini_vec![5, 6],
+  )
+  .unwrap();
+  assert_eq!(edges.len(), 2);
+  assert_eq!(edges.vec(), [1, 2]);
+  assert_eq!(edges.map(), [0.5, 1.5]);
+  assert_eq!(edges.seq(), [3, 4]);
+  assert_eq!(edges.key(), [5, 6]);
+  assert_eq!(*edges.get(1).unwrap().key, 6);
+
+  let uneven = EdgeMiniVec::from_columns(mini_vec![1], mini_vec![], mini_vec![], mini_vec![]);
+  assert!(uneven.is_none());
+}
+
+#[cfg(feature = "serde")]
+#[derive(MiniSoA)]
+#[mini_soa(serde)]
+struct Point {
+  x: i32,
+  y: i32,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn soa_deserialize() {
+  use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+  use serde::Deserialize;
+
+  fn column(
+    values: &[i32],
+  ) -> SeqDeserializer<std::iter::Copied<std::slice::Iter<'_, i32>>, Error> {
+    SeqDeserializer::new(values.iter().copied())
+  }
+
+  let map = MapDeserializer::<_, Error>::new(
+    vec![
+      ("y", column(&[3, 4])),
+      ("z", column(&[0])),
+      ("x", column(&[1, 2])),
+    ]
+    .into_iter(),
+  );
+  let points = PointMiniVec::deserialize(map).unwrap();
+  assert_eq!(points.x(), [1, 2]);
+  assert_eq!(points.y(), [3, 4]);
+
+  let seq = SeqDeserializer::<_, Error>::new(vec![column(&[5]), column(&[6])].into_iter());
+  let points = PointMiniVec::deserialize(seq).unwrap();
+  assert_eq!(
+    (*points.get(0).unwrap().x, *points.get(0).unwrap().y),
+    (5, 6)
+  );
+
+  let uneven =
+    MapDeserializer::<_, Error>::new(vec![("x", column(&[1, 2])), ("y", column(&[3]))].into_iter());
+  assert!(PointMiniVec::deserialize(uneven).is_err());
+
+  let missing = MapDeserializer::<_, Error>::new(vec![("x", column(&[1]))].into_iter());
+  assert!(PointMiniVec::deserialize(missing).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn soa_deserialize_fields_named_like_locals() {
+  use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+  use serde::Deserialize;
+
+  let map = MapDeserializer::<_, Error>::new(
+    vec![
+      ("vec", SeqDeserializer::new(vec![1_u64].into_iter())),
+      ("map", SeqDeserializer::new(vec![2_u64].into_iter())),
+      ("seq", SeqDeserializer::new(vec![3_u64].into_iter())),
+      ("key", SeqDeserializer::new(vec![4_u64].into_iter())),
+    ]
+    .into_iter(),
+  );
+  let edges = EdgeMiniVec::deserialize(map).unwrap();
+  assert_eq!(edges.vec(), [1]);
+  assert_eq!(edges.seq(), [3]);
+  assert_eq!(edges.key(), [4]);
+  assert_eq!(edges.len(), 1);
+}
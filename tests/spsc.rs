@@ -0,0 +1,262 @@
+extern crate minivec;
+
+mod common;
+
+// The model tests run with: RUSTFLAGS="--cfg loom" cargo test --test spsc --release
+
+#[cfg(not(loom))]
+mod threads {
+  use minivec::spsc;
+
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  use crate::common::SyncDropCounter;
+
+  #[test]
+  fn spsc_halves_are_pointer_sized() {
+    assert_eq!(
+      std::mem::size_of::<spsc::Producer<String>>(),
+      std::mem::size_of::<*const ()>()
+    );
+    assert_eq!(
+      std::mem::size_of::<spsc::Consumer<String>>(),
+      std::mem::size_of::<*const ()>()
+    );
+  }
+
+  #[test]
+  fn spsc_push_pop_wraps_around() {
+    let (mut tx, mut rx) = spsc::channel(3);
+    assert_eq!(tx.capacity(), 3);
+    assert!(rx.is_empty());
+
+    for round in 0..10 {
+      assert_eq!(tx.push(round * 2), Ok(()));
+      assert_eq!(tx.push(round * 2 + 1), Ok(()));
+      assert_eq!(rx.len(), 2);
+
+      assert_eq!(rx.pop(), Some(round * 2));
+      assert_eq!(rx.pop(), Some(round * 2 + 1));
+      assert_eq!(rx.pop(), None);
+    }
+
+    tx.push(0).unwrap();
+    tx.push(1).unwrap();
+    tx.push(2).unwrap();
+    assert!(tx.is_full());
+    assert_eq!(tx.push(3), Err(3));
+  }
+
+  #[test]
+  fn spsc_zero_capacity() {
+    let (mut tx, mut rx) = spsc::channel::<u8>(0);
+    assert_eq!(tx.push(1), Err(1));
+    assert_eq!(tx.push_slice(&[1, 2]), 0);
+    assert_eq!(rx.pop(), None);
+    assert_eq!(rx.pop_into_slice(&mut [0; 2]), 0);
+  }
+
+  #[test]
+  fn spsc_batches_wrap_around() {
+    let (mut tx, mut rx) = spsc::channel(5);
+    let mut out = [0; 5];
+
+    assert_eq!(tx.push_slice(&[1, 2, 3]), 3);
+    assert_eq!(rx.pop_into_slice(&mut out[..2]), 2);
+    assert_eq!(out[..2], [1, 2]);
+
+    assert_eq!(tx.push_slice(&[4, 5, 6, 7, 8, 9]), 4);
+    assert_eq!(rx.pop_into_slice(&mut out), 5);
+    assert_eq!(out, [3, 4, 5, 6, 7]);
+  }
+
+  #[test]
+  fn spsc_alignment() {
+    #[repr(align(128))]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Wide(u8);
+
+    let (mut tx, mut rx) = spsc::channel(2);
+    tx.push(Wide(1)).unwrap();
+    tx.push(Wide(2)).unwrap();
+
+    assert_eq!(rx.pop(), Some(Wide(1)));
+    assert_eq!(rx.pop(), Some(Wide(2)));
+
+    assert!(matches!(
+      spsc::channel_with_alignment::<u8>(4, 8),
+      Err(minivec::LayoutErr::AlignmentTooSmall)
+    ));
+    assert!(matches!(
+      spsc::channel_with_alignment::<u8>(4, 192),
+      Err(minivec::LayoutErr::AlignmentNotDivisibleByTwo)
+    ));
+
+    let (mut tx, mut rx) = spsc::channel_with_alignment::<u8>(4, 256).unwrap();
+    assert_eq!(tx.push_slice(b"abcd"), 4);
+
+    let mut out = [0; 4];
+    assert_eq!(rx.pop_into_slice(&mut out), 4);
+    assert_eq!(&out, b"abcd");
+  }
+
+  #[test]
+  fn spsc_drops_elements_in_flight() {
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let (mut tx, mut rx) = spsc::channel(4);
+    for _ in 0..4 {
+      assert!(tx
+        .push(SyncDropCounter {
+          count: count.clone(),
+        })
+        .is_ok());
+    }
+
+    drop(rx.pop());
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+
+    drop(tx);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+
+    drop(rx);
+    assert_eq!(count.load(Ordering::Relaxed), 4);
+  }
+
+  #[test]
+  fn spsc_threads() {
+    let (mut tx, mut rx) = spsc::channel(16);
+    let total = 10_000_u64;
+
+    let consumer = std::thread::spawn(move || {
+      let mut expected = 0;
+      let mut buf = [0; 8];
+
+      while expected < total {
+        if expected % 2 == 0 {
+          if let Some(x) = rx.pop() {
+            assert_eq!(x, expected);
+            expected += 1;
+          }
+        } else {
+          let count = rx.pop_into_slice(&mut buf);
+          for &x in &buf[..count] {
+            assert_eq!(x, expected);
+            expected += 1;
+          }
+        }
+      }
+    });
+
+    let mut next = 0;
+    while next < total {
+      if next % 3 == 0 {
+        if tx.push(next).is_ok() {
+          next += 1;
+        }
+      } else {
+        let end = std::cmp::min(next + 5, total);
+        let batch: Vec<u64> = (next..end).collect();
+        next += tx.push_slice(&batch) as u64;
+      }
+    }
+
+    consumer.join().unwrap();
+  }
+
+  #[test]
+  fn spsc_debug() {
+    let (mut tx, rx) = spsc::channel(2);
+    tx.push(1).unwrap();
+
+    assert_eq!(format!("{:?}", tx), "Producer { capacity: 2, len: 1 }");
+    assert_eq!(format!("{:?}", rx), "Consumer { capacity: 2, len: 1 }");
+  }
+}
+
+#[cfg(loom)]
+mod model {
+  use minivec::spsc;
+
+  use loom::thread;
+
+  #[test]
+  fn loom_spsc_push_pop() {
+    loom::model(|| {
+      let (mut tx, mut rx) = spsc::channel(2);
+
+      let producer = thread::spawn(move || {
+        for i in 0..3 {
+          let mut value = String::from(["a", "b", "c"][i]);
+          loop {
+            match tx.push(value) {
+              Ok(()) => break,
+              Err(rejected) => {
+                value = rejected;
+                thread::yield_now();
+              }
+            }
+          }
+        }
+      });
+
+      let mut received = Vec::new();
+      while received.len() < 3 {
+        match rx.pop() {
+          Some(value) => received.push(value),
+          None => thread::yield_now(),
+        }
+      }
+
+      producer.join().unwrap();
+      assert_eq!(received, ["a", "b", "c"]);
+    });
+  }
+
+  #[test]
+  fn loom_spsc_batches() {
+    loom::model(|| {
+      let (mut tx, mut rx) = spsc::channel(2);
+
+      let producer = thread::spawn(move || {
+        let values = [1, 2, 3];
+        let mut sent = 0;
+        while sent < values.len() {
+          let count = tx.push_slice(&values[sent..]);
+          if count == 0 {
+            thread::yield_now();
+          }
+          sent += count;
+        }
+      });
+
+      let mut received = Vec::new();
+      let mut buf = [0; 2];
+      while received.len() < 3 {
+        let count = rx.pop_into_slice(&mut buf);
+        if count == 0 {
+          thread::yield_now();
+        }
+        received.extend_from_slice(&buf[..count]);
+      }
+
+      producer.join().unwrap();
+      assert_eq!(received, [1, 2, 3]);
+    });
+  }
+
+  #[test]
+  fn loom_spsc_drop_while_in_flight() {
+    loom::model(|| {
+      let (mut tx, rx) = spsc::channel(2);
+
+      let producer = thread::spawn(move || {
+        let _ = tx.push(String::from("a"));
+      });
+
+      drop(rx);
+      producer.join().unwrap();
+    });
+  }
+}
@@ -0,0 +1,197 @@
+extern crate minivec;
+
+use minivec::{mini_vec, MiniVec, TaggedMiniVec};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[test]
+fn tagged_vec_empty_keeps_tag() {
+  let mut vec = TaggedMiniVec::<String, 3>::new();
+  assert_eq!(vec.tag(), 0);
+  assert!(vec.is_empty());
+  assert_eq!(vec.capacity(), 0);
+
+  for tag in 0..8 {
+    vec.set_tag(tag);
+    assert_eq!(vec.tag(), tag);
+    assert!(vec.is_empty());
+    assert_eq!(vec.capacity(), 0);
+  }
+
+  let (vec, tag) = vec.into_parts();
+  assert_eq!(tag, 7);
+  assert_eq!(vec.capacity(), 0);
+}
+
+#[test]
+fn tagged_vec_tag_survives_reallocation() {
+  let mut vec = TaggedMiniVec::<usize, 3>::new();
+  vec.set_tag(5);
+
+  for i in 0..100 {
+    vec.push(i);
+    assert_eq!(vec.tag(), 5);
+  }
+
+  assert_eq!(vec.len(), 100);
+  assert!(vec.iter().copied().eq(0..100));
+
+  vec.truncate(10);
+  vec.shrink_to_fit();
+  assert_eq!(vec.capacity(), 10);
+  assert_eq!(vec.tag(), 5);
+
+  vec.insert(0, 42);
+  assert_eq!(vec.remove(1), 0);
+  assert_eq!(vec.swap_remove(0), 42);
+  assert_eq!(vec.pop(), Some(8));
+  assert_eq!(vec, [9, 1, 2, 3, 4, 5, 6, 7]);
+
+  vec.retain(|x| x % 2 == 1);
+  assert_eq!(vec, [9, 1, 3, 5, 7]);
+
+  vec.clear();
+  assert!(vec.is_empty());
+  assert_eq!(vec.tag(), 5);
+}
+
+#[test]
+fn tagged_vec_set_tag_preserves_elements() {
+  let mut vec = TaggedMiniVec::<_, 3>::from_vec(mini_vec![1_u8, 2, 3], 2);
+  vec.set_tag(6);
+  vec.set_tag(1);
+
+  assert_eq!(vec.tag(), 1);
+  assert_eq!(vec, [1, 2, 3]);
+
+  vec[0] = 10;
+  assert_eq!(vec.into_vec(), [10, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn tagged_vec_set_tag_too_wide() {
+  let mut vec = TaggedMiniVec::<u8, 2>::new();
+  vec.set_tag(4);
+}
+
+#[test]
+fn tagged_vec_over_aligned() {
+  let vec: MiniVec<u8> = MiniVec::with_alignment(8, 64).unwrap();
+  let mut vec = TaggedMiniVec::<u8, 3>::from_vec(vec, 7);
+
+  vec.extend_from_slice(&[1, 2, 3]);
+  assert_eq!(vec.tag(), 7);
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+
+  let vec = vec.into_vec();
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+  assert_eq!(vec, [1, 2, 3]);
+}
+
+#[test]
+fn tagged_vec_wide_element_alignment() {
+  #[repr(align(64))]
+  #[derive(Clone, Copy, PartialEq, Debug)]
+  struct Wide(u8);
+
+  let mut vec = TaggedMiniVec::<Wide, 6>::new();
+  vec.set_tag(63);
+  vec.push(Wide(1));
+  vec.push(Wide(2));
+
+  assert_eq!(vec.tag(), 63);
+  assert_eq!(vec, [Wide(1), Wide(2)]);
+}
+
+#[test]
+fn tagged_vec_with_mut_retags() {
+  let mut vec = TaggedMiniVec::<_, 2>::from_vec(mini_vec![1, 2, 3, 4, 5], 3);
+
+  let drained: Vec<_> = vec.with_mut(|v| {
+    v.reserve(1000);
+    v.drain(1..3).collect()
+  });
+
+  assert_eq!(drained, [2, 3]);
+  assert_eq!(vec, [1, 4, 5]);
+  assert_eq!(vec.tag(), 3);
+  assert!(vec.capacity() >= 1000);
+}
+
+#[test]
+fn tagged_vec_with_mut_retags_on_panic() {
+  let mut vec = TaggedMiniVec::<_, 2>::from_vec(mini_vec![1, 2, 3], 2);
+
+  let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    vec.with_mut(|v| {
+      v.reserve(100);
+      v.push(4);
+      panic!("oops");
+    })
+  }));
+
+  assert!(result.is_err());
+  assert_eq!(vec.tag(), 2);
+  assert_eq!(vec, [1, 2, 3, 4]);
+}
+
+#[test]
+fn tagged_vec_drops_elements() {
+  let count = Rc::new(Cell::new(0));
+
+  {
+    let mut vec = TaggedMiniVec::<_, 3>::new();
+    vec.set_tag(4);
+    for _ in 0..10 {
+      vec.push(DropCounter {
+        count: count.clone(),
+      });
+    }
+  }
+
+  assert_eq!(count.get(), 10);
+
+  let vec = TaggedMiniVec::<_, 3>::from_vec(
+    (0..4)
+      .map(|_| DropCounter {
+        count: count.clone(),
+      })
+      .collect(),
+    1,
+  );
+
+  assert_eq!(vec.into_iter().count(), 4);
+  assert_eq!(count.get(), 14);
+}
+
+#[test]
+fn tagged_vec_clone_eq_hash() {
+  use std::collections::hash_map::DefaultHasher;
+  use std::hash::{Hash, Hasher};
+
+  let hash = |v: &TaggedMiniVec<i32, 2>| {
+    let mut hasher = DefaultHasher::new();
+    v.hash(&mut hasher);
+    hasher.finish()
+  };
+
+  let a = TaggedMiniVec::<_, 2>::from_vec(mini_vec![1, 2], 1);
+  let b = a.clone();
+  assert_eq!(a, b);
+  assert_eq!(hash(&a), hash(&b));
+
+  let mut c = b.clone();
+  c.set_tag(2);
+  assert_ne!(a, c);
+
+  assert_eq!(
+    format!("{:?}", a),
+    "TaggedMiniVec { tag: 1, elements: [1, 2] }"
+  );
+}
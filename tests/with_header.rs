@@ -0,0 +1,192 @@
+extern crate minivec;
+
+use minivec::MiniVecWithHeader;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+mod common;
+
+use common::DropCounter;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Meta {
+  generation: u32,
+  name: String,
+}
+
+#[test]
+fn with_header_new_does_not_allocate() {
+  let vec = MiniVecWithHeader::<Meta, i32>::new();
+  assert!(vec.header().is_none());
+  assert_eq!(vec.len(), 0);
+  assert_eq!(vec.capacity(), 0);
+  assert!(vec.is_empty());
+  assert_eq!(vec.as_slice(), &[] as &[i32]);
+
+  let clone = vec.clone();
+  assert!(clone.header().is_none());
+}
+
+#[test]
+fn with_header_materializes_default_header() {
+  let mut vec = MiniVecWithHeader::<Meta, i32>::new();
+  vec.push(1);
+
+  assert_eq!(vec.header(), Some(&Meta::default()));
+
+  let mut vec = MiniVecWithHeader::<Meta, i32>::new();
+  vec.header_mut().name.push_str("hello");
+
+  assert_eq!(vec.capacity(), 0);
+  assert_eq!(vec.header().unwrap().name, "hello");
+
+  vec.extend(0..100);
+  assert_eq!(vec.header().unwrap().name, "hello");
+  assert!(vec.iter().copied().eq(0..100));
+}
+
+#[test]
+fn with_header_preserves_header_across_reallocation() {
+  let meta = Meta {
+    generation: 7,
+    name: String::from("items"),
+  };
+
+  let mut vec = MiniVecWithHeader::<_, String>::with_header(meta.clone());
+  for i in 0..64 {
+    vec.push(i.to_string());
+    assert_eq!(vec.header(), Some(&meta));
+  }
+
+  vec.truncate(3);
+  vec.shrink_to_fit();
+  assert_eq!(vec.capacity(), 3);
+  assert_eq!(vec, ["0", "1", "2"]);
+
+  vec.clear();
+  vec.shrink_to_fit();
+  assert_eq!(vec.capacity(), 0);
+  assert_eq!(vec.header(), Some(&meta));
+}
+
+#[test]
+fn with_header_over_aligned_header() {
+  #[repr(align(256))]
+  #[derive(Default, Debug)]
+  struct Wide(u64);
+
+  let mut vec = MiniVecWithHeader::<Wide, u8>::with_header_and_capacity(Wide(3), 4);
+  vec.extend_from_slice(b"abcdef");
+
+  assert_eq!(vec.header().unwrap().0, 3);
+  assert_eq!(vec.header().unwrap() as *const Wide as usize % 256, 0);
+  assert_eq!(vec, *b"abcdef");
+}
+
+#[test]
+fn with_header_over_aligned_elements() {
+  #[repr(align(64))]
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  struct Wide(u8);
+
+  let mut vec = MiniVecWithHeader::<u8, Wide>::with_header(1);
+  vec.push(Wide(1));
+  vec.push(Wide(2));
+
+  assert_eq!(vec.as_ptr() as usize % 64, 0);
+  assert_eq!(vec.header(), Some(&1));
+  assert_eq!(vec, [Wide(1), Wide(2)]);
+}
+
+#[test]
+fn with_header_custom_alignment() {
+  let mut vec = MiniVecWithHeader::<u16, u8>::with_header_and_alignment(3, 1, 256).unwrap();
+  assert_eq!(vec.alignment(), 256);
+
+  vec.extend_from_slice(&[1; 100]);
+  assert_eq!(vec.as_ptr() as usize % 256, 0);
+  assert_eq!(vec.header(), Some(&3));
+
+  let clone = vec.clone();
+  assert_eq!(clone.alignment(), 256);
+  assert_eq!(clone.as_ptr() as usize % 256, 0);
+  assert_eq!(clone, vec[..]);
+
+  assert!(matches!(
+    MiniVecWithHeader::<u64, u8>::with_header_and_alignment(0, 0, 4),
+    Err(minivec::LayoutErr::AlignmentTooSmall)
+  ));
+  assert!(matches!(
+    MiniVecWithHeader::<u8, u8>::with_header_and_alignment(0, 0, 63),
+    Err(minivec::LayoutErr::AlignmentNotDivisibleByTwo)
+  ));
+}
+
+#[test]
+fn with_header_vector_operations() {
+  let mut vec: MiniVecWithHeader<u32, i32> = (1..=5).collect();
+
+  vec.insert(0, 0);
+  assert_eq!(vec, [0, 1, 2, 3, 4, 5]);
+
+  assert_eq!(vec.remove(1), 1);
+  assert_eq!(vec.swap_remove(0), 0);
+  assert_eq!(vec, [5, 2, 3, 4]);
+
+  vec.retain(|&x| x != 3);
+  assert_eq!(vec, [5, 2, 4]);
+
+  vec.sort_unstable();
+  assert_eq!(vec.pop(), Some(5));
+  assert_eq!(vec, [2, 4]);
+
+  vec.reserve_exact(10);
+  assert_eq!(vec.capacity(), 12);
+}
+
+#[test]
+fn with_header_drops_header_and_elements() {
+  let count = Rc::new(Cell::new(0));
+
+  {
+    let header = DropCounter {
+      count: count.clone(),
+    };
+
+    let mut vec = MiniVecWithHeader::with_header(header);
+    for _ in 0..10 {
+      vec.push(DropCounter {
+        count: count.clone(),
+      });
+    }
+
+    vec.retain({
+      let mut keep = false;
+      move |_| {
+        keep = !keep;
+        keep
+      }
+    });
+
+    assert_eq!(count.get(), 5);
+  }
+
+  assert_eq!(count.get(), 11);
+}
+
+#[test]
+fn with_header_clone() {
+  let mut vec = MiniVecWithHeader::<Meta, i32>::new();
+  vec.header_mut().generation = 2;
+  vec.extend_from_slice(&[1, 2, 3]);
+
+  let clone = vec.clone();
+  assert_eq!(clone.header(), vec.header());
+  assert_eq!(clone, [1, 2, 3]);
+
+  assert_eq!(
+    format!("{:?}", MiniVecWithHeader::<u8, u8>::with_header(1)),
+    "MiniVecWithHeader { header: Some(1), elements: [] }"
+  );
+}